@@ -0,0 +1,271 @@
+// ---------- A bank that many threads can use at once ----------
+// Each account has its own `Mutex`, so threads working on different accounts
+// never wait for each other. A transfer must hold two locks at once, which is
+// where deadlocks come from: thread 1 locks A then waits for B while thread 2
+// locks B then waits for A, and both wait forever.
+//
+// The fix is a lock order. Every thread that needs several accounts locks them
+// in increasing `AccountId` order, so no two threads can each hold a lock the
+// other one is waiting for.
+//
+// Money is counted in whole cents (`u64`), not `f32`: floats cannot hold 0.10
+// exactly, and their rounding errors would make the total drift.
+
+use std::fmt; // `fmt` for `Display`
+use std::sync::{Mutex, MutexGuard}; // `MutexGuard` = the lock, released on drop
+
+pub type AccountId = usize; // accounts are numbered from 0
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BankError {
+    NoSuchAccount(AccountId),
+    SameAccount(AccountId), // transfer to itself
+    ZeroAmount,             // nothing to move
+    InsufficientFunds {
+        account: AccountId,
+        balance: u64,
+        needed: u64,
+    },
+    Overflow(AccountId), // balance would pass `u64::MAX`
+}
+
+impl fmt::Display for BankError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            // `*self` copies the enum out so fields bind by value
+            BankError::NoSuchAccount(id) => write!(f, "account {} does not exist", id),
+            BankError::SameAccount(id) => {
+                write!(f, "cannot transfer from account {} to itself", id)
+            }
+            BankError::ZeroAmount => write!(f, "the amount must be more than zero"),
+            BankError::InsufficientFunds {
+                account,
+                balance,
+                needed,
+            } => write!(
+                f,
+                "account {} holds {} but {} is needed",
+                account,
+                cents(balance),
+                cents(needed)
+            ),
+            BankError::Overflow(id) => write!(f, "account {} cannot hold that much", id),
+        }
+    }
+}
+
+impl std::error::Error for BankError {}
+
+// Formats cents as dollars, like "12.05".
+pub fn cents(amount: u64) -> String {
+    format!("{}.{:02}", amount / 100, amount % 100)
+}
+
+#[derive(Debug)]
+pub struct Bank {
+    accounts: Vec<Mutex<u64>>, // one lock per balance, in `AccountId` order
+}
+
+impl Bank {
+    // A bank with one account per opening balance, numbered from 0.
+    pub fn new(balances: &[u64]) -> Bank {
+        Bank {
+            accounts: balances
+                .iter()
+                .map(|&balance| Mutex::new(balance))
+                .collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn balance(&self, id: AccountId) -> Result<u64, BankError> {
+        Ok(*self.lock(id)?) // the guard is dropped at the end of the statement
+    }
+
+    // Refuses only when the balance is short of `amount`.
+    pub fn withdraw(&self, id: AccountId, amount: u64) -> Result<u64, BankError> {
+        check_amount(amount)?;
+        let mut balance = self.lock(id)?;
+        *balance = take(id, *balance, amount)?;
+        Ok(*balance)
+    }
+
+    // Moves `amount` between two accounts. Both locks are held from the
+    // check to the update, so no other thread ever sees the money missing
+    // from one account before it arrives in the other.
+    pub fn transfer(&self, from: AccountId, to: AccountId, amount: u64) -> Result<(), BankError> {
+        check_amount(amount)?;
+        if from == to {
+            return Err(BankError::SameAccount(from)); // a second `lock` would deadlock on itself
+        }
+        // Lock in increasing id order, whichever way the money moves.
+        let (mut from_balance, mut to_balance) = if from < to {
+            let first = self.lock(from)?;
+            (first, self.lock(to)?)
+        } else {
+            let first = self.lock(to)?;
+            (self.lock(from)?, first)
+        };
+        // Work out both new balances before changing either, so an error
+        // leaves both accounts as they were.
+        let new_from = take(from, *from_balance, amount)?;
+        let new_to = to_balance
+            .checked_add(amount)
+            .ok_or(BankError::Overflow(to))?;
+        *from_balance = new_from;
+        *to_balance = new_to;
+        Ok(())
+    } // guards dropped here → both locks released
+
+    // The sum of all balances at one instant. All locks are taken, in id
+    // order like a transfer, so no transfer can be half seen.
+    pub fn total(&self) -> u64 {
+        let guards: Vec<MutexGuard<u64>> = self.accounts.iter().map(lock_ignoring_poison).collect();
+        guards.iter().map(|balance| **balance).sum()
+    }
+
+    fn lock(&self, id: AccountId) -> Result<MutexGuard<'_, u64>, BankError> {
+        let account = self.accounts.get(id).ok_or(BankError::NoSuchAccount(id))?;
+        Ok(lock_ignoring_poison(account))
+    }
+}
+
+// A `Mutex` is "poisoned" when a thread panics while holding it. Balances
+// here are only written after every check has passed, in single assignments,
+// so a balance is never left half updated and the poison can be ignored.
+fn lock_ignoring_poison(account: &Mutex<u64>) -> MutexGuard<'_, u64> {
+    account
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn check_amount(amount: u64) -> Result<(), BankError> {
+    if amount == 0 {
+        Err(BankError::ZeroAmount)
+    } else {
+        Ok(())
+    }
+}
+
+// `balance - amount`, or why not.
+fn take(id: AccountId, balance: u64, amount: u64) -> Result<u64, BankError> {
+    balance
+        .checked_sub(amount)
+        .ok_or(BankError::InsufficientFunds {
+            account: id,
+            balance,
+            needed: amount,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn single_account_operations() {
+        let bank = Bank::new(&[2_000, 0]);
+        assert_eq!(bank.withdraw(0, 150), Ok(1_850));
+        // The old bank refused any withdrawal under 5.00; now only the amount matters.
+        assert_eq!(bank.withdraw(0, 1_850), Ok(0));
+        assert_eq!(
+            bank.withdraw(0, 1),
+            Err(BankError::InsufficientFunds {
+                account: 0,
+                balance: 0,
+                needed: 1
+            })
+        );
+        assert_eq!(bank.balance(2), Err(BankError::NoSuchAccount(2)));
+        assert_eq!(bank.withdraw(1, 0), Err(BankError::ZeroAmount));
+    }
+
+    #[test]
+    fn failed_transfers_change_nothing() {
+        let bank = Bank::new(&[500, 100, u64::MAX]);
+        assert_eq!(bank.transfer(1, 0, 100), Ok(()));
+        assert_eq!((bank.balance(0), bank.balance(1)), (Ok(600), Ok(0)));
+        assert!(matches!(
+            bank.transfer(1, 0, 1),
+            Err(BankError::InsufficientFunds { .. })
+        ));
+        assert_eq!(bank.transfer(0, 2, 1), Err(BankError::Overflow(2)));
+        assert_eq!(bank.transfer(0, 0, 1), Err(BankError::SameAccount(0)));
+        assert_eq!(bank.transfer(0, 3, 1), Err(BankError::NoSuchAccount(3)));
+        assert_eq!(bank.transfer(3, 0, 1), Err(BankError::NoSuchAccount(3)));
+        assert_eq!((bank.balance(0), bank.balance(2)), (Ok(600), Ok(u64::MAX)));
+    }
+
+    #[test]
+    fn concurrent_transfers_keep_the_total() {
+        const ACCOUNTS: usize = 8; // few accounts, so threads collide often
+        const THREADS: u64 = 16;
+        const TRANSFERS: usize = 20_000;
+        let opening: Vec<u64> = (0..ACCOUNTS as u64).map(|i| 1_000 * (i + 1)).collect();
+        let expected: u64 = opening.iter().sum();
+        let bank = Arc::new(Bank::new(&opening));
+        let done = Arc::new(AtomicBool::new(false)); // tells the auditor to stop
+
+        // An auditor keeps summing while the transfers run: the total must
+        // never change, not even for an instant.
+        let auditor = {
+            let (bank, done) = (Arc::clone(&bank), Arc::clone(&done));
+            thread::spawn(move || {
+                let mut audits = 0;
+                while !done.load(Ordering::Relaxed) || audits == 0 {
+                    assert_eq!(bank.total(), expected);
+                    audits += 1;
+                }
+                audits
+            })
+        };
+
+        let workers: Vec<_> = (0..THREADS)
+            .map(|seed| {
+                let bank = Arc::clone(&bank);
+                thread::spawn(move || {
+                    // A linear congruential generator per thread, so the
+                    // test needs no `rand` crate. Amounts are often larger
+                    // than the balance, so refusals get exercised too.
+                    let mut state = seed;
+                    let mut next = |limit: u64| {
+                        state = state
+                            .wrapping_mul(6_364_136_223_846_793_005)
+                            .wrapping_add(1);
+                        (state >> 33) % limit
+                    };
+                    let mut moved = 0;
+                    for _ in 0..TRANSFERS {
+                        let from = next(ACCOUNTS as u64) as usize;
+                        let to = next(ACCOUNTS as u64) as usize;
+                        match bank.transfer(from, to, 1 + next(2_000)) {
+                            Ok(()) => moved += 1,
+                            Err(
+                                BankError::InsufficientFunds { .. } | BankError::SameAccount(_),
+                            ) => {}
+                            Err(error) => panic!("unexpected error: {}", error),
+                        }
+                    }
+                    moved
+                })
+            })
+            .collect();
+
+        let moved: usize = workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .sum();
+        done.store(true, Ordering::Relaxed);
+        let audits = auditor.join().unwrap();
+        assert!(moved > 0 && audits > 0);
+        assert_eq!(bank.total(), expected);
+        let balances: u64 = (0..bank.len()).map(|id| bank.balance(id).unwrap()).sum();
+        assert_eq!(balances, expected);
+    }
+}
@@ -1,9 +1,9 @@
 use std::thread; // bring module `thread` into scope
 use std::time::Duration; // bring `Duration` into scope
-use std::sync::{
-    Arc,
-    Mutex
-}; // bring two concurrent types into scope
+use std::sync::Arc; // bring the shared-ownership pointer into scope; each account has its own `Mutex`
+
+mod bank; // `bank.rs`: accounts, transfers and lock ordering
+use bank::{cents, Bank}; // bring the bank and its money formatter into scope
 
 // ---------- 1.  Background thread that may be killed early ----------
 fn check_thread_incomplete() { // `fn` declares a function
@@ -39,32 +39,13 @@ fn check_thread_complete() {
 
 // ---------- 3.  Shared state with atomic reference counting & mutex ----------
 fn check_bank_feature() {
-    struct Bank { // `struct` defines a new named type
-        balance: f32, // field `balance` of type `f32`
-    }
-
-    fn withdraw(bank: &Arc<Mutex<Bank>> , amt: f32) { // `&` = borrow, `Arc` = shared ownership, `Mutex` = mutual exclusion
-        let mut account = bank.lock().unwrap(); // `lock()` blocks until acquired; `unwrap` panics if poisoned
-        if account.balance < 5.00 { // compare field
-            println!("Current balance {} is too low", account.balance);
-        } else {
-            account.balance -= amt; // mutate inside mutex
-            println!("Withdrew {}; new balance {}", amt, account.balance);
-        }
-    } // `account` guard dropped here → lock released
-
-    fn customer(bank: &Arc<Mutex<Bank>> ) { // wrapper chosen for `spawn` closure
-        withdraw(bank, 5.0); // call `withdraw` with 5.0
-    }
-
-    let bank = Arc::new(Mutex::new(Bank {
-        balance: 20.0
-    })); // `Arc` allows shared ownership across threads
+    let bank = Arc::new(Bank::new(&[2_000, 500, 0, 10_000])); // balances in cents; `Arc` shares the bank
+    let before = bank.total(); // sum of every balance
 
     let handles: Vec<_> = (0..10) // range `0..10` produces 0-9
-        .map( | _ | { // `map` turns each index into a handle
+        .map( | i | { // `map` turns each index into a handle
             let bank_clone = Arc::clone( & bank); // `clone` increments ref-count (cheap)
-            thread::spawn(move || customer( & bank_clone)) // `move` closure takes ownership of `bank_clone`
+            thread::spawn(move || customer( & bank_clone, i)) // `move` closure takes ownership of `bank_clone`
         })
         .collect(); // collect iterators into `Vec<JoinHandle<_>>`
 
@@ -72,7 +53,29 @@ fn check_bank_feature() {
         h.join().unwrap(); // wait for each thread to finish
     }
 
-    println!("Final balance: {}", bank.lock().unwrap().balance); // lock again to read final value
+    for id in 0..bank.len() { // `balance` returns `Result`: the id might not exist
+        println!("Account {} final balance: {}", id, cents(bank.balance(id).unwrap()));
+    }
+    println!("Total before {} and after {}", cents(before), cents(bank.total())); // transfers move money, never make it
+
+    // `withdraw` refuses only when the balance is short of the amount asked for.
+    let low = (0..bank.len()).min_by_key(|&id| bank.balance(id).unwrap()).unwrap(); // account with least money
+    for amt in [1, 1_000_000] { // a cent, then ten thousand dollars
+        match bank.withdraw(low, amt) {
+            Ok(left) => println!("Withdrew {} from account {}; new balance {}", cents(amt), low, cents(left)),
+            Err(error) => println!("Withdrawal refused: {}", error),
+        }
+    }
+}
+
+// Each customer moves money between two accounts; the bank refuses transfers it cannot cover.
+fn customer(bank: &Arc<Bank>, i: usize) { // `&Arc<Bank>` = borrow the shared pointer
+    let (from, to) = (i % bank.len(), (i * 3 + 1) % bank.len()); // accounts chosen from the customer number
+    let amount = 250 * (i as u64 + 1); // 2.50, 5.00, 7.50, ...
+    match bank.transfer(from, to, amount) { // locks both accounts, lower id first
+        Ok(()) => println!("Customer {} moved {} from account {} to {}", i, cents(amount), from, to),
+        Err(error) => println!("Customer {} refused: {}", i, error), // `Display` from `BankError`
+    }
 }
 
 fn main() { // program entry point
@@ -0,0 +1,160 @@
+// Console: Where a game reads the player's input and writes what happens.
+//
+// The games only talk to a `Console`, which wraps any `BufRead` and `Write`, so
+// the same code runs on a terminal, on a script file or on in-memory buffers.
+// An interactive console writes prompts and messages for a person. A scripted
+// console reads the player's lines from the script without prompting and
+// writes a transcript instead: one JSON object per line, one line per event.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+/// A value in a transcript event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field<'a> {
+    Text(&'a str),
+    Number(i64),
+    Flag(bool),
+}
+
+impl<'a> From<&'a str> for Field<'a> {
+    fn from(text: &'a str) -> Field<'a> {
+        Field::Text(text)
+    }
+}
+
+impl<'a> From<&'a String> for Field<'a> {
+    fn from(text: &'a String) -> Field<'a> {
+        Field::Text(text)
+    }
+}
+
+impl From<i64> for Field<'_> {
+    fn from(number: i64) -> Self {
+        Field::Number(number)
+    }
+}
+
+impl From<u32> for Field<'_> {
+    fn from(number: u32) -> Self {
+        Field::Number(i64::from(number))
+    }
+}
+
+impl From<usize> for Field<'_> {
+    fn from(number: usize) -> Self {
+        Field::Number(number as i64)
+    }
+}
+
+impl From<bool> for Field<'_> {
+    fn from(flag: bool) -> Self {
+        Field::Flag(flag)
+    }
+}
+
+/// The player's side of a game: input lines in, messages or transcript out.
+pub struct Console<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+    scripted: bool,
+}
+
+impl<R: BufRead, W: Write> Console<R, W> {
+    /// A console for a person: prompts and messages are written as text.
+    pub fn interactive(input: R, output: W) -> Console<R, W> {
+        Console {
+            input,
+            output,
+            scripted: false,
+        }
+    }
+
+    /// A console replaying a script: only transcript events are written.
+    pub fn scripted(input: R, output: W) -> Console<R, W> {
+        Console {
+            input,
+            output,
+            scripted: true,
+        }
+    }
+
+    /// True if the input is a script rather than a person.
+    pub fn is_scripted(&self) -> bool {
+        self.scripted
+    }
+
+    /// Writes a line for a person to read. Scripted runs leave it out.
+    pub fn say(&mut self, text: impl fmt::Display) -> io::Result<()> {
+        if self.scripted {
+            return Ok(());
+        }
+        writeln!(self.output, "{}", text)
+    }
+
+    /// Shows `prompt` (unless scripted) and reads one line, without its line ending.
+    /// Returns `None` at the end of the input.
+    pub fn prompt(&mut self, prompt: &str) -> io::Result<Option<String>> {
+        if !self.scripted {
+            write!(self.output, "{}", prompt)?;
+            // Flush: `write!` does not end the line, so the prompt has to be pushed out by hand.
+            self.output.flush()?;
+        }
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+    }
+
+    /// Tells the player why their input was not accepted; it does not count as a turn.
+    pub fn reject(&mut self, input: &str, reason: impl fmt::Display) -> io::Result<()> {
+        let reason = reason.to_string();
+        self.say(format_args!("Error: {}", reason))?;
+        self.record(
+            "rejected",
+            &[("input", input.into()), ("reason", (&reason).into())],
+        )
+    }
+
+    /// Adds an event to the transcript. Interactive runs leave it out.
+    ///
+    /// The event is written as `{"event":"<event>","<name>":<value>,...}`.
+    pub fn record(&mut self, event: &str, fields: &[(&str, Field)]) -> io::Result<()> {
+        if !self.scripted {
+            return Ok(());
+        }
+        let mut line = format!("{{\"event\":{}", json_string(event));
+        for (name, value) in fields {
+            let value = match value {
+                Field::Text(text) => json_string(text),
+                Field::Number(number) => number.to_string(),
+                Field::Flag(flag) => flag.to_string(),
+            };
+            line.push_str(&format!(",{}:{}", json_string(name), value));
+        }
+        line.push('}');
+        writeln!(self.output, "{}", line)?;
+        self.output.flush()
+    }
+}
+
+// JSON Strings: Quotes `text`, escaping the characters JSON does not allow inside a string.
+fn json_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for character in text.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            control if control.is_control() => {
+                quoted.push_str(&format!("\\u{:04x}", control as u32))
+            }
+            other => quoted.push(other),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,308 @@
+// Word Game Engine: The rules of one round, kept apart from any terminal input or output.
+//
+// A round has a secret word, a limited number of attempts and a list of the
+// guesses made so far. Every wrong guess unlocks more hints, and a correct
+// guess earns a score that shrinks with each attempt used.
+
+use std::fmt;
+
+/// Attempts allowed per round when no `--attempts` option is given.
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 6;
+
+/// Score for guessing the word on the first attempt.
+pub const BASE_SCORE: u32 = 100;
+
+/// Each further attempt keeps this fraction of the previous attempt's score.
+const SCORE_DECAY: f64 = 0.75;
+
+/// How a single guess compares to the secret word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuessResult {
+    /// The guess is the secret word (ignoring case).
+    Correct,
+    /// Not the word, but only `distance` single-letter edits away from it.
+    Close { distance: usize },
+    /// Not the word and not close to it either.
+    Incorrect,
+}
+
+// Helper Function: Compares the guessed word with the correct word, ignoring case.
+// Guesses within a few typos of the answer are reported as close rather than simply wrong.
+pub fn guess_checker(guess_f: &str, corr_f: &str) -> GuessResult {
+    // String Comparison: Perform a case-insensitive comparison first.
+    if guess_f.eq_ignore_ascii_case(corr_f) {
+        return GuessResult::Correct;
+    }
+    // Fuzzy Matching: Count the edits between the lowercase forms and allow a few for long words.
+    let distance = edit_distance(&guess_f.to_lowercase(), &corr_f.to_lowercase());
+    if distance <= close_threshold(corr_f) {
+        GuessResult::Close { distance }
+    } else {
+        GuessResult::Incorrect
+    }
+}
+
+/// Most edits a guess may be away from `word` and still count as close:
+/// one for words up to seven letters, then one more for every four letters.
+pub fn close_threshold(word: &str) -> usize {
+    (word.chars().count() / 4).max(1)
+}
+
+/// Levenshtein distance: the fewest single-letter insertions, deletions or
+/// substitutions that turn `a` into `b`.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // Dynamic Programming: `row[j]` is the distance between the prefix of `a` read so far and `b[..j]`.
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for j in 1..=b.len() {
+            let substitution = diagonal + usize::from(a_char != b[j - 1]);
+            diagonal = row[j];
+            row[j] = substitution.min(row[j] + 1).min(row[j - 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Score for a word guessed on attempt number `attempts` (counting from 1).
+pub fn score_for_attempts(attempts: u32) -> u32 {
+    let decayed = BASE_SCORE as f64 * SCORE_DECAY.powi(attempts.saturating_sub(1) as i32);
+    (decayed.round() as u32).max(1)
+}
+
+/// Something the player has learned about the secret word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hint {
+    /// How many letters the word has.
+    Length(usize),
+    /// The word's first letter.
+    FirstLetter(char),
+    /// Letters of the last guess that also appear in the word.
+    CommonLetters { guess: String, letters: Vec<char> },
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Hint::Length(length) => write!(f, "The word has {} letters.", length),
+            Hint::FirstLetter(letter) => write!(f, "The word starts with '{}'.", letter),
+            Hint::CommonLetters { guess, letters } if letters.is_empty() => {
+                write!(f, "'{}' has no letters in common with the word.", guess)
+            }
+            Hint::CommonLetters { guess, letters } => {
+                let letters: Vec<String> =
+                    letters.iter().map(|letter| letter.to_string()).collect();
+                write!(
+                    f,
+                    "'{}' shares these letters with the word: {}",
+                    guess,
+                    letters.join(", ")
+                )
+            }
+        }
+    }
+}
+
+/// One round of the word game.
+#[derive(Debug, Clone)]
+pub struct WordGame {
+    secret: String,
+    max_attempts: u32,
+    guesses: Vec<String>,
+    won: bool,
+}
+
+impl WordGame {
+    /// Starts a round with the given secret word and attempt limit (at least one).
+    pub fn new(secret: &str, max_attempts: u32) -> WordGame {
+        WordGame {
+            secret: secret.to_lowercase(),
+            max_attempts: max_attempts.max(1),
+            guesses: Vec::new(),
+            won: false,
+        }
+    }
+
+    /// The secret word, for revealing it once the round is over.
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    pub fn attempts_used(&self) -> u32 {
+        self.guesses.len() as u32
+    }
+
+    pub fn attempts_left(&self) -> u32 {
+        self.max_attempts - self.attempts_used()
+    }
+
+    /// True once the word is guessed or no attempts are left.
+    pub fn is_over(&self) -> bool {
+        self.won || self.attempts_left() == 0
+    }
+
+    /// Records a guess and uses up one attempt.
+    ///
+    /// Returns `None` without counting the guess if the round is already over.
+    pub fn guess(&mut self, guess: &str) -> Option<GuessResult> {
+        if self.is_over() {
+            return None;
+        }
+        let guess = guess.trim().to_lowercase();
+        let result = guess_checker(&guess, &self.secret);
+        self.won = result == GuessResult::Correct;
+        self.guesses.push(guess);
+        Some(result)
+    }
+
+    /// Hints unlocked so far. They build up with each wrong guess:
+    /// the letters shared with the last guess straight away, then the
+    /// word's length after the second miss and its first letter after the third.
+    pub fn hints(&self) -> Vec<Hint> {
+        let mut hints = Vec::new();
+        let misses = self.attempts_used() as usize - usize::from(self.won);
+        if misses >= 2 {
+            hints.push(Hint::Length(self.secret.chars().count()));
+        }
+        if misses >= 3 {
+            if let Some(letter) = self.secret.chars().next() {
+                hints.push(Hint::FirstLetter(letter));
+            }
+        }
+        if let (false, Some(guess)) = (self.won, self.guesses.last()) {
+            hints.push(Hint::CommonLetters {
+                guess: guess.clone(),
+                letters: common_letters(guess, &self.secret),
+            });
+        }
+        hints
+    }
+
+    /// The round's score: `None` until the word is guessed.
+    pub fn score(&self) -> Option<u32> {
+        if self.won {
+            Some(score_for_attempts(self.attempts_used()))
+        } else {
+            None
+        }
+    }
+}
+
+// Helper Function: Lists, in order and without repeats, the letters of `guess` that also appear in `secret`.
+fn common_letters(guess: &str, secret: &str) -> Vec<char> {
+    let mut letters: Vec<char> = Vec::new();
+    for letter in guess.chars() {
+        if secret.contains(letter) && !letters.contains(&letter) {
+            letters.push(letter);
+        }
+    }
+    letters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distance_counts_single_letter_edits() {
+        assert_eq!(edit_distance("apple", "apple"), 0);
+        assert_eq!(edit_distance("aple", "apple"), 1);
+        assert_eq!(edit_distance("applle", "apple"), 1);
+        assert_eq!(edit_distance("appel", "apple"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "fig"), 3);
+        assert_eq!(edit_distance("fig", ""), 3);
+    }
+
+    #[test]
+    fn close_guesses_depend_on_word_length() {
+        assert_eq!(close_threshold("fig"), 1);
+        assert_eq!(close_threshold("banana"), 1);
+        assert_eq!(close_threshold("elderberry"), 2);
+
+        assert_eq!(guess_checker("BaNaNa", "banana"), GuessResult::Correct);
+        assert_eq!(
+            guess_checker("banan", "banana"),
+            GuessResult::Close { distance: 1 }
+        );
+        assert_eq!(
+            guess_checker("bandana", "banana"),
+            GuessResult::Close { distance: 1 }
+        );
+        assert_eq!(guess_checker("bananas!", "banana"), GuessResult::Incorrect);
+        assert_eq!(
+            guess_checker("Eldrberri", "elderberry"),
+            GuessResult::Close { distance: 2 }
+        );
+        assert_eq!(guess_checker("date", "grape"), GuessResult::Incorrect);
+    }
+
+    #[test]
+    fn scores_shrink_with_each_attempt() {
+        assert_eq!(score_for_attempts(1), BASE_SCORE);
+        assert_eq!(score_for_attempts(2), 75);
+        assert_eq!(score_for_attempts(3), 56);
+        assert_eq!(score_for_attempts(100), 1);
+    }
+
+    #[test]
+    fn hints_are_revealed_one_miss_at_a_time() {
+        let mut game = WordGame::new("Cherry", 5);
+        assert!(game.hints().is_empty());
+
+        game.guess("grape");
+        let common = Hint::CommonLetters {
+            guess: String::from("grape"),
+            letters: vec!['r', 'e'],
+        };
+        assert_eq!(game.hints(), vec![common]);
+
+        game.guess("fig");
+        assert_eq!(
+            game.hints(),
+            vec![
+                Hint::Length(6),
+                Hint::CommonLetters {
+                    guess: String::from("fig"),
+                    letters: vec![],
+                },
+            ]
+        );
+        assert_eq!(
+            game.hints()[1].to_string(),
+            "'fig' has no letters in common with the word."
+        );
+
+        game.guess("  PEACH ");
+        let hints = game.hints();
+        assert_eq!(hints[..2], [Hint::Length(6), Hint::FirstLetter('c')]);
+        assert_eq!(
+            hints[2].to_string(),
+            "'peach' shares these letters with the word: e, c, h"
+        );
+
+        // Guessing the word keeps the earned hints but drops the letter comparison
+        assert_eq!(game.guess("cherry"), Some(GuessResult::Correct));
+        assert_eq!(game.hints(), vec![Hint::Length(6), Hint::FirstLetter('c')]);
+        assert_eq!(game.score(), Some(score_for_attempts(4)));
+    }
+
+    #[test]
+    fn a_round_ends_after_the_last_attempt() {
+        let mut game = WordGame::new("fig", 2);
+        assert_eq!(game.guess("date"), Some(GuessResult::Incorrect));
+        assert!(!game.is_over());
+        assert_eq!(game.guess("fog"), Some(GuessResult::Close { distance: 1 }));
+        assert!(game.is_over());
+        assert_eq!(game.attempts_left(), 0);
+        assert_eq!(game.guess("fig"), None);
+        assert_eq!(game.score(), None);
+        assert_eq!(WordGame::new("fig", 0).max_attempts(), 1);
+    }
+}
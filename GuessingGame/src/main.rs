@@ -66,71 +66,233 @@
 
 //////////////////////////////////////////
 
-use rand::prelude::*;
-use std::io; // This brings in Rng, thread_rng, and SliceRandom
+mod console;
+mod engine;
+mod numeric;
+mod play;
+mod scores;
+mod wordle;
+mod words;
+
+use console::Console;
+use numeric::{DEFAULT_MAX, DEFAULT_MIN};
+use play::PlayError;
+use scores::DEFAULT_SCORES_FILE;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process;
+use words::{WordList, DEFAULT_WORDS_DIR};
+
+const USAGE: &str = "\
+Usage: guessing_game [OPTIONS]
+
+Options:
+  --words <dir>       Directory of <category>.txt word lists (default: words)
+  --category <name>   Category to play, instead of choosing one at the start
+  --attempts <n>      Guesses allowed per round (default: 6, unlimited for --numbers)
+  --wordle            Play Wordle: a 5-letter word with coloured letter hints
+  --hard              Wordle hard mode: revealed hints must be used (implies --wordle)
+  --daily             Wordle with today's word, the same for everyone (implies --wordle)
+  --numbers           Guess my number, with higher/lower hints
+  --solver            Think of a number and let me guess it
+  --min <n>           Smallest number for --numbers and --solver (default: 1)
+  --max <n>           Largest number for --numbers and --solver (default: 100)
+  --scores <file>     High-score file (default: guessing_game_scores.txt)
+  --seed <n>          Seed for choosing the secret, to replay the same game
+  --script <file>     Read the player's lines from <file> and print a JSON Lines
+                      transcript instead of the game text (no high scores are saved)
+  --help              Show this message";
+
+// Game Modes: Which game the command line asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Words,
+    Wordle,
+    Numbers,
+    Solver,
+}
+
+// Settings: Everything that can be changed from the command line.
+struct Options {
+    mode: Mode,
+    words_dir: PathBuf,
+    category: Option<String>,
+    max_attempts: Option<u32>,
+    scores_file: PathBuf,
+    hard: bool,
+    daily: bool,
+    min: i64,
+    max: i64,
+    seed: Option<u64>,
+    script: Option<PathBuf>,
+}
 
 fn main() {
-    // Game Setup: Define the list of fruits available for guessing.
-    // Using a mutable array, though for a fixed list, `Vec<&str>` is often more flexible.
-    let guess_fruitlist = [
-        "apple",
-        "banana",
-        "cherry",
-        "date",
-        "elderberry",
-        "fig",
-        "grape",
-    ];
-    // Random Fruit Selection:
-    // 1. Initialize the random number generator.
-    let mut rng = thread_rng();
-    // 2. Choose a random fruit from the list.
-    //    `choose()` is preferred as it directly selects an item.
-    let random_fruit = guess_fruitlist
-        .choose(&mut rng)
-        .expect("Failed to choose a fruit. The fruit list might be empty.");
-    // User Prompt: Inform the user that a fruit has been chosen.
-    println!("I've chosen a fruit. Can you guess it? {}", random_fruit);
-    // Game Loop: Continue prompting the user until they guess correctly or an error occurs.
-    loop {
-        // Input Buffer: Create a new String to store each guess, preventing accumulation of previous inputs.
-        let mut input = String::new();
-        // Prompt for Guess: Display the prompt to the user.
-        print!("Your guess: ");
-        // Read Input: Attempt to read a line from standard input.
-        match io::stdin().read_line(&mut input) {
-            Ok(_) => {
-                // Input Cleaning: Trim whitespace and convert the input to lowercase for case-insensitive comparison.
-                let selected_fruit = input.trim().to_lowercase();
-                // List Check (Optional but good for feedback): See if the guessed fruit is even in our known list.
-                // Note: `as_str()` is needed to convert `String` to `&str` for comparison with array elements.
-                if !guess_fruitlist.contains(&selected_fruit.as_str()) {
-                    println!("'{}' is not in our list of fruits.", selected_fruit);
-                    // Decided to let them try again rather than counting it as a loss immediately.
-                }
-                // Guess Evaluation: Check if the user's guess matches the randomly chosen fruit.
-                // The `guess_checker` function handles case-insensitivity.
-                if guess_checker(&selected_fruit, random_fruit) {
-                    println!("Congratulations! You guessed correctly: {}", random_fruit);
-                    break; // Exit the loop if the guess is correct.
-                } else {
-                    println!(
-                        "Sorry, your guess '{}' is incorrect. Try again!",
-                        selected_fruit
-                    );
-                }
-            }
+    // Command Line: Read the options, stopping with the usage text if they make no sense.
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(message) => {
+            eprintln!("Error: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+
+    // Seeding: Without --seed a random seed is used, so every game can still be replayed from its transcript.
+    let seed = options.seed.unwrap_or_else(rand::random);
+
+    // Input And Output: A script file replaces the keyboard and the game text becomes a transcript.
+    let result = match &options.script {
+        Some(path) => match File::open(path) {
+            Ok(file) => run(
+                &options,
+                seed,
+                &mut Console::scripted(BufReader::new(file), io::stdout().lock()),
+            ),
             Err(error) => {
-                // Error Handling: Report any critical errors encountered during input reading.
-                eprintln!("Error reading input: {}", error);
-                break; // Exit the loop on an unrecoverable input error.
+                eprintln!(
+                    "Error: Could not open script '{}': {}",
+                    path.display(),
+                    error
+                );
+                process::exit(2);
             }
+        },
+        None => run(
+            &options,
+            seed,
+            &mut Console::interactive(io::stdin().lock(), io::stdout().lock()),
+        ),
+    };
+    // Error Handling: A bad --category is a usage error; anything else is a failed run.
+    if let Err(error) = result {
+        eprintln!("Error: {}", error);
+        let code = match error {
+            PlayError::UnknownCategory { .. } => 2,
+            PlayError::NoWordleWords | PlayError::Io(_) => 1,
+        };
+        process::exit(code);
+    }
+}
+
+// Game Mode: Runs the game the options ask for on the given console.
+fn run<R: BufRead, W: Write>(
+    options: &Options,
+    seed: u64,
+    console: &mut Console<R, W>,
+) -> Result<(), PlayError> {
+    // Number Modes: These need no word lists.
+    match options.mode {
+        Mode::Numbers => return Ok(play::play_numbers(options, seed, console)?),
+        Mode::Solver => return Ok(play::play_solver(options, console)?),
+        Mode::Words | Mode::Wordle => {}
+    }
+
+    // Game Setup: Load the word lists, falling back to the built-in fruits if none are found.
+    let categories = load_word_lists(options);
+    if options.mode == Mode::Wordle {
+        play::play_wordle(options, &categories, seed, console)
+    } else {
+        play::play_word_game(options, &categories, seed, console)
+    }
+}
+
+// Word Lists: Loads the category files, falling back to the built-in fruits if none are found.
+fn load_word_lists(options: &Options) -> Vec<WordList> {
+    match words::load_categories(&options.words_dir) {
+        Ok(categories) if !categories.is_empty() => categories,
+        Ok(_) => {
+            eprintln!(
+                "No word lists found in '{}'. Using the built-in fruits.",
+                options.words_dir.display()
+            );
+            words::builtin_categories()
+        }
+        Err(error) => {
+            eprintln!(
+                "Could not read word lists from '{}' ({}). Using the built-in fruits.",
+                options.words_dir.display(),
+                error
+            );
+            words::builtin_categories()
         }
     }
 }
 
-// Helper Function: Compares the guessed fruit with the correct fruit, ignoring case.
-fn guess_checker(guess_f: &str, corr_f: &str) -> bool {
-    // String Comparison: Perform a case-insensitive comparison.
-    guess_f.eq_ignore_ascii_case(corr_f)
+// Argument Parsing: Turns the command line into `Options`, or `None` if help was asked for.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options {
+        mode: Mode::Words,
+        words_dir: PathBuf::from(DEFAULT_WORDS_DIR),
+        category: None,
+        max_attempts: None,
+        scores_file: PathBuf::from(DEFAULT_SCORES_FILE),
+        hard: false,
+        daily: false,
+        min: DEFAULT_MIN,
+        max: DEFAULT_MAX,
+        seed: None,
+        script: None,
+    };
+    while let Some(arg) = args.next() {
+        // Option Values: Options that need a value take it from the next argument.
+        let mut value = |name: &str| args.next().ok_or(format!("{} needs a value", name));
+        match arg.as_str() {
+            "--words" => options.words_dir = PathBuf::from(value("--words")?),
+            "--category" => options.category = Some(value("--category")?.to_lowercase()),
+            "--attempts" => {
+                let attempts = value("--attempts")?;
+                options.max_attempts = match attempts.parse() {
+                    Ok(attempts) if attempts > 0 => Some(attempts),
+                    _ => return Err(format!("'{}' is not a valid number of attempts", attempts)),
+                };
+            }
+            "--scores" => options.scores_file = PathBuf::from(value("--scores")?),
+            "--wordle" => options.mode = Mode::Wordle,
+            "--hard" => {
+                options.mode = Mode::Wordle;
+                options.hard = true;
+            }
+            "--daily" => {
+                options.mode = Mode::Wordle;
+                options.daily = true;
+            }
+            "--numbers" => options.mode = Mode::Numbers,
+            "--solver" => options.mode = Mode::Solver,
+            "--min" | "--max" => {
+                let number = value(&arg)?;
+                let number = number
+                    .parse()
+                    .map_err(|_| format!("'{}' is not a whole number", number))?;
+                if arg == "--min" {
+                    options.min = number;
+                } else {
+                    options.max = number;
+                }
+            }
+            "--seed" => {
+                let seed = value("--seed")?;
+                let seed = seed
+                    .parse()
+                    .map_err(|_| format!("'{}' is not a valid seed", seed))?;
+                options.seed = Some(seed);
+            }
+            "--script" => options.script = Some(PathBuf::from(value("--script")?)),
+            "--help" | "-h" => return Ok(None),
+            other => return Err(format!("unknown option '{}'", other)),
+        }
+    }
+    // Range Check: The number modes need at least two numbers to choose from.
+    if options.min >= options.max {
+        return Err(format!(
+            "--min ({}) must be smaller than --max ({})",
+            options.min, options.max
+        ));
+    }
+    Ok(Some(options))
 }
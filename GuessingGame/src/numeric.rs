@@ -0,0 +1,371 @@
+// Number Modes: The classic higher/lower game, played either way round.
+//
+// In `NumberGame` the computer picks a number and the player guesses it. In
+// `Solver` the player picks a number and the computer finds it by binary
+// search, noticing when the player's answers cannot all be true.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// Smallest secret number when no `--min` option is given.
+pub const DEFAULT_MIN: i64 = 1;
+
+/// Largest secret number when no `--max` option is given.
+pub const DEFAULT_MAX: i64 = 100;
+
+/// One round of guessing the computer's number.
+#[derive(Debug, Clone)]
+pub struct NumberGame {
+    secret: i64,
+    range: RangeInclusive<i64>,
+    max_attempts: Option<u32>,
+    attempts: u32,
+    solved: bool,
+}
+
+/// Why a guess was not counted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NumberError {
+    /// The guess is outside the range the secret was picked from.
+    OutOfRange { low: i64, high: i64 },
+    /// The round has already been won or lost.
+    GameOver,
+}
+
+impl fmt::Display for NumberError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NumberError::OutOfRange { low, high } => {
+                write!(f, "The number is between {} and {}.", low, high)
+            }
+            NumberError::GameOver => write!(f, "The game is already over."),
+        }
+    }
+}
+
+impl NumberGame {
+    /// Starts a round. `max_attempts` of `None` allows unlimited guesses.
+    pub fn new(secret: i64, range: RangeInclusive<i64>, max_attempts: Option<u32>) -> NumberGame {
+        NumberGame {
+            secret,
+            range,
+            max_attempts,
+            attempts: 0,
+            solved: false,
+        }
+    }
+
+    pub fn secret(&self) -> i64 {
+        self.secret
+    }
+
+    pub fn range(&self) -> &RangeInclusive<i64> {
+        &self.range
+    }
+
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.solved
+    }
+
+    /// True once the number is found or the attempts run out.
+    pub fn is_over(&self) -> bool {
+        self.solved || self.max_attempts.is_some_and(|max| self.attempts >= max)
+    }
+
+    /// Compares a guess to the secret: `Less` means the guess is too small,
+    /// `Greater` too big. Guesses outside the range are not counted.
+    pub fn guess(&mut self, guess: i64) -> Result<Ordering, NumberError> {
+        if self.is_over() {
+            return Err(NumberError::GameOver);
+        }
+        if !self.range.contains(&guess) {
+            return Err(NumberError::OutOfRange {
+                low: *self.range.start(),
+                high: *self.range.end(),
+            });
+        }
+        self.attempts += 1;
+        let ordering = guess.cmp(&self.secret);
+        self.solved = ordering == Ordering::Equal;
+        Ok(ordering)
+    }
+}
+
+/// The player's answers ruled out every number in the range.
+///
+/// `above` and `below` are the guesses behind the bounds that crossed:
+/// the player said their number was higher than `above` and lower than
+/// `below`. `None` stands for the edge of the original range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contradiction {
+    pub above: Option<i64>,
+    pub below: Option<i64>,
+}
+
+impl fmt::Display for Contradiction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.above, self.below) {
+            (Some(above), Some(below)) => write!(
+                f,
+                "you said your number is higher than {} but lower than {}",
+                above, below
+            ),
+            (Some(above), None) => write!(
+                f,
+                "you said your number is higher than {}, the largest allowed",
+                above
+            ),
+            (None, Some(below)) => write!(
+                f,
+                "you said your number is lower than {}, the smallest allowed",
+                below
+            ),
+            (None, None) => write!(f, "the range is empty"),
+        }
+    }
+}
+
+/// Finds the player's number by halving the possible range after every answer.
+#[derive(Debug, Clone)]
+pub struct Solver {
+    low: i64,
+    high: i64,
+    above: Option<i64>,
+    below: Option<i64>,
+    guesses: u32,
+    found: Option<i64>,
+}
+
+impl Solver {
+    /// Starts searching `range`.
+    pub fn new(range: RangeInclusive<i64>) -> Solver {
+        Solver {
+            low: *range.start(),
+            high: *range.end(),
+            above: None,
+            below: None,
+            guesses: 0,
+            found: None,
+        }
+    }
+
+    pub fn guesses(&self) -> u32 {
+        self.guesses
+    }
+
+    /// The player's number, once they have confirmed a guess.
+    pub fn found(&self) -> Option<i64> {
+        self.found
+    }
+
+    /// The middle of the remaining range: the guess that rules out the most numbers.
+    /// `None` once the number is found.
+    pub fn next_guess(&self) -> Option<i64> {
+        if self.found.is_some() || self.low > self.high {
+            return None;
+        }
+        // Wide Arithmetic: `i128` keeps the sum from overflowing at the ends of the `i64` range.
+        Some((self.low as i128 + self.high as i128).div_euclid(2) as i64)
+    }
+
+    /// Most guesses binary search can need for the remaining range.
+    pub fn worst_case_guesses(&self) -> u32 {
+        let size = (self.high as i128 - self.low as i128 + 1).max(1) as u128;
+        // Every guess splits the range in two, so it takes about log2 guesses to reach one number.
+        u128::BITS - size.leading_zeros()
+    }
+
+    /// Records the player's answer to `next_guess`: how their number compares
+    /// to the guess (`Greater` means their number is higher).
+    ///
+    /// Returns the contradiction, and leaves the search unchanged, if the answer
+    /// leaves no possible numbers.
+    pub fn answer(&mut self, answer: Ordering) -> Result<(), Contradiction> {
+        let Some(guess) = self.next_guess() else {
+            return Ok(());
+        };
+        match answer {
+            Ordering::Equal => {
+                self.found = Some(guess);
+            }
+            Ordering::Greater => {
+                // Contradiction Check: "Higher" at the top of the range leaves nothing.
+                if guess >= self.high {
+                    return Err(Contradiction {
+                        above: Some(guess),
+                        below: self.below,
+                    });
+                }
+                self.low = guess + 1;
+                self.above = Some(guess);
+            }
+            Ordering::Less => {
+                if guess <= self.low {
+                    return Err(Contradiction {
+                        above: self.above,
+                        below: Some(guess),
+                    });
+                }
+                self.high = guess - 1;
+                self.below = Some(guess);
+            }
+        }
+        self.guesses += 1;
+        Ok(())
+    }
+}
+
+/// Reads a player's answer to the solver's guess, in words or symbols.
+/// `Greater` means "my number is higher".
+pub fn parse_answer(input: &str) -> Option<Ordering> {
+    match input.trim().to_lowercase().as_str() {
+        "h" | "higher" | "+" | ">" | "up" => Some(Ordering::Greater),
+        "l" | "lower" | "-" | "<" | "down" => Some(Ordering::Less),
+        "c" | "correct" | "=" | "y" | "yes" => Some(Ordering::Equal),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays the solver against an honest player thinking of `secret`.
+    /// Returns how many guesses it took.
+    fn solve(range: RangeInclusive<i64>, secret: i64) -> u32 {
+        let mut solver = Solver::new(range);
+        while solver.found().is_none() {
+            let guess = solver.next_guess().unwrap();
+            solver.answer(secret.cmp(&guess)).unwrap();
+        }
+        assert_eq!(solver.found(), Some(secret));
+        solver.guesses()
+    }
+
+    /// ⌈log2(n + 1)⌉: binary search over n numbers needs at most this many
+    /// guesses, counting the final correct one.
+    fn log2_bound(n: u64) -> u32 {
+        (n + 1).next_power_of_two().trailing_zeros()
+    }
+
+    #[test]
+    fn solver_needs_at_most_log2_guesses() {
+        for range in [1..=100, 1..=64, -5..=5, 7..=7, DEFAULT_MIN..=1_000] {
+            let size = (range.end() - range.start() + 1) as u64;
+            let bound = log2_bound(size);
+            assert_eq!(Solver::new(range.clone()).worst_case_guesses(), bound);
+            let most = range
+                .clone()
+                .map(|secret| solve(range.clone(), secret))
+                .max();
+            assert_eq!(most, Some(bound), "range {:?}", range);
+        }
+        assert_eq!(log2_bound(100), 7);
+    }
+
+    #[test]
+    fn solver_handles_the_whole_i64_range() {
+        let bound = Solver::new(i64::MIN..=i64::MAX).worst_case_guesses();
+        assert_eq!(bound, 65);
+        for secret in [i64::MIN, i64::MIN + 1, -1, 0, i64::MAX - 1, i64::MAX] {
+            assert!(solve(i64::MIN..=i64::MAX, secret) <= bound);
+        }
+    }
+
+    #[test]
+    fn crossing_answers_are_a_contradiction() {
+        let mut solver = Solver::new(1..=100);
+        let answers = [
+            (50, Ordering::Greater),
+            (75, Ordering::Less),
+            (62, Ordering::Less),
+            (56, Ordering::Less),
+            (53, Ordering::Less),
+        ];
+        for (guess, answer) in answers {
+            assert_eq!(solver.next_guess(), Some(guess));
+            solver.answer(answer).unwrap();
+        }
+        assert_eq!(solver.next_guess(), Some(51));
+        let contradiction = solver.answer(Ordering::Less).unwrap_err();
+        assert_eq!(
+            contradiction,
+            Contradiction {
+                above: Some(50),
+                below: Some(51)
+            }
+        );
+        assert_eq!(
+            contradiction.to_string(),
+            "you said your number is higher than 50 but lower than 51"
+        );
+        // The bad answer is not counted, so the player can answer again
+        assert_eq!(solver.guesses(), 5);
+        assert_eq!(solver.next_guess(), Some(51));
+        solver.answer(Ordering::Equal).unwrap();
+        assert_eq!(solver.found(), Some(51));
+        assert_eq!(solver.next_guess(), None);
+    }
+
+    #[test]
+    fn answers_past_the_edges_are_a_contradiction() {
+        let mut solver = Solver::new(1..=3);
+        solver.answer(Ordering::Greater).unwrap();
+        assert_eq!(
+            solver.answer(Ordering::Greater),
+            Err(Contradiction {
+                above: Some(3),
+                below: None
+            })
+        );
+
+        let mut solver = Solver::new(1..=3);
+        solver.answer(Ordering::Less).unwrap();
+        let contradiction = solver.answer(Ordering::Less).unwrap_err();
+        assert_eq!(contradiction.below, Some(1));
+        assert_eq!(
+            contradiction.to_string(),
+            "you said your number is lower than 1, the smallest allowed"
+        );
+    }
+
+    #[test]
+    fn answers_in_words_or_symbols() {
+        for input in ["h", "Higher", " + ", ">", "UP"] {
+            assert_eq!(parse_answer(input), Some(Ordering::Greater), "{}", input);
+        }
+        for input in ["l", "lower", "-", "<", "Down\n"] {
+            assert_eq!(parse_answer(input), Some(Ordering::Less), "{}", input);
+        }
+        for input in ["c", "Correct", "=", "y", "yes"] {
+            assert_eq!(parse_answer(input), Some(Ordering::Equal), "{}", input);
+        }
+        for input in ["", "high", "no", "50"] {
+            assert_eq!(parse_answer(input), None, "{}", input);
+        }
+    }
+
+    #[test]
+    fn number_game_counts_only_valid_guesses() {
+        let mut game = NumberGame::new(42, 1..=100, Some(3));
+        assert_eq!(
+            game.guess(0),
+            Err(NumberError::OutOfRange { low: 1, high: 100 })
+        );
+        assert_eq!(game.guess(50), Ok(Ordering::Greater));
+        assert_eq!(game.guess(25), Ok(Ordering::Less));
+        assert_eq!(game.attempts(), 2);
+        assert_eq!(game.guess(42), Ok(Ordering::Equal));
+        assert!(game.is_solved() && game.is_over());
+        assert_eq!(game.guess(42), Err(NumberError::GameOver));
+    }
+}
@@ -0,0 +1,821 @@
+// Game Loops: One function per mode, talking to the player only through a `Console`.
+//
+// Every loop takes its randomness from a seed, so the same seed and the same
+// input lines always replay the same game. Each step is both said to a person
+// and recorded as a transcript event; the console decides which one is shown.
+
+use crate::console::Console;
+use crate::engine::{self, GuessResult, WordGame, DEFAULT_MAX_ATTEMPTS};
+use crate::numeric::{self, NumberGame, Solver};
+use crate::scores::{self, HighScore, HighScores};
+use crate::wordle::{self, WordleGame, DEFAULT_MAX_GUESSES, WORD_LENGTH};
+use crate::words::WordList;
+use crate::Options;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+/// Why a game could not be played to the end.
+#[derive(Debug)]
+pub enum PlayError {
+    /// `--category` named a category that was not loaded.
+    UnknownCategory {
+        requested: String,
+        available: Vec<String>,
+    },
+    /// No category has a word of the right length for Wordle.
+    NoWordleWords,
+    /// Reading the player's input or writing the game failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for PlayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlayError::UnknownCategory {
+                requested,
+                available,
+            } => write!(
+                f,
+                "There is no '{}' category. Choose one of: {}",
+                requested,
+                available.join(", ")
+            ),
+            PlayError::NoWordleWords => write!(
+                f,
+                "There are no {}-letter words to play Wordle with.",
+                WORD_LENGTH
+            ),
+            PlayError::Io(error) => error.fmt(f),
+        }
+    }
+}
+
+impl From<io::Error> for PlayError {
+    fn from(error: io::Error) -> PlayError {
+        PlayError::Io(error)
+    }
+}
+
+// Word Game: Guess a word from one category, with hints after each miss.
+pub fn play_word_game<R: BufRead, W: Write>(
+    options: &Options,
+    categories: &[WordList],
+    seed: u64,
+    console: &mut Console<R, W>,
+) -> Result<(), PlayError> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    // Unknown Category: A bad --category is an error, running out of input is not.
+    let Some(list) = choose_category(categories, options.category.as_deref(), &mut rng, console)?
+    else {
+        return Ok(console.record("end", &[("outcome", "quit".into())])?);
+    };
+
+    // Random Word Selection: Pick the secret from the chosen category.
+    let secret = list
+        .words
+        .choose(&mut rng)
+        .expect("Failed to choose a word. The word list might be empty.");
+    let mut game = WordGame::new(secret, options.max_attempts.unwrap_or(DEFAULT_MAX_ATTEMPTS));
+    console.record(
+        "start",
+        &[
+            ("mode", "words".into()),
+            ("seed", seed.to_string().as_str().into()),
+            ("category", (&list.category).into()),
+            ("secret", game.secret().into()),
+            ("max_attempts", game.max_attempts().into()),
+        ],
+    )?;
+
+    // User Prompt: Say which category the word is from, but not the word itself.
+    console.say(format_args!(
+        "I've chosen a word from '{}'. You have {} attempts to guess it.",
+        list.category,
+        game.max_attempts()
+    ))?;
+
+    // Game Loop: Keep asking until the word is guessed or the attempts run out.
+    while !game.is_over() {
+        let prompt = format!(
+            "Guess {}/{}: ",
+            game.attempts_used() + 1,
+            game.max_attempts()
+        );
+        let Some(input) = console.prompt(&prompt)? else {
+            console.say(format_args!("\nGoodbye! The word was '{}'.", game.secret()))?;
+            return Ok(record_end(console, "quit", game.attempts_used(), None)?);
+        };
+        // Input Cleaning: Trim whitespace and convert the input to lowercase for case-insensitive comparison.
+        let guess = input.trim().to_lowercase();
+        if guess.is_empty() {
+            console.reject(&input, "Your guess cannot be empty. Please enter a word.")?;
+            continue;
+        }
+        // List Check: Let the player know when a guess cannot be right, but still count it.
+        if !list.contains(&guess) {
+            console.say(format_args!(
+                "'{}' is not in our list of {}.",
+                guess, list.category
+            ))?;
+        }
+
+        let Some(result) = game.guess(&guess) else {
+            break;
+        };
+        let attempt = game.attempts_used();
+        match result {
+            GuessResult::Correct => {
+                console.say(format_args!(
+                    "Congratulations! You guessed correctly: {}",
+                    game.secret()
+                ))?;
+                record_guess(console, attempt, &guess, &[("result", "correct".into())])?;
+            }
+            GuessResult::Close { distance } => {
+                console.say(format_args!(
+                    "So close! '{}' is {} letter{} away from the word.",
+                    guess,
+                    distance,
+                    if distance == 1 { "" } else { "s" }
+                ))?;
+                record_guess(
+                    console,
+                    attempt,
+                    &guess,
+                    &[("result", "close".into()), ("distance", distance.into())],
+                )?;
+            }
+            GuessResult::Incorrect => {
+                console.say(format_args!("Sorry, your guess '{}' is incorrect.", guess))?;
+                record_guess(console, attempt, &guess, &[("result", "incorrect".into())])?;
+            }
+        }
+
+        // Hints: Each miss reveals a little more about the word.
+        if !game.is_over() {
+            for hint in game.hints() {
+                let hint = hint.to_string();
+                console.say(format_args!("  Hint: {}", hint))?;
+                console.record("hint", &[("hint", (&hint).into())])?;
+            }
+        }
+    }
+
+    // Game Outcome: Score a win, or reveal the word after a loss.
+    match game.score() {
+        Some(score) => {
+            console.say(format_args!(
+                "You scored {} points in {} attempt{}.",
+                score,
+                game.attempts_used(),
+                if game.attempts_used() == 1 { "" } else { "s" }
+            ))?;
+            record_end(console, "won", game.attempts_used(), Some(score))?;
+            Ok(record_score(
+                console,
+                &options.scores_file,
+                score,
+                &list.category,
+                game.attempts_used(),
+            )?)
+        }
+        None => {
+            console.say(format_args!(
+                "Out of attempts! The word was '{}'.",
+                game.secret()
+            ))?;
+            Ok(record_end(console, "lost", game.attempts_used(), None)?)
+        }
+    }
+}
+
+// Wordle: Guess a five-letter word, with every letter coloured after each guess.
+pub fn play_wordle<R: BufRead, W: Write>(
+    options: &Options,
+    categories: &[WordList],
+    seed: u64,
+    console: &mut Console<R, W>,
+) -> Result<(), PlayError> {
+    // Candidate Words: Every category is used unless one was asked for.
+    let lists: Vec<&WordList> = match options.category.as_deref() {
+        Some(requested) => vec![find_category(categories, requested)?],
+        None => categories.iter().collect(),
+    };
+    let candidates = wordle::candidate_words(lists.iter().flat_map(|list| &list.words));
+    if candidates.is_empty() {
+        return Err(PlayError::NoWordleWords);
+    }
+
+    // Secret Selection: The daily puzzle depends only on the date, otherwise on the seed.
+    let day = wordle::today();
+    let secret = if options.daily {
+        console.say(format_args!("Daily puzzle #{}", day))?;
+        wordle::daily_word(&candidates, day)
+    } else {
+        candidates.choose(&mut StdRng::seed_from_u64(seed))
+    }
+    .expect("Failed to choose a word. The word list might be empty.");
+    let mut game = WordleGame::new(
+        secret,
+        options.max_attempts.unwrap_or(DEFAULT_MAX_GUESSES),
+        options.hard,
+    );
+    let seed = if options.daily {
+        format!("daily-{}", day)
+    } else {
+        seed.to_string()
+    };
+    console.record(
+        "start",
+        &[
+            ("mode", "wordle".into()),
+            ("seed", (&seed).into()),
+            ("hard", options.hard.into()),
+            ("secret", game.secret().into()),
+            ("max_attempts", game.max_guesses().into()),
+        ],
+    )?;
+
+    console.say(format_args!(
+        "I've chosen a {}-letter word. You have {} guesses{}.",
+        WORD_LENGTH,
+        game.max_guesses(),
+        if options.hard { " (hard mode)" } else { "" }
+    ))?;
+    console.say(format_args!(
+        "{} right place   {} wrong place   {} not in the word",
+        wordle::LetterFeedback::Green.symbol(),
+        wordle::LetterFeedback::Yellow.symbol(),
+        wordle::LetterFeedback::Grey.symbol()
+    ))?;
+
+    // Game Loop: Show the whole board after every accepted guess.
+    while !game.is_over() {
+        let prompt = format!("Guess {}/{}: ", game.guesses_used() + 1, game.max_guesses());
+        let Some(input) = console.prompt(&prompt)? else {
+            console.say(format_args!("\nGoodbye! The word was '{}'.", game.secret()))?;
+            return Ok(record_end(console, "quit", game.guesses_used(), None)?);
+        };
+        match game.guess(&input) {
+            Ok(feedback) => {
+                for (guess, feedback) in game.history() {
+                    console.say(format_args!("  {}", wordle::format_row(guess, feedback)))?;
+                }
+                let code = wordle::feedback_code(&feedback);
+                let guess = input.trim().to_lowercase();
+                record_guess(
+                    console,
+                    game.guesses_used(),
+                    &guess,
+                    &[("feedback", (&code).into())],
+                )?;
+            }
+            // Rejected Guess: Explain why; it does not cost an attempt.
+            Err(error) => console.reject(&input, error)?,
+        }
+    }
+
+    if game.is_won() {
+        let score = engine::score_for_attempts(game.guesses_used());
+        console.say(format_args!(
+            "Solved in {}/{}! You scored {} points.",
+            game.guesses_used(),
+            game.max_guesses(),
+            score
+        ))?;
+        record_end(console, "won", game.guesses_used(), Some(score))?;
+        let category = if options.hard {
+            "wordle-hard"
+        } else {
+            "wordle"
+        };
+        Ok(record_score(
+            console,
+            &options.scores_file,
+            score,
+            category,
+            game.guesses_used(),
+        )?)
+    } else {
+        console.say(format_args!(
+            "Out of guesses! The word was '{}'.",
+            game.secret()
+        ))?;
+        Ok(record_end(console, "lost", game.guesses_used(), None)?)
+    }
+}
+
+// Number Game: Guess the computer's number with higher/lower feedback.
+pub fn play_numbers<R: BufRead, W: Write>(
+    options: &Options,
+    seed: u64,
+    console: &mut Console<R, W>,
+) -> io::Result<()> {
+    let range = options.min..=options.max;
+    let secret = StdRng::seed_from_u64(seed).gen_range(range.clone());
+    let mut game = NumberGame::new(secret, range, options.max_attempts);
+    let (low, high) = (*game.range().start(), *game.range().end());
+    console.record(
+        "start",
+        &[
+            ("mode", "numbers".into()),
+            ("seed", seed.to_string().as_str().into()),
+            ("min", low.into()),
+            ("max", high.into()),
+            ("secret", secret.into()),
+        ],
+    )?;
+    match game.max_attempts() {
+        Some(max) => console.say(format_args!(
+            "I'm thinking of a number between {} and {}. You have {} attempts to guess it.",
+            low, high, max
+        ))?,
+        None => console.say(format_args!(
+            "I'm thinking of a number between {} and {}. Can you guess it?",
+            low, high
+        ))?,
+    }
+
+    // Game Loop: Compare each guess with `Ordering` until the number is found or attempts run out.
+    while !game.is_over() {
+        let prompt = match game.max_attempts() {
+            Some(max) => format!("Guess {}/{}: ", game.attempts() + 1, max),
+            None => format!("Guess #{}: ", game.attempts() + 1),
+        };
+        let Some(input) = console.prompt(&prompt)? else {
+            console.say(format_args!("\nGoodbye! My number was {}.", game.secret()))?;
+            return record_end(console, "quit", game.attempts(), None);
+        };
+        // Input Parsing: Only whole numbers count as guesses.
+        let guess: i64 = match input.trim().parse() {
+            Ok(guess) => guess,
+            Err(_) => {
+                console.reject(
+                    &input,
+                    format_args!("'{}' is not a whole number.", input.trim()),
+                )?;
+                continue;
+            }
+        };
+        let ordering = match game.guess(guess) {
+            Ok(ordering) => ordering,
+            Err(error) => {
+                console.reject(&input, error)?;
+                continue;
+            }
+        };
+        let result = match ordering {
+            Ordering::Less => {
+                console.say("Too small! Try higher.")?;
+                "too_small"
+            }
+            Ordering::Greater => {
+                console.say("Too big! Try lower.")?;
+                "too_big"
+            }
+            Ordering::Equal => {
+                console.say(format_args!(
+                    "Congratulations! {} is my number. You found it in {} attempt{}.",
+                    guess,
+                    game.attempts(),
+                    if game.attempts() == 1 { "" } else { "s" }
+                ))?;
+                "correct"
+            }
+        };
+        console.record(
+            "guess",
+            &[
+                ("attempt", game.attempts().into()),
+                ("guess", guess.into()),
+                ("result", result.into()),
+            ],
+        )?;
+    }
+
+    if game.is_solved() {
+        record_end(console, "won", game.attempts(), None)
+    } else {
+        console.say(format_args!(
+            "Out of attempts! My number was {}.",
+            game.secret()
+        ))?;
+        record_end(console, "lost", game.attempts(), None)
+    }
+}
+
+// Solver: The player thinks of a number and the computer finds it by binary search.
+pub fn play_solver<R: BufRead, W: Write>(
+    options: &Options,
+    console: &mut Console<R, W>,
+) -> io::Result<()> {
+    let mut solver = Solver::new(options.min..=options.max);
+    console.record(
+        "start",
+        &[
+            ("mode", "solver".into()),
+            ("min", options.min.into()),
+            ("max", options.max.into()),
+        ],
+    )?;
+    console.say(format_args!(
+        "Think of a whole number between {} and {}. I'll find it in at most {} guesses.",
+        options.min,
+        options.max,
+        solver.worst_case_guesses()
+    ))?;
+    console.say("Answer each guess with 'higher', 'lower' or 'correct' (or h, l, c).")?;
+
+    // Game Loop: Keep halving the range until the player says a guess is correct.
+    while let Some(guess) = solver.next_guess() {
+        let prompt = format!("Guess #{}: Is it {}? ", solver.guesses() + 1, guess);
+        let Some(input) = console.prompt(&prompt)? else {
+            console.say("\nGoodbye!")?;
+            return record_end(console, "quit", solver.guesses(), None);
+        };
+        let Some(answer) = numeric::parse_answer(&input) else {
+            console.reject(&input, "Please answer 'higher', 'lower' or 'correct'.")?;
+            continue;
+        };
+        let answer_name = match answer {
+            Ordering::Greater => "higher",
+            Ordering::Less => "lower",
+            Ordering::Equal => "correct",
+        };
+        // Contradiction: The answer is not recorded, so the player can correct it.
+        match solver.answer(answer) {
+            Ok(()) => console.record(
+                "guess",
+                &[
+                    ("attempt", solver.guesses().into()),
+                    ("guess", guess.into()),
+                    ("answer", answer_name.into()),
+                ],
+            )?,
+            Err(contradiction) => {
+                let reason = contradiction.to_string();
+                console.say(format_args!(
+                    "That can't be right: {}. Please check your number and answer again.",
+                    reason
+                ))?;
+                console.record(
+                    "contradiction",
+                    &[
+                        ("guess", guess.into()),
+                        ("answer", answer_name.into()),
+                        ("reason", (&reason).into()),
+                    ],
+                )?;
+            }
+        }
+    }
+
+    if let Some(number) = solver.found() {
+        console.say(format_args!(
+            "Got it! Your number is {}. I needed {} guess{}.",
+            number,
+            solver.guesses(),
+            if solver.guesses() == 1 { "" } else { "es" }
+        ))?;
+        console.record(
+            "end",
+            &[
+                ("outcome", "found".into()),
+                ("attempts", solver.guesses().into()),
+                ("number", number.into()),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+// Category Selection: Uses the requested category, or asks the player to pick one.
+// `None` means the input ran out before a category was chosen.
+fn choose_category<'a, R: BufRead, W: Write>(
+    categories: &'a [WordList],
+    requested: Option<&str>,
+    rng: &mut StdRng,
+    console: &mut Console<R, W>,
+) -> Result<Option<&'a WordList>, PlayError> {
+    if let Some(requested) = requested {
+        return find_category(categories, requested).map(Some);
+    }
+    if categories.len() == 1 {
+        return Ok(categories.first());
+    }
+
+    console.say("Categories:")?;
+    for (number, list) in categories.iter().enumerate() {
+        console.say(format_args!("  {}. {}", number + 1, list.category))?;
+    }
+    loop {
+        let prompt = format!(
+            "Choose a category [1-{}], or press Enter for a random one: ",
+            categories.len()
+        );
+        let Some(input) = console.prompt(&prompt)? else {
+            return Ok(None);
+        };
+        let choice = input.trim().to_lowercase();
+        // Random Category: An empty answer leaves the choice to chance.
+        if choice.is_empty() {
+            return Ok(categories.choose(rng));
+        }
+        // Numbered Or Named: Accept either the number in the list or the category's name.
+        let chosen = match choice.parse::<usize>() {
+            Ok(number) if number >= 1 => categories.get(number - 1),
+            Ok(_) => None,
+            Err(_) => categories.iter().find(|list| list.category == choice),
+        };
+        match chosen {
+            Some(list) => return Ok(Some(list)),
+            None => console.reject(
+                &input,
+                format_args!("'{}' is not one of the categories.", choice),
+            )?,
+        }
+    }
+}
+
+// Category Lookup: Finds a category by name, listing the real ones if it does not exist.
+fn find_category<'a>(
+    categories: &'a [WordList],
+    requested: &str,
+) -> Result<&'a WordList, PlayError> {
+    categories
+        .iter()
+        .find(|list| list.category == requested)
+        .ok_or_else(|| PlayError::UnknownCategory {
+            requested: requested.to_string(),
+            available: categories
+                .iter()
+                .map(|list| list.category.clone())
+                .collect(),
+        })
+}
+
+// Transcript Helpers: The events every mode records in the same shape.
+fn record_guess<R: BufRead, W: Write>(
+    console: &mut Console<R, W>,
+    attempt: u32,
+    guess: &str,
+    details: &[(&str, crate::console::Field)],
+) -> io::Result<()> {
+    let mut fields = vec![("attempt", attempt.into()), ("guess", guess.into())];
+    fields.extend_from_slice(details);
+    console.record("guess", &fields)
+}
+
+fn record_end<R: BufRead, W: Write>(
+    console: &mut Console<R, W>,
+    outcome: &str,
+    attempts: u32,
+    score: Option<u32>,
+) -> io::Result<()> {
+    let mut fields = vec![("outcome", outcome.into()), ("attempts", attempts.into())];
+    if let Some(score) = score {
+        fields.push(("score", score.into()));
+    }
+    console.record("end", &fields)
+}
+
+// High Scores: Offers a place on the table for a good score and saves it.
+// Scripted runs never touch the high-score file.
+fn record_score<R: BufRead, W: Write>(
+    console: &mut Console<R, W>,
+    path: &Path,
+    score: u32,
+    category: &str,
+    attempts: u32,
+) -> io::Result<()> {
+    if console.is_scripted() {
+        return Ok(());
+    }
+    let mut table = match HighScores::load(path) {
+        Ok(table) => table,
+        Err(error) => {
+            // A damaged file is left alone rather than overwritten.
+            eprintln!(
+                "Could not read high scores from '{}': {}",
+                path.display(),
+                error
+            );
+            return Ok(());
+        }
+    };
+    if !table.qualifies(score) {
+        return Ok(());
+    }
+
+    let name = console
+        .prompt("New high score! Enter your name: ")?
+        .unwrap_or_default();
+    let name = match scores::clean_name(&name) {
+        name if name.is_empty() => String::from("Anonymous"),
+        name => name,
+    };
+    table.insert(HighScore {
+        name,
+        score,
+        category: category.to_string(),
+        attempts,
+    });
+    if let Err(error) = table.save(path) {
+        eprintln!(
+            "Could not save high scores to '{}': {}",
+            path.display(),
+            error
+        );
+    }
+
+    console.say("\nHigh Scores:")?;
+    for (rank, entry) in table.entries().iter().enumerate() {
+        console.say(format_args!(
+            "  {:>2}. {:<16} {:>3} points  ({}, {} attempt{})",
+            rank + 1,
+            entry.name,
+            entry.score,
+            entry.category,
+            entry.attempts,
+            if entry.attempts == 1 { "" } else { "s" }
+        ))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Mode;
+
+    /// Plays the game the command line asks for with `script` as the player's
+    /// lines, the way `--script` does, and returns the transcript.
+    fn replay(args: &[&str], categories: &[WordList], script: &str) -> Result<String, PlayError> {
+        let options = crate::parse_args(args.iter().map(|arg| arg.to_string()))
+            .unwrap()
+            .unwrap();
+        let seed = options.seed.unwrap();
+        let mut output = Vec::new();
+        let mut console = Console::scripted(script.as_bytes(), &mut output);
+        match options.mode {
+            Mode::Words => play_word_game(&options, categories, seed, &mut console)?,
+            Mode::Wordle => play_wordle(&options, categories, seed, &mut console)?,
+            Mode::Numbers => play_numbers(&options, seed, &mut console)?,
+            Mode::Solver => play_solver(&options, &mut console)?,
+        }
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    fn veg() -> Vec<WordList> {
+        vec![WordList::parse("veg", "carrot\nleek\nonion\n")]
+    }
+
+    #[test]
+    fn number_game_transcript() {
+        let transcript = replay(&["--numbers", "--seed", "7"], &[], "0\nabc\n50\n4\n").unwrap();
+        assert_eq!(
+            transcript,
+            concat!(
+                r#"{"event":"start","mode":"numbers","seed":"7","min":1,"max":100,"secret":4}"#,
+                "\n",
+                r#"{"event":"rejected","input":"0","reason":"The number is between 1 and 100."}"#,
+                "\n",
+                r#"{"event":"rejected","input":"abc","reason":"'abc' is not a whole number."}"#,
+                "\n",
+                r#"{"event":"guess","attempt":1,"guess":50,"result":"too_big"}"#,
+                "\n",
+                r#"{"event":"guess","attempt":2,"guess":4,"result":"correct"}"#,
+                "\n",
+                r#"{"event":"end","outcome":"won","attempts":2}"#,
+                "\n",
+            )
+        );
+    }
+
+    #[test]
+    fn solver_transcript_records_contradictions() {
+        let script = "x\nh\nl\nl\nl\nl\nl\nc\n";
+        let transcript = replay(&["--solver", "--seed", "1"], &[], script).unwrap();
+        assert_eq!(
+            transcript,
+            concat!(
+                r#"{"event":"start","mode":"solver","min":1,"max":100}"#,
+                "\n",
+                r#"{"event":"rejected","input":"x","reason":"Please answer 'higher', 'lower' or 'correct'."}"#,
+                "\n",
+                r#"{"event":"guess","attempt":1,"guess":50,"answer":"higher"}"#,
+                "\n",
+                r#"{"event":"guess","attempt":2,"guess":75,"answer":"lower"}"#,
+                "\n",
+                r#"{"event":"guess","attempt":3,"guess":62,"answer":"lower"}"#,
+                "\n",
+                r#"{"event":"guess","attempt":4,"guess":56,"answer":"lower"}"#,
+                "\n",
+                r#"{"event":"guess","attempt":5,"guess":53,"answer":"lower"}"#,
+                "\n",
+                r#"{"event":"contradiction","guess":51,"answer":"lower","reason":"you said your number is higher than 50 but lower than 51"}"#,
+                "\n",
+                r#"{"event":"guess","attempt":6,"guess":51,"answer":"correct"}"#,
+                "\n",
+                r#"{"event":"end","outcome":"found","attempts":6,"number":51}"#,
+                "\n",
+            )
+        );
+    }
+
+    #[test]
+    fn word_game_transcript() {
+        let transcript = replay(
+            &["--seed", "3", "--attempts", "3"],
+            &veg(),
+            "kale\n\nLEEK\nonion\n",
+        )
+        .unwrap();
+        assert_eq!(
+            transcript,
+            concat!(
+                r#"{"event":"start","mode":"words","seed":"3","category":"veg","secret":"carrot","max_attempts":3}"#,
+                "\n",
+                r#"{"event":"guess","attempt":1,"guess":"kale","result":"incorrect"}"#,
+                "\n",
+                r#"{"event":"hint","hint":"'kale' shares these letters with the word: a"}"#,
+                "\n",
+                r#"{"event":"rejected","input":"","reason":"Your guess cannot be empty. Please enter a word."}"#,
+                "\n",
+                r#"{"event":"guess","attempt":2,"guess":"leek","result":"incorrect"}"#,
+                "\n",
+                r#"{"event":"hint","hint":"The word has 6 letters."}"#,
+                "\n",
+                r#"{"event":"hint","hint":"'leek' has no letters in common with the word."}"#,
+                "\n",
+                r#"{"event":"guess","attempt":3,"guess":"onion","result":"incorrect"}"#,
+                "\n",
+                r#"{"event":"end","outcome":"lost","attempts":3}"#,
+                "\n",
+            )
+        );
+    }
+
+    #[test]
+    fn wordle_transcript() {
+        let transcript =
+            replay(&["--wordle", "--seed", "3"], &veg(), "kale\nlemon\nonion\n").unwrap();
+        assert_eq!(
+            transcript,
+            concat!(
+                r#"{"event":"start","mode":"wordle","seed":"3","hard":false,"secret":"onion","max_attempts":6}"#,
+                "\n",
+                r#"{"event":"rejected","input":"kale","reason":"Guesses must have 5 letters, not 4."}"#,
+                "\n",
+                r#"{"event":"guess","attempt":1,"guess":"lemon","feedback":"---GG"}"#,
+                "\n",
+                r#"{"event":"guess","attempt":2,"guess":"onion","feedback":"GGGGG"}"#,
+                "\n",
+                r#"{"event":"end","outcome":"won","attempts":2,"score":75}"#,
+                "\n",
+            )
+        );
+    }
+
+    #[test]
+    fn same_seed_and_script_replay_the_same_transcript() {
+        let args = ["--seed", "42"];
+        let script = "1\n\ncarrot\nleek\nonion\n";
+        let first = replay(&args, &veg(), script).unwrap();
+        assert_eq!(replay(&args, &veg(), script).unwrap(), first);
+    }
+
+    #[test]
+    fn unknown_category_and_missing_wordle_words_are_errors() {
+        let error = replay(&["--category", "fruit", "--seed", "1"], &veg(), "").unwrap_err();
+        assert!(
+            matches!(&error, PlayError::UnknownCategory { requested, .. } if requested == "fruit")
+        );
+        assert_eq!(
+            error.to_string(),
+            "There is no 'fruit' category. Choose one of: veg"
+        );
+        let error = replay(
+            &["--wordle", "--category", "fruit", "--seed", "1"],
+            &veg(),
+            "",
+        )
+        .unwrap_err();
+        assert!(matches!(error, PlayError::UnknownCategory { .. }));
+
+        let short = [WordList::parse("short", "fig\nkiwi\n")];
+        let error = replay(&["--wordle", "--seed", "1"], &short, "").unwrap_err();
+        assert!(matches!(error, PlayError::NoWordleWords));
+    }
+
+    #[test]
+    fn running_out_of_input_quits() {
+        let categories = [veg()[0].clone(), WordList::parse("fruits", "apple\n")];
+        let transcript = replay(&["--seed", "1"], &categories, "").unwrap();
+        assert_eq!(transcript, "{\"event\":\"end\",\"outcome\":\"quit\"}\n");
+    }
+}
@@ -0,0 +1,198 @@
+// High Scores: The best rounds so far, kept in a small text file between games.
+//
+// Each line of the file holds one entry as tab-separated fields:
+// `name<TAB>score<TAB>category<TAB>attempts`. Lines starting with '#' are comments.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// High-score file used when no `--scores` option is given.
+pub const DEFAULT_SCORES_FILE: &str = "guessing_game_scores.txt";
+
+/// How many entries the table keeps.
+pub const MAX_HIGH_SCORES: usize = 10;
+
+/// One finished round on the high-score table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighScore {
+    pub name: String,
+    pub score: u32,
+    pub category: String,
+    pub attempts: u32,
+}
+
+/// The best entries, highest score first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HighScores {
+    entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    pub fn entries(&self) -> &[HighScore] {
+        &self.entries
+    }
+
+    /// True if `score` would earn a place on the table.
+    pub fn qualifies(&self, score: u32) -> bool {
+        self.entries.len() < MAX_HIGH_SCORES || self.entries.iter().any(|entry| score > entry.score)
+    }
+
+    /// Adds an entry and returns its 1-based rank, or `None` if it did not make the table.
+    ///
+    /// Ties go to the older entry, so a new score has to beat an existing one to pass it.
+    pub fn insert(&mut self, entry: HighScore) -> Option<usize> {
+        let position = self
+            .entries
+            .iter()
+            .position(|existing| entry.score > existing.score)
+            .unwrap_or(self.entries.len());
+        if position >= MAX_HIGH_SCORES {
+            return None;
+        }
+        self.entries.insert(position, entry);
+        self.entries.truncate(MAX_HIGH_SCORES);
+        Some(position + 1)
+    }
+
+    /// Parses the contents of a high-score file.
+    pub fn from_text(text: &str) -> io::Result<HighScores> {
+        let mut scores = HighScores::default();
+        for (number, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = parse_entry(line).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "line {}: expected name, score, category and attempts",
+                        number + 1
+                    ),
+                )
+            })?;
+            scores.insert(entry);
+        }
+        Ok(scores)
+    }
+
+    /// Formats the table in the file format read by `from_text`.
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("# name\tscore\tcategory\tattempts\n");
+        for entry in &self.entries {
+            text.push_str(&format!(
+                "{}\t{}\t{}\t{}\n",
+                entry.name, entry.score, entry.category, entry.attempts
+            ));
+        }
+        text
+    }
+
+    /// Reads the table from `path`. A missing file is an empty table.
+    pub fn load(path: &Path) -> io::Result<HighScores> {
+        match fs::read_to_string(path) {
+            Ok(text) => HighScores::from_text(&text),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(HighScores::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Writes the table to `path`, replacing its old contents.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+}
+
+// Helper Function: Splits one tab-separated line into a high-score entry.
+fn parse_entry(line: &str) -> Option<HighScore> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    match fields[..] {
+        [name, score, category, attempts] if !name.is_empty() => Some(HighScore {
+            name: name.to_string(),
+            score: score.trim().parse().ok()?,
+            category: category.to_string(),
+            attempts: attempts.trim().parse().ok()?,
+        }),
+        _ => None,
+    }
+}
+
+/// Removes tabs and line breaks so a player's name fits on one line of the file.
+pub fn clean_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, score: u32) -> HighScore {
+        HighScore {
+            name: name.to_string(),
+            score,
+            category: String::from("fruits"),
+            attempts: 1,
+        }
+    }
+
+    #[test]
+    fn entries_are_kept_highest_first() {
+        let mut scores = HighScores::default();
+        assert_eq!(scores.insert(entry("ann", 56)), Some(1));
+        assert_eq!(scores.insert(entry("bob", 100)), Some(1));
+        assert_eq!(scores.insert(entry("cat", 75)), Some(2));
+        // A tie goes behind the older entry
+        assert_eq!(scores.insert(entry("dan", 75)), Some(3));
+        let names: Vec<&str> = scores.entries().iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["bob", "cat", "dan", "ann"]);
+    }
+
+    #[test]
+    fn the_table_is_capped() {
+        let mut scores = HighScores::default();
+        for score in 1..=MAX_HIGH_SCORES as u32 {
+            assert!(scores.qualifies(score));
+            scores.insert(entry("ann", score * 10));
+        }
+        assert!(!scores.qualifies(10));
+        assert_eq!(scores.insert(entry("bob", 10)), None);
+        assert!(scores.qualifies(11));
+        assert_eq!(scores.insert(entry("bob", 11)), Some(MAX_HIGH_SCORES));
+        assert_eq!(scores.entries().len(), MAX_HIGH_SCORES);
+        assert_eq!(scores.entries().last().unwrap().score, 11);
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let mut scores = HighScores::default();
+        scores.insert(entry("Ann Lee", 75));
+        scores.insert(HighScore {
+            category: String::from("veg"),
+            attempts: 3,
+            ..entry("bob", 56)
+        });
+        let text = scores.to_text();
+        assert!(text.ends_with("Ann Lee\t75\tfruits\t1\nbob\t56\tveg\t3\n"));
+        assert_eq!(HighScores::from_text(&text).unwrap(), scores);
+
+        let error = HighScores::from_text("ann\t75\tfruits\n").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(HighScores::from_text("\tbob\t1\tfruits\t1\n").is_err());
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("{}-high-scores.txt", std::process::id()));
+        let mut scores = HighScores::default();
+        scores.insert(entry(&clean_name(" Ann\tLee\n"), 100));
+        let missing = HighScores::load(&path).unwrap();
+        scores.save(&path).unwrap();
+        let loaded = HighScores::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(missing, HighScores::default());
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded, scores);
+        assert_eq!(loaded.entries()[0].name, "Ann Lee");
+    }
+}
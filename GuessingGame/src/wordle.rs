@@ -0,0 +1,402 @@
+// Wordle Mode: Guess a fixed-length word, with per-letter colours after every guess.
+//
+// Green means the letter is in the right place, yellow that it is in the word
+// somewhere else, grey that it is not in the word (or not as many times as it
+// was guessed). The scoring functions here are pure, so they can be checked
+// without a terminal; the game loop is `play_wordle` in `play.rs`.
+
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Length of every Wordle secret and guess.
+pub const WORD_LENGTH: usize = 5;
+
+/// Guesses allowed in Wordle mode when no `--attempts` option is given.
+pub const DEFAULT_MAX_GUESSES: u32 = 6;
+
+/// The colour given to one letter of a guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LetterFeedback {
+    /// Right letter, right place.
+    Green,
+    /// Right letter, wrong place.
+    Yellow,
+    /// Not in the word, or already accounted for by other greens and yellows.
+    Grey,
+}
+
+impl LetterFeedback {
+    /// The square shown for this colour.
+    pub fn symbol(self) -> char {
+        match self {
+            LetterFeedback::Green => '🟩',
+            LetterFeedback::Yellow => '🟨',
+            LetterFeedback::Grey => '⬜',
+        }
+    }
+}
+
+// Scoring: Colours each letter of `guess` against `secret`, ignoring case.
+//
+// Repeated Letters: Greens are handed out first. Each remaining letter of the secret can then
+// turn at most one guessed letter yellow, left to right. Guessing "hello" against "world"
+// colours the second 'l' green, so the first one is grey: "world" has only one 'l'.
+pub fn score_guess(guess: &str, secret: &str) -> Vec<LetterFeedback> {
+    let guess: Vec<char> = guess.to_lowercase().chars().collect();
+    let secret: Vec<char> = secret.to_lowercase().chars().collect();
+    let mut feedback = vec![LetterFeedback::Grey; guess.len()];
+
+    // First Pass: Mark greens and collect the secret letters they did not use.
+    let mut unused: Vec<char> = Vec::new();
+    for (i, &letter) in secret.iter().enumerate() {
+        if guess.get(i) == Some(&letter) {
+            feedback[i] = LetterFeedback::Green;
+        } else {
+            unused.push(letter);
+        }
+    }
+    // Second Pass: Each unused secret letter can make one other guessed letter yellow.
+    for (i, letter) in guess.iter().enumerate() {
+        if feedback[i] == LetterFeedback::Green {
+            continue;
+        }
+        if let Some(found) = unused
+            .iter()
+            .position(|unused_letter| unused_letter == letter)
+        {
+            feedback[i] = LetterFeedback::Yellow;
+            unused.swap_remove(found);
+        }
+    }
+    feedback
+}
+
+/// True if every letter is green.
+pub fn is_solved(feedback: &[LetterFeedback]) -> bool {
+    feedback
+        .iter()
+        .all(|&letter| letter == LetterFeedback::Green)
+}
+
+/// Formats a scored guess as a row of squares followed by the guessed letters.
+pub fn format_row(guess: &str, feedback: &[LetterFeedback]) -> String {
+    let squares: String = feedback.iter().map(|letter| letter.symbol()).collect();
+    let letters: Vec<String> = guess
+        .chars()
+        .map(|letter| letter.to_uppercase().to_string())
+        .collect();
+    format!("{}  {}", squares, letters.join(" "))
+}
+
+/// Formats feedback as one letter per square for transcripts: `G` green, `Y` yellow, `-` grey.
+pub fn feedback_code(feedback: &[LetterFeedback]) -> String {
+    feedback
+        .iter()
+        .map(|letter| match letter {
+            LetterFeedback::Green => 'G',
+            LetterFeedback::Yellow => 'Y',
+            LetterFeedback::Grey => '-',
+        })
+        .collect()
+}
+
+/// Why a guess was not accepted. Rejected guesses do not use up an attempt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WordleError {
+    /// The guess has the wrong number of letters.
+    WrongLength { expected: usize, found: usize },
+    /// The guess contains something other than the letters a to z.
+    NotLetters,
+    /// Hard mode: a green letter was moved or dropped (`position` counts from 1).
+    MustKeep { letter: char, position: usize },
+    /// Hard mode: a revealed letter is missing, or appears fewer times than revealed.
+    MustContain { letter: char, count: usize },
+    /// The game has already been won or lost.
+    GameOver,
+}
+
+impl fmt::Display for WordleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WordleError::WrongLength { expected, found } => {
+                write!(f, "Guesses must have {} letters, not {}.", expected, found)
+            }
+            WordleError::NotLetters => write!(f, "Guesses may only contain the letters a to z."),
+            WordleError::MustKeep { letter, position } => {
+                write!(
+                    f,
+                    "Hard mode: letter {} must be '{}'.",
+                    position,
+                    letter.to_ascii_uppercase()
+                )
+            }
+            WordleError::MustContain { letter, count: 1 } => {
+                write!(
+                    f,
+                    "Hard mode: the guess must contain '{}'.",
+                    letter.to_ascii_uppercase()
+                )
+            }
+            WordleError::MustContain { letter, count } => {
+                write!(
+                    f,
+                    "Hard mode: the guess must contain '{}' {} times.",
+                    letter.to_ascii_uppercase(),
+                    count
+                )
+            }
+            WordleError::GameOver => write!(f, "The game is already over."),
+        }
+    }
+}
+
+// Hard Mode Check: Every hint revealed by an earlier guess has to be used again.
+// Greens must stay where they are, and each letter must appear at least as often
+// as it was shown green or yellow in any one earlier guess.
+pub fn check_hard_mode(
+    guess: &str,
+    previous: &[(String, Vec<LetterFeedback>)],
+) -> Result<(), WordleError> {
+    let guess: Vec<char> = guess.to_lowercase().chars().collect();
+    for (earlier, feedback) in previous {
+        let earlier: Vec<char> = earlier.chars().collect();
+        for (i, (&letter, &colour)) in earlier.iter().zip(feedback).enumerate() {
+            if colour == LetterFeedback::Green && guess.get(i) != Some(&letter) {
+                return Err(WordleError::MustKeep {
+                    letter,
+                    position: i + 1,
+                });
+            }
+        }
+        for (&letter, &colour) in earlier.iter().zip(feedback) {
+            if colour == LetterFeedback::Grey {
+                continue;
+            }
+            let revealed = earlier
+                .iter()
+                .zip(feedback)
+                .filter(|&(&other, &shown)| other == letter && shown != LetterFeedback::Grey)
+                .count();
+            if guess.iter().filter(|&&other| other == letter).count() < revealed {
+                return Err(WordleError::MustContain {
+                    letter,
+                    count: revealed,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// One game of Wordle.
+#[derive(Debug, Clone)]
+pub struct WordleGame {
+    secret: String,
+    max_guesses: u32,
+    hard_mode: bool,
+    history: Vec<(String, Vec<LetterFeedback>)>,
+}
+
+impl WordleGame {
+    /// Starts a game. The secret should be `WORD_LENGTH` lowercase letters.
+    pub fn new(secret: &str, max_guesses: u32, hard_mode: bool) -> WordleGame {
+        WordleGame {
+            secret: secret.to_lowercase(),
+            max_guesses: max_guesses.max(1),
+            hard_mode,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+
+    pub fn max_guesses(&self) -> u32 {
+        self.max_guesses
+    }
+
+    pub fn guesses_used(&self) -> u32 {
+        self.history.len() as u32
+    }
+
+    /// Every accepted guess with its colours, oldest first.
+    pub fn history(&self) -> &[(String, Vec<LetterFeedback>)] {
+        &self.history
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.history
+            .last()
+            .is_some_and(|(_, feedback)| is_solved(feedback))
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.guesses_used() >= self.max_guesses
+    }
+
+    /// Checks and scores a guess, recording it if it is accepted.
+    pub fn guess(&mut self, guess: &str) -> Result<Vec<LetterFeedback>, WordleError> {
+        if self.is_over() {
+            return Err(WordleError::GameOver);
+        }
+        let guess = guess.trim().to_lowercase();
+        let length = guess.chars().count();
+        if length != self.secret.chars().count() {
+            return Err(WordleError::WrongLength {
+                expected: self.secret.chars().count(),
+                found: length,
+            });
+        }
+        if !guess.chars().all(|letter| letter.is_ascii_lowercase()) {
+            return Err(WordleError::NotLetters);
+        }
+        if self.hard_mode {
+            check_hard_mode(&guess, &self.history)?;
+        }
+        let feedback = score_guess(&guess, &self.secret);
+        self.history.push((guess, feedback.clone()));
+        Ok(feedback)
+    }
+}
+
+/// Keeps the words that can be Wordle secrets: `WORD_LENGTH` letters a to z,
+/// sorted and without repeats so a seed always picks the same word.
+pub fn candidate_words<'a>(words: impl IntoIterator<Item = &'a String>) -> Vec<String> {
+    let mut candidates: Vec<String> = words
+        .into_iter()
+        .filter(|word| {
+            word.len() == WORD_LENGTH && word.chars().all(|letter| letter.is_ascii_lowercase())
+        })
+        .cloned()
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+    candidates
+}
+
+/// Days since 1 January 1970 (UTC): the number of today's daily puzzle.
+pub fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() / 86_400)
+        .unwrap_or(0)
+}
+
+/// The secret for daily puzzle number `day`: everyone with the same word list gets the same word.
+pub fn daily_word(candidates: &[String], day: u64) -> Option<&String> {
+    candidates.choose(&mut StdRng::seed_from_u64(day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LetterFeedback::{Green as G, Grey as X, Yellow as Y};
+    use super::*;
+
+    #[test]
+    fn scores_exact_and_missing_letters() {
+        assert_eq!(score_guess("lemon", "lemon"), vec![G, G, G, G, G]);
+        assert_eq!(score_guess("fudgy", "lemon"), vec![X, X, X, X, X]);
+        assert_eq!(score_guess("melon", "lemon"), vec![Y, G, Y, G, G]);
+        assert_eq!(score_guess("MELON", "Lemon"), vec![Y, G, Y, G, G]);
+    }
+
+    #[test]
+    fn repeated_guess_letters_only_match_once() {
+        // The secret's only 'l' is taken by the green, so the other 'l' is grey.
+        assert_eq!(score_guess("hello", "world"), vec![X, X, X, G, Y]);
+        // The green 'p' uses up one of the secret's 'p's, leaving one for a yellow.
+        assert_eq!(score_guess("poppy", "apple"), vec![Y, X, G, X, X]);
+        // Two 'e's in the secret: one green, one for the first unmatched 'e'.
+        assert_eq!(score_guess("eerie", "there"), vec![Y, X, Y, X, G]);
+    }
+
+    #[test]
+    fn repeated_secret_letters_can_all_be_found() {
+        assert_eq!(score_guess("plate", "apple"), vec![Y, Y, Y, X, G]);
+        assert_eq!(score_guess("pppzz", "apple"), vec![X, G, G, X, X]);
+    }
+
+    #[test]
+    fn hard_mode_requires_revealed_letters() {
+        let previous = vec![(String::from("melon"), score_guess("melon", "lemon"))];
+        assert_eq!(check_hard_mode("lemon", &previous), Ok(()));
+        assert_eq!(
+            check_hard_mode("mason", &previous),
+            Err(WordleError::MustKeep {
+                letter: 'e',
+                position: 2
+            })
+        );
+        assert_eq!(
+            check_hard_mode("demon", &previous),
+            Err(WordleError::MustContain {
+                letter: 'l',
+                count: 1
+            })
+        );
+    }
+
+    #[test]
+    fn hard_mode_counts_repeated_hints() {
+        // One green and one yellow 'p': later guesses need two of them.
+        let previous = vec![(String::from("peppy"), score_guess("peppy", "apple"))];
+        assert_eq!(
+            check_hard_mode("repay", &previous),
+            Err(WordleError::MustContain {
+                letter: 'p',
+                count: 2
+            })
+        );
+        assert_eq!(check_hard_mode("upper", &previous), Ok(()));
+    }
+
+    #[test]
+    fn game_rejects_bad_guesses_without_using_an_attempt() {
+        let mut game = WordleGame::new("lemon", 2, true);
+        assert_eq!(
+            game.guess("lime"),
+            Err(WordleError::WrongLength {
+                expected: 5,
+                found: 4
+            })
+        );
+        assert_eq!(game.guess("l3mon"), Err(WordleError::NotLetters));
+        assert_eq!(game.guesses_used(), 0);
+
+        game.guess("melon").unwrap();
+        assert!(matches!(
+            game.guess("mango"),
+            Err(WordleError::MustKeep { .. })
+        ));
+        assert_eq!(game.guess("lemon"), Ok(vec![G, G, G, G, G]));
+        assert!(game.is_won());
+        assert_eq!(game.guess("lemon"), Err(WordleError::GameOver));
+    }
+
+    #[test]
+    fn game_is_lost_after_the_last_guess() {
+        let mut game = WordleGame::new("lemon", 1, false);
+        game.guess("mango").unwrap();
+        assert!(game.is_over());
+        assert!(!game.is_won());
+    }
+
+    #[test]
+    fn daily_word_depends_only_on_the_day() {
+        let words: Vec<String> = ["grape", "apple", "kiwis", "lemon", "apple", "fig"]
+            .iter()
+            .map(|word| word.to_string())
+            .collect();
+        let candidates = candidate_words(&words);
+        assert_eq!(candidates, vec!["apple", "grape", "kiwis", "lemon"]);
+        assert_eq!(
+            daily_word(&candidates, 20_000),
+            daily_word(&candidates, 20_000)
+        );
+        assert!(candidates.contains(daily_word(&candidates, 20_001).unwrap()));
+        assert_eq!(daily_word(&[], 20_000), None);
+    }
+}
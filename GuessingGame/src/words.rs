@@ -0,0 +1,131 @@
+// Word Lists: Secret words grouped into categories, one text file per category.
+//
+// A category file is named after its category (`fruits.txt` holds the fruits)
+// and lists one word per line. Blank lines and lines starting with '#' are
+// skipped, so the files can carry comments.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Directory searched for category files when no `--words` option is given.
+pub const DEFAULT_WORDS_DIR: &str = "words";
+
+/// The original fruit list, used when no category files can be found.
+const BUILTIN_FRUITS: [&str; 7] = [
+    "apple",
+    "banana",
+    "cherry",
+    "date",
+    "elderberry",
+    "fig",
+    "grape",
+];
+
+/// A named list of words the secret can be chosen from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordList {
+    pub category: String,
+    pub words: Vec<String>,
+}
+
+impl WordList {
+    /// Builds a list from the text of a category file.
+    pub fn parse(category: &str, text: &str) -> WordList {
+        let mut words: Vec<String> = Vec::new();
+        for line in text.lines() {
+            // Input Cleaning: Words are compared in lowercase, comments and blanks are skipped.
+            let word = line.trim().to_lowercase();
+            if word.is_empty() || word.starts_with('#') || words.contains(&word) {
+                continue;
+            }
+            words.push(word);
+        }
+        WordList {
+            category: category.to_string(),
+            words,
+        }
+    }
+
+    /// Returns true if `word` is in the list, ignoring case.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(word))
+    }
+}
+
+/// The built-in fruit category, available even without any word files.
+pub fn builtin_categories() -> Vec<WordList> {
+    vec![WordList {
+        category: String::from("fruits"),
+        words: BUILTIN_FRUITS.iter().map(|word| word.to_string()).collect(),
+    }]
+}
+
+/// Loads every `<category>.txt` file in `dir`, sorted by category name.
+///
+/// Files without any words are left out. Fails if the directory cannot be
+/// read or a file in it cannot be opened.
+pub fn load_categories(dir: &Path) -> io::Result<Vec<WordList>> {
+    let mut categories = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("txt") {
+            continue;
+        }
+        // Category Name: The file name without its extension.
+        let category = match path.file_stem().and_then(|stem| stem.to_str()) {
+            Some(stem) => stem.to_lowercase(),
+            None => continue,
+        };
+        let list = WordList::parse(&category, &fs::read_to_string(&path)?);
+        if !list.words.is_empty() {
+            categories.push(list);
+        }
+    }
+    categories.sort_by(|a, b| a.category.cmp(&b.category));
+    Ok(categories)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_skips_comments_blanks_and_repeats() {
+        let text = "# Fruits\n\nApple\n  kiwi  \napple\n#banana\nLIME\r\n";
+        let list = WordList::parse("fruits", text);
+        assert_eq!(list.category, "fruits");
+        assert_eq!(list.words, vec!["apple", "kiwi", "lime"]);
+        assert!(list.contains("KIWI"));
+        assert!(!list.contains("banana"));
+    }
+
+    #[test]
+    fn builtin_fruits_are_lowercase_and_unique() {
+        let fruits = &builtin_categories()[0];
+        assert_eq!(WordList::parse("fruits", &fruits.words.join("\n")), *fruits);
+    }
+
+    #[test]
+    fn categories_are_loaded_from_text_files() {
+        let dir = std::env::temp_dir().join(format!("{}-word-lists", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("Veg.txt"), "carrot\nleek\n").unwrap();
+        fs::write(dir.join("animals.txt"), "otter\n# only a comment left\n").unwrap();
+        fs::write(dir.join("empty.txt"), "# nothing here\n").unwrap();
+        fs::write(dir.join("notes.md"), "not a word list\n").unwrap();
+        let categories = load_categories(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let categories = categories.unwrap();
+        let names: Vec<&str> = categories
+            .iter()
+            .map(|list| list.category.as_str())
+            .collect();
+        assert_eq!(names, vec!["animals", "veg"]);
+        assert_eq!(categories[1].words, vec!["carrot", "leek"]);
+        assert!(load_categories(&dir).is_err());
+    }
+}
@@ -1,6 +1,60 @@
 mod restaurant; // Bring the `restaurant` module into scope of this crate.
 use crate::restaurant::order_food; // Bring the `order_food` function into scope so we can call it without the full path.
+use crate::restaurant::history::{View, DEFAULT_LOG_FILE}; // Items from a nested module, by their path.
+
+use std::env; // `env::args` reads the command line.
+use std::path::PathBuf; // An owned file path.
+use std::process; // `process::exit` ends the program with an exit code.
+
+const USAGE: &str = "Usage:
+  modules                   Run the restaurant for an evening
+  modules report [OPTIONS]  Show sales from the order log
+
+Report options:
+  --log <FILE>    The order log to read (default: order_history.log)
+  --by <VIEW>     Only this view: day, item, topping or hour (repeatable)
+  --csv           Write CSV instead of a text report";
 
 fn main() {
-    order_food(); // Call the `order_food` function from the `restaurant` module.
-}
\ No newline at end of file
+    let args: Vec<String> = env::args().skip(1).collect(); // `skip(1)` drops the program's own name.
+    // A slice pattern splits off the command; `options @ ..` binds the rest.
+    match args.as_slice() {
+        [] => order_food(), // Call the `order_food` function from the `restaurant` module.
+        [command, options @ ..] if command == "report" => {
+            if let Err(message) = report(options) {
+                eprintln!("Error: {}\n\n{}", message, USAGE); // `eprintln!` prints to stderr.
+                process::exit(2); // A non-zero exit code tells the shell something went wrong.
+            }
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    }
+}
+
+// Reads the report options and prints the report. Problems come back as a message.
+fn report(options: &[String]) -> Result<(), String> {
+    let mut log = PathBuf::from(DEFAULT_LOG_FILE);
+    let mut views = Vec::new();
+    let mut csv = false;
+    let mut options = options.iter(); // `mut`: the loop below calls `next()` on it.
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            // `ok_or` turns a missing value (`None`) into an error message for `?`.
+            "--log" => log = PathBuf::from(options.next().ok_or("--log needs a file")?),
+            "--by" => {
+                let name = options.next().ok_or("--by needs a view")?;
+                let view = View::from_name(name).ok_or_else(|| format!("unknown view '{}'", name))?;
+                views.push(view);
+            }
+            "--csv" => csv = true,
+            other => return Err(format!("unknown option '{}'", other)),
+        }
+    }
+    if views.is_empty() {
+        views = View::ALL.to_vec(); // No `--by`: every view.
+    }
+    restaurant::print_sales_report(&log, &views, csv)
+        .map_err(|error| format!("could not read {}: {}", log.display(), error))
+}
@@ -0,0 +1,665 @@
+// `history` module: what was sold, kept on disk.
+// Every served order is appended to a log file, one line per dish, so nothing
+// is lost when the program ends. Reports read the log back and add it up by
+// day, by item, by topping and by hour, as text or as CSV.
+//
+// A log line holds these fields, separated by tabs:
+// `order  date  time  customer  item  quantity  amount  pizza`
+// `order` numbers the orders in the log, so dishes from one order can be
+// counted together. `amount` is in cents, before discounts, tax and tips. `pizza` is the text
+// written by `Pizza::to_text`, or `-` for anything that is not a pizza.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::pizza_order::menu::{format_price, Cents};
+use super::pizza_order::{Order, Pizza};
+use super::reservations::{format_time, Minutes};
+
+// Where `order_food` keeps its log, relative to the working directory.
+pub const DEFAULT_LOG_FILE: &str = "order_history.log";
+
+// The first line of a new log file, naming the columns.
+const HEADER: &str = "# order\tdate\ttime\tcustomer\titem\tquantity\tamount\tpizza";
+
+// A calendar date. Deriving `PartialOrd` and `Ord` compares the fields in
+// order (year, then month, then day), which is exactly date order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    // Today's date in UTC, from the system clock.
+    pub fn today() -> Date {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        Date::from_days(seconds as i64 / 86_400)
+    }
+
+    // The date `days` days after 1970-01-01.
+    // This is the standard "civil from days" calculation: it counts in
+    // 400-year eras (146,097 days each) whose years start on March 1st, so
+    // the leap day falls at the very end of a year.
+    pub fn from_days(days: i64) -> Date {
+        let shifted = days + 719_468; // days from 0000-03-01 to 1970-01-01
+        let era = shifted.div_euclid(146_097);
+        let day_of_era = shifted.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_from_march = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+        let month = if month_from_march < 10 {
+            month_from_march + 3
+        } else {
+            month_from_march - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        Date {
+            year: year as i32,
+            month: month as u32,
+            day: day as u32,
+        }
+    }
+
+    // Reads "YYYY-MM-DD".
+    pub fn parse(text: &str) -> Option<Date> {
+        let mut parts = text.trim().splitn(3, '-');
+        // `?` on an `Option` returns `None` early, just as it returns errors for `Result`.
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+// One dish from one served order: a line of the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub order: u64,
+    pub date: Date,
+    pub time: Minutes,
+    pub customer: String,
+    pub item: String,
+    pub quantity: u32,
+    pub amount: Cents,
+    pub pizza: Option<Pizza>,
+}
+
+impl Record {
+    // The records for a served `order`, numbered `number` in the log.
+    // `pizzas` is what `serve_customer` returned: one entry per order line.
+    pub fn from_order(
+        number: u64,
+        order: &Order,
+        pizzas: &[Option<Pizza>],
+        date: Date,
+        time: Minutes,
+    ) -> Vec<Record> {
+        // `zip` walks both lists together; `chain(repeat(&None))` pads
+        // `pizzas` in case it is shorter than the order.
+        let padded = pizzas.iter().chain(std::iter::repeat(&None));
+        order
+            .lines()
+            .iter()
+            .zip(padded)
+            .map(|(line, pizza)| Record {
+                order: number,
+                date,
+                time,
+                customer: clean(order.customer()),
+                item: clean(&line.item.name),
+                quantity: line.quantity,
+                amount: line.total(),
+                pizza: pizza.clone(),
+            })
+            .collect()
+    }
+
+    fn to_line(&self) -> String {
+        let pizza = match &self.pizza {
+            Some(pizza) => pizza.to_text(),
+            None => String::from("-"),
+        };
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.order,
+            self.date,
+            format_time(self.time),
+            self.customer,
+            self.item,
+            self.quantity,
+            self.amount,
+            pizza
+        )
+    }
+
+    // Reads a line written by `to_line`; `None` if it is not one.
+    fn parse(line: &str) -> Option<Record> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        // A slice pattern checks the length and names the fields in one go.
+        let [order, date, time, customer, item, quantity, amount, pizza] = fields.as_slice() else {
+            return None;
+        };
+        let (hour, minute) = time.split_once(':')?;
+        let pizza = match *pizza {
+            "-" => None,
+            text => Some(text.parse().ok()?),
+        };
+        Some(Record {
+            order: order.parse().ok()?,
+            date: Date::parse(date)?,
+            time: hour.parse::<u32>().ok()? * 60 + minute.parse::<u32>().ok()?,
+            customer: customer.to_string(),
+            item: item.to_string(),
+            quantity: quantity.parse().ok()?,
+            amount: amount.parse().ok()?,
+            pizza,
+        })
+    }
+}
+
+// Tabs and line breaks would break the log format, so they become spaces.
+fn clean(text: &str) -> String {
+    text.replace(['\t', '\n', '\r'], " ")
+}
+
+// The log file on disk.
+pub struct OrderLog {
+    path: PathBuf,
+}
+
+// What was read from a log: the records, and the line numbers that could not be read.
+pub struct History {
+    pub records: Vec<Record>,
+    pub unreadable: Vec<usize>,
+}
+
+impl OrderLog {
+    // `impl AsRef<Path>` accepts a `&str`, a `String`, a `PathBuf`...
+    pub fn new(path: impl AsRef<Path>) -> OrderLog {
+        OrderLog {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Adds `records` to the end of the log, creating the file (with its
+    // header line) if it does not exist yet.
+    pub fn append(&self, records: &[Record]) -> io::Result<()> {
+        let is_new = !self.path.exists();
+        // `append(true)` writes after whatever is already there; `create(true)`
+        // makes the file if needed.
+        let mut file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&self.path)?;
+        let mut text = String::new();
+        if is_new {
+            text.push_str(HEADER);
+            text.push('\n');
+        }
+        for record in records {
+            text.push_str(&record.to_line());
+            text.push('\n');
+        }
+        // One `write_all` per order keeps its lines together in the file.
+        file.write_all(text.as_bytes())
+    }
+
+    // The number for the next order: one more than the highest in the log.
+    pub fn next_order_number(&self) -> io::Result<u64> {
+        let history = self.load()?;
+        let highest = history.records.iter().map(|record| record.order).max();
+        Ok(highest.unwrap_or(0) + 1)
+    }
+
+    // Reads the whole log. A log that does not exist yet is simply empty.
+    pub fn load(&self) -> io::Result<History> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error),
+        };
+        let mut history = History {
+            records: Vec::new(),
+            unreadable: Vec::new(),
+        };
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match Record::parse(line) {
+                Some(record) => history.records.push(record),
+                // Line numbers count from 1, like an editor's.
+                None => history.unreadable.push(index + 1),
+            }
+        }
+        Ok(history)
+    }
+}
+
+// The ways a report can group sales.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    Day,
+    Item,
+    Topping,
+    Hour,
+}
+
+impl View {
+    pub const ALL: [View; 4] = [View::Day, View::Item, View::Topping, View::Hour];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            View::Day => "day",
+            View::Item => "item",
+            View::Topping => "topping",
+            View::Hour => "hour",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<View> {
+        View::ALL.into_iter().find(|view| view.name() == name)
+    }
+
+    // The heading of this view in the text report.
+    fn title(&self) -> &'static str {
+        match self {
+            View::Day => "Revenue by day",
+            View::Item => "Popular items",
+            View::Topping => "Popular toppings (pizzas)",
+            View::Hour => "Orders by hour",
+        }
+    }
+}
+
+// One group in a report: how many orders it appeared in, how many units were
+// sold and what they brought in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub key: String,
+    pub orders: usize,
+    pub quantity: u32,
+    pub revenue: Cents,
+}
+
+// The running totals of one group while `aggregate` adds up the records.
+#[derive(Default)]
+struct Group {
+    // The distinct order numbers seen; a set ignores repeats.
+    orders: HashSet<u64>,
+    quantity: u32,
+    revenue: Cents,
+}
+
+// Adds up `records` grouped by `view`.
+// Days and hours come out in time order; items and toppings most popular first.
+pub fn aggregate(records: &[Record], view: View) -> Vec<Row> {
+    // `BTreeMap` keeps its keys sorted, which gives days and hours their order.
+    let mut groups: BTreeMap<String, Group> = BTreeMap::new();
+    for record in records {
+        // Each key the record counts towards, with its share of the revenue.
+        let keys: Vec<(String, Cents)> = match view {
+            View::Day => vec![(record.date.to_string(), record.amount)],
+            View::Item => vec![(record.item.clone(), record.amount)],
+            // A pizza counts once for each of its toppings; other dishes not at all.
+            // Its amount is shared out among the toppings, so the toppings
+            // never add up to more than the pizzas brought in.
+            View::Topping => match &record.pizza {
+                Some(pizza) => {
+                    let toppings = pizza.toppings();
+                    toppings
+                        .iter()
+                        .map(|topping| topping.to_string())
+                        .zip(split(record.amount, toppings.len()))
+                        .collect()
+                }
+                None => Vec::new(),
+            },
+            View::Hour => vec![(format_time(record.time / 60 * 60), record.amount)],
+        };
+        for (key, revenue) in keys {
+            // `entry(...).or_default()` inserts an empty group the first time a key is seen.
+            let group = groups.entry(key).or_default();
+            group.orders.insert(record.order);
+            group.quantity += record.quantity;
+            group.revenue += revenue;
+        }
+    }
+
+    let mut rows: Vec<Row> = groups
+        .into_iter()
+        .map(|(key, group)| Row {
+            key,
+            orders: group.orders.len(),
+            quantity: group.quantity,
+            revenue: group.revenue,
+        })
+        .collect();
+    if matches!(view, View::Item | View::Topping) {
+        // Most units sold first; `then_with` breaks ties by revenue.
+        // `sort_by` is stable, so equal rows stay in name order.
+        rows.sort_by(|a, b| {
+            b.quantity
+                .cmp(&a.quantity)
+                .then_with(|| b.revenue.cmp(&a.revenue))
+        });
+    }
+    rows
+}
+
+// Splits `amount` into `parts` shares that differ by at most a cent and add
+// up to exactly `amount`; the first shares get the leftover cents.
+fn split(amount: Cents, parts: usize) -> impl Iterator<Item = Cents> {
+    // `max(1)` keeps a pizza without toppings from dividing by zero; it has
+    // no toppings to zip the shares with anyway.
+    let parts = parts.max(1) as Cents;
+    let (share, left_over) = (amount / parts, amount % parts);
+    (0..parts).map(move |index| share + Cents::from(index < left_over))
+}
+
+// The rows of several views as CSV, with a header line.
+// Every row starts with its view's name, so all views fit in one table.
+pub fn to_csv(records: &[Record], views: &[View]) -> String {
+    let mut csv = String::from("view,key,orders,quantity,revenue\n");
+    for &view in views {
+        for row in aggregate(records, view) {
+            csv.push_str(&format!(
+                "{},{},{},{},{}.{:02}\n",
+                view.name(),
+                csv_field(&row.key),
+                row.orders,
+                row.quantity,
+                row.revenue / 100,
+                row.revenue % 100
+            ));
+        }
+    }
+    csv
+}
+
+// Quotes a CSV field if it contains a comma, a quote or a line break;
+// quotes inside are doubled, as CSV requires.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+// A printable sales report over some views of the records.
+pub struct SalesReport<'a> {
+    records: &'a [Record],
+    views: &'a [View],
+}
+
+impl<'a> SalesReport<'a> {
+    pub fn new(records: &'a [Record], views: &'a [View]) -> SalesReport<'a> {
+        SalesReport { records, views }
+    }
+}
+
+impl fmt::Display for SalesReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // The totals use the same grouping code: every record has a date.
+        let days = aggregate(self.records, View::Day);
+        let orders: usize = days.iter().map(|row| row.orders).sum();
+        let quantity: u32 = days.iter().map(|row| row.quantity).sum();
+        let revenue: Cents = days.iter().map(|row| row.revenue).sum();
+        write!(
+            f,
+            "Sales report: {} orders, {} dishes, {}",
+            orders,
+            quantity,
+            format_price(revenue)
+        )?;
+        // `first()` and `last()` are `None` for an empty log.
+        if let (Some(first), Some(last)) = (days.first(), days.last()) {
+            write!(f, " ({} to {})", first.key, last.key)?;
+        }
+        writeln!(f)?;
+
+        for &view in self.views {
+            let rows = aggregate(self.records, view);
+            // The busiest hour is marked as the peak.
+            let peak = match view {
+                View::Hour => rows.iter().map(|row| row.orders).max(),
+                _ => None,
+            };
+            writeln!(f)?;
+            writeln!(f, "{}:", view.title())?;
+            writeln!(
+                f,
+                "  {:<20}{:>8}{:>8}{:>12}",
+                view.name(),
+                "orders",
+                "qty",
+                "revenue"
+            )?;
+            for row in &rows {
+                write!(
+                    f,
+                    "  {:<20}{:>8}{:>8}{:>12}",
+                    row.key,
+                    row.orders,
+                    row.quantity,
+                    format_price(row.revenue)
+                )?;
+                if Some(row.orders) == peak {
+                    write!(f, "  <- peak")?;
+                }
+                writeln!(f)?;
+            }
+            if rows.is_empty() {
+                writeln!(f, "  (nothing yet)")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::restaurant::pizza_order::{Menu, PizzaBuilder};
+    use crate::restaurant::reservations::at;
+
+    const DAY: Date = Date {
+        year: 2026,
+        month: 3,
+        day: 14,
+    };
+
+    fn order(customer: &str, requests: &[(&str, u32)]) -> Order {
+        let menu = Menu::standard();
+        let mut order = Order::new(customer);
+        for &(name, quantity) in requests {
+            order.add(&menu, name, quantity, &[]).unwrap();
+        }
+        order
+    }
+
+    fn pizza(name: &str) -> Option<Pizza> {
+        Some(PizzaBuilder::house(name).unwrap().build().unwrap())
+    }
+
+    // Three orders over two days: two pepperoni orders and a veggie one.
+    fn records() -> Vec<Record> {
+        let mut records = Record::from_order(
+            1,
+            &order("Ada", &[("pepperoni", 2), ("cola", 1)]),
+            &[pizza("pepperoni"), None],
+            DAY,
+            at(18, 10),
+        );
+        records.extend(Record::from_order(
+            2,
+            &order("Bob", &[("veggies", 1), ("pepperoni", 1)]),
+            &[pizza("veggies"), pizza("pepperoni")],
+            DAY,
+            at(18, 50),
+        ));
+        let next_day = Date { day: 15, ..DAY };
+        records.extend(Record::from_order(
+            3,
+            &order("Cy", &[("cola", 3)]),
+            &[None],
+            next_day,
+            at(19, 5),
+        ));
+        records
+    }
+
+    // A file in the system's temp directory that is removed when dropped.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> TempFile {
+            let path = std::env::temp_dir().join(format!("{}-{}", std::process::id(), name));
+            let _ = fs::remove_file(&path);
+            TempFile(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn converts_days_since_1970_to_dates() {
+        assert_eq!(Date::from_days(0).to_string(), "1970-01-01");
+        assert_eq!(Date::from_days(59).to_string(), "1970-03-01");
+        assert_eq!(Date::from_days(11_016).to_string(), "2000-02-29");
+        assert_eq!(Date::from_days(20_526).to_string(), "2026-03-14");
+        assert_eq!(Date::from_days(-1).to_string(), "1969-12-31");
+        assert_eq!(Date::parse("2026-03-14"), Some(DAY));
+        assert_eq!(Date::parse("2026-13-01"), None);
+    }
+
+    #[test]
+    fn appends_to_the_log_and_reads_it_back() {
+        let file = TempFile::new("history-round-trip.log");
+        let log = OrderLog::new(&file.0);
+        assert!(log.load().unwrap().records.is_empty());
+        assert_eq!(log.next_order_number().unwrap(), 1);
+
+        let records = records();
+        log.append(&records[..2]).unwrap();
+        log.append(&records[2..]).unwrap();
+        let text = fs::read_to_string(&file.0).unwrap();
+        assert_eq!(text.lines().filter(|line| line.starts_with('#')).count(), 1);
+
+        let history = log.load().unwrap();
+        assert_eq!(history.records, records);
+        assert!(history.unreadable.is_empty());
+        assert_eq!(log.next_order_number().unwrap(), 4);
+    }
+
+    #[test]
+    fn skips_lines_it_cannot_read() {
+        let file = TempFile::new("history-damaged.log");
+        fs::write(
+            &file.0,
+            "# header\n7\t2026-03-14\t18:00\tAda\tcola\t1\t250\t-\nnot a record\n",
+        )
+        .unwrap();
+        let history = OrderLog::new(&file.0).load().unwrap();
+        assert_eq!(history.records.len(), 1);
+        assert_eq!(history.unreadable, vec![3]);
+    }
+
+    #[test]
+    fn aggregates_by_day_item_topping_and_hour() {
+        let records = records();
+        let days = aggregate(&records, View::Day);
+        assert_eq!(days.len(), 2);
+        assert_eq!(
+            (days[0].key.as_str(), days[0].orders, days[0].quantity),
+            ("2026-03-14", 2, 5)
+        );
+        assert_eq!(days[0].revenue, 2 * 1200 + 250 + 1400 + 1200);
+        assert_eq!((days[1].orders, days[1].revenue), (1, 750));
+
+        let items = aggregate(&records, View::Item);
+        let popular: Vec<(&str, u32, usize)> = items
+            .iter()
+            .map(|row| (row.key.as_str(), row.quantity, row.orders))
+            .collect();
+        assert_eq!(
+            popular,
+            [("cola", 4, 2), ("pepperoni", 3, 2), ("veggies", 1, 1)]
+        );
+
+        let toppings = aggregate(&records, View::Topping);
+        assert_eq!(toppings[0].key, "pepperoni");
+        assert_eq!(toppings[0].quantity, 3);
+        assert_eq!(toppings[0].revenue, 3 * 1200);
+        assert_eq!(toppings.len(), 5); // pepperoni plus the four veggie toppings
+        // The veggie pizza's $14.00 is shared by its four toppings.
+        assert!(toppings[1..].iter().all(|row| row.revenue == 350));
+        // Toppings share the pizzas' revenue; they never add up to more.
+        let pizza_revenue: Cents = items
+            .iter()
+            .filter(|row| row.key != "cola")
+            .map(|row| row.revenue)
+            .sum();
+        let topping_revenue: Cents = toppings.iter().map(|row| row.revenue).sum();
+        assert!(topping_revenue <= pizza_revenue);
+        assert_eq!(split(1000, 3).collect::<Vec<_>>(), [334, 333, 333]);
+
+        let hours = aggregate(&records, View::Hour);
+        let hours: Vec<(&str, usize)> = hours
+            .iter()
+            .map(|row| (row.key.as_str(), row.orders))
+            .collect();
+        assert_eq!(hours, [("18:00", 2), ("19:00", 1)]);
+    }
+
+    #[test]
+    fn exports_csv_and_marks_the_peak_hour() {
+        let records = records();
+        let csv = to_csv(&records, &[View::Day]);
+        assert_eq!(
+            csv,
+            "view,key,orders,quantity,revenue\nday,2026-03-14,2,5,52.50\nday,2026-03-15,1,3,7.50\n"
+        );
+        let csv = to_csv(&records, &[View::Topping]);
+        assert!(csv.contains("\ntopping,pepperoni,2,3,36.00\n"));
+        assert!(csv.contains("\ntopping,olives,1,1,3.50\n"));
+        assert_eq!(csv_field("tea, iced"), "\"tea, iced\"");
+        assert_eq!(csv_field("the \"special\""), "\"the \"\"special\"\"\"");
+
+        let report = SalesReport::new(&records, &View::ALL).to_string();
+        assert!(report
+            .starts_with("Sales report: 3 orders, 8 dishes, $60.00 (2026-03-14 to 2026-03-15)"));
+        assert!(report
+            .lines()
+            .any(|line| line.contains("18:00") && line.ends_with("<- peak")));
+    }
+}
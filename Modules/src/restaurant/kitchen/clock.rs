@@ -0,0 +1,80 @@
+// `clock` module: where the kitchen gets the time from.
+// Cooks never call `Instant::now()` or `thread::sleep` themselves; they ask a
+// `Clock`. The restaurant hands the kitchen a `SystemClock`, while tests hand it
+// a `ManualClock` so that every timing they check is exact and instant.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+// A `trait` describes behaviour that several types can share.
+// `Send + Sync` are supertraits: every clock must be safe to share between
+// threads, because all the cooks read the same one.
+pub trait Clock: Send + Sync {
+    // Time passed since the clock started.
+    fn now(&self) -> Duration;
+
+    // Lets `duration` of clock time pass for the calling thread.
+    fn sleep(&self, duration: Duration);
+}
+
+// The real clock, optionally sped up so a simulated dinner rush does not take
+// an evening: at `speed` 600, a 12 minute pizza is ready after 1.2 seconds.
+pub struct SystemClock {
+    start: Instant,
+    speed: u32,
+}
+
+impl SystemClock {
+    // A clock running `speed` times faster than real time (1 = real time).
+    pub fn new(speed: u32) -> SystemClock {
+        // `max(1)` keeps a speed of 0 from dividing by zero in `sleep`.
+        SystemClock {
+            start: Instant::now(),
+            speed: speed.max(1),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed() * self.speed
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration / self.speed);
+    }
+}
+
+// A clock that only moves when told to, for tests.
+// `sleep` returns at once: the kitchen's schedule keeps each cook's timeline
+// (see `Queue::book` in `kitchen/mod.rs`), so prep times still add up without
+// anyone waiting.
+// `#[cfg(test)]` compiles it only for `cargo test`, the only place it is used.
+#[cfg(test)]
+#[derive(Default)]
+pub struct ManualClock {
+    // `Mutex` gives the shared clock interior mutability: `advance` takes `&self`.
+    now: std::sync::Mutex<Duration>,
+}
+
+#[cfg(test)]
+impl ManualClock {
+    // A clock standing at zero.
+    pub fn new() -> ManualClock {
+        ManualClock::default()
+    }
+
+    // Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+#[cfg(test)]
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        *self.now.lock().unwrap()
+    }
+
+    fn sleep(&self, _duration: Duration) {}
+}
@@ -0,0 +1,521 @@
+// `kitchen` module: the back of house.
+// Orders from every table go into one shared queue, and a pool of cook threads
+// takes them off it one at a time. Each dish takes a simulated prep time, and
+// when an order is done the cook sends it back to the customer over a channel.
+// Closing the kitchen waits for the cooks and reports how it went.
+//
+// This is the thread pool pattern: an `mpsc` channel is the queue, and its
+// single `Receiver` is shared by all the cooks behind `Arc<Mutex<...>>`.
+// The same lock guards the cooks' schedule, so each ticket goes to the cook
+// who is free soonest, whichever thread happens to take it off the queue.
+
+pub mod clock;
+
+pub use clock::{Clock, SystemClock};
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use super::pizza_order::menu::{Category, MenuItem};
+use super::pizza_order::Order;
+
+// One minute as a `Duration`; prep times read better in minutes.
+const MINUTE: Duration = Duration::from_secs(60);
+
+// How long each dish takes to make.
+// A dish's own time wins over its category's, which wins over `default`.
+#[derive(Debug, Clone)]
+pub struct PrepTimes {
+    default: Duration,
+    categories: HashMap<Category, Duration>,
+    // Keys are lowercase, because menu names are matched without regard to case.
+    items: HashMap<String, Duration>,
+}
+
+impl PrepTimes {
+    // Every dish takes `default` until told otherwise.
+    pub fn new(default: Duration) -> PrepTimes {
+        PrepTimes {
+            default,
+            categories: HashMap::new(),
+            items: HashMap::new(),
+        }
+    }
+
+    // The house timings used by `order_food`.
+    pub fn standard() -> PrepTimes {
+        let mut times = PrepTimes::new(2 * MINUTE);
+        times.set_category(Category::Pizza, 12 * MINUTE);
+        times.set_category(Category::Side, 6 * MINUTE);
+        times.set_category(Category::Drink, MINUTE);
+        times.set_category(Category::Dessert, 3 * MINUTE);
+        // The salad is only tossed, not baked like the garlic bread.
+        times.set_item("caesar salad", 4 * MINUTE);
+        times
+    }
+
+    pub fn set_category(&mut self, category: Category, time: Duration) {
+        self.categories.insert(category, time);
+    }
+
+    pub fn set_item(&mut self, name: &str, time: Duration) {
+        self.items.insert(name.to_lowercase(), time);
+    }
+
+    // Time to make one unit of `item`.
+    pub fn for_item(&self, item: &MenuItem) -> Duration {
+        // `or_else` only runs the second lookup if the first found nothing;
+        // `copied` turns `Option<&Duration>` into `Option<Duration>`.
+        self.items
+            .get(&item.name.to_lowercase())
+            .or_else(|| self.categories.get(&item.category))
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    // Time for one cook to make a whole order, one unit after another.
+    pub fn for_order(&self, order: &Order) -> Duration {
+        order
+            .lines()
+            .iter()
+            .map(|line| self.for_item(&line.item) * line.quantity)
+            .sum()
+    }
+}
+
+// A finished order, sent back to the customer who placed it.
+// The kitchen took ownership of the `Order` when it was submitted; the meal
+// gives it back so the customer can be served and billed.
+#[derive(Debug)]
+pub struct Meal {
+    pub ticket: u32,
+    // Which cook made it, counting from 1.
+    pub cook: usize,
+    pub order: Order,
+    // Clock times: when the order was placed, when a cook picked it up and
+    // when it was ready.
+    pub placed_at: Duration,
+    pub started_at: Duration,
+    pub ready_at: Duration,
+}
+
+impl Meal {
+    // How long the customer waited, from placing the order to getting the food.
+    pub fn wait(&self) -> Duration {
+        self.ready_at - self.placed_at
+    }
+}
+
+// An order waiting in the queue, with the channel to send the meal back on.
+struct Ticket {
+    number: u32,
+    order: Order,
+    placed_at: Duration,
+    notify: Sender<Meal>,
+}
+
+// The queue and the schedule, behind one lock.
+// Taking a ticket and booking its cook happen together, so tickets are booked
+// in the order they were placed.
+struct Queue {
+    tickets: Receiver<Ticket>,
+    // When each cook is next free; cook 1 is at index 0.
+    free_at: Vec<Duration>,
+}
+
+impl Queue {
+    // Books `prep` with the cook who is free soonest, no earlier than `earliest`.
+    // Returns the cook, counting from 1, and when they start.
+    fn book(&mut self, earliest: Duration, prep: Duration) -> (usize, Duration) {
+        // `min_by_key` returns the first of several equal keys, so ties go to
+        // the lowest numbered cook.
+        let (index, free_at) = self
+            .free_at
+            .iter()
+            .copied()
+            .enumerate()
+            .min_by_key(|&(_, free_at)| free_at)
+            .expect("a kitchen has at least one cook");
+        let started_at = free_at.max(earliest);
+        self.free_at[index] = started_at + prep;
+        (index + 1, started_at)
+    }
+}
+
+// What the kitchen remembers about each cooked order, for the report.
+struct Cooked {
+    cook: usize,
+    dishes: u32,
+    placed_at: Duration,
+    ready_at: Duration,
+}
+
+// The kitchen: a queue of tickets and the cooks working through it.
+pub struct Kitchen {
+    orders: Sender<Ticket>,
+    cooks: Vec<JoinHandle<()>>,
+    clock: Arc<dyn Clock>,
+    // Shared with the cooks, who add to it as they finish orders.
+    log: Arc<Mutex<Vec<Cooked>>>,
+    next_ticket: u32,
+}
+
+impl Kitchen {
+    // Opens a kitchen with `cooks` cook threads.
+    // `Arc<dyn Clock>`: any clock, shared by every cook. Tests pass a `ManualClock`.
+    //
+    // # Panics
+    // If `cooks` is 0, as nobody would ever cook the orders.
+    pub fn open(cooks: usize, prep_times: PrepTimes, clock: Arc<dyn Clock>) -> Kitchen {
+        assert!(cooks > 0, "a kitchen needs at least one cook");
+        let (orders, tickets) = mpsc::channel();
+        let queue = Arc::new(Mutex::new(Queue {
+            tickets,
+            free_at: vec![Duration::ZERO; cooks],
+        }));
+        let prep_times = Arc::new(prep_times);
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        // Each cook gets its own clone of every `Arc`: cloning only bumps a count.
+        let cooks = (1..=cooks)
+            .map(|_| {
+                let queue = Arc::clone(&queue);
+                let prep_times = Arc::clone(&prep_times);
+                let clock = Arc::clone(&clock);
+                let log = Arc::clone(&log);
+                // `move` hands those clones to the new thread.
+                thread::spawn(move || cook(&queue, &prep_times, clock.as_ref(), &log))
+            })
+            .collect();
+
+        Kitchen {
+            orders,
+            cooks,
+            clock,
+            log,
+            next_ticket: 1,
+        }
+    }
+
+    // Puts `order` in the queue and returns the channel its meal will arrive on.
+    // The customer can wait with `recv()` or check now and then with `try_recv()`.
+    pub fn submit(&mut self, order: Order) -> Receiver<Meal> {
+        let (notify, meal) = mpsc::channel();
+        let ticket = Ticket {
+            number: self.next_ticket,
+            order,
+            placed_at: self.clock.now(),
+            notify,
+        };
+        self.next_ticket += 1;
+        // Sending only fails once every cook has gone, which only happens if one panicked.
+        self.orders
+            .send(ticket)
+            .expect("the kitchen has no cooks left");
+        meal
+    }
+
+    // Stops taking orders, lets the cooks finish everything already queued,
+    // and reports on the whole service.
+    pub fn close(self) -> KitchenReport {
+        // Destructuring moves the fields out of `self`. Dropping the last
+        // `Sender` closes the queue: once it is empty, each cook's `recv` fails
+        // and the cook goes home.
+        let Kitchen {
+            orders, cooks, log, ..
+        } = self;
+        drop(orders);
+        let cook_count = cooks.len();
+        for handle in cooks {
+            handle.join().expect("a cook panicked");
+        }
+        let log = log.lock().unwrap();
+        KitchenReport::from_log(&log, cook_count)
+    }
+}
+
+// The body of one cook thread: take a ticket, cook it, send it out, repeat.
+// The thread cooks on behalf of whichever cook the schedule booked, so the
+// timings do not depend on which thread the operating system wakes first.
+fn cook(
+    queue: &Mutex<Queue>,
+    prep_times: &PrepTimes,
+    clock: &dyn Clock,
+    log: &Mutex<Vec<Cooked>>,
+) {
+    loop {
+        // The block holds the lock while taking the ticket and booking its
+        // cook, and drops it at the closing brace so other cooks can take
+        // tickets while this one is cooking.
+        let (ticket, id, prep, started_at) = {
+            let mut queue = queue.lock().unwrap();
+            let ticket = match queue.tickets.recv() {
+                Ok(ticket) => ticket,
+                // The queue is closed and empty: the kitchen is closing.
+                Err(_) => break,
+            };
+            let prep = prep_times.for_order(&ticket.order);
+            let (id, started_at) = queue.book(clock.now().max(ticket.placed_at), prep);
+            (ticket, id, prep, started_at)
+        };
+
+        let ready_at = started_at + prep;
+        clock.sleep(ready_at.saturating_sub(clock.now()));
+
+        log.lock().unwrap().push(Cooked {
+            cook: id,
+            dishes: ticket.order.item_count(),
+            placed_at: ticket.placed_at,
+            ready_at,
+        });
+        let meal = Meal {
+            ticket: ticket.number,
+            cook: id,
+            order: ticket.order,
+            placed_at: ticket.placed_at,
+            started_at,
+            ready_at,
+        };
+        // If the customer left (dropped their `Receiver`), the meal goes nowhere;
+        // that is no reason to stop cooking.
+        let _ = ticket.notify.send(meal);
+    }
+}
+
+// How the kitchen did over one service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KitchenReport {
+    pub orders: usize,
+    pub dishes: u32,
+    // From the first order placed to the last one ready.
+    pub elapsed: Duration,
+    pub average_wait: Duration,
+    pub longest_wait: Duration,
+    // Orders made by each cook; cook 1 is at index 0.
+    pub orders_per_cook: Vec<usize>,
+}
+
+impl KitchenReport {
+    fn from_log(log: &[Cooked], cooks: usize) -> KitchenReport {
+        let waits: Vec<Duration> = log
+            .iter()
+            .map(|cooked| cooked.ready_at - cooked.placed_at)
+            .collect();
+        let total_wait: Duration = waits.iter().sum();
+        let mut orders_per_cook = vec![0; cooks];
+        for cooked in log {
+            orders_per_cook[cooked.cook - 1] += 1;
+        }
+        // `min()` and `max()` return `None` for an empty log; `unwrap_or_default` makes that zero.
+        let first_placed = log.iter().map(|cooked| cooked.placed_at).min();
+        let last_ready = log.iter().map(|cooked| cooked.ready_at).max();
+
+        KitchenReport {
+            orders: log.len(),
+            dishes: log.iter().map(|cooked| cooked.dishes).sum(),
+            elapsed: last_ready.unwrap_or_default() - first_placed.unwrap_or_default(),
+            // `checked_div` is `None` when dividing by zero orders.
+            average_wait: total_wait
+                .checked_div(log.len() as u32)
+                .unwrap_or_default(),
+            longest_wait: waits.iter().max().copied().unwrap_or_default(),
+            orders_per_cook,
+        }
+    }
+
+    // Throughput: orders finished per hour of service.
+    pub fn orders_per_hour(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            return 0.0;
+        }
+        self.orders as f64 * 3600.0 / self.elapsed.as_secs_f64()
+    }
+}
+
+impl fmt::Display for KitchenReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Kitchen report: {} orders ({} dishes) in {}",
+            self.orders,
+            self.dishes,
+            format_minutes(self.elapsed)
+        )?;
+        writeln!(f, "  Throughput:   {:.1} orders per hour", self.orders_per_hour())?;
+        writeln!(f, "  Average wait: {}", format_minutes(self.average_wait))?;
+        writeln!(f, "  Longest wait: {}", format_minutes(self.longest_wait))?;
+        // `enumerate` pairs each count with its index.
+        for (index, count) in self.orders_per_cook.iter().enumerate() {
+            write!(f, "  Cook {}: {} orders", index + 1, count)?;
+            if index + 1 < self.orders_per_cook.len() {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Formats a duration as minutes and seconds, e.g. "14m 20s".
+pub fn format_minutes(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}m {:02}s", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::clock::ManualClock;
+    use super::*;
+    use crate::restaurant::pizza_order::Menu;
+
+    // A menu whose dishes take 10, 5 and 3 minutes.
+    fn setup() -> (Menu, PrepTimes) {
+        let mut menu = Menu::new();
+        menu.add_item("pizza", 1000, Category::Pizza);
+        menu.add_item("salad", 600, Category::Side);
+        menu.add_item("soda", 200, Category::Drink);
+        let mut times = PrepTimes::new(3 * MINUTE);
+        times.set_category(Category::Pizza, 10 * MINUTE);
+        times.set_category(Category::Side, 5 * MINUTE);
+        (menu, times)
+    }
+
+    fn order(menu: &Menu, customer: &str, dish: &str, quantity: u32) -> Order {
+        let mut order = Order::new(customer);
+        order.add(menu, dish, quantity, &[]).unwrap();
+        order
+    }
+
+    #[test]
+    fn prep_times_prefer_the_dish_then_its_category() {
+        let (menu, mut times) = setup();
+        times.set_item("Salad", 4 * MINUTE);
+        assert_eq!(times.for_item(menu.item("pizza").unwrap()), 10 * MINUTE);
+        assert_eq!(times.for_item(menu.item("salad").unwrap()), 4 * MINUTE);
+        assert_eq!(times.for_item(menu.item("soda").unwrap()), 3 * MINUTE);
+
+        let mut two_pizzas_and_a_soda = order(&menu, "Ada", "pizza", 2);
+        two_pizzas_and_a_soda.add(&menu, "soda", 1, &[]).unwrap();
+        assert_eq!(times.for_order(&two_pizzas_and_a_soda), 23 * MINUTE);
+    }
+
+    #[test]
+    fn one_cook_works_through_the_queue_in_order() {
+        let (menu, times) = setup();
+        let clock = Arc::new(ManualClock::new());
+        let mut kitchen = Kitchen::open(1, times, clock);
+        let pizza = kitchen.submit(order(&menu, "Ada", "pizza", 1));
+        let salad = kitchen.submit(order(&menu, "Bob", "salad", 1));
+        let soda = kitchen.submit(order(&menu, "Cy", "soda", 1));
+
+        // Each customer hears about their own order on their own channel.
+        let pizza = pizza.recv().unwrap();
+        let salad = salad.recv().unwrap();
+        let soda = soda.recv().unwrap();
+        assert_eq!((pizza.ticket, pizza.order.customer()), (1, "Ada"));
+        assert_eq!(pizza.wait(), 10 * MINUTE);
+        assert_eq!(salad.started_at, 10 * MINUTE);
+        assert_eq!(salad.wait(), 15 * MINUTE);
+        assert_eq!(soda.wait(), 18 * MINUTE);
+
+        let report = kitchen.close();
+        assert_eq!(report.orders, 3);
+        assert_eq!(report.dishes, 3);
+        assert_eq!(report.elapsed, 18 * MINUTE);
+        assert_eq!(report.average_wait, Duration::from_secs((10 + 15 + 18) * 60 / 3));
+        assert_eq!(report.longest_wait, 18 * MINUTE);
+        assert_eq!(report.orders_per_hour(), 10.0);
+        assert_eq!(report.orders_per_cook, vec![3]);
+    }
+
+    #[test]
+    fn an_idle_cook_starts_when_the_order_is_placed() {
+        let (menu, times) = setup();
+        let clock = Arc::new(ManualClock::new());
+        // `clock.clone()` keeps a handle for the test; the kitchen gets the other.
+        let mut kitchen = Kitchen::open(1, times, clock.clone());
+        let first = kitchen.submit(order(&menu, "Ada", "soda", 1));
+        assert_eq!(first.recv().unwrap().ready_at, 3 * MINUTE);
+
+        clock.advance(30 * MINUTE);
+        let second = kitchen.submit(order(&menu, "Bob", "pizza", 2)).recv().unwrap();
+        assert_eq!(second.started_at, 30 * MINUTE);
+        assert_eq!(second.ready_at, 50 * MINUTE);
+        assert_eq!(second.wait(), 20 * MINUTE);
+
+        let report = kitchen.close();
+        assert_eq!(report.elapsed, 50 * MINUTE);
+        assert_eq!(report.dishes, 3);
+    }
+
+    #[test]
+    fn every_customer_is_served_by_a_pool_of_cooks() {
+        let (menu, times) = setup();
+        let mut kitchen = Kitchen::open(3, times, Arc::new(ManualClock::new()));
+        let customers: Vec<String> = (1..=12).map(|table| format!("Table {}", table)).collect();
+        let meals: Vec<Receiver<Meal>> = customers
+            .iter()
+            .map(|customer| kitchen.submit(order(&menu, customer, "pizza", 1)))
+            .collect();
+        // Closing first is fine: queued orders are still cooked and sent.
+        let report = kitchen.close();
+
+        // The cooks take the pizzas in turns, three every ten minutes.
+        for (index, (customer, meal)) in customers.iter().zip(meals).enumerate() {
+            let meal = meal.recv().unwrap();
+            assert_eq!(meal.order.customer(), customer);
+            assert_eq!(meal.cook, index % 3 + 1);
+            assert_eq!(meal.wait(), (index as u32 / 3 + 1) * 10 * MINUTE);
+        }
+        assert_eq!(report.orders, 12);
+        assert_eq!(report.elapsed, 40 * MINUTE);
+        assert_eq!(report.orders_per_cook, vec![4, 4, 4]);
+    }
+
+    #[test]
+    fn the_cook_free_soonest_takes_the_next_ticket() {
+        let (menu, times) = setup();
+        let mut kitchen = Kitchen::open(3, times, Arc::new(ManualClock::new()));
+        let dishes = ["pizza", "salad", "soda", "soda", "salad", "pizza"];
+        let meals: Vec<Receiver<Meal>> = dishes
+            .iter()
+            .map(|dish| kitchen.submit(order(&menu, "Ada", dish, 1)))
+            .collect();
+        let report = kitchen.close();
+
+        // Cook 3 is free first (3 minutes), then again after the second soda
+        // (6 minutes), so it gets the last pizza while cooks 1 and 2 are busy
+        // until 10 minutes.
+        let served: Vec<(usize, Duration)> = meals
+            .into_iter()
+            .map(|meal| meal.recv().unwrap())
+            .map(|meal| (meal.cook, meal.wait()))
+            .collect();
+        assert_eq!(
+            served,
+            vec![
+                (1, 10 * MINUTE),
+                (2, 5 * MINUTE),
+                (3, 3 * MINUTE),
+                (3, 6 * MINUTE),
+                (2, 10 * MINUTE),
+                (3, 16 * MINUTE),
+            ]
+        );
+        assert_eq!(report.orders_per_cook, vec![1, 2, 3]);
+        assert_eq!(report.longest_wait, 16 * MINUTE);
+    }
+
+    #[test]
+    fn an_empty_service_reports_zeros() {
+        let (_, times) = setup();
+        let report = Kitchen::open(2, times, Arc::new(ManualClock::new())).close();
+        assert_eq!(report.orders, 0);
+        assert_eq!(report.average_wait, Duration::ZERO);
+        assert_eq!(report.orders_per_hour(), 0.0);
+        assert_eq!(report.orders_per_cook, vec![0, 0]);
+    }
+}
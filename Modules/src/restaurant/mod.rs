@@ -1,93 +1,325 @@
 // `mod` keyword defines a module - a namespace for organizing code.
 // Modules provide encapsulation and control visibility of items.
 // `pizza_order` module contains all pizza-related functionality.
-mod pizza_order {
-
-    // `pub` keyword makes the struct visible outside the module.
-    // Without `pub`, Pizza would only be accessible within pizza_order module.
-    // `struct` defines a custom data type representing a pizza.
-    pub struct Pizza {
-        // `pub` on fields makes them directly accessible from outside the struct.
-        // `String` is a heap-allocated, growable, UTF-8 encoded string type.
-        // Each field represents a pizza component.
-        pub dough: String,
-        pub cheese: String,
-        pub topping: String,
-    }
-
-    // `impl` block defines methods associated with the Pizza struct.
-    // All functions here are associated with Pizza type.
-    impl Pizza {
-        // `pub fn` makes this function publicly accessible.
-        // `lunch` is an associated function (no self parameter) - a constructor.
-        // `topping` parameter (note: typo in original "tooping") specifies the topping.
-        // `-> Pizza` specifies the return type.
-        pub fn lunch(topping: String) -> Pizza {
-            // Creates and returns a new Pizza instance.
-            // `String::from()` converts string literals (&str) to owned String.
-            // Field init: when variable name matches field name, shorthand can be used.
-            Pizza {
-                // `String::from("cross")` creates a String from a string literal.
-                // String literals (&str) are immutable and stored in program binary.
-                dough: String::from("regular crust"),
-                cheese: String::from("mozzarella"),
-                // `topping` uses the parameter value passed to the function.
-                // Could also be written as: topping: topping (long form).
-                topping,
+// `pub mod pizza_order;` (with a semicolon) tells Rust to load the module from
+// `restaurant/pizza_order/mod.rs`. `pub` makes it the restaurant's public API.
+pub mod pizza_order;
+
+// `kitchen` lives in `restaurant/kitchen/mod.rs`: the cooks and their queue.
+pub mod kitchen;
+
+// `reservations` lives in `restaurant/reservations.rs`: tables, bookings and the waitlist.
+pub mod reservations;
+
+// `history` lives in `restaurant/history.rs`: the log of served orders and sales reports.
+pub mod history;
+
+// `pub use` makes the front desk part of the restaurant's own API:
+// callers can write `restaurant::Reservations`.
+pub use reservations::{at, Reservations, Seating, TimeSlot};
+
+// `use` shortens the paths used below.
+use std::io;
+use std::path::Path;
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+
+use history::{Date, OrderLog, Record, SalesReport, View};
+use kitchen::{format_minutes, Kitchen, Meal, PrepTimes, SystemClock};
+use pizza_order::help_customer;
+use pizza_order::menu::format_price;
+use pizza_order::{Bill, Category, Discount, Order, Receipt, Tip};
+use pizza_order::{Crust, Pizza, PizzaBuilder, PricingTable, Size, Topping};
+use reservations::{format_time, Minutes};
+
+// Sales tax charged by the restaurant, in basis points (8.25%).
+const TAX_RATE: u32 = 825;
+
+// Cooks working tonight.
+const COOKS: usize = 2;
+
+// How much faster than real time the kitchen clock runs: a 12 minute pizza
+// takes 0.12 seconds.
+const CLOCK_SPEED: u32 = 6000;
+
+// When the demo's dinner service starts, and when the first tables leave.
+const OPENING: Minutes = at(18, 0);
+const FIRST_DEPARTURES: Minutes = at(19, 15);
+
+// A table waiting for its food: the channel the kitchen will answer on, and
+// how the bill should be worked out once the food arrives.
+struct Waiting {
+    meal: Receiver<Meal>,
+    discount: Option<Discount>,
+    tip: Tip,
+}
+
+// `pub fn` defines a public function at the crate root level.
+// This function is the public API for ordering food.
+// Can be called from other modules or external crates.
+pub fn order_food() {
+    // `crate::` is an absolute path starting from the crate root.
+    // `crate` keyword refers to the root of the current crate (binary or library).
+    // Full path: crate → restaurant → pizza_order → Menu (re-exported from `menu`)
+    let menu = crate::restaurant::pizza_order::Menu::standard();
+    println!("{}", menu);
+    println!(
+        "Today we have {} pizzas to choose from.\n",
+        menu.in_category(Category::Pizza).count()
+    );
+    build_your_own();
+
+    let mut desk = Reservations::standard();
+    let okafor = take_booking(&mut desk, "Okafor", 2, TimeSlot::new(OPENING, 90));
+    // Table 1 was just given to the Okafors, so this overlaps and is refused.
+    if let Err(error) = desk.book_table(1, "Smith", 2, TimeSlot::new(at(18, 30), 90)) {
+        println!("Could not book for Smith: {}", error);
+    }
+    // Any free table will do, then; but later the Smiths call to cancel.
+    if let Some(smith) = take_booking(&mut desk, "Smith", 2, TimeSlot::new(at(18, 30), 90)) {
+        // Nobody is waiting yet, so the cancellation seats no one.
+        if let Ok((booking, _)) = desk.cancel(smith, OPENING) {
+            println!("Smith cancelled, so table {} is free again.", booking.table);
+        }
+    }
+
+    // Walk-ins arriving at opening time; the last one has to wait.
+    let mut seated = Vec::new();
+    for (name, party) in [("Garcia", 4), ("Chen", 3), ("Novak", 5), ("Patel", 4)] {
+        match help_customer::seat_at_table(&mut desk, name, party, OPENING) {
+            Ok(Seating::Seated(table)) => seated.push(table),
+            Ok(Seating::Waitlisted(_)) => {}
+            Err(error) => println!("Could not seat {}: {}", name, error),
+        }
+    }
+    // `if let Some(...)` only runs when the booking above succeeded.
+    if let Some(booking) = okafor {
+        match desk.check_in(booking) {
+            Ok(table) => {
+                println!("Okafor checked in at table {}.", table);
+                seated.push(table);
+            }
+            Err(error) => println!("Could not check in Okafor: {}", error),
+        }
+    }
+    println!();
+
+    // `Arc::new(...)` becomes an `Arc<dyn Clock>` when passed to `open`.
+    let clock = Arc::new(SystemClock::new(CLOCK_SPEED));
+    let mut kitchen = Kitchen::open(COOKS, PrepTimes::standard(), clock);
+    let mut tables = Vec::new();
+
+    // Each request is (dish, quantity, extras); `&[]` means no extras.
+    let requests: &[help_customer::Request] = &[
+        ("veggies", 1, &[]),
+        ("pepperoni", 2, &["extra cheese"]),
+        ("garlic bread", 1, &[]),
+        ("lemonade", 3, &[]),
+    ];
+    // `match` on the `Result`: send a good order to the kitchen, report a bad one.
+    match help_customer::take_order(&menu, "Table 3", requests) {
+        Ok(mut order) => {
+            // The customer changes their mind about the garlic bread (line 3).
+            if let Err(error) = order.remove(2) {
+                println!("Could not change the order: {}", error);
+            }
+            // `submit` takes ownership of the order; it comes back with the meal.
+            tables.push(Waiting {
+                meal: kitchen.submit(order),
+                discount: Some(Discount::Percent(1000)),
+                tip: Tip::Percent(1500),
+            });
+        }
+        Err(error) => println!("Could not take the order: {}", error),
+    }
+
+    // A voucher worth $5.00 and a fixed $3.00 tip.
+    let gluten_free: &[help_customer::Request] = &[("margherita", 1, &["gluten-free crust"])];
+    match help_customer::take_order(&menu, "Table 1", gluten_free) {
+        Ok(order) => tables.push(Waiting {
+            meal: kitchen.submit(order),
+            discount: Some(Discount::Amount(500)),
+            tip: Tip::Amount(300),
+        }),
+        Err(error) => println!("Could not take the order: {}", error),
+    }
+
+    // A quick order that the second cook can take while the first is busy.
+    match help_customer::take_order(&menu, "Table 4", &[("tiramisu", 2, &[]), ("cola", 2, &[])]) {
+        Ok(order) => tables.push(Waiting {
+            meal: kitchen.submit(order),
+            discount: None,
+            tip: Tip::Percent(1000),
+        }),
+        Err(error) => println!("Could not take the order: {}", error),
+    }
+
+    // A request that is not on the menu is reported instead of cooked.
+    match help_customer::take_order(&menu, "Table 5", &[("calzone", 1, &[])]) {
+        Ok(order) => tables.push(Waiting {
+            meal: kitchen.submit(order),
+            discount: None,
+            tip: Tip::None,
+        }),
+        Err(error) => println!("Could not take the order: {}", error),
+    }
+    println!();
+
+    // Every served order is added to the log on disk.
+    let log = OrderLog::new(history::DEFAULT_LOG_FILE);
+    let today = Date::today();
+    let mut logged = 0;
+    // Numbering carries on from earlier evenings; an unreadable log starts again at 1.
+    let mut next_order = log.next_order_number().unwrap_or(1);
+
+    // The cooks are already working. `recv()` blocks until that table's meal is ready.
+    for table in tables {
+        match table.meal.recv() {
+            Ok(meal) => {
+                println!(
+                    "Ticket {} for {} is ready after {} ({} in the queue, cook {}).",
+                    meal.ticket,
+                    meal.order.customer(),
+                    format_minutes(meal.wait()),
+                    format_minutes(meal.started_at - meal.placed_at),
+                    meal.cook
+                );
+                let pizzas = help_customer::serve_customer(&meal.order);
+                check_out(&meal.order, table.discount, table.tip);
+                // The kitchen clock started at opening time.
+                let served_at = OPENING + (meal.ready_at.as_secs() / 60) as Minutes;
+                let records =
+                    Record::from_order(next_order, &meal.order, &pizzas, today, served_at);
+                match log.append(&records) {
+                    Ok(()) => {
+                        logged += 1;
+                        next_order += 1;
+                    }
+                    Err(error) => println!("Could not log the order: {}", error),
+                }
             }
+            // The cook dropped the ticket's `Sender` without sending: nothing to serve.
+            Err(_) => println!("An order was lost in the kitchen."),
         }
     }
 
-    // `pub mod` creates a public submodule within pizza_order.
-    // Nested modules help organize related functionality hierarchically.
-    // This module handles customer service operations.
-    pub mod help_customer {
+    // `close` consumes the kitchen: it cannot take orders afterwards.
+    println!("{}\n", kitchen.close());
+    println!(
+        "Logged {} orders to {}; `cargo run -- report` shows the sales so far.\n",
+        logged,
+        log.path().display()
+    );
 
-        // Private function (no `pub` keyword) - only accessible within this module.
-        // `fn` defines a function with no parameters and no return value (returns unit `()`).
-        // Functions without `pub` are module-private by default.
-        fn seat_at_table() {
-            // `println!` macro prints formatted text with a newline to stdout.
-            println!("Customer seated at table.");
+    // Tables free up one after another; the front desk seats the waitlist as they do.
+    for (minutes_later, table) in (0..).step_by(10).zip(seated) {
+        let now = FIRST_DEPARTURES + minutes_later;
+        match desk.leave(table, now) {
+            Ok(newly_seated) => {
+                println!("{} Table {} leaves.", format_time(now), table);
+                for party in newly_seated {
+                    println!(
+                        "{} {} (party of {}) seated from the waitlist at table {}.",
+                        format_time(now),
+                        party.party.name,
+                        party.party.size,
+                        party.table
+                    );
+                }
+            }
+            Err(error) => println!("{}", error),
         }
+    }
+    println!(
+        "{} bookings left on the book, {} parties still waiting.",
+        desk.bookings().len(),
+        desk.waitlist().count()
+    );
+}
+
+// `pub fn`: the sales report for the log at `path`, grouped by each of
+// `views`, printed as text or (with `csv`) as CSV.
+// `io::Result<()>`: reading the log can fail, and the caller decides what to do.
+pub fn print_sales_report(path: &Path, views: &[View], csv: bool) -> io::Result<()> {
+    let history = OrderLog::new(path).load()?;
+    if !history.unreadable.is_empty() {
+        // `eprintln!` writes to stderr, so the CSV on stdout stays clean.
+        eprintln!(
+            "Skipped {} unreadable lines in {}: {:?}",
+            history.unreadable.len(),
+            path.display(),
+            history.unreadable
+        );
+    }
+    if csv {
+        print!("{}", history::to_csv(&history.records, views));
+    } else {
+        print!("{}", SalesReport::new(&history.records, views));
+    }
+    Ok(())
+}
+
+// Private helper: shows custom pizzas being built, priced and stored.
+fn build_your_own() {
+    let prices = PricingTable::standard();
+    println!("Build your own pizza:");
+    let lunch = Pizza::lunch(Topping::Mushroom);
+    println!("  Lunch special: {} - {}", lunch, format_price(prices.price(&lunch)));
 
-        // `pub fn` makes this function callable from outside the module.
-        // This is the main entry point for the ordering process.
-        // No parameters, returns unit type `()`.
-        pub fn take_order() {
-            // Calls the private function within the same module.
-            // No path needed - same module scope.
-            seat_at_table();
-            // `let` declares an immutable variable binding.
-            // `super::Pizza` uses the `super` keyword to access parent module.
-            // `super` refers to pizza_order module (one level up).
-            // `::` is the path separator for navigating module hierarchy.
-            let customer_order: super::Pizza = super::Pizza::lunch(String::from("veggies"));
-            // Calls private function, passing the Pizza instance.
-            // Ownership of customer_order moves to serve_customer.
-            serve_customer(customer_order);
+    // The builder checks the rules in `build`, so both results are `Result`s.
+    let custom = PizzaBuilder::new(Size::Large)
+        .crust(Crust::Thin)
+        .toppings(&[Topping::Ham, Topping::Pineapple, Topping::Olives])
+        .extra_cheese()
+        .build();
+    match custom {
+        Ok(pizza) => {
+            println!("  {} - {}", pizza, format_price(prices.price(&pizza)));
+            // Stored as one line of text, then read back with `parse`.
+            let text = pizza.to_text();
+            println!("  Stored as: {}", text);
+            match text.parse::<Pizza>() {
+                Ok(restored) if restored == pizza => println!("  Read back the same pizza."),
+                Ok(restored) => println!("  Read back a different pizza: {}", restored),
+                Err(error) => println!("  Could not read it back: {}", error),
+            }
         }
+        Err(error) => println!("  Could not make that pizza: {}", error),
+    }
+    let clash = PizzaBuilder::new(Size::Medium)
+        .toppings(&[Topping::Pineapple, Topping::Anchovies])
+        .build();
+    if let Err(error) = clash {
+        println!("  Could not make that pizza: {}", error);
+    }
+    println!();
+}
 
-        // Private function that takes ownership of a Pizza instance.
-        // `cust_pizza: super::Pizza` parameter takes ownership (not borrowed).
-        // When function ends, cust_pizza is dropped (memory cleaned up).
-        fn serve_customer(cust_pizza: super::Pizza) {
-            // `{}` placeholder formats the value using Display trait.
-            // `cust_pizza.topping` accesses the topping field using dot notation.
-            println!("Serving pizza with {} topping.", cust_pizza.topping);
+// Private helper: books the best table for a slot and reports how it went.
+// Returns the booking number, or `None` if nothing was free.
+fn take_booking(desk: &mut Reservations, name: &str, party: u32, slot: TimeSlot) -> Option<u32> {
+    match desk.book(name, party, slot) {
+        Ok(booking) => {
+            println!("Booked table {} for {} (party of {}), {}.", booking.table, name, party, slot);
+            Some(booking.id)
+        }
+        Err(error) => {
+            println!("Could not book for {}: {}", name, error);
+            None
         }
     }
 }
 
-// `pub fn` defines a public function at the crate root level.
-// This function is the public API for ordering food.
-// Can be called from other modules or external crates.
-pub fn order_food() {
-    // `crate::` is an absolute path starting from the crate root.
-    // `crate` keyword refers to the root of the current crate (binary or library).
-    // Full path: crate → restaurant → pizza_order → help_customer → take_order
-    // NOTE: This assumes the module is inside a `restaurant` module at crate root.
-    crate::restaurant::pizza_order::help_customer::take_order();
+// Private helper: prints the bill for a served order.
+// Borrowing `&Order` means the caller keeps ownership of the order.
+fn check_out(order: &Order, discount: Option<Discount>, tip: Tip) {
+    if order.is_empty() {
+        println!("{} ordered nothing, so there is nothing to pay.", order.customer());
+        return;
+    }
+    let bill = Bill::calculate(order, TAX_RATE, discount, tip);
+    println!("{} items for {}:", order.item_count(), order.customer());
+    println!("{}\n", Receipt::new(order, &bill));
 }
 
 // =============================================================================
@@ -115,21 +347,23 @@ pub fn order_food() {
 //    - `self::` refers to current module (usually implicit)
 //
 // 4. PATH EXAMPLES:
-//    - Absolute: `crate::pizza_order::Pizza`
-//    - Relative: `super::Pizza` (from help_customer to pizza_order)
+//    - Absolute: `crate::restaurant::pizza_order::Menu`
+//    - Relative: `super::PizzaBuilder` (from help_customer to pizza_order)
 //    - Nested: `pizza_order::help_customer::take_order`
+//    - Re-exported: `pizza_order::Pizza` is `pizza_order::pizza::Pizza`
 //
 // 5. ASSOCIATED FUNCTIONS VS METHODS:
 //    - Associated function: No `self` parameter
 //      Example: `Pizza::lunch(...)` - called on type
 //    - Method: Has `self`, `&self`, or `&mut self`
-//      Example: `pizza.eat()` - called on instance
+//      Example: `pizza.toppings()` - called on instance
 //
 // 6. OWNERSHIP IN PARAMETERS:
-//    - `serve_customer(cust_pizza: Pizza)` takes ownership
-//    - After call, cust_pizza can't be used by caller
-//    - Alternative: `&Pizza` borrows immutably
-//    - Alternative: `&mut Pizza` borrows mutably
+//    - `serve_customer(customer_order: &Order) -> Vec<Option<Pizza>>` borrows immutably
+//    - After call, the caller still owns the order and can bill it
+//    - The returned `Vec` is owned: each served `Pizza` moves out to the caller
+//    - Alternative: `Order` would take ownership, so the order is gone after serving
+//    - Alternative: `&mut Order` borrows mutably, e.g. to mark lines as served
 //
 // 7. STRING TYPES:
 //    - `String`: Owned, heap-allocated, growable
@@ -137,78 +371,25 @@ pub fn order_food() {
 //    - `String::from("text")` converts &str to String
 //    - `.to_string()` is another way to convert
 //
-// 8. MODULE STRUCTURE ISSUE:
-//    The code has a path mismatch:
-//    - order_food() calls: crate::restaurant::pizza_order::...
-//    - But pizza_order is defined at crate root, not in restaurant
-//    
-//    To fix, either:
-//    A) Wrap in restaurant module:
-//       mod restaurant {
-//           pub mod pizza_order { ... }
-//       }
-//    
-//    B) Change the path in order_food():
-//       crate::pizza_order::help_customer::take_order();
+// 8. MODULE FILES:
+//    - `main.rs` declares `mod restaurant;`, loaded from `restaurant/mod.rs`
+//    - `restaurant/mod.rs` declares `pizza_order`, `kitchen`, `reservations`
+//      and `history`, each loaded from a file or folder next to it
+//    - So `order_food()` can reach the menu as `crate::restaurant::pizza_order::Menu`
 //
 // 9. PRIVACY BOUNDARIES:
-//    - seat_at_table(): Private, only help_customer can call
-//    - serve_customer(): Private, only help_customer can call
+//    - seat_at_table(): Public, order_food() calls it for each walk-in
+//    - serve_customer(): Public, order_food() calls it once the kitchen is done
 //    - take_order(): Public, can be called from anywhere
-//    - Pizza struct: Public, but if fields weren't pub, only lunch() could set them
+//    - Pizza struct: Public, but its fields are private, so code outside
+//      `pizza.rs` can only get a `Pizza` from `PizzaBuilder::build()`,
+//      `Pizza::lunch()` or parsing. The builder checks the rules first, so
+//      every `Pizza` that exists is a valid one
 //
 // 10. TYPICAL MODULE PATTERNS:
 //     - File-based: Each module in separate file (mod.rs or module_name.rs)
-//     - Inline: Modules defined in same file (as shown here)
+//     - Inline: Modules defined in same file (`help_customer` in pizza_order/mod.rs)
 //     - Nested: Submodules for organizing related functionality
 //     - Re-exports: Use `pub use` to flatten deep hierarchies
 //
 // =============================================================================
-
-// CORRECTED VERSION (fixing the path issue):
-/*
-mod restaurant {
-    pub mod pizza_order {
-        pub struct Pizza {
-            pub dough: String,
-            pub cheese: String,
-            pub topping: String,
-        }
-        
-        impl Pizza {
-            pub fn lunch(topping: String) -> Pizza {
-                Pizza {
-                    dough: String::from("regular crust"),
-                    cheese: String::from("mozzarella"),
-                    topping,
-                }
-            }
-        }
-
-        pub mod help_customer {
-            fn seat_at_table() {
-                println!("Customer seated at table.");
-            }
-
-            pub fn take_order() {
-                seat_at_table();
-                let customer_order = super::Pizza::lunch(String::from("veggies"));
-                serve_customer(customer_order);
-            }
-            
-            fn serve_customer(cust_pizza: super::Pizza) {
-                println!("Serving pizza with {} topping.", cust_pizza.topping);
-            }
-        }
-    }
-}
-
-pub fn order_food() {
-    // Now this path is correct
-    crate::restaurant::pizza_order::help_customer::take_order();
-}
-
-fn main() {
-    order_food();
-}
-*/
\ No newline at end of file
@@ -0,0 +1,223 @@
+// `billing` module: turning an order into money owed.
+// The order's subtotal is reduced by any discount, tax is charged on what is
+// left, and the tip is worked out on the subtotal before discount and tax.
+
+use std::fmt;
+
+use super::menu::{format_price, Cents};
+use super::order::Order;
+
+// Rates are given in basis points: hundredths of a percent.
+// 825 basis points = 8.25%. Whole numbers again keep the arithmetic exact.
+pub type BasisPoints = u32;
+
+// A reduction of the bill.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Discount {
+    // A share of the subtotal, e.g. `Percent(1000)` for 10% off.
+    Percent(BasisPoints),
+    // A fixed amount off, never more than the subtotal.
+    Amount(Cents),
+}
+
+// What the customer adds for the staff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tip {
+    None,
+    Percent(BasisPoints),
+    Amount(Cents),
+}
+
+// The money side of an order, every part already rounded to whole cents.
+// `total = subtotal - discount + tax + tip`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bill {
+    pub subtotal: Cents,
+    pub discount: Cents,
+    pub tax: Cents,
+    pub tip: Cents,
+    pub total: Cents,
+    // The rules used, kept so a receipt can show them.
+    pub tax_rate: BasisPoints,
+    pub discount_rule: Option<Discount>,
+    pub tip_rule: Tip,
+}
+
+impl Bill {
+    // Works out the bill for `order`.
+    //
+    // # Arguments
+    // * `tax_rate` - Sales tax in basis points, charged after the discount
+    // * `discount` - `None` for no discount
+    // * `tip` - Calculated on the subtotal, before discount and tax
+    pub fn calculate(
+        order: &Order,
+        tax_rate: BasisPoints,
+        discount: Option<Discount>,
+        tip: Tip,
+    ) -> Bill {
+        let subtotal = order.subtotal();
+        // `match` on `Option<Discount>` covers "no discount" and both kinds at once.
+        let discount_amount = match discount {
+            None => 0,
+            Some(Discount::Percent(rate)) => percent_of(subtotal, rate),
+            // `.min(subtotal)` stops a voucher from making the bill negative.
+            Some(Discount::Amount(amount)) => amount,
+        }
+        .min(subtotal);
+        let taxable = subtotal - discount_amount;
+        let tax = percent_of(taxable, tax_rate);
+        let tip_amount = match tip {
+            Tip::None => 0,
+            Tip::Percent(rate) => percent_of(subtotal, rate),
+            Tip::Amount(amount) => amount,
+        };
+
+        Bill {
+            subtotal,
+            discount: discount_amount,
+            tax,
+            tip: tip_amount,
+            total: taxable + tax + tip_amount,
+            tax_rate,
+            discount_rule: discount,
+            tip_rule: tip,
+        }
+    }
+}
+
+// `rate` basis points of `amount`, rounded to the nearest cent (halves round up).
+// Adding 5_000 before dividing by 10_000 does the rounding in integer arithmetic.
+pub fn percent_of(amount: Cents, rate: BasisPoints) -> Cents {
+    (amount * Cents::from(rate) + 5_000) / 10_000
+}
+
+// Formats a rate, e.g. 825 -> "8.25%", 1000 -> "10%".
+pub fn format_rate(rate: BasisPoints) -> String {
+    if rate.is_multiple_of(100) {
+        format!("{}%", rate / 100)
+    } else {
+        // `trim_end_matches('0')` turns "8.50" into "8.5".
+        let text = format!("{}.{:02}", rate / 100, rate % 100);
+        format!("{}%", text.trim_end_matches('0'))
+    }
+}
+
+// A printable receipt. It borrows the order and bill (`'a` is their lifetime),
+// so making a receipt copies nothing.
+pub struct Receipt<'a> {
+    order: &'a Order,
+    bill: &'a Bill,
+}
+
+impl<'a> Receipt<'a> {
+    pub fn new(order: &'a Order, bill: &'a Bill) -> Receipt<'a> {
+        Receipt { order, bill }
+    }
+}
+
+// Width of a receipt line, in characters.
+const RECEIPT_WIDTH: usize = 40;
+
+impl fmt::Display for Receipt<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let double_rule = "=".repeat(RECEIPT_WIDTH);
+        let rule = "-".repeat(RECEIPT_WIDTH);
+        // An inner function: writes a label on the left and an amount on the right.
+        fn line(f: &mut fmt::Formatter, label: &str, amount: &str) -> fmt::Result {
+            writeln!(f, "{:<width$}{:>12}", label, amount, width = RECEIPT_WIDTH - 12)
+        }
+
+        writeln!(f, "{}", double_rule)?;
+        writeln!(f, "RECEIPT for {}", self.order.customer())?;
+        writeln!(f, "{}", rule)?;
+        for entry in self.order.lines() {
+            let label = format!("{} x {}", entry.quantity, entry.item.name);
+            line(f, &label, &format_price(entry.total()))?;
+            for modifier in &entry.modifiers {
+                writeln!(f, "    + {}", modifier.name)?;
+            }
+        }
+        writeln!(f, "{}", rule)?;
+
+        let bill = self.bill;
+        line(f, "Subtotal", &format_price(bill.subtotal))?;
+        if let Some(rule) = bill.discount_rule {
+            let label = match rule {
+                Discount::Percent(rate) => format!("Discount ({})", format_rate(rate)),
+                Discount::Amount(_) => String::from("Discount"),
+            };
+            line(f, &label, &format!("-{}", format_price(bill.discount)))?;
+        }
+        line(f, &format!("Tax ({})", format_rate(bill.tax_rate)), &format_price(bill.tax))?;
+        match bill.tip_rule {
+            Tip::None => {}
+            Tip::Percent(rate) => line(f, &format!("Tip ({})", format_rate(rate)), &format_price(bill.tip))?,
+            Tip::Amount(_) => line(f, "Tip", &format_price(bill.tip))?,
+        }
+        writeln!(f, "{}", rule)?;
+        line(f, "TOTAL", &format_price(bill.total))?;
+        write!(f, "{}", double_rule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::restaurant::pizza_order::menu::{Category, Menu};
+
+    // An order with a subtotal of $33.33.
+    fn order() -> Order {
+        let mut menu = Menu::new();
+        menu.add_item("pizza", 1111, Category::Pizza);
+        let mut order = Order::new("Ada");
+        order.add(&menu, "pizza", 3, &[]).unwrap();
+        order
+    }
+
+    #[test]
+    fn rounds_rates_to_the_nearest_cent() {
+        assert_eq!(percent_of(3333, 825), 275); // 274.97
+        assert_eq!(percent_of(1000, 5), 1); // exactly half a cent rounds up
+        assert_eq!(percent_of(1000, 4), 0);
+        assert_eq!(percent_of(0, 825), 0);
+    }
+
+    #[test]
+    fn taxes_after_the_discount_and_tips_before_it() {
+        let bill = Bill::calculate(&order(), 1000, Some(Discount::Percent(5000)), Tip::Percent(2000));
+        assert_eq!(bill.subtotal, 3333);
+        assert_eq!(bill.discount, 1667);
+        assert_eq!(bill.tax, 167); // 10% of 16.66
+        assert_eq!(bill.tip, 667); // 20% of 33.33
+        assert_eq!(bill.total, 3333 - 1667 + 167 + 667);
+    }
+
+    #[test]
+    fn fixed_discounts_never_go_below_zero() {
+        let bill = Bill::calculate(&order(), 825, Some(Discount::Amount(5000)), Tip::Amount(200));
+        assert_eq!(bill.discount, 3333);
+        assert_eq!(bill.tax, 0);
+        assert_eq!(bill.total, 200);
+    }
+
+    #[test]
+    fn formats_rates_without_trailing_zeros() {
+        assert_eq!(format_rate(825), "8.25%");
+        assert_eq!(format_rate(850), "8.5%");
+        assert_eq!(format_rate(1000), "10%");
+        assert_eq!(format_rate(5), "0.05%");
+    }
+
+    #[test]
+    fn receipt_lists_lines_and_totals() {
+        let order = order();
+        let bill = Bill::calculate(&order, 825, None, Tip::None);
+        let receipt = Receipt::new(&order, &bill).to_string();
+        assert!(receipt.contains("RECEIPT for Ada"));
+        assert!(receipt.contains("3 x pizza"));
+        assert!(receipt.contains("Tax (8.25%)"));
+        assert!(!receipt.contains("Tip"));
+        assert!(receipt.lines().any(|line| line.starts_with("TOTAL") && line.ends_with("$36.08")));
+    }
+}
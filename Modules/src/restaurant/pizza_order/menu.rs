@@ -0,0 +1,190 @@
+// `menu` module: what the restaurant sells and what it costs.
+// This file is the body of `mod menu;` declared in `pizza_order/mod.rs`.
+// File-based modules keep each part of the domain in its own file,
+// while the `mod` declarations still decide how they nest.
+
+// `use` brings the `fmt` module into scope so we can write `fmt::Display`.
+use std::fmt;
+
+use super::pizza::PricingTable;
+
+// Prices are whole cents (`u64`) everywhere in the restaurant.
+// Integers avoid floating-point rounding errors like 0.1 + 0.2 != 0.3.
+pub type Cents = u64;
+
+// `enum` lists every kind of dish on the menu.
+// `#[derive(...)]` asks the compiler to write common trait impls for us:
+// `Copy` lets a Category be duplicated implicitly, like an integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Category {
+    Pizza,
+    Side,
+    Drink,
+    Dessert,
+}
+
+impl Category {
+    // An associated constant: every category in menu order.
+    pub const ALL: [Category; 4] = [
+        Category::Pizza,
+        Category::Side,
+        Category::Drink,
+        Category::Dessert,
+    ];
+}
+
+// Implementing `Display` lets `{}` print a category.
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Category::Pizza => "Pizzas",
+            Category::Side => "Sides",
+            Category::Drink => "Drinks",
+            Category::Dessert => "Desserts",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// One dish on the menu.
+// `pub` on the struct and its fields: callers can read them directly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuItem {
+    pub name: String,
+    pub price: Cents,
+    pub category: Category,
+}
+
+// A change a customer can ask for, such as "extra cheese".
+// The price is added to the item's price for every unit ordered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Modifier {
+    pub name: String,
+    pub price: Cents,
+}
+
+// The whole menu: dishes plus the modifiers that can be applied to them.
+// Fields are private (no `pub`), so items can only be added through `add_item`,
+// which keeps names unique.
+#[derive(Debug, Clone, Default)]
+pub struct Menu {
+    items: Vec<MenuItem>,
+    modifiers: Vec<Modifier>,
+}
+
+impl Menu {
+    // An empty menu. `Self` is shorthand for the type being implemented (`Menu`).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The house menu used by `order_food`.
+    pub fn standard() -> Menu {
+        let mut menu = Menu::new();
+        // A slice of tuples keeps the data compact; the loop turns each into a `MenuItem`.
+        // Pizzas are priced by the pricing table, so a house pizza costs the
+        // same on the menu as when it is built with `PizzaBuilder::house`.
+        let table = PricingTable::standard();
+        for name in ["veggies", "margherita", "pepperoni", "mushroom"] {
+            let price = table.house_price(name).expect("every house pizza is valid");
+            menu.add_item(name, price, Category::Pizza);
+        }
+        let items: &[(&str, Cents, Category)] = &[
+            ("garlic bread", 450, Category::Side),
+            ("caesar salad", 650, Category::Side),
+            ("lemonade", 300, Category::Drink),
+            ("cola", 250, Category::Drink),
+            ("tiramisu", 550, Category::Dessert),
+        ];
+        for &(name, price, category) in items {
+            // `add_item` only fails on duplicate names, which this list does not have.
+            menu.add_item(name, price, category);
+        }
+        menu.add_modifier("extra cheese", 150);
+        menu.add_modifier("gluten-free crust", 300);
+        menu.add_modifier("no onions", 0);
+        menu
+    }
+
+    // Adds a dish. Returns `false` (and changes nothing) if the name is already taken.
+    // Names are compared without regard to case: "Cola" and "cola" are the same dish.
+    pub fn add_item(&mut self, name: &str, price: Cents, category: Category) -> bool {
+        if self.item(name).is_some() {
+            return false;
+        }
+        self.items.push(MenuItem {
+            name: name.to_string(),
+            price,
+            category,
+        });
+        true
+    }
+
+    // Adds a modifier, replacing the price of one with the same name.
+    pub fn add_modifier(&mut self, name: &str, price: Cents) {
+        // `iter_mut().find(...)` borrows the matching element mutably, if any.
+        match self
+            .modifiers
+            .iter_mut()
+            .find(|modifier| modifier.name.eq_ignore_ascii_case(name))
+        {
+            Some(existing) => existing.price = price,
+            None => self.modifiers.push(Modifier {
+                name: name.to_string(),
+                price,
+            }),
+        }
+    }
+
+    // Looks up a dish by name. `Option<&MenuItem>` borrows it from the menu.
+    pub fn item(&self, name: &str) -> Option<&MenuItem> {
+        self.items
+            .iter()
+            .find(|item| item.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    // Looks up a modifier by name.
+    pub fn modifier(&self, name: &str) -> Option<&Modifier> {
+        self.modifiers
+            .iter()
+            .find(|modifier| modifier.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    // Every dish in one category, in the order they were added.
+    // `impl Iterator` returns a lazy iterator without naming its exact type.
+    pub fn in_category(&self, category: Category) -> impl Iterator<Item = &MenuItem> {
+        self.items
+            .iter()
+            .filter(move |item| item.category == category)
+    }
+}
+
+// Printing a menu lists each category with its dishes, then the modifiers.
+impl fmt::Display for Menu {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for category in Category::ALL {
+            // `peekable` lets us skip the heading of an empty category.
+            let mut items = self.in_category(category).peekable();
+            if items.peek().is_none() {
+                continue;
+            }
+            writeln!(f, "{}:", category)?;
+            for item in items {
+                writeln!(f, "  {:<20}{:>8}", item.name, format_price(item.price))?;
+            }
+        }
+        if !self.modifiers.is_empty() {
+            writeln!(f, "Extras:")?;
+            for modifier in &self.modifiers {
+                writeln!(f, "  {:<20}{:>8}", modifier.name, format_price(modifier.price))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Formats cents as dollars, e.g. 1250 -> "$12.50".
+pub fn format_price(cents: Cents) -> String {
+    // `/` and `%` split the cents into whole dollars and the remainder.
+    format!("${}.{:02}", cents / 100, cents % 100)
+}
@@ -0,0 +1,134 @@
+// `pizza_order` module: the front of house - menu, orders, bills and the
+// staff who help customers. This file is the body of `pub mod pizza_order;`
+// in `restaurant/mod.rs`.
+
+// Submodules in their own files next to this `mod.rs`: `menu.rs`, `order.rs`,
+// `billing.rs` and `pizza.rs`. `pub mod` makes each one part of the public API.
+pub mod billing;
+pub mod menu;
+pub mod order;
+pub mod pizza;
+
+// `pub use` re-exports the most used types, so callers can write
+// `pizza_order::Menu` instead of `pizza_order::menu::Menu`.
+pub use billing::{Bill, Discount, Receipt, Tip};
+pub use menu::{Category, Menu};
+pub use order::Order;
+pub use pizza::{Crust, Pizza, PizzaBuilder, PricingTable, Size, Topping};
+
+// `pub mod` creates a public submodule within pizza_order.
+// Nested modules help organize related functionality hierarchically.
+// This module handles customer service operations.
+pub mod help_customer {
+    // `use` inside a module brings items from sibling modules into scope here.
+    // `super::menu` is `pizza_order::menu`, one level up and then down.
+    use super::menu::{Category, Menu};
+    use super::order::{Order, OrderError};
+    // `crate::` paths work from anywhere: the front desk is a sibling of `pizza_order`.
+    use crate::restaurant::reservations::{Minutes, Reservations, Seating, SeatingError};
+
+    // One thing a customer asks for: the dish, how many, and any extras.
+    // A `type` alias gives a long tuple type a readable name.
+    // `'a` says the borrowed strings must live at least as long as the request.
+    pub type Request<'a> = (&'a str, u32, &'a [&'a str]);
+
+    // Seats a walk-in party through the front desk, which gives them the table
+    // that fits best or puts them on the waitlist.
+    // `&mut Reservations` lends the desk to us mutably just for this call.
+    pub fn seat_at_table(
+        desk: &mut Reservations,
+        name: &str,
+        party: u32,
+        now: Minutes,
+    ) -> Result<Seating, SeatingError> {
+        let seating = desk.walk_in(name, party, now)?;
+        // `println!` macro prints formatted text with a newline to stdout.
+        match seating {
+            Seating::Seated(table) => {
+                println!("{} (party of {}) seated at table {}.", name, party, table)
+            }
+            Seating::Waitlisted(position) => println!(
+                "{} (party of {}) is number {} on the waitlist.",
+                name, party, position
+            ),
+        }
+        Ok(seating)
+    }
+
+    // `pub fn` makes this function callable from outside the module.
+    // This is the main entry point for the ordering process: it checks every
+    // request of a seated customer against the menu.
+    // Returns the `Order` for the kitchen, or the first request that could
+    // not be taken.
+    pub fn take_order(
+        menu: &Menu,
+        customer: &str,
+        requests: &[Request],
+    ) -> Result<Order, OrderError> {
+        // `let mut` because the order grows as each request is added.
+        let mut customer_order = Order::new(customer);
+        // `&(name, ...)` destructures each borrowed tuple into its parts.
+        for &(name, quantity, modifiers) in requests {
+            // `?` hands an `OrderError` straight back to our caller.
+            customer_order.add(menu, name, quantity, modifiers)?;
+        }
+        Ok(customer_order)
+    }
+
+    // Brings the food to the table once the kitchen has made it.
+    // Borrows the order (`&Order`), so it can still be billed afterwards.
+    // Returns what was made for each line, in order: the `Pizza` for a pizza
+    // line, `None` for everything else, so the caller can keep a record.
+    pub fn serve_customer(customer_order: &Order) -> Vec<Option<super::Pizza>> {
+        let mut served = Vec::new();
+        for line in customer_order.lines() {
+            // `super::PizzaBuilder` uses the `super` keyword to access parent module.
+            // `house` only knows the menu's pizzas; anything else is served as it is.
+            let house_pizza = match line.item.category {
+                Category::Pizza => super::PizzaBuilder::house(&line.item.name),
+                _ => None,
+            };
+            let Some(mut builder) = house_pizza else {
+                println!("Serving {} {}.", line.quantity, line.item.name);
+                served.push(None);
+                continue;
+            };
+            // The extras the customer asked for change how the pizza is made.
+            for modifier in &line.modifiers {
+                match modifier.name.as_str() {
+                    "extra cheese" => builder = builder.extra_cheese(),
+                    "gluten-free crust" => builder = builder.crust(super::Crust::GlutenFree),
+                    _ => {}
+                }
+            }
+            // `{}` placeholder formats the value using Display trait.
+            match builder.build() {
+                Ok(pizza) => {
+                    println!("Serving {} x {}.", line.quantity, pizza);
+                    served.push(Some(pizza));
+                }
+                Err(error) => {
+                    println!("Could not make the {}: {}", line.item.name, error);
+                    served.push(None);
+                }
+            }
+        }
+        served
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn takes_every_request_or_reports_the_first_bad_one() {
+            let menu = Menu::standard();
+            let order = take_order(&menu, "Ada", &[("veggies", 2, &[]), ("cola", 1, &[])]).unwrap();
+            assert_eq!(order.customer(), "Ada");
+            assert_eq!(order.item_count(), 3);
+
+            let error = take_order(&menu, "Ada", &[("cola", 1, &[]), ("calzone", 1, &[])]);
+            assert_eq!(error, Err(OrderError::UnknownItem(String::from("calzone"))));
+        }
+    }
+}
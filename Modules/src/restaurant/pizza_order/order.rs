@@ -0,0 +1,230 @@
+// `order` module: the customer's cart.
+// An `Order` is a list of line items; each line is a dish from the menu,
+// how many of it, and any modifiers such as "extra cheese".
+
+use std::error::Error;
+use std::fmt;
+
+// `super::` reaches the parent module (`pizza_order`), then down into `menu`.
+use super::menu::{Cents, Menu, MenuItem, Modifier};
+
+// Everything that can go wrong while building an order.
+// Returning `Result<_, OrderError>` lets the caller decide how to react.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderError {
+    // The dish is not on the menu.
+    UnknownItem(String),
+    // The modifier is not on the menu.
+    UnknownModifier(String),
+    // Asked for zero of something.
+    ZeroQuantity,
+    // Tried to remove a line that does not exist.
+    NoSuchLine(usize),
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderError::UnknownItem(name) => write!(f, "'{}' is not on the menu", name),
+            OrderError::UnknownModifier(name) => write!(f, "'{}' is not an available extra", name),
+            OrderError::ZeroQuantity => write!(f, "quantity must be at least 1"),
+            OrderError::NoSuchLine(index) => write!(f, "the order has no line {}", index + 1),
+        }
+    }
+}
+
+// An empty `impl` is enough: `Error` only requires `Debug` and `Display`.
+impl Error for OrderError {}
+
+// One line of an order: `quantity` units of `item`, each with the same `modifiers`.
+// The line keeps its own copy of the item and modifiers, so later menu price
+// changes do not alter orders already taken.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineItem {
+    pub item: MenuItem,
+    pub quantity: u32,
+    pub modifiers: Vec<Modifier>,
+}
+
+impl LineItem {
+    // Price of a single unit: the dish plus its modifiers.
+    pub fn unit_price(&self) -> Cents {
+        // `iter().map(...).sum()` adds up all modifier prices.
+        self.item.price + self.modifiers.iter().map(|modifier| modifier.price).sum::<Cents>()
+    }
+
+    // Price of the whole line.
+    pub fn total(&self) -> Cents {
+        self.unit_price() * Cents::from(self.quantity)
+    }
+
+    // True if this line is for the same dish with the same modifiers.
+    fn same_dish(&self, item: &MenuItem, modifiers: &[Modifier]) -> bool {
+        self.item == *item && self.modifiers == modifiers
+    }
+}
+
+// A customer's order. Fields are private: lines can only be changed through
+// `add` and `remove`, which check everything against the menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Order {
+    customer: String,
+    lines: Vec<LineItem>,
+}
+
+impl Order {
+    // An empty order for `customer`.
+    pub fn new(customer: &str) -> Order {
+        Order {
+            customer: customer.to_string(),
+            lines: Vec::new(),
+        }
+    }
+
+    pub fn customer(&self) -> &str {
+        &self.customer
+    }
+
+    // The lines as a slice: callers can read them but not change them.
+    pub fn lines(&self) -> &[LineItem] {
+        &self.lines
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    // Total number of units across all lines.
+    pub fn item_count(&self) -> u32 {
+        self.lines.iter().map(|line| line.quantity).sum()
+    }
+
+    // Sum of all line totals, before discounts, tax and tip.
+    pub fn subtotal(&self) -> Cents {
+        self.lines.iter().map(LineItem::total).sum()
+    }
+
+    // Adds `quantity` of the dish called `name`, each with the named modifiers.
+    // Ordering the same dish with the same modifiers again adds to the existing line.
+    //
+    // `ok_or_else` turns a missing lookup (`None`) into an error, and `?`
+    // returns that error to the caller straight away.
+    pub fn add(
+        &mut self,
+        menu: &Menu,
+        name: &str,
+        quantity: u32,
+        modifiers: &[&str],
+    ) -> Result<(), OrderError> {
+        if quantity == 0 {
+            return Err(OrderError::ZeroQuantity);
+        }
+        let item = menu
+            .item(name)
+            .ok_or_else(|| OrderError::UnknownItem(name.to_string()))?
+            .clone();
+        let mut chosen = Vec::new();
+        for &modifier_name in modifiers {
+            let modifier = menu
+                .modifier(modifier_name)
+                .ok_or_else(|| OrderError::UnknownModifier(modifier_name.to_string()))?;
+            // Asking for the same extra twice is treated as asking once.
+            if !chosen.contains(modifier) {
+                chosen.push(modifier.clone());
+            }
+        }
+        // Sorting makes ["a", "b"] and ["b", "a"] the same set of modifiers.
+        chosen.sort_by(|a, b| a.name.cmp(&b.name));
+
+        match self
+            .lines
+            .iter_mut()
+            .find(|line| line.same_dish(&item, &chosen))
+        {
+            Some(line) => line.quantity += quantity,
+            None => self.lines.push(LineItem {
+                item,
+                quantity,
+                modifiers: chosen,
+            }),
+        }
+        Ok(())
+    }
+
+    // Removes line number `index` (counting from 0) and hands it back.
+    pub fn remove(&mut self, index: usize) -> Result<LineItem, OrderError> {
+        if index >= self.lines.len() {
+            return Err(OrderError::NoSuchLine(index));
+        }
+        Ok(self.lines.remove(index))
+    }
+}
+
+// `#[cfg(test)]` compiles this module only for `cargo test`.
+#[cfg(test)]
+mod tests {
+    // `super::*` imports everything from the parent module (`order`).
+    use super::*;
+    use crate::restaurant::pizza_order::menu::Category;
+
+    fn menu() -> Menu {
+        let mut menu = Menu::new();
+        menu.add_item("pepperoni", 1200, Category::Pizza);
+        menu.add_item("cola", 250, Category::Drink);
+        menu.add_modifier("extra cheese", 150);
+        menu.add_modifier("no onions", 0);
+        menu
+    }
+
+    #[test]
+    fn prices_lines_with_quantities_and_modifiers() {
+        let menu = menu();
+        let mut order = Order::new("Ada");
+        order.add(&menu, "pepperoni", 2, &["extra cheese"]).unwrap();
+        order.add(&menu, "Cola", 3, &[]).unwrap();
+
+        assert_eq!(order.lines()[0].unit_price(), 1350);
+        assert_eq!(order.lines()[0].total(), 2700);
+        assert_eq!(order.item_count(), 5);
+        assert_eq!(order.subtotal(), 2700 + 750);
+    }
+
+    #[test]
+    fn merges_identical_dishes_but_not_different_modifiers() {
+        let menu = menu();
+        let mut order = Order::new("Ada");
+        order.add(&menu, "pepperoni", 1, &["extra cheese", "no onions"]).unwrap();
+        order.add(&menu, "pepperoni", 1, &["no onions", "extra cheese"]).unwrap();
+        order.add(&menu, "pepperoni", 1, &[]).unwrap();
+
+        assert_eq!(order.lines().len(), 2);
+        assert_eq!(order.lines()[0].quantity, 2);
+        assert_eq!(order.lines()[1].quantity, 1);
+    }
+
+    #[test]
+    fn rejects_unknown_dishes_modifiers_and_zero_quantities() {
+        let menu = menu();
+        let mut order = Order::new("Ada");
+        assert_eq!(
+            order.add(&menu, "calzone", 1, &[]),
+            Err(OrderError::UnknownItem(String::from("calzone")))
+        );
+        assert_eq!(
+            order.add(&menu, "cola", 1, &["ice"]),
+            Err(OrderError::UnknownModifier(String::from("ice")))
+        );
+        assert_eq!(order.add(&menu, "cola", 0, &[]), Err(OrderError::ZeroQuantity));
+        assert!(order.is_empty());
+    }
+
+    #[test]
+    fn removes_lines_by_index() {
+        let menu = menu();
+        let mut order = Order::new("Ada");
+        order.add(&menu, "cola", 1, &[]).unwrap();
+        assert_eq!(order.remove(1), Err(OrderError::NoSuchLine(1)));
+        assert_eq!(order.remove(0).unwrap().item.name, "cola");
+        assert_eq!(order.subtotal(), 0);
+    }
+}
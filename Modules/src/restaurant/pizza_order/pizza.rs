@@ -0,0 +1,713 @@
+// `pizza` module: what goes on a pizza, and what it costs.
+// A `Pizza` can only be made through a `PizzaBuilder`, which checks the rules
+// (how many toppings, which ingredients go together) before handing one out.
+// A `PricingTable` prices a pizza part by part, and pizzas can be written to
+// and read back from one line of text so orders can be stored.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+// `FromStr` is the trait behind `str::parse`: implementing it for `Pizza`
+// lets us write `"size=small;...".parse::<Pizza>()`.
+use std::str::FromStr;
+
+use super::menu::Cents;
+
+// No pizza may carry more toppings than this.
+pub const MAX_TOPPINGS: usize = 5;
+
+// A trait shared by the ingredient enums below.
+// Implementors only list their variants and names; `from_name` comes for free
+// as a default method that searches `ALL`.
+pub trait Named: Sized + Copy + 'static {
+    // Every variant, in display order.
+    const ALL: &'static [Self];
+
+    // The lowercase name used in text and on screen.
+    fn name(&self) -> &'static str;
+
+    // The variant called `name`, ignoring case.
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|variant| variant.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Size {
+    Small,
+    Medium,
+    Large,
+}
+
+impl Named for Size {
+    const ALL: &'static [Size] = &[Size::Small, Size::Medium, Size::Large];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Size::Small => "small",
+            Size::Medium => "medium",
+            Size::Large => "large",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Crust {
+    Regular,
+    Thin,
+    Stuffed,
+    GlutenFree,
+}
+
+impl Named for Crust {
+    const ALL: &'static [Crust] = &[
+        Crust::Regular,
+        Crust::Thin,
+        Crust::Stuffed,
+        Crust::GlutenFree,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Crust::Regular => "regular",
+            Crust::Thin => "thin",
+            Crust::Stuffed => "stuffed",
+            Crust::GlutenFree => "gluten-free",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cheese {
+    Mozzarella,
+    Cheddar,
+    Parmesan,
+    Vegan,
+    // A pizza without cheese is still a pizza. (`Cheese::None` is this enum's
+    // own variant, not `Option::None`.)
+    None,
+}
+
+impl Named for Cheese {
+    const ALL: &'static [Cheese] = &[
+        Cheese::Mozzarella,
+        Cheese::Cheddar,
+        Cheese::Parmesan,
+        Cheese::Vegan,
+        Cheese::None,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Cheese::Mozzarella => "mozzarella",
+            Cheese::Cheddar => "cheddar",
+            Cheese::Parmesan => "parmesan",
+            Cheese::Vegan => "vegan",
+            Cheese::None => "none",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Topping {
+    Pepperoni,
+    Ham,
+    Sausage,
+    Anchovies,
+    Mushroom,
+    Onion,
+    Peppers,
+    Olives,
+    Pineapple,
+    Tomato,
+    Basil,
+    Spinach,
+}
+
+impl Named for Topping {
+    const ALL: &'static [Topping] = &[
+        Topping::Pepperoni,
+        Topping::Ham,
+        Topping::Sausage,
+        Topping::Anchovies,
+        Topping::Mushroom,
+        Topping::Onion,
+        Topping::Peppers,
+        Topping::Olives,
+        Topping::Pineapple,
+        Topping::Tomato,
+        Topping::Basil,
+        Topping::Spinach,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Topping::Pepperoni => "pepperoni",
+            Topping::Ham => "ham",
+            Topping::Sausage => "sausage",
+            Topping::Anchovies => "anchovies",
+            Topping::Mushroom => "mushroom",
+            Topping::Onion => "onion",
+            Topping::Peppers => "peppers",
+            Topping::Olives => "olives",
+            Topping::Pineapple => "pineapple",
+            Topping::Tomato => "tomato",
+            Topping::Basil => "basil",
+            Topping::Spinach => "spinach",
+        }
+    }
+}
+
+// One generic helper for all four enums: anything `Named` prints its name.
+// Each enum's `Display` just forwards to it.
+fn write_name(f: &mut fmt::Formatter, named: &impl Named) -> fmt::Result {
+    write!(f, "{}", named.name())
+}
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_name(f, self)
+    }
+}
+
+impl fmt::Display for Crust {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_name(f, self)
+    }
+}
+
+impl fmt::Display for Cheese {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_name(f, self)
+    }
+}
+
+impl fmt::Display for Topping {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_name(f, self)
+    }
+}
+
+// Any one choice made on a pizza, so the rules can name what clashes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    Size(Size),
+    Crust(Crust),
+    Cheese(Cheese),
+    ExtraCheese,
+    Topping(Topping),
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::Size(size) => write!(f, "{} size", size),
+            Part::Crust(crust) => write!(f, "{} crust", crust),
+            Part::Cheese(Cheese::None) => write!(f, "no cheese"),
+            Part::Cheese(cheese) => write!(f, "{} cheese", cheese),
+            Part::ExtraCheese => write!(f, "extra cheese"),
+            Part::Topping(topping) => write!(f, "{}", topping),
+        }
+    }
+}
+
+// Pairs of parts that cannot go on the same pizza.
+// Keeping the rules as data means adding one is a one-line change.
+const INCOMPATIBLE: &[(Part, Part)] = &[
+    // Vegan cheese is for vegan pizzas.
+    (
+        Part::Cheese(Cheese::Vegan),
+        Part::Topping(Topping::Pepperoni),
+    ),
+    (Part::Cheese(Cheese::Vegan), Part::Topping(Topping::Ham)),
+    (Part::Cheese(Cheese::Vegan), Part::Topping(Topping::Sausage)),
+    (
+        Part::Cheese(Cheese::Vegan),
+        Part::Topping(Topping::Anchovies),
+    ),
+    (Part::Cheese(Cheese::None), Part::ExtraCheese),
+    // A small base is too small to stuff.
+    (Part::Crust(Crust::Stuffed), Part::Size(Size::Small)),
+    // The kitchen's one firm opinion.
+    (
+        Part::Topping(Topping::Pineapple),
+        Part::Topping(Topping::Anchovies),
+    ),
+];
+
+// Why a pizza could not be made (or read back from text).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PizzaError {
+    TooManyToppings { count: usize, max: usize },
+    DuplicateTopping(Topping),
+    Incompatible(Part, Part),
+    // The text is not a pizza; the message says what was wrong with it.
+    Parse(String),
+}
+
+impl fmt::Display for PizzaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PizzaError::TooManyToppings { count, max } => {
+                write!(f, "{} toppings is too many (at most {})", count, max)
+            }
+            PizzaError::DuplicateTopping(topping) => {
+                write!(f, "{} is already on the pizza", topping)
+            }
+            PizzaError::Incompatible(first, second) => {
+                write!(f, "{} and {} do not go together", first, second)
+            }
+            PizzaError::Parse(message) => write!(f, "not a pizza: {}", message),
+        }
+    }
+}
+
+impl Error for PizzaError {}
+
+// `pub` keyword makes the struct visible outside the module.
+// The fields are private, unlike the first version of `Pizza`: only
+// `PizzaBuilder::build` can make one, so every `Pizza` has passed the rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pizza {
+    size: Size,
+    crust: Crust,
+    cheese: Cheese,
+    extra_cheese: bool,
+    toppings: Vec<Topping>,
+}
+
+// `impl` block defines methods associated with the Pizza struct.
+impl Pizza {
+    // `lunch` is an associated function (no self parameter) - a constructor.
+    // The lunch special: a medium pizza on regular crust with mozzarella
+    // and the one `topping` the customer picks.
+    pub fn lunch(topping: Topping) -> Pizza {
+        // One topping on the default base breaks no rule, so `expect` cannot fail.
+        PizzaBuilder::new(Size::Medium)
+            .topping(topping)
+            .build()
+            .expect("the lunch special is always valid")
+    }
+
+    pub fn toppings(&self) -> &[Topping] {
+        &self.toppings
+    }
+
+    // Writes the pizza as one line of `key=value` pairs, e.g.
+    // `size=medium;crust=thin;cheese=mozzarella;extra=no;toppings=ham,olives`.
+    // `str::parse` reads it back (see `FromStr` below).
+    pub fn to_text(&self) -> String {
+        // `map(...).collect::<Vec<_>>().join(",")` makes "ham,olives".
+        let toppings: Vec<&str> = self.toppings.iter().map(Topping::name).collect();
+        format!(
+            "size={};crust={};cheese={};extra={};toppings={}",
+            self.size,
+            self.crust,
+            self.cheese,
+            if self.extra_cheese { "yes" } else { "no" },
+            toppings.join(",")
+        )
+    }
+
+    // True if `part` is one of this pizza's choices.
+    fn has(&self, part: Part) -> bool {
+        match part {
+            Part::Size(size) => self.size == size,
+            Part::Crust(crust) => self.crust == crust,
+            Part::Cheese(cheese) => self.cheese == cheese,
+            Part::ExtraCheese => self.extra_cheese,
+            Part::Topping(topping) => self.toppings.contains(&topping),
+        }
+    }
+}
+
+// A human-readable description, e.g.
+// "medium pizza on thin crust with mozzarella, topped with ham and olives".
+impl fmt::Display for Pizza {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} pizza on {} crust", self.size, self.crust)?;
+        match self.cheese {
+            Cheese::None => write!(f, " without cheese")?,
+            cheese if self.extra_cheese => write!(f, " with extra {}", cheese)?,
+            cheese => write!(f, " with {}", cheese)?,
+        }
+        // Slice patterns match on the length of the slice.
+        match self.toppings.as_slice() {
+            [] => Ok(()),
+            [only] => write!(f, ", topped with {}", only),
+            [rest @ .., last] => {
+                let rest: Vec<&str> = rest.iter().map(Topping::name).collect();
+                write!(f, ", topped with {} and {}", rest.join(", "), last)
+            }
+        }
+    }
+}
+
+impl FromStr for Pizza {
+    type Err = PizzaError;
+
+    // Reads the format written by `to_text`. The pizza is rebuilt through the
+    // builder, so a stored pizza that breaks today's rules is refused.
+    fn from_str(text: &str) -> Result<Pizza, PizzaError> {
+        let mut fields = HashMap::new();
+        for pair in text.trim().split(';') {
+            // `split_once` cuts at the first '=': "size=small" -> ("size", "small").
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| PizzaError::Parse(format!("'{}' is not key=value", pair)))?;
+            if fields.insert(key.trim(), value.trim()).is_some() {
+                return Err(PizzaError::Parse(format!("'{}' appears twice", key.trim())));
+            }
+        }
+        // Each required field, looked up and converted with its enum's `from_name`.
+        let size: Size = parse_field(&fields, "size")?;
+        let crust: Crust = parse_field(&fields, "crust")?;
+        let cheese: Cheese = parse_field(&fields, "cheese")?;
+        let extra_cheese = match fields.get("extra").copied() {
+            Some("yes") => true,
+            Some("no") => false,
+            _ => return Err(PizzaError::Parse(String::from("extra must be yes or no"))),
+        };
+
+        let mut builder = PizzaBuilder::new(size).crust(crust).cheese(cheese);
+        if extra_cheese {
+            builder = builder.extra_cheese();
+        }
+        let toppings = fields
+            .get("toppings")
+            .ok_or_else(|| PizzaError::Parse(String::from("toppings are missing")))?;
+        // `filter` skips the empty name that splitting "" produces.
+        for name in toppings.split(',').filter(|name| !name.trim().is_empty()) {
+            let topping = Topping::from_name(name)
+                .ok_or_else(|| PizzaError::Parse(format!("unknown topping '{}'", name)))?;
+            builder = builder.topping(topping);
+        }
+        builder.build()
+    }
+}
+
+// Looks up `key` and turns its value into a `T`.
+// The generic `T: Named` works for sizes, crusts and cheeses alike.
+fn parse_field<T: Named>(fields: &HashMap<&str, &str>, key: &str) -> Result<T, PizzaError> {
+    let value = fields
+        .get(key)
+        .ok_or_else(|| PizzaError::Parse(format!("{} is missing", key)))?;
+    T::from_name(value).ok_or_else(|| PizzaError::Parse(format!("unknown {} '{}'", key, value)))
+}
+
+// Builds a `Pizza` one choice at a time, checking the rules only at the end.
+// Each method takes `self` by value and returns it, so calls chain:
+// `PizzaBuilder::new(Size::Large).crust(Crust::Thin).topping(Topping::Ham).build()`.
+#[derive(Debug, Clone)]
+pub struct PizzaBuilder {
+    pizza: Pizza,
+}
+
+// Pizzas on the menu, by menu name, and their toppings.
+const HOUSE_PIZZAS: &[(&str, &[Topping])] = &[
+    (
+        "veggies",
+        &[
+            Topping::Mushroom,
+            Topping::Onion,
+            Topping::Peppers,
+            Topping::Olives,
+        ],
+    ),
+    ("margherita", &[Topping::Tomato, Topping::Basil]),
+    ("pepperoni", &[Topping::Pepperoni]),
+    ("mushroom", &[Topping::Mushroom]),
+];
+
+impl PizzaBuilder {
+    // Starts a pizza: regular crust, mozzarella and no toppings until told otherwise.
+    pub fn new(size: Size) -> PizzaBuilder {
+        PizzaBuilder {
+            pizza: Pizza {
+                size,
+                crust: Crust::Regular,
+                cheese: Cheese::Mozzarella,
+                extra_cheese: false,
+                toppings: Vec::new(),
+            },
+        }
+    }
+
+    // Starts the medium house pizza with this menu name, if there is one.
+    pub fn house(name: &str) -> Option<PizzaBuilder> {
+        let (_, toppings) = HOUSE_PIZZAS
+            .iter()
+            .find(|(house_name, _)| house_name.eq_ignore_ascii_case(name.trim()))?;
+        Some(PizzaBuilder::new(Size::Medium).toppings(toppings))
+    }
+
+    // `mut self` lets the method change the builder it was given before returning it.
+    pub fn crust(mut self, crust: Crust) -> PizzaBuilder {
+        self.pizza.crust = crust;
+        self
+    }
+
+    pub fn cheese(mut self, cheese: Cheese) -> PizzaBuilder {
+        self.pizza.cheese = cheese;
+        self
+    }
+
+    pub fn extra_cheese(mut self) -> PizzaBuilder {
+        self.pizza.extra_cheese = true;
+        self
+    }
+
+    pub fn topping(mut self, topping: Topping) -> PizzaBuilder {
+        self.pizza.toppings.push(topping);
+        self
+    }
+
+    pub fn toppings(mut self, toppings: &[Topping]) -> PizzaBuilder {
+        self.pizza.toppings.extend_from_slice(toppings);
+        self
+    }
+
+    // Checks every rule and hands over the pizza, or the first rule it breaks.
+    pub fn build(self) -> Result<Pizza, PizzaError> {
+        let pizza = self.pizza;
+        if pizza.toppings.len() > MAX_TOPPINGS {
+            return Err(PizzaError::TooManyToppings {
+                count: pizza.toppings.len(),
+                max: MAX_TOPPINGS,
+            });
+        }
+        for (index, topping) in pizza.toppings.iter().enumerate() {
+            // A topping is a duplicate if it also appears earlier in the list.
+            if pizza.toppings[..index].contains(topping) {
+                return Err(PizzaError::DuplicateTopping(*topping));
+            }
+        }
+        for &(first, second) in INCOMPATIBLE {
+            if pizza.has(first) && pizza.has(second) {
+                return Err(PizzaError::Incompatible(first, second));
+            }
+        }
+        Ok(pizza)
+    }
+}
+
+// What each part of a pizza costs. A pizza's price is its size's base price
+// plus the crust, the cheese, extra cheese and every topping.
+// Anything missing from the table costs nothing extra.
+#[derive(Debug, Clone, Default)]
+pub struct PricingTable {
+    sizes: HashMap<Size, Cents>,
+    crusts: HashMap<Crust, Cents>,
+    cheeses: HashMap<Cheese, Cents>,
+    toppings: HashMap<Topping, Cents>,
+    extra_cheese: Cents,
+}
+
+impl PricingTable {
+    // A table where everything is free, to fill in with the `set_*` methods.
+    pub fn new() -> PricingTable {
+        PricingTable::default()
+    }
+
+    // The prices used by `order_food`.
+    pub fn standard() -> PricingTable {
+        let mut table = PricingTable::new();
+        table.set_size(Size::Small, 800);
+        table.set_size(Size::Medium, 1000);
+        table.set_size(Size::Large, 1300);
+        table.set_crust(Crust::Stuffed, 250);
+        table.set_crust(Crust::GlutenFree, 300);
+        table.set_cheese(Cheese::Parmesan, 100);
+        table.set_cheese(Cheese::Vegan, 150);
+        table.set_extra_cheese(150);
+        // Vegetables are cheaper than meat and fish.
+        for &topping in Topping::ALL {
+            let price = match topping {
+                Topping::Pepperoni | Topping::Ham | Topping::Sausage | Topping::Anchovies => 200,
+                _ => 100,
+            };
+            table.set_topping(topping, price);
+        }
+        table
+    }
+
+    pub fn set_size(&mut self, size: Size, price: Cents) {
+        self.sizes.insert(size, price);
+    }
+
+    pub fn set_crust(&mut self, crust: Crust, price: Cents) {
+        self.crusts.insert(crust, price);
+    }
+
+    pub fn set_cheese(&mut self, cheese: Cheese, price: Cents) {
+        self.cheeses.insert(cheese, price);
+    }
+
+    pub fn set_topping(&mut self, topping: Topping, price: Cents) {
+        self.toppings.insert(topping, price);
+    }
+
+    pub fn set_extra_cheese(&mut self, price: Cents) {
+        self.extra_cheese = price;
+    }
+
+    // The price of `pizza`, part by part.
+    pub fn price(&self, pizza: &Pizza) -> Cents {
+        // `get(...).copied().unwrap_or(0)`: a part missing from the table is free.
+        let size = self.sizes.get(&pizza.size).copied().unwrap_or(0);
+        let crust = self.crusts.get(&pizza.crust).copied().unwrap_or(0);
+        let cheese = self.cheeses.get(&pizza.cheese).copied().unwrap_or(0);
+        let extra = if pizza.extra_cheese {
+            self.extra_cheese
+        } else {
+            0
+        };
+        let toppings: Cents = pizza
+            .toppings
+            .iter()
+            .map(|topping| self.toppings.get(topping).copied().unwrap_or(0))
+            .sum();
+        size + crust + cheese + extra + toppings
+    }
+
+    // The price of the house pizza called `name`, or `None` if there is none.
+    pub fn house_price(&self, name: &str) -> Option<Cents> {
+        let pizza = PizzaBuilder::house(name)?.build().ok()?;
+        Some(self.price(&pizza))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_a_pizza_with_defaults_and_choices() {
+        let pizza = PizzaBuilder::new(Size::Large)
+            .crust(Crust::Thin)
+            .toppings(&[Topping::Ham, Topping::Pineapple])
+            .extra_cheese()
+            .build()
+            .unwrap();
+        assert_eq!(pizza.cheese, Cheese::Mozzarella);
+        assert_eq!(pizza.toppings, [Topping::Ham, Topping::Pineapple]);
+        assert_eq!(
+            pizza.to_string(),
+            "large pizza on thin crust with extra mozzarella, topped with ham and pineapple"
+        );
+        assert_eq!(
+            Pizza::lunch(Topping::Basil).to_string(),
+            "medium pizza on regular crust with mozzarella, topped with basil"
+        );
+    }
+
+    #[test]
+    fn refuses_too_many_or_repeated_toppings() {
+        let six = &Topping::ALL[..6];
+        assert_eq!(
+            PizzaBuilder::new(Size::Large).toppings(six).build(),
+            Err(PizzaError::TooManyToppings {
+                count: 6,
+                max: MAX_TOPPINGS
+            })
+        );
+        assert_eq!(
+            PizzaBuilder::new(Size::Small)
+                .topping(Topping::Olives)
+                .topping(Topping::Olives)
+                .build(),
+            Err(PizzaError::DuplicateTopping(Topping::Olives))
+        );
+    }
+
+    #[test]
+    fn refuses_incompatible_combinations() {
+        assert_eq!(
+            PizzaBuilder::new(Size::Medium)
+                .cheese(Cheese::Vegan)
+                .topping(Topping::Sausage)
+                .build(),
+            Err(PizzaError::Incompatible(
+                Part::Cheese(Cheese::Vegan),
+                Part::Topping(Topping::Sausage)
+            ))
+        );
+        let error = PizzaBuilder::new(Size::Small)
+            .crust(Crust::Stuffed)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "stuffed crust and small size do not go together"
+        );
+        assert!(PizzaBuilder::new(Size::Large)
+            .crust(Crust::Stuffed)
+            .build()
+            .is_ok());
+        assert!(PizzaBuilder::new(Size::Medium)
+            .cheese(Cheese::None)
+            .extra_cheese()
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn prices_every_part_from_the_table() {
+        let table = PricingTable::standard();
+        let plain = PizzaBuilder::new(Size::Medium).build().unwrap();
+        assert_eq!(table.price(&plain), 1000);
+        let loaded = PizzaBuilder::new(Size::Large)
+            .crust(Crust::GlutenFree)
+            .cheese(Cheese::Parmesan)
+            .extra_cheese()
+            .toppings(&[Topping::Pepperoni, Topping::Mushroom])
+            .build()
+            .unwrap();
+        assert_eq!(table.price(&loaded), 1300 + 300 + 100 + 150 + 200 + 100);
+        assert_eq!(PricingTable::new().price(&loaded), 0);
+    }
+
+    #[test]
+    fn house_pizzas_cost_the_same_on_the_menu() {
+        let table = PricingTable::standard();
+        let menu = crate::restaurant::pizza_order::Menu::standard();
+        for (name, _) in HOUSE_PIZZAS {
+            let house = PizzaBuilder::house(name).unwrap().build().unwrap();
+            assert_eq!(menu.item(name).unwrap().price, table.price(&house));
+        }
+        assert_eq!(table.house_price("veggies"), Some(1400));
+        assert_eq!(table.house_price("hawaiian"), None);
+    }
+
+    #[test]
+    fn text_round_trips_and_is_validated() {
+        let pizza = PizzaBuilder::house("Veggies")
+            .unwrap()
+            .crust(Crust::Thin)
+            .build()
+            .unwrap();
+        let text = pizza.to_text();
+        assert_eq!(
+            text,
+            "size=medium;crust=thin;cheese=mozzarella;extra=no;toppings=mushroom,onion,peppers,olives"
+        );
+        assert_eq!(text.parse::<Pizza>(), Ok(pizza));
+
+        let plain: Pizza = "size=small;crust=regular;cheese=none;extra=no;toppings="
+            .parse()
+            .unwrap();
+        assert!(plain.toppings.is_empty());
+        assert_eq!(
+            "size=huge;crust=thin;cheese=vegan;extra=no;toppings=".parse::<Pizza>(),
+            Err(PizzaError::Parse(String::from("unknown size 'huge'")))
+        );
+        assert!("size=small;crust=thin".parse::<Pizza>().is_err());
+        assert!(matches!(
+            "size=small;crust=regular;cheese=vegan;extra=no;toppings=ham".parse::<Pizza>(),
+            Err(PizzaError::Incompatible(..))
+        ));
+    }
+}
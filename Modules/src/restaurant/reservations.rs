@@ -0,0 +1,533 @@
+// `reservations` module: the front desk.
+// The restaurant has tables of different sizes. Parties can book a table for a
+// time slot ahead of time, or walk in and take whatever fits best right now.
+// When nothing fits, walk-ins join a waitlist and are seated automatically as
+// soon as a table frees up.
+//
+// This file is the body of `pub mod reservations;` in `restaurant/mod.rs`.
+// A module can be a single file (`reservations.rs`) or a folder with a
+// `mod.rs` (like `pizza_order/`); the `mod` declaration looks the same.
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+
+// Times of day are minutes after midnight: 18:30 is 18 * 60 + 30 = 1110.
+pub type Minutes = u32;
+
+// How long a walk-in party is expected to stay.
+pub const DINING_TIME: Minutes = 90;
+
+// The time `hour:minute`, e.g. `at(18, 30)`.
+pub const fn at(hour: u32, minute: u32) -> Minutes {
+    hour * 60 + minute
+}
+
+// Formats a time of day, e.g. 1110 -> "18:30".
+pub fn format_time(time: Minutes) -> String {
+    format!("{:02}:{:02}", time / 60, time % 60)
+}
+
+// A stretch of time from `start` up to, but not including, `end`.
+// A booking ending at 19:30 therefore does not clash with one starting at 19:30.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeSlot {
+    pub start: Minutes,
+    pub end: Minutes,
+}
+
+impl TimeSlot {
+    // A slot of `length` minutes starting at `start`.
+    pub fn new(start: Minutes, length: Minutes) -> TimeSlot {
+        TimeSlot {
+            start,
+            end: start + length,
+        }
+    }
+
+    // True if the two slots share at least one minute.
+    pub fn overlaps(&self, other: &TimeSlot) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+}
+
+impl fmt::Display for TimeSlot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", format_time(self.start), format_time(self.end))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Table {
+    pub number: u32,
+    pub seats: u32,
+}
+
+// A table held for a party during `slot`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Booking {
+    pub id: u32,
+    pub name: String,
+    pub party: u32,
+    pub table: u32,
+    pub slot: TimeSlot,
+}
+
+// A party waiting for a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Party {
+    pub name: String,
+    pub size: u32,
+}
+
+// A party sitting at a table, and until when the table is theirs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Seated {
+    pub party: Party,
+    pub table: u32,
+    pub stay: TimeSlot,
+}
+
+// What happened to a walk-in party.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Seating {
+    // Seated at this table straight away.
+    Seated(u32),
+    // Waiting; 1 is the front of the waitlist.
+    Waitlisted(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeatingError {
+    // A party of nobody.
+    EmptyParty,
+    // A slot that ends before (or when) it starts.
+    EmptySlot(TimeSlot),
+    // No table in the restaurant has this many seats.
+    PartyTooLarge(u32),
+    NoSuchTable(u32),
+    TableTooSmall { table: u32, seats: u32, party: u32 },
+    // The table is already booked for part of the slot.
+    Overlap { table: u32, booking: u32 },
+    // Every table big enough is taken for the slot.
+    NoTableFree(TimeSlot),
+    NoSuchBooking(u32),
+    // Someone is still sitting at the table.
+    TableOccupied(u32),
+    // Nobody is sitting at the table.
+    TableEmpty(u32),
+}
+
+impl fmt::Display for SeatingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SeatingError::EmptyParty => write!(f, "a party needs at least one guest"),
+            SeatingError::EmptySlot(slot) => write!(f, "the slot {} is empty", slot),
+            SeatingError::PartyTooLarge(size) => write!(f, "no table seats a party of {}", size),
+            SeatingError::NoSuchTable(table) => write!(f, "there is no table {}", table),
+            SeatingError::TableTooSmall { table, seats, party } => write!(
+                f,
+                "table {} seats {}, not a party of {}",
+                table, seats, party
+            ),
+            SeatingError::Overlap { table, booking } => {
+                write!(f, "table {} is already booked (booking {})", table, booking)
+            }
+            SeatingError::NoTableFree(slot) => write!(f, "no table is free for {}", slot),
+            SeatingError::NoSuchBooking(id) => write!(f, "there is no booking {}", id),
+            SeatingError::TableOccupied(table) => write!(f, "table {} is still occupied", table),
+            SeatingError::TableEmpty(table) => write!(f, "nobody is sitting at table {}", table),
+        }
+    }
+}
+
+impl Error for SeatingError {}
+
+// The front desk's book: tables, bookings, who is sitting where, and who is waiting.
+#[derive(Debug, Clone, Default)]
+pub struct Reservations {
+    tables: Vec<Table>,
+    bookings: Vec<Booking>,
+    // Parties at their tables right now, in the order they sat down.
+    seated: Vec<Seated>,
+    // `VecDeque` is a queue: parties join at the back and leave from the front.
+    waitlist: VecDeque<Party>,
+    next_booking: u32,
+}
+
+impl Reservations {
+    // A restaurant with no tables yet.
+    pub fn new() -> Reservations {
+        Reservations {
+            next_booking: 1,
+            ..Reservations::default()
+        }
+    }
+
+    // The dining room used by `order_food`.
+    pub fn standard() -> Reservations {
+        let mut reservations = Reservations::new();
+        for (number, seats) in [(1, 2), (2, 2), (3, 4), (4, 4), (5, 6)] {
+            reservations.add_table(number, seats);
+        }
+        reservations
+    }
+
+    // Adds a table. Returns `false` (and changes nothing) if the number is taken.
+    pub fn add_table(&mut self, number: u32, seats: u32) -> bool {
+        if self.table(number).is_some() {
+            return false;
+        }
+        self.tables.push(Table { number, seats });
+        true
+    }
+
+    pub fn table(&self, number: u32) -> Option<&Table> {
+        self.tables.iter().find(|table| table.number == number)
+    }
+
+    pub fn bookings(&self) -> &[Booking] {
+        &self.bookings
+    }
+
+    // Who is sitting at `table`, if anyone.
+    pub fn seated_at(&self, table: u32) -> Option<&Seated> {
+        self.seated.iter().find(|seated| seated.table == table)
+    }
+
+    // The waitlist, front first.
+    pub fn waitlist(&self) -> impl Iterator<Item = &Party> {
+        self.waitlist.iter()
+    }
+
+    // Books a specific table, refusing slots that overlap another booking.
+    pub fn book_table(
+        &mut self,
+        table: u32,
+        name: &str,
+        party: u32,
+        slot: TimeSlot,
+    ) -> Result<Booking, SeatingError> {
+        check_request(party, slot)?;
+        let seats = self.table(table).ok_or(SeatingError::NoSuchTable(table))?.seats;
+        if seats < party {
+            return Err(SeatingError::TableTooSmall { table, seats, party });
+        }
+        if let Some(other) = self.clashing_booking(table, slot) {
+            return Err(SeatingError::Overlap {
+                table,
+                booking: other.id,
+            });
+        }
+        if self.stay_clashes(table, slot) {
+            return Err(SeatingError::TableOccupied(table));
+        }
+        Ok(self.add_booking(table, name, party, slot))
+    }
+
+    // Books the best-fitting table that is free for the whole slot.
+    pub fn book(&mut self, name: &str, party: u32, slot: TimeSlot) -> Result<Booking, SeatingError> {
+        check_request(party, slot)?;
+        self.check_fits(party)?;
+        let table = self
+            .best_fit(party, slot, false)
+            .ok_or(SeatingError::NoTableFree(slot))?;
+        Ok(self.add_booking(table, name, party, slot))
+    }
+
+    // Cancels a booking at `now` and hands it back. The freed table may let
+    // waiting parties sit down, so they are seated as in `leave`, and the
+    // newly seated are returned with the booking.
+    pub fn cancel(&mut self, id: u32, now: Minutes) -> Result<(Booking, Vec<Seated>), SeatingError> {
+        let index = self.booking_index(id)?;
+        let booking = self.bookings.remove(index);
+        Ok((booking, self.seat_waitlist(now)))
+    }
+
+    // Seats a party that booked ahead. The table is theirs until the booking ends.
+    pub fn check_in(&mut self, id: u32) -> Result<u32, SeatingError> {
+        let booking = self.bookings[self.booking_index(id)?].clone();
+        if self.seated_at(booking.table).is_some() {
+            return Err(SeatingError::TableOccupied(booking.table));
+        }
+        self.seated.push(Seated {
+            party: Party {
+                name: booking.name,
+                size: booking.party,
+            },
+            table: booking.table,
+            stay: booking.slot,
+        });
+        Ok(booking.table)
+    }
+
+    // A party arrives without a booking at `now`.
+    // They get the best-fitting table that is empty and not booked for the next
+    // `DINING_TIME` minutes, or a place at the back of the waitlist.
+    pub fn walk_in(&mut self, name: &str, party: u32, now: Minutes) -> Result<Seating, SeatingError> {
+        if party == 0 {
+            return Err(SeatingError::EmptyParty);
+        }
+        self.check_fits(party)?;
+        let party = Party {
+            name: name.to_string(),
+            size: party,
+        };
+        // Nobody jumps the queue: with people already waiting, newcomers wait too.
+        if self.waitlist.is_empty() {
+            if let Some(seated) = self.seat_walk_in(&party, now) {
+                return Ok(Seating::Seated(seated.table));
+            }
+        }
+        self.waitlist.push_back(party);
+        Ok(Seating::Waitlisted(self.waitlist.len()))
+    }
+
+    // The party at `table` leaves at `now`. If they had booked, the rest of
+    // their slot is released. Waiting parties are then seated wherever they
+    // now fit, front of the waitlist first; the newly seated are returned.
+    pub fn leave(&mut self, table: u32, now: Minutes) -> Result<Vec<Seated>, SeatingError> {
+        let index = self
+            .seated
+            .iter()
+            .position(|seated| seated.table == table)
+            .ok_or(SeatingError::TableEmpty(table))?;
+        let leaving = self.seated.remove(index);
+        // Ending the booking early, or dropping it if they left before it began.
+        if let Some(booking) = self
+            .bookings
+            .iter_mut()
+            .find(|booking| booking.table == table && booking.slot == leaving.stay)
+        {
+            booking.slot.end = booking.slot.end.min(now).max(booking.slot.start);
+        }
+        self.bookings.retain(|booking| booking.slot.start < booking.slot.end);
+        Ok(self.seat_waitlist(now))
+    }
+
+    // Seats waiting parties in order wherever they fit at `now`.
+    // A party that does not fit yet keeps its place; smaller parties behind
+    // it may still be seated at a small table.
+    fn seat_waitlist(&mut self, now: Minutes) -> Vec<Seated> {
+        let mut newly_seated = Vec::new();
+        let mut still_waiting = VecDeque::new();
+        // `drain(..)` empties the queue, handing over each party by value.
+        let waiting: Vec<Party> = self.waitlist.drain(..).collect();
+        for party in waiting {
+            match self.seat_walk_in(&party, now) {
+                Some(seated) => newly_seated.push(seated),
+                None => still_waiting.push_back(party),
+            }
+        }
+        self.waitlist = still_waiting;
+        newly_seated
+    }
+
+    // Seats `party` at the best free table for a stay starting at `now`, if any.
+    fn seat_walk_in(&mut self, party: &Party, now: Minutes) -> Option<Seated> {
+        let stay = TimeSlot::new(now, DINING_TIME);
+        let table = self.best_fit(party.size, stay, true)?;
+        let seated = Seated {
+            party: party.clone(),
+            table,
+            stay,
+        };
+        self.seated.push(seated.clone());
+        Some(seated)
+    }
+
+    // Best fit: of the tables big enough and free for `slot`, the one with the
+    // fewest seats, so big tables stay free for big parties. Ties go to the
+    // lowest table number.
+    // A walk-in also needs the table empty right now, even if the party at it
+    // has stayed longer than expected.
+    fn best_fit(&self, party: u32, slot: TimeSlot, walk_in: bool) -> Option<u32> {
+        // Each `filter` drops the tables failing one rule; `min_by_key` picks from the rest.
+        self.tables
+            .iter()
+            .filter(|table| table.seats >= party)
+            .filter(|table| self.clashing_booking(table.number, slot).is_none())
+            .filter(|table| !self.stay_clashes(table.number, slot))
+            .filter(|table| !walk_in || self.seated_at(table.number).is_none())
+            .min_by_key(|table| (table.seats, table.number))
+            .map(|table| table.number)
+    }
+
+    // A booking on `table` that overlaps `slot`, if there is one.
+    fn clashing_booking(&self, table: u32, slot: TimeSlot) -> Option<&Booking> {
+        self.bookings
+            .iter()
+            .find(|booking| booking.table == table && booking.slot.overlaps(&slot))
+    }
+
+    // True if the party sitting at `table` is expected to still be there during `slot`.
+    fn stay_clashes(&self, table: u32, slot: TimeSlot) -> bool {
+        self.seated_at(table)
+            .is_some_and(|seated| seated.stay.overlaps(&slot))
+    }
+
+    fn check_fits(&self, party: u32) -> Result<(), SeatingError> {
+        if self.tables.iter().any(|table| table.seats >= party) {
+            Ok(())
+        } else {
+            Err(SeatingError::PartyTooLarge(party))
+        }
+    }
+
+    fn booking_index(&self, id: u32) -> Result<usize, SeatingError> {
+        self.bookings
+            .iter()
+            .position(|booking| booking.id == id)
+            .ok_or(SeatingError::NoSuchBooking(id))
+    }
+
+    fn add_booking(&mut self, table: u32, name: &str, party: u32, slot: TimeSlot) -> Booking {
+        let booking = Booking {
+            id: self.next_booking,
+            name: name.to_string(),
+            party,
+            table,
+            slot,
+        };
+        self.next_booking += 1;
+        self.bookings.push(booking.clone());
+        booking
+    }
+}
+
+// Checks the parts of a booking request that do not depend on the tables.
+fn check_request(party: u32, slot: TimeSlot) -> Result<(), SeatingError> {
+    if party == 0 {
+        return Err(SeatingError::EmptyParty);
+    }
+    if slot.end <= slot.start {
+        return Err(SeatingError::EmptySlot(slot));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Two 2-tops, two 4-tops and a 6-top.
+    fn room() -> Reservations {
+        Reservations::standard()
+    }
+
+    #[test]
+    fn slots_that_only_touch_do_not_overlap() {
+        let dinner = TimeSlot::new(at(18, 0), 90);
+        assert!(dinner.overlaps(&TimeSlot::new(at(19, 0), 60)));
+        assert!(dinner.overlaps(&TimeSlot::new(at(18, 30), 15)));
+        assert!(!dinner.overlaps(&TimeSlot::new(at(19, 30), 60)));
+        assert!(!dinner.overlaps(&TimeSlot::new(at(17, 0), 60)));
+        assert_eq!(dinner.to_string(), "18:00-19:30");
+    }
+
+    #[test]
+    fn bookings_on_the_same_table_cannot_overlap() {
+        let mut room = room();
+        let first = room.book_table(3, "Ada", 4, TimeSlot::new(at(18, 0), 90)).unwrap();
+        assert_eq!(
+            room.book_table(3, "Bob", 2, TimeSlot::new(at(19, 0), 60)),
+            Err(SeatingError::Overlap { table: 3, booking: first.id })
+        );
+        // Straight after, or on another table, is fine.
+        assert!(room.book_table(3, "Bob", 2, TimeSlot::new(at(19, 30), 60)).is_ok());
+        assert!(room.book_table(4, "Cy", 2, TimeSlot::new(at(19, 0), 60)).is_ok());
+        assert_eq!(
+            room.book_table(1, "Dee", 3, TimeSlot::new(at(12, 0), 60)),
+            Err(SeatingError::TableTooSmall { table: 1, seats: 2, party: 3 })
+        );
+        assert_eq!(
+            room.book_table(1, "Dee", 2, TimeSlot::new(at(12, 0), 0)),
+            Err(SeatingError::EmptySlot(TimeSlot::new(at(12, 0), 0)))
+        );
+    }
+
+    #[test]
+    fn booking_picks_the_smallest_free_table_that_fits() {
+        let mut room = room();
+        let slot = TimeSlot::new(at(19, 0), 90);
+        assert_eq!(room.book("Ada", 3, slot).unwrap().table, 3);
+        assert_eq!(room.book("Bob", 3, slot).unwrap().table, 4);
+        // Both 4-tops are taken, so the 6-top is the best left.
+        assert_eq!(room.book("Cy", 4, slot).unwrap().table, 5);
+        assert_eq!(room.book("Dee", 4, slot), Err(SeatingError::NoTableFree(slot)));
+        // Later in the evening the 4-tops are free again.
+        assert_eq!(room.book("Dee", 4, TimeSlot::new(at(20, 30), 90)).unwrap().table, 3);
+        assert_eq!(room.book("Eve", 7, slot), Err(SeatingError::PartyTooLarge(7)));
+    }
+
+    #[test]
+    fn walk_ins_avoid_tables_booked_during_their_meal() {
+        let mut room = room();
+        room.book_table(1, "Ada", 2, TimeSlot::new(at(19, 0), 90)).unwrap();
+        // Table 1 is free at 18:00 but booked before the walk-in would leave.
+        assert_eq!(room.walk_in("Bob", 2, at(18, 0)), Ok(Seating::Seated(2)));
+        assert_eq!(room.walk_in("Cy", 2, at(18, 0)), Ok(Seating::Seated(3)));
+        assert_eq!(room.seated_at(3).unwrap().stay, TimeSlot::new(at(18, 0), DINING_TIME));
+        assert_eq!(room.walk_in("Dee", 0, at(18, 0)), Err(SeatingError::EmptyParty));
+    }
+
+    #[test]
+    fn the_waitlist_is_seated_in_order_as_tables_free_up() {
+        let mut room = Reservations::new();
+        room.add_table(1, 2);
+        room.add_table(2, 4);
+        assert_eq!(room.walk_in("Ada", 4, at(18, 0)), Ok(Seating::Seated(2)));
+        assert_eq!(room.walk_in("Bob", 2, at(18, 0)), Ok(Seating::Seated(1)));
+        assert_eq!(room.walk_in("Cy", 4, at(18, 5)), Ok(Seating::Waitlisted(1)));
+        // A table is free for Dee, but Cy was first in line.
+        assert_eq!(room.walk_in("Dee", 2, at(18, 10)), Ok(Seating::Waitlisted(2)));
+
+        // The 2-top frees first: too small for Cy, so Dee gets it.
+        let seated = room.leave(1, at(19, 0)).unwrap();
+        assert_eq!(seated.len(), 1);
+        assert_eq!((seated[0].party.name.as_str(), seated[0].table), ("Dee", 1));
+        assert_eq!(seated[0].stay.start, at(19, 0));
+
+        let seated = room.leave(2, at(19, 15)).unwrap();
+        assert_eq!((seated[0].party.name.as_str(), seated[0].table), ("Cy", 2));
+        assert_eq!(room.waitlist().count(), 0);
+        assert_eq!(room.leave(2, at(20, 0)).unwrap(), Vec::new());
+        assert_eq!(room.leave(2, at(20, 0)), Err(SeatingError::TableEmpty(2)));
+    }
+
+    #[test]
+    fn leaving_early_releases_the_rest_of_a_booking() {
+        let mut room = Reservations::new();
+        room.add_table(1, 4);
+        let booking = room.book("Ada", 4, TimeSlot::new(at(18, 0), 120)).unwrap();
+        assert_eq!(room.check_in(booking.id), Ok(1));
+        assert_eq!(room.check_in(booking.id), Err(SeatingError::TableOccupied(1)));
+        assert_eq!(room.walk_in("Bob", 3, at(18, 30)), Ok(Seating::Waitlisted(1)));
+
+        // Ada leaves at 19:00 instead of 20:00; Bob is seated at once.
+        let seated = room.leave(1, at(19, 0)).unwrap();
+        assert_eq!(seated[0].party.name, "Bob");
+        assert_eq!(room.bookings()[0].slot, TimeSlot::new(at(18, 0), 60));
+        assert_eq!(room.cancel(99, at(19, 0)), Err(SeatingError::NoSuchBooking(99)));
+        let (cancelled, seated) = room.cancel(booking.id, at(19, 0)).unwrap();
+        assert_eq!(cancelled.name, "Ada");
+        assert!(seated.is_empty());
+        assert!(room.bookings().is_empty());
+    }
+
+    #[test]
+    fn cancelling_a_booking_seats_the_waitlist() {
+        let mut room = Reservations::new();
+        room.add_table(1, 2);
+        let booking = room.book("Ada", 2, TimeSlot::new(at(18, 30), 90)).unwrap();
+        // The table is empty, but Ada's booking starts before Bob would leave.
+        assert_eq!(room.walk_in("Bob", 2, at(18, 0)), Ok(Seating::Waitlisted(1)));
+
+        let (cancelled, seated) = room.cancel(booking.id, at(18, 10)).unwrap();
+        assert_eq!(cancelled.id, booking.id);
+        assert_eq!(seated.len(), 1);
+        assert_eq!((seated[0].party.name.as_str(), seated[0].table), ("Bob", 1));
+        assert_eq!(seated[0].stay, TimeSlot::new(at(18, 10), DINING_TIME));
+        assert_eq!(room.waitlist().count(), 0);
+    }
+}
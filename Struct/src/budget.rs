@@ -0,0 +1,421 @@
+// `budget` module: monthly spending limits per category.
+// A `Budget` sets, for one currency, how much may be spent on each category
+// in a month. The wallet's ledger already says what was spent, so the budget
+// stores no spending of its own: it adds up the wallet's expenses whenever it
+// needs a figure, and can never disagree with the ledger.
+//
+// A limit is either a warning, which lets the expense through and reports
+// that the budget is exceeded, or a hard limit, which refuses the expense.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::calendar::{Month, Timestamp};
+use crate::money::{Currency, Money};
+use crate::wallet::{EntryKind, Wallet, WalletError};
+
+// How much may be spent on a category each month, and what happens beyond it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    Warn(Money),
+    Hard(Money),
+}
+
+impl Limit {
+    pub fn amount(&self) -> Money {
+        match self {
+            Limit::Warn(amount) | Limit::Hard(amount) => *amount,
+        }
+    }
+}
+
+// An expense that went through but took a category past its monthly budget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetWarning {
+    pub category: String,
+    pub month: Month,
+    pub currency: Currency,
+    pub budgeted: Money,
+    pub spent: Money,
+}
+
+impl fmt::Display for BudgetWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} has spent {} of its {} budget for {}",
+            self.category,
+            self.spent.display(self.currency),
+            self.budgeted.display(self.currency),
+            self.month
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BudgetError {
+    // A hard limit refused the expense. `spent` is what was already spent.
+    OverBudget {
+        category: String,
+        month: Month,
+        currency: Currency,
+        budgeted: Money,
+        spent: Money,
+        amount: Money,
+    },
+    // The wallet itself refused the expense.
+    Wallet(WalletError),
+}
+
+impl fmt::Display for BudgetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BudgetError::OverBudget {
+                category,
+                month,
+                currency,
+                budgeted,
+                spent,
+                amount,
+            } => write!(
+                f,
+                "{} more on {} would exceed its {} budget for {} ({} spent)",
+                amount.display(*currency),
+                category,
+                budgeted.display(*currency),
+                month,
+                spent.display(*currency)
+            ),
+            BudgetError::Wallet(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for BudgetError {}
+
+impl From<WalletError> for BudgetError {
+    fn from(error: WalletError) -> BudgetError {
+        BudgetError::Wallet(error)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Budget {
+    currency: Currency,
+    // Keyed by category. A `BTreeMap` keeps reports in alphabetical order.
+    limits: BTreeMap<String, Limit>,
+}
+
+impl Budget {
+    // A budget in `currency` with no limits yet.
+    pub fn new(currency: Currency) -> Budget {
+        Budget {
+            currency,
+            limits: BTreeMap::new(),
+        }
+    }
+
+    // Sets the monthly limit for `category`, replacing any earlier one.
+    pub fn set(&mut self, category: &str, limit: Limit) {
+        self.limits.insert(category.to_string(), limit);
+    }
+
+    // What `wallet` spent on `category` during `month`, in the budget's currency.
+    pub fn spent(&self, wallet: &Wallet, category: &str, month: Month) -> Money {
+        self.spending(wallet, month)
+            .get(category)
+            .copied()
+            .unwrap_or(Money::ZERO)
+    }
+
+    // Spends from `wallet` like `Wallet::expense`, but first checks the
+    // category's budget for the month of `at`. A hard limit refuses an
+    // expense that would exceed it; a warning limit lets it through and
+    // returns `Some(warning)`.
+    pub fn spend(
+        &self,
+        wallet: &mut Wallet,
+        at: Timestamp,
+        amount: Money,
+        category: &str,
+        description: &str,
+    ) -> Result<Option<BudgetWarning>, BudgetError> {
+        let month = Month::of(at.date());
+        let spent = self.spent(wallet, category, month);
+        // Going past `Money`'s largest value is certainly over any budget.
+        let after = spent.saturating_add(amount);
+        let warning = match self.limits.get(category) {
+            Some(limit) if after > limit.amount() => match limit {
+                Limit::Hard(budgeted) => {
+                    return Err(BudgetError::OverBudget {
+                        category: category.to_string(),
+                        month,
+                        currency: self.currency,
+                        budgeted: *budgeted,
+                        spent,
+                        amount,
+                    })
+                }
+                Limit::Warn(budgeted) => Some(BudgetWarning {
+                    category: category.to_string(),
+                    month,
+                    currency: self.currency,
+                    budgeted: *budgeted,
+                    spent: after,
+                }),
+            },
+            _ => None,
+        };
+        wallet.expense(at, self.currency, amount, category, description)?;
+        Ok(warning)
+    }
+
+    // Spent, budgeted and remaining per category for `month`: every category
+    // with a budget, and every category with spending but no budget.
+    pub fn report(&self, wallet: &Wallet, month: Month) -> MonthlyReport {
+        let mut spending = self.spending(wallet, month);
+        for category in self.limits.keys() {
+            // `entry().or_insert` adds budgeted categories nothing was spent on.
+            spending.entry(category.clone()).or_insert(Money::ZERO);
+        }
+        let rows = spending
+            .into_iter()
+            .map(|(category, spent)| ReportRow {
+                limit: self.limits.get(&category).copied(),
+                category,
+                spent,
+            })
+            .collect();
+        MonthlyReport {
+            month,
+            currency: self.currency,
+            rows,
+        }
+    }
+
+    // Total expenses per category in `month`, read from the ledger.
+    fn spending(&self, wallet: &Wallet, month: Month) -> BTreeMap<String, Money> {
+        let mut spending: BTreeMap<String, Money> = BTreeMap::new();
+        for entry in wallet.ledger() {
+            if entry.currency != self.currency || Month::of(entry.at.date()) != month {
+                continue;
+            }
+            // `if let` matches one pattern and skips every other kind of entry.
+            if let EntryKind::Expense { category } = &entry.kind {
+                let total = spending.entry(category.clone()).or_insert(Money::ZERO);
+                *total = total.saturating_add(entry.amount);
+            }
+        }
+        spending
+    }
+}
+
+// What is left of a budget, or how far it was overspent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Remaining {
+    Left(Money),
+    Over(Money),
+}
+
+impl Remaining {
+    // The amount in `currency`, with a minus sign when overspent.
+    pub fn to_text(self, currency: Currency) -> String {
+        match self {
+            Remaining::Left(amount) => amount.display(currency).to_string(),
+            Remaining::Over(amount) => format!("-{}", amount.display(currency)),
+        }
+    }
+}
+
+// One category in a `MonthlyReport`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportRow {
+    pub category: String,
+    pub spent: Money,
+    // `None` for a category with spending but no budget.
+    pub limit: Option<Limit>,
+}
+
+impl ReportRow {
+    pub fn remaining(&self) -> Option<Remaining> {
+        let budgeted = self.limit?.amount();
+        // `checked_sub` fails exactly when more was spent than budgeted.
+        Some(match budgeted.checked_sub(self.spent) {
+            Ok(left) => Remaining::Left(left),
+            Err(_) => Remaining::Over(self.spent.saturating_sub(budgeted)),
+        })
+    }
+}
+
+pub struct MonthlyReport {
+    pub month: Month,
+    pub currency: Currency,
+    pub rows: Vec<ReportRow>,
+}
+
+impl fmt::Display for MonthlyReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Budget report for {} ({})", self.month, self.currency)?;
+        write!(
+            f,
+            "  {:<14} {:>10} {:>10} {:>10}",
+            "Category", "Spent", "Budgeted", "Remaining"
+        )?;
+        for row in &self.rows {
+            let currency = self.currency;
+            // `map_or` gives a placeholder for categories without a budget.
+            let budgeted = row.limit.map_or(String::from("-"), |l| {
+                l.amount().display(currency).to_string()
+            });
+            let remaining = row
+                .remaining()
+                .map_or(String::from("-"), |r| r.to_text(currency));
+            let hard = match row.limit {
+                Some(Limit::Hard(_)) => "  (hard limit)",
+                _ => "",
+            };
+            write!(
+                f,
+                "\n  {:<14} {:>10} {:>10} {:>10}{}",
+                row.category,
+                row.spent.display(currency),
+                budgeted,
+                remaining,
+                hard
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::Date;
+
+    fn eur() -> Currency {
+        Currency::new("EUR").unwrap()
+    }
+
+    fn money(text: &str) -> Money {
+        Money::parse(text, eur()).unwrap()
+    }
+
+    fn on(month: u32, day: u32) -> Timestamp {
+        Timestamp::at(Date::new(2026, month, day).unwrap(), 12, 0)
+    }
+
+    fn march() -> Month {
+        Month::of(Date::new(2026, 3, 1).unwrap())
+    }
+
+    fn setup() -> (Budget, Wallet) {
+        let eur = Currency::new("EUR").unwrap();
+        let mut budget = Budget::new(eur);
+        budget.set("groceries", Limit::Warn(money("300")));
+        budget.set("rent", Limit::Hard(money("800")));
+        budget.set("fun", Limit::Hard(money("50")));
+        let mut wallet = Wallet::new("Alice");
+        wallet
+            .deposit(on(2, 28), eur, money("5000"), "Salary")
+            .unwrap();
+        (budget, wallet)
+    }
+
+    #[test]
+    fn warning_limits_let_expenses_through() {
+        let (budget, mut wallet) = setup();
+        assert_eq!(
+            budget.spend(&mut wallet, on(3, 2), money("250"), "groceries", "Market"),
+            Ok(None)
+        );
+        let warning = budget
+            .spend(&mut wallet, on(3, 9), money("60.50"), "groceries", "Market")
+            .unwrap()
+            .unwrap();
+        assert_eq!(warning.spent, money("310.50"));
+        assert_eq!(
+            warning.to_string(),
+            "groceries has spent 310.50 of its 300.00 budget for 2026-03"
+        );
+        assert_eq!(budget.spent(&wallet, "groceries", march()), money("310.50"));
+    }
+
+    #[test]
+    fn hard_limits_refuse_expenses() {
+        let (budget, mut wallet) = setup();
+        budget
+            .spend(&mut wallet, on(3, 1), money("800"), "rent", "Landlord")
+            .unwrap();
+        let refused = budget.spend(&mut wallet, on(3, 5), money("0.01"), "rent", "Landlord");
+        assert_eq!(
+            refused,
+            Err(BudgetError::OverBudget {
+                category: String::from("rent"),
+                month: march(),
+                currency: eur(),
+                budgeted: money("800"),
+                spent: money("800"),
+                amount: money("0.01"),
+            })
+        );
+        // A new month starts a new budget.
+        assert_eq!(
+            budget.spend(&mut wallet, on(4, 1), money("800"), "rent", "Landlord"),
+            Ok(None)
+        );
+        // Categories without a budget have no limit, but the wallet still does.
+        let too_much = budget.spend(&mut wallet, on(4, 2), money("9999"), "travel", "Airline");
+        assert!(matches!(too_much, Err(BudgetError::Wallet(_))));
+        assert_eq!(wallet.ledger().len(), 3);
+    }
+
+    #[test]
+    fn monthly_report() {
+        let (budget, mut wallet) = setup();
+        let eur = Currency::new("EUR").unwrap();
+        budget
+            .spend(&mut wallet, on(3, 1), money("800"), "rent", "Landlord")
+            .unwrap();
+        budget
+            .spend(&mut wallet, on(3, 3), money("320"), "groceries", "Market")
+            .unwrap();
+        budget
+            .spend(&mut wallet, on(3, 4), money("12"), "books", "Bookshop")
+            .unwrap();
+        budget
+            .spend(&mut wallet, on(4, 1), money("40"), "fun", "Cinema")
+            .unwrap();
+        // Other currencies are not part of a euro budget.
+        let usd = Currency::new("USD").unwrap();
+        wallet.deposit(on(4, 2), usd, money("10"), "Gift").unwrap();
+        wallet
+            .expense(on(4, 2), usd, money("10"), "books", "Bookshop")
+            .unwrap();
+
+        let report = budget.report(&wallet, march());
+        let rows: Vec<(&str, Option<Remaining>)> = report
+            .rows
+            .iter()
+            .map(|row| (row.category.as_str(), row.remaining()))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("books", None),
+                ("fun", Some(Remaining::Left(money("50")))),
+                ("groceries", Some(Remaining::Over(money("20")))),
+                ("rent", Some(Remaining::Left(money("0")))),
+            ]
+        );
+        assert_eq!(report.currency, eur);
+        assert_eq!(
+            report.to_string().lines().nth(4),
+            Some("  groceries          320.00     300.00     -20.00")
+        );
+        assert_eq!(
+            budget.spent(&wallet, "books", Month::of(on(4, 2).date())),
+            Money::ZERO
+        );
+    }
+}
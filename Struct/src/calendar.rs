@@ -0,0 +1,169 @@
+// `calendar` module: dates and timestamps without any external crate.
+// A `Timestamp` counts seconds since 1970-01-01 00:00 UTC (the Unix epoch),
+// which makes timestamps easy to compare and store. `Date` is the
+// human-readable calendar day that a timestamp falls on.
+
+use std::fmt;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+// A calendar date. Deriving `PartialOrd` and `Ord` compares the fields in
+// order (year, then month, then day), which is exactly date order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    // Builds a date, or `None` if the day does not exist (2026-02-30).
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Date> {
+        let date = Date { year, month, day };
+        // A real date survives the round trip through a day count unchanged;
+        // an impossible one like February 30th comes back as March 2nd.
+        if (1..=12).contains(&month) && Date::from_days(date.days()) == date {
+            Some(date)
+        } else {
+            None
+        }
+    }
+
+    // The date `days` days after 1970-01-01.
+    // This is the standard "civil from days" calculation: it counts in
+    // 400-year eras (146,097 days each) whose years start on March 1st, so
+    // the leap day falls at the very end of a year.
+    pub fn from_days(days: i64) -> Date {
+        let shifted = days + 719_468; // days from 0000-03-01 to 1970-01-01
+        let era = shifted.div_euclid(146_097);
+        let day_of_era = shifted.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_from_march = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+        let month = if month_from_march < 10 {
+            month_from_march + 3
+        } else {
+            month_from_march - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        Date {
+            year: year as i32,
+            month: month as u32,
+            day: day as u32,
+        }
+    }
+
+    // The number of days from 1970-01-01 to this date: the inverse of `from_days`.
+    pub fn days(&self) -> i64 {
+        let month = i64::from(self.month);
+        let year = i64::from(self.year) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let month_from_march = (month + 9) % 12;
+        let day_of_year = (153 * month_from_march + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    // Reads "YYYY-MM-DD".
+    pub fn parse(text: &str) -> Option<Date> {
+        let mut parts = text.trim().splitn(3, '-');
+        // `?` on an `Option` returns `None` early, just as it returns errors for `Result`.
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        Date::new(year, month, day)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+// A calendar month, such as 2026-03. Budgets are kept per month.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Month {
+    pub year: i32,
+    pub month: u32,
+}
+
+impl Month {
+    // The month `date` falls in.
+    pub fn of(date: Date) -> Month {
+        Month {
+            year: date.year,
+            month: date.month,
+        }
+    }
+}
+
+impl fmt::Display for Month {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}", self.year, self.month)
+    }
+}
+
+// A moment in time, to the second. A tuple struct: `Timestamp(0)` is midnight
+// on 1970-01-01 UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(pub i64);
+
+impl Timestamp {
+    // `hour:minute` on `date`.
+    pub fn at(date: Date, hour: u32, minute: u32) -> Timestamp {
+        Timestamp(date.days() * SECONDS_PER_DAY + i64::from(hour * 3_600 + minute * 60))
+    }
+
+    // The day this moment falls on. `div_euclid` rounds towards minus
+    // infinity, so moments before 1970 still land on the right day.
+    pub fn date(&self) -> Date {
+        Date::from_days(self.0.div_euclid(SECONDS_PER_DAY))
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let seconds = self.0.rem_euclid(SECONDS_PER_DAY);
+        write!(
+            f,
+            "{} {:02}:{:02}",
+            self.date(),
+            seconds / 3_600,
+            seconds % 3_600 / 60
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_and_dates_round_trip() {
+        assert_eq!(Date::from_days(0).to_string(), "1970-01-01");
+        assert_eq!(Date::from_days(11_016).to_string(), "2000-02-29");
+        for days in -800_000..800_000 {
+            assert_eq!(Date::from_days(days).days(), days);
+        }
+        assert_eq!(Date::parse("2026-02-28"), Date::new(2026, 2, 28));
+        assert_eq!(Date::parse("2026-02-29"), None);
+        assert_eq!(Date::parse("28/02/2026"), None);
+        assert_eq!(Date::new(2024, 2, 29).map(|d| d.day), Some(29));
+        assert_eq!(Date::new(2026, 13, 1), None);
+        assert_eq!(Date::new(2026, 4, 0), None);
+    }
+
+    #[test]
+    fn timestamps_know_their_day() {
+        let day = Date::new(2026, 3, 14).unwrap();
+        let noon = Timestamp::at(day, 12, 30);
+        assert_eq!(noon.date(), day);
+        assert_eq!(noon.to_string(), "2026-03-14 12:30");
+        assert_eq!(Month::of(day).to_string(), "2026-03");
+        assert_eq!(Timestamp(-1).to_string(), "1969-12-31 23:59");
+    }
+}
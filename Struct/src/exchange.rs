@@ -0,0 +1,303 @@
+// `exchange` module: converting money between currencies.
+// Rates are read from a text file such as `rates.txt`:
+//
+//     # Lines starting with '#' are comments.
+//     rounding half-even
+//     EUR USD 1.085
+//
+// `EUR USD 1.085` means 1 EUR buys 1.085 USD. The same line also converts
+// USD back to EUR, by dividing by the rate, unless the file gives a
+// `USD EUR` rate of its own. The `rounding` line is required: it names the
+// `Rounding` rule used when a converted amount falls between two minor units
+// of the target currency (cents for USD, whole yen for JPY).
+// A converted amount is rounded exactly once, at the very end.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::money::{parse_fixed, Currency, Money, Rounding};
+
+// Where `main` looks for exchange rates, relative to the working directory.
+pub const DEFAULT_RATES_FILE: &str = "rates.txt";
+
+// Rates are fixed-point too, with six decimal places.
+const RATE_PLACES: u32 = 6;
+const MICROS_PER_UNIT: u128 = 1_000_000;
+
+// How many units of one currency a single unit of another buys, in millionths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rate(u64);
+
+impl Rate {
+    // Reads a positive decimal with at most six decimal places, like "1.085".
+    pub fn parse(text: &str) -> Option<Rate> {
+        let micros = parse_fixed(text, RATE_PLACES)?;
+        match u64::try_from(micros) {
+            Ok(micros) if micros > 0 => Some(Rate(micros)),
+            _ => None,
+        }
+    }
+}
+
+// Why a rates file could not be used.
+#[derive(Debug)]
+pub enum RatesError {
+    Io(io::Error),
+    // Line `line` (counting from 1) is not a rate, a rule or a comment.
+    BadLine { line: usize, problem: String },
+    NoRounding,
+}
+
+impl fmt::Display for RatesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RatesError::Io(error) => write!(f, "{}", error),
+            RatesError::BadLine { line, problem } => write!(f, "line {}: {}", line, problem),
+            RatesError::NoRounding => write!(f, "no `rounding` line"),
+        }
+    }
+}
+
+impl std::error::Error for RatesError {}
+
+// `From` lets `?` turn an `io::Error` into a `RatesError` automatically.
+impl From<io::Error> for RatesError {
+    fn from(error: io::Error) -> RatesError {
+        RatesError::Io(error)
+    }
+}
+
+// Why an amount could not be converted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExchangeError {
+    NoRate { from: Currency, to: Currency },
+    // The converted amount does not fit in `Money`.
+    Overflow { from: Currency, to: Currency },
+}
+
+impl fmt::Display for ExchangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExchangeError::NoRate { from, to } => write!(f, "no rate from {} to {}", from, to),
+            ExchangeError::Overflow { from, to } => {
+                write!(f, "amount too large to convert from {} to {}", from, to)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExchangeError {}
+
+#[derive(Debug, Clone)]
+pub struct ExchangeRates {
+    // Keyed by (from, to) as given in the file.
+    rates: HashMap<(Currency, Currency), Rate>,
+    rounding: Rounding,
+}
+
+impl ExchangeRates {
+    pub fn load(path: &Path) -> Result<ExchangeRates, RatesError> {
+        let text = fs::read_to_string(path)?;
+        ExchangeRates::parse(&text)
+    }
+
+    // Reads the text of a rates file (see the top of this module).
+    pub fn parse(text: &str) -> Result<ExchangeRates, RatesError> {
+        let mut rates = HashMap::new();
+        let mut rounding = None;
+        // `enumerate` numbers the lines from 0; people count them from 1.
+        for (index, line) in text.lines().enumerate() {
+            let bad = |problem: String| RatesError::BadLine {
+                line: index + 1,
+                problem,
+            };
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                [] => {}
+                [first, ..] if first.starts_with('#') => {}
+                ["rounding", name] => match Rounding::from_name(name) {
+                    Some(rule) => rounding = Some(rule),
+                    None => return Err(bad(format!("unknown rounding rule `{}`", name))),
+                },
+                [from, to, rate] => {
+                    let currency = |code: &str| {
+                        Currency::new(code).ok_or_else(|| bad(format!("bad currency `{}`", code)))
+                    };
+                    let (from, to) = (currency(from)?, currency(to)?);
+                    let rate =
+                        Rate::parse(rate).ok_or_else(|| bad(format!("bad rate `{}`", rate)))?;
+                    if from == to {
+                        return Err(bad(format!("{} cannot have a rate to itself", from)));
+                    }
+                    // `insert` returns the old value if the key was already there.
+                    if rates.insert((from, to), rate).is_some() {
+                        return Err(bad(format!("second rate from {} to {}", from, to)));
+                    }
+                }
+                _ => {
+                    return Err(bad(format!(
+                        "expected `FROM TO RATE`, found `{}`",
+                        line.trim()
+                    )))
+                }
+            }
+        }
+        Ok(ExchangeRates {
+            rates,
+            rounding: rounding.ok_or(RatesError::NoRounding)?,
+        })
+    }
+
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+
+    // `amount` of `from`, in `to`, rounded to `to`'s minor unit by the table's rule.
+    pub fn convert(
+        &self,
+        amount: Money,
+        from: Currency,
+        to: Currency,
+    ) -> Result<Money, ExchangeError> {
+        if from == to {
+            return Ok(amount);
+        }
+        // The rate as a fraction: `to` per `from` is `numerator / denominator`.
+        let (numerator, denominator) = if let Some(Rate(micros)) = self.rates.get(&(from, to)) {
+            (u128::from(*micros), MICROS_PER_UNIT)
+        } else if let Some(Rate(micros)) = self.rates.get(&(to, from)) {
+            (MICROS_PER_UNIT, u128::from(*micros))
+        } else {
+            return Err(ExchangeError::NoRate { from, to });
+        };
+        // Currencies with different decimal places scale by a power of ten:
+        // a cent is 1/100 USD, but a yen is a whole JPY.
+        let (from_digits, to_digits) = (from.minor_digits(), to.minor_digits());
+        let overflow = ExchangeError::Overflow { from, to };
+        // `u128` holds any `u64` times any `u64`; only the extra power of ten
+        // for a currency with more decimal places can overflow it.
+        let numerator = u128::from(amount.minor())
+            .checked_mul(numerator)
+            .and_then(|n| n.checked_mul(10u128.pow(to_digits.saturating_sub(from_digits))))
+            .ok_or(overflow)?;
+        let denominator = denominator * 10u128.pow(from_digits.saturating_sub(to_digits));
+        u64::try_from(self.rounding.divide(numerator, denominator))
+            .map(Money::from_minor)
+            .map_err(|_| overflow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(code: &str) -> Currency {
+        Currency::new(code).unwrap()
+    }
+
+    // An amount of euros, dollars or any other two-decimal currency.
+    fn money(text: &str) -> Money {
+        Money::parse(text, code("EUR")).unwrap()
+    }
+
+    #[test]
+    fn parses_a_rates_file() {
+        let rates = ExchangeRates::parse("# rates\nrounding half-up\n\nEUR USD 1.085\n").unwrap();
+        assert_eq!(rates.rounding(), Rounding::HalfUp);
+        assert_eq!(
+            rates.convert(money("10"), code("EUR"), code("USD")),
+            Ok(money("10.85"))
+        );
+
+        let error = |text: &str| ExchangeRates::parse(text).unwrap_err().to_string();
+        assert_eq!(error("EUR USD 1.1"), "no `rounding` line");
+        assert_eq!(
+            error("rounding sideways"),
+            "line 1: unknown rounding rule `sideways`"
+        );
+        assert_eq!(
+            error("rounding up\nEUR usd 1.1"),
+            "line 2: bad currency `usd`"
+        );
+        assert_eq!(error("rounding up\nEUR USD 0"), "line 2: bad rate `0`");
+        assert_eq!(
+            error("rounding up\nEUR USD 1.0000001"),
+            "line 2: bad rate `1.0000001`"
+        );
+        assert_eq!(
+            error("rounding up\nEUR USD 1\nEUR USD 2"),
+            "line 3: second rate from EUR to USD"
+        );
+    }
+
+    #[test]
+    fn conversions_round_once_by_the_table_rule() {
+        let (eur, usd, jpy) = (code("EUR"), code("USD"), code("JPY"));
+        let text = "EUR USD 1.085\nUSD JPY 149.5\n";
+        let with =
+            |rule: &str| ExchangeRates::parse(&format!("rounding {}\n{}", rule, text)).unwrap();
+
+        // 0.10 EUR is exactly 0.1085 USD: past the half cent, so only `down` rounds down.
+        let dime = money("0.10");
+        assert_eq!(with("down").convert(dime, eur, usd), Ok(money("0.10")));
+        assert_eq!(with("up").convert(dime, eur, usd), Ok(money("0.11")));
+        assert_eq!(with("half-up").convert(dime, eur, usd), Ok(money("0.11")));
+        assert_eq!(with("half-even").convert(dime, eur, usd), Ok(money("0.11")));
+
+        // Yen have no minor unit, so conversions round to whole yen.
+        // 1 USD is exactly 149.5 JPY; 3 USD is exactly 448.5 JPY.
+        let yen = |text| Money::parse(text, jpy).unwrap();
+        let half_even = with("half-even");
+        assert_eq!(half_even.convert(money("1"), usd, jpy), Ok(yen("150")));
+        assert_eq!(half_even.convert(money("3"), usd, jpy), Ok(yen("448")));
+        assert_eq!(
+            with("half-up").convert(money("3"), usd, jpy),
+            Ok(yen("449"))
+        );
+        assert_eq!(with("down").convert(money("0.01"), usd, jpy), Ok(yen("1")));
+        // And back: 1000 JPY is 6.688963... USD.
+        assert_eq!(half_even.convert(yen("1000"), jpy, usd), Ok(money("6.69")));
+        assert_eq!(
+            with("down").convert(yen("1000"), jpy, usd),
+            Ok(money("6.68"))
+        );
+
+        // The reverse direction divides: 1 USD is 0.921658... EUR.
+        assert_eq!(half_even.convert(money("1"), usd, eur), Ok(money("0.92")));
+        assert_eq!(with("up").convert(money("1"), usd, eur), Ok(money("0.93")));
+        assert_eq!(half_even.convert(money("5"), eur, eur), Ok(money("5")));
+    }
+
+    #[test]
+    fn conversions_refuse_missing_rates_and_overflow() {
+        let (eur, usd, gbp) = (code("EUR"), code("USD"), code("GBP"));
+        let rates = ExchangeRates::parse("rounding half-even\nEUR USD 2").unwrap();
+        assert_eq!(
+            rates.convert(money("1"), eur, gbp),
+            Err(ExchangeError::NoRate { from: eur, to: gbp })
+        );
+        assert_eq!(
+            rates.convert(Money::from_minor(u64::MAX), eur, usd),
+            Err(ExchangeError::Overflow { from: eur, to: usd })
+        );
+        assert_eq!(
+            rates.convert(Money::from_minor(u64::MAX), usd, eur),
+            Ok(Money::from_minor(u64::MAX / 2 + 1))
+        );
+
+        // Dinars have thousandths: 1 EUR at 0.41 BHD is 410 of them.
+        let bhd = code("BHD");
+        let rates = ExchangeRates::parse("rounding half-even\nEUR BHD 0.41").unwrap();
+        assert_eq!(
+            rates.convert(money("1"), eur, bhd),
+            Ok(Money::from_minor(410))
+        );
+        assert_eq!(
+            rates.convert(Money::from_minor(u64::MAX), eur, bhd),
+            Err(ExchangeError::Overflow { from: eur, to: bhd })
+        );
+    }
+}
@@ -0,0 +1,296 @@
+// `intersect` module: whether two shapes share a point.
+// Rather than a test for every pair of the six shapes, each shape reduces
+// itself to an `Outline`, and only the pairs of the three outlines need
+// their own tests.
+
+use super::{Ellipse, Point, Segment, EPSILON};
+
+// The forms a shape can take for intersection tests. Circles become
+// ellipses; rectangles and triangles become polygons.
+#[derive(Debug, Clone)]
+pub enum Outline {
+    Polygon(Vec<Point>),
+    Ellipse(Ellipse),
+    Segment(Segment),
+}
+
+pub(super) fn outlines_intersect(first: &Outline, second: &Outline) -> bool {
+    // `|` in a pattern matches either side; both sides must bind the same names.
+    match (first, second) {
+        (Outline::Ellipse(ellipse), other) | (other, Outline::Ellipse(ellipse)) => {
+            ellipse_meets(ellipse, other)
+        }
+        (Outline::Segment(first), Outline::Segment(second)) => {
+            segments_intersect(first.start, first.end, second.start, second.end)
+        }
+        (Outline::Polygon(vertices), Outline::Segment(segment))
+        | (Outline::Segment(segment), Outline::Polygon(vertices)) => {
+            polygon_meets_segment(vertices, segment.start, segment.end)
+        }
+        (Outline::Polygon(first), Outline::Polygon(second)) => {
+            // Either some edges cross, or one polygon lies wholly inside the
+            // other, and then any one of its vertices is inside too.
+            edges(first).any(|(a, b)| polygon_meets_segment(second, a, b))
+                || polygon_contains(first, second[0])
+        }
+    }
+}
+
+// Whether `point` is inside the polygon or on its boundary.
+pub(super) fn polygon_contains(vertices: &[Point], point: Point) -> bool {
+    if edges(vertices).any(|(a, b)| segment_distance(point, a, b) <= EPSILON) {
+        return true;
+    }
+    // Ray casting: follow a ray from `point` to the right. Each time it
+    // crosses an edge it goes from inside to outside or back.
+    let mut inside = false;
+    for (a, b) in edges(vertices) {
+        if (a.y > point.y) != (b.y > point.y) {
+            let crossing_x = a.x + (point.y - a.y) * (b.x - a.x) / (b.y - a.y);
+            if point.x < crossing_x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+// The distance from `point` to the nearest point of the segment `a`-`b`.
+pub(super) fn segment_distance(point: Point, a: Point, b: Point) -> f64 {
+    let along = b - a;
+    let length_squared = along.dot(along);
+    if length_squared == 0.0 {
+        return point.distance(a);
+    }
+    // How far along the segment the nearest point is, from 0 at `a` to 1 at `b`.
+    let t = ((point - a).dot(along) / length_squared).clamp(0.0, 1.0);
+    point.distance(a + along * t)
+}
+
+// Each edge of a polygon as (start, end), including the closing edge.
+// `cycle().skip(1)` pairs every vertex with the next, and the last with the first.
+pub(super) fn edges(vertices: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+fn segments_intersect(a1: Point, a2: Point, b1: Point, b2: Point) -> bool {
+    // Each cross product says which side of one segment an end of the other
+    // lies on. The segments cross when each one's ends are on opposite
+    // sides of the other.
+    let side = |from: Point, to: Point, point: Point| (to - from).cross(point - from);
+    let crossing =
+        side(b1, b2, a1) * side(b1, b2, a2) < 0.0 && side(a1, a2, b1) * side(a1, a2, b2) < 0.0;
+    // Otherwise they can still touch: an end of one lies on the other.
+    // This also covers segments on the same line that overlap.
+    crossing
+        || segment_distance(a1, b1, b2) <= EPSILON
+        || segment_distance(a2, b1, b2) <= EPSILON
+        || segment_distance(b1, a1, a2) <= EPSILON
+        || segment_distance(b2, a1, a2) <= EPSILON
+}
+
+fn polygon_meets_segment(vertices: &[Point], start: Point, end: Point) -> bool {
+    // A segment that crosses no edge is either wholly inside or wholly outside.
+    polygon_contains(vertices, start)
+        || edges(vertices).any(|(a, b)| segments_intersect(a, b, start, end))
+}
+
+// Ellipses are tested by moving everything into the ellipse's own frame
+// and stretching it there, so that the ellipse becomes the unit circle
+// around the origin. Stretching keeps straight lines straight and maps
+// ellipses to ellipses, so the other shape stays the same kind of shape,
+// and the question becomes: is it within distance 1 of the origin?
+fn ellipse_meets(ellipse: &Ellipse, other: &Outline) -> bool {
+    let to_unit = |point: Point| {
+        let local = ellipse.to_local(point);
+        Point::new(local.x / ellipse.rx, local.y / ellipse.ry)
+    };
+    let reach = 1.0 + EPSILON;
+    match other {
+        Outline::Segment(segment) => {
+            segment_distance(Point::ORIGIN, to_unit(segment.start), to_unit(segment.end)) <= reach
+        }
+        Outline::Polygon(vertices) => {
+            let vertices: Vec<Point> = vertices.iter().map(|&p| to_unit(p)).collect();
+            polygon_contains(&vertices, Point::ORIGIN)
+                || edges(&vertices).any(|(a, b)| segment_distance(Point::ORIGIN, a, b) <= reach)
+        }
+        Outline::Ellipse(other) => {
+            let stretched = stretch(other, ellipse);
+            ellipse_distance(&stretched, Point::ORIGIN) <= reach
+        }
+    }
+}
+
+// `other` as it looks in the unit-circle frame of `frame`.
+//
+// The other ellipse is the unit circle put through the 2x2 matrix
+// `M = S * R(-frame.angle) * R(other.angle) * D`, where `D` scales by its
+// radii, `R` rotates and `S` divides by the frame's radii. Any such matrix
+// can be written `R(phi) * diag(sx, sy) * R(theta)`, its singular value
+// decomposition, and `R(theta)` does not change the unit circle. So the
+// result is the ellipse with radii `sx`, `sy`, turned `phi`.
+fn stretch(other: &Ellipse, frame: &Ellipse) -> Ellipse {
+    let turn = other.angle - frame.angle;
+    let (sin, cos) = turn.sin_cos();
+    // The matrix entries, row by row.
+    let (a, b) = (cos * other.rx / frame.rx, -sin * other.ry / frame.rx);
+    let (c, d) = (sin * other.rx / frame.ry, cos * other.ry / frame.ry);
+    // The closed form of a 2x2 singular value decomposition.
+    let (e, f) = ((a + d) / 2.0, (a - d) / 2.0);
+    let (g, h) = ((c + b) / 2.0, (c - b) / 2.0);
+    let (q, r) = (e.hypot(h), f.hypot(g));
+    let phi = (h.atan2(e) + g.atan2(f)) / 2.0;
+    let local = frame.to_local(other.center);
+    let center = Point::new(local.x / frame.rx, local.y / frame.ry);
+    Ellipse::new(center, q + r, q - r, phi)
+}
+
+// The distance from `point` to the nearest point of the filled ellipse:
+// zero when it is inside.
+fn ellipse_distance(ellipse: &Ellipse, point: Point) -> f64 {
+    let local = ellipse.to_local(point);
+    // By symmetry only the first quadrant matters. Put the longer radius on x.
+    let (a, b, x, y) = if ellipse.rx >= ellipse.ry {
+        (ellipse.rx, ellipse.ry, local.x.abs(), local.y.abs())
+    } else {
+        (ellipse.ry, ellipse.rx, local.y.abs(), local.x.abs())
+    };
+    if b <= EPSILON * a {
+        // Flat: the distance to the line from (-a, 0) to (a, 0).
+        return (x - a).max(0.0).hypot(y);
+    }
+    if (x / a).powi(2) + (y / b).powi(2) <= 1.0 {
+        return 0.0;
+    }
+    distance_outside(a, b, x, y)
+}
+
+// The distance from (x, y), outside the ellipse with radii a >= b, to its
+// edge. This is David Eberly's method: the nearest point on the edge
+// solves a one-variable equation whose root can be found by bisection.
+// See "Distance from a Point to an Ellipse, an Ellipsoid, or a Hyperellipsoid".
+fn distance_outside(a: f64, b: f64, x: f64, y: f64) -> f64 {
+    // On an axis the nearest point is the end of that axis.
+    if y == 0.0 {
+        return x - a;
+    }
+    if x == 0.0 {
+        return y - b;
+    }
+    let (z0, z1) = (x / a, y / b);
+    let ratio = (a / b).powi(2);
+    let g = z0 * z0 + z1 * z1 - 1.0;
+    let n0 = ratio * z0;
+    let mut low = z1 - 1.0;
+    let mut high = if g < 0.0 { 0.0 } else { n0.hypot(z1) - 1.0 };
+    let mut s = low;
+    // Halving the range until it stops shrinking gives full precision.
+    for _ in 0..200 {
+        s = (low + high) / 2.0;
+        if s == low || s == high {
+            break;
+        }
+        let value = (n0 / (s + ratio)).powi(2) + (z1 / (s + 1.0)).powi(2) - 1.0;
+        if value > 0.0 {
+            low = s;
+        } else if value < 0.0 {
+            high = s;
+        } else {
+            break;
+        }
+    }
+    let near = Point::new(ratio * x / (s + ratio), y / (s + 1.0));
+    near.distance(Point::new(x, y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{Circle, Polygon, Rectangle, Shape, Transform, Triangle};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn segments_touch_cross_and_miss() {
+        let p = Point::new;
+        // Crossing, touching at an end, overlapping on one line, and apart.
+        assert!(segments_intersect(
+            p(0.0, 0.0),
+            p(2.0, 2.0),
+            p(0.0, 2.0),
+            p(2.0, 0.0)
+        ));
+        assert!(segments_intersect(
+            p(0.0, 0.0),
+            p(1.0, 1.0),
+            p(1.0, 1.0),
+            p(2.0, 0.0)
+        ));
+        assert!(segments_intersect(
+            p(0.0, 0.0),
+            p(2.0, 0.0),
+            p(1.0, 0.0),
+            p(3.0, 0.0)
+        ));
+        assert!(!segments_intersect(
+            p(0.0, 0.0),
+            p(1.0, 0.0),
+            p(2.0, 0.0),
+            p(3.0, 0.0)
+        ));
+        assert!(!segments_intersect(
+            p(0.0, 0.0),
+            p(2.0, 0.0),
+            p(0.0, 1.0),
+            p(2.0, 1.0)
+        ));
+    }
+
+    #[test]
+    fn ellipse_pairs() {
+        let wide = Ellipse::new(Point::ORIGIN, 4.0, 1.0, 0.0);
+        let tall = Ellipse::new(Point::new(5.0, 0.0), 1.0, 4.0, 0.0);
+        // Just touching at (4, 0), then pulled a little apart.
+        assert!(wide.intersects(&tall));
+        assert!(!wide.intersects(&tall.translate(0.01, 0.0)));
+        // Turned a quarter turn, the tall one lies flat and overlaps.
+        assert!(wide.intersects(&tall.rotate(PI / 2.0, tall.center).translate(2.0, 0.0)));
+        // A circle next to the flat side of the wide ellipse.
+        let circle = Circle::new(Point::new(0.0, 2.0), 0.99);
+        assert!(!wide.intersects(&circle));
+        assert!(wide.intersects(&circle.translate(0.0, -0.02)));
+    }
+
+    #[test]
+    fn shapes_inside_each_other() {
+        // No edges cross, yet one shape holds the other.
+        let big = Rectangle::new(Point::ORIGIN, 10.0, 10.0, 0.3);
+        let small = Triangle::new(Point::ORIGIN, Point::new(1.0, 0.0), Point::new(0.0, 1.0));
+        assert!(big.intersects(&small) && small.intersects(&big));
+        let ring = Circle::new(Point::ORIGIN, 20.0);
+        let square = Polygon::new(big.corners().to_vec()).unwrap();
+        assert!(ring.intersects(&square) && square.intersects(&ring));
+        let inner = Ellipse::new(Point::ORIGIN, 1.0, 0.5, 1.0);
+        assert!(ring.intersects(&inner) && inner.intersects(&ring));
+    }
+
+    #[test]
+    fn distance_to_an_ellipse() {
+        let ellipse = Ellipse::new(Point::ORIGIN, 3.0, 1.0, 0.0);
+        assert_eq!(ellipse_distance(&ellipse, Point::new(1.0, 0.5)), 0.0);
+        assert!((ellipse_distance(&ellipse, Point::new(5.0, 0.0)) - 2.0).abs() < 1e-12);
+        assert!((ellipse_distance(&ellipse, Point::new(0.0, 4.0)) - 3.0).abs() < 1e-12);
+        // Against a fine polygon around the ellipse from far off the axes.
+        let fine = ellipse.to_polygon(100_000);
+        let point = Point::new(4.0, 3.0);
+        let nearest = fine
+            .vertices()
+            .iter()
+            .map(|&v| v.distance(point))
+            .fold(f64::INFINITY, f64::min);
+        assert!((ellipse_distance(&ellipse, point) - nearest).abs() < 1e-6);
+    }
+}
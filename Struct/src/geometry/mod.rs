@@ -0,0 +1,500 @@
+// `geometry` module: shapes in the plane and questions about them.
+// The old `Shape` trait forced every shape through `new(length, width)`, which
+// made no sense for a circle. Now each shape has its own constructor, and the
+// behaviour is split between two traits:
+//
+// * `Shape`: measurements and queries (area, perimeter, bounding box,
+//   centroid, point containment, intersection with any other shape).
+// * `Transform`: moving shapes around (translate, rotate, scale).
+//
+// Shapes are closed regions: a point on the edge is inside, and two shapes
+// that only touch do intersect. Coordinates are `f64`, and comparisons allow
+// for rounding errors of up to `EPSILON`.
+
+mod intersect;
+mod shapes;
+
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+pub use intersect::Outline;
+pub use shapes::{Circle, Ellipse, Polygon, Rectangle, Segment, Triangle};
+
+// How far apart two values may be and still count as equal.
+pub const EPSILON: f64 = 1e-9;
+
+// A point, or the offset between two points, with `f64` coordinates.
+// `Copy` is fine for two floats and saves writing `.clone()` everywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0.0, y: 0.0 };
+
+    pub const fn new(x: f64, y: f64) -> Point {
+        Point { x, y }
+    }
+
+    // The dot product: how far two offsets point the same way.
+    pub fn dot(self, other: Point) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    // The 2D cross product: positive when `other` is counter-clockwise
+    // from `self`, negative when clockwise and zero when they line up.
+    pub fn cross(self, other: Point) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    // Length of the offset from the origin to this point.
+    pub fn length(self) -> f64 {
+        // `hypot` computes sqrt(x² + y²) without overflowing on large values.
+        self.x.hypot(self.y)
+    }
+
+    pub fn distance(self, other: Point) -> f64 {
+        (self - other).length()
+    }
+
+    pub fn translate(self, dx: f64, dy: f64) -> Point {
+        Point::new(self.x + dx, self.y + dy)
+    }
+
+    // Turns the point `radians` counter-clockwise around `about`.
+    pub fn rotate(self, radians: f64, about: Point) -> Point {
+        // `sin_cos` computes both at once.
+        let (sin, cos) = radians.sin_cos();
+        let offset = self - about;
+        about
+            + Point::new(
+                offset.x * cos - offset.y * sin,
+                offset.x * sin + offset.y * cos,
+            )
+    }
+
+    // Moves the point `factor` times as far from `about`.
+    pub fn scale(self, factor: f64, about: Point) -> Point {
+        about + (self - about) * factor
+    }
+}
+
+// Operator overloading: implementing `Add` lets us write `a + b` for points.
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<f64> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: f64) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // `{:.2}` prints two decimal places.
+        write!(f, "({:.2}, {:.2})", self.x, self.y)
+    }
+}
+
+// The smallest upright rectangle around a shape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl BoundingBox {
+    // The box around all of `points`. There must be at least one.
+    pub fn around(points: &[Point]) -> BoundingBox {
+        let first = points[0];
+        // `fold` grows the box one point at a time.
+        points.iter().fold(
+            BoundingBox {
+                min: first,
+                max: first,
+            },
+            |bounds, point| BoundingBox {
+                min: Point::new(bounds.min.x.min(point.x), bounds.min.y.min(point.y)),
+                max: Point::new(bounds.max.x.max(point.x), bounds.max.y.max(point.y)),
+            },
+        )
+    }
+
+    pub fn width(&self) -> f64 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f64 {
+        self.max.y - self.min.y
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        point.x >= self.min.x - EPSILON
+            && point.x <= self.max.x + EPSILON
+            && point.y >= self.min.y - EPSILON
+            && point.y <= self.max.y + EPSILON
+    }
+
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.min.x <= other.max.x + EPSILON
+            && other.min.x <= self.max.x + EPSILON
+            && self.min.y <= other.max.y + EPSILON
+            && other.min.y <= self.max.y + EPSILON
+    }
+}
+
+impl fmt::Display for BoundingBox {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} to {}", self.min, self.max)
+    }
+}
+
+// `trait` keyword defines a shared interface that types can implement.
+// Traits are similar to interfaces in other languages.
+// Nothing here mentions `Self` by value, so the trait can be used as
+// `&dyn Shape`: one function can take a circle or a polygon alike.
+pub trait Shape {
+    // The kind of shape, such as "circle", for printing.
+    fn name(&self) -> &'static str;
+    fn area(&self) -> f64;
+    // The length of the boundary. For a line segment, its length.
+    fn perimeter(&self) -> f64;
+    fn bounding_box(&self) -> BoundingBox;
+    // The centre of mass of the shape's area (of its length, for a segment).
+    fn centroid(&self) -> Point;
+    // Whether `point` is inside the shape or on its boundary.
+    fn contains(&self, point: Point) -> bool;
+    // The shape reduced to one of the three forms intersection tests work
+    // with: a polygon, an ellipse or a segment.
+    fn outline(&self) -> Outline;
+
+    // A provided method: every shape gets it for free from `outline`.
+    // Whether the two shapes share at least one point.
+    fn intersects(&self, other: &dyn Shape) -> bool {
+        // Shapes whose boxes are apart cannot meet, and the box test is cheap.
+        self.bounding_box().intersects(&other.bounding_box())
+            && intersect::outlines_intersect(&self.outline(), &other.outline())
+    }
+}
+
+// Moving a shape gives back a new shape of the same type, so the trait needs
+// `Self: Sized` to return `Self` by value. Keeping it apart from `Shape`
+// leaves `Shape` usable as `&dyn Shape`.
+// Scaling is uniform: a circle must stay a circle. A negative `factor`
+// also turns the shape half a turn around `about`.
+pub trait Transform: Sized {
+    fn translate(&self, dx: f64, dy: f64) -> Self;
+    // Turns the shape `radians` counter-clockwise around `about`.
+    fn rotate(&self, radians: f64, about: Point) -> Self;
+    fn scale(&self, factor: f64, about: Point) -> Self;
+}
+
+#[cfg(test)]
+mod tests {
+    // Property tests: each one checks a rule that must hold for every shape,
+    // on many shapes and points made by a seeded pseudo-random generator, so
+    // a failure can always be reproduced.
+    use super::*;
+    use crate::test_rng::TestRng;
+    use std::f64::consts::PI;
+
+    // A point in the square from `-size` to `size` on both axes.
+    fn random_point(random: &mut TestRng, size: f64) -> Point {
+        Point::new(random.range(-size, size), random.range(-size, size))
+    }
+
+    // One shape of every kind, generated in a way that lets the tests
+    // check each of them through the same traits.
+    #[derive(Debug, Clone)]
+    enum AnyShape {
+        Circle(Circle),
+        Ellipse(Ellipse),
+        Rectangle(Rectangle),
+        Triangle(Triangle),
+        Polygon(Polygon),
+        Segment(Segment),
+    }
+
+    impl AnyShape {
+        fn random(random: &mut TestRng, kind: usize) -> AnyShape {
+            let center = random_point(random, 10.0);
+            let angle = random.range(-PI, PI);
+            match kind % 6 {
+                0 => AnyShape::Circle(Circle::new(center, random.range(0.5, 5.0))),
+                1 => AnyShape::Ellipse(Ellipse::new(
+                    center,
+                    random.range(0.5, 5.0),
+                    random.range(0.5, 5.0),
+                    angle,
+                )),
+                2 => AnyShape::Rectangle(Rectangle::new(
+                    center,
+                    random.range(0.5, 8.0),
+                    random.range(0.5, 8.0),
+                    angle,
+                )),
+                3 => AnyShape::Triangle(Triangle::new(
+                    center + random_point(random, 4.0),
+                    center + random_point(random, 4.0),
+                    center + random_point(random, 4.0),
+                )),
+                4 => {
+                    // A star-shaped polygon: points at increasing angles around
+                    // the centre, less than half a turn apart, never cross
+                    // each other's edges.
+                    let count = 3 + (random.range(0.0, 6.0) as usize);
+                    let step = 2.0 * PI / count as f64;
+                    let angles: Vec<f64> = (0..count)
+                        .map(|i| (i as f64 + random.range(0.0, 0.5)) * step)
+                        .collect();
+                    let vertices = angles
+                        .iter()
+                        .map(|&a| center + Point::new(a.cos(), a.sin()) * random.range(1.0, 5.0))
+                        .collect();
+                    AnyShape::Polygon(Polygon::new(vertices).expect("at least three vertices"))
+                }
+                _ => AnyShape::Segment(Segment::new(center, center + random_point(random, 5.0))),
+            }
+        }
+
+        fn shape(&self) -> &dyn Shape {
+            match self {
+                AnyShape::Circle(shape) => shape,
+                AnyShape::Ellipse(shape) => shape,
+                AnyShape::Rectangle(shape) => shape,
+                AnyShape::Triangle(shape) => shape,
+                AnyShape::Polygon(shape) => shape,
+                AnyShape::Segment(shape) => shape,
+            }
+        }
+
+        // Applies the same change to whichever shape this is.
+        // A closure cannot be generic over the six types, so the change is
+        // described by a `Move` and applied by a generic inner function.
+        fn moved(&self, change: Move) -> AnyShape {
+            fn apply<T: Transform>(shape: &T, change: Move) -> T {
+                match change {
+                    Move::Translate(dx, dy) => shape.translate(dx, dy),
+                    Move::Rotate(radians, about) => shape.rotate(radians, about),
+                    Move::Scale(factor, about) => shape.scale(factor, about),
+                }
+            }
+            match self {
+                AnyShape::Circle(shape) => AnyShape::Circle(apply(shape, change)),
+                AnyShape::Ellipse(shape) => AnyShape::Ellipse(apply(shape, change)),
+                AnyShape::Rectangle(shape) => AnyShape::Rectangle(apply(shape, change)),
+                AnyShape::Triangle(shape) => AnyShape::Triangle(apply(shape, change)),
+                AnyShape::Polygon(shape) => AnyShape::Polygon(apply(shape, change)),
+                AnyShape::Segment(shape) => AnyShape::Segment(apply(shape, change)),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy)]
+    enum Move {
+        Translate(f64, f64),
+        Rotate(f64, Point),
+        Scale(f64, Point),
+    }
+
+    impl Move {
+        fn random(random: &mut TestRng) -> Move {
+            match (random.range(0.0, 3.0)) as usize {
+                0 => Move::Translate(random.range(-20.0, 20.0), random.range(-20.0, 20.0)),
+                1 => Move::Rotate(
+                    random.range(-2.0 * PI, 2.0 * PI),
+                    random_point(random, 10.0),
+                ),
+                _ => Move::Scale(random.range(-3.0, 3.0), random_point(random, 10.0)),
+            }
+        }
+
+        // Where the move takes a single point.
+        fn point(&self, point: Point) -> Point {
+            match *self {
+                Move::Translate(dx, dy) => point.translate(dx, dy),
+                Move::Rotate(radians, about) => point.rotate(radians, about),
+                Move::Scale(factor, about) => point.scale(factor, about),
+            }
+        }
+
+        // How the move changes lengths.
+        fn length_factor(&self) -> f64 {
+            match *self {
+                Move::Scale(factor, _) => factor.abs(),
+                _ => 1.0,
+            }
+        }
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-6 * (1.0 + a.abs().max(b.abs()))
+    }
+
+    const CASES: usize = 600;
+
+    #[test]
+    fn transforms_keep_measurements_consistent() {
+        let mut random = TestRng::new(1);
+        for case in 0..CASES {
+            let original = AnyShape::random(&mut random, case);
+            let change = Move::random(&mut random);
+            let moved = original.moved(change);
+            let (before, after) = (original.shape(), moved.shape());
+            let k = change.length_factor();
+            assert!(
+                close(after.area(), before.area() * k * k),
+                "{:?} {:?}",
+                original,
+                change
+            );
+            assert!(
+                close(after.perimeter(), before.perimeter() * k),
+                "{:?} {:?}",
+                original,
+                change
+            );
+            let expected = change.point(before.centroid());
+            assert!(
+                close(after.centroid().distance(expected), 0.0),
+                "{:?} {:?}",
+                original,
+                change
+            );
+        }
+    }
+
+    #[test]
+    fn contained_points_move_with_the_shape() {
+        let mut random = TestRng::new(2);
+        for case in 0..CASES {
+            let original = AnyShape::random(&mut random, case);
+            let change = Move::random(&mut random);
+            if change.length_factor() < 0.1 {
+                continue; // too small to tell points apart reliably
+            }
+            let moved = original.moved(change);
+            for _ in 0..20 {
+                let point = original.shape().centroid() + random_point(&mut random, 6.0);
+                let inside = original.shape().contains(point);
+                // Points right on the edge may land either side after rounding.
+                if inside != moved.shape().contains(change.point(point)) {
+                    let nudged = (0..8).any(|i| {
+                        let offset =
+                            Point::new(1e-6, 0.0).rotate(i as f64 * PI / 4.0, Point::ORIGIN);
+                        original.shape().contains(point + offset) != inside
+                    });
+                    assert!(nudged, "{:?} {:?} {}", original, change, point);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn boxes_hold_the_shape() {
+        let mut random = TestRng::new(3);
+        for case in 0..CASES {
+            let any = AnyShape::random(&mut random, case);
+            let shape = any.shape();
+            let bounds = shape.bounding_box();
+            assert!(bounds.contains(shape.centroid()), "{:?}", any);
+            assert!(
+                shape.contains(shape.centroid()) || shape.name() == "polygon",
+                "{:?}",
+                any
+            );
+            for _ in 0..50 {
+                let point = shape.centroid() + random_point(&mut random, 8.0);
+                if shape.contains(point) {
+                    assert!(bounds.contains(point), "{:?} {}", any, point);
+                }
+            }
+            // The box is tight: each of its sides touches the shape.
+            let corners = [bounds.min, bounds.max];
+            for corner in corners {
+                let edge_x = Segment::new(
+                    Point::new(corner.x, bounds.min.y),
+                    Point::new(corner.x, bounds.max.y),
+                );
+                let edge_y = Segment::new(
+                    Point::new(bounds.min.x, corner.y),
+                    Point::new(bounds.max.x, corner.y),
+                );
+                assert!(
+                    shape.intersects(&edge_x) && shape.intersects(&edge_y),
+                    "{:?}",
+                    any
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn intersection_agrees_with_shared_points() {
+        let mut random = TestRng::new(4);
+        for case in 0..CASES * 4 {
+            let first = AnyShape::random(&mut random, case);
+            let second = AnyShape::random(&mut random, case / 6 + case);
+            let (a, b) = (first.shape(), second.shape());
+            let meets = a.intersects(b);
+            assert_eq!(meets, b.intersects(a), "{:?} {:?}", first, second);
+            assert!(a.intersects(a), "{:?}", first);
+            // A point inside both proves they meet.
+            for _ in 0..30 {
+                let point = a.centroid() + random_point(&mut random, 6.0);
+                if a.contains(point) && b.contains(point) {
+                    assert!(meets, "{:?} {:?} share {}", first, second, point);
+                }
+            }
+            // Moving the second shape far away separates them.
+            let far = second.moved(Move::Translate(100.0, 0.0));
+            assert!(!a.intersects(far.shape()), "{:?} {:?}", first, second);
+        }
+    }
+
+    #[test]
+    fn curved_shapes_match_fine_polygons() {
+        // An ellipse and a polygon with many vertices on its edge should
+        // agree on area, perimeter and on which shapes they meet.
+        let mut random = TestRng::new(5);
+        for case in 0..100 {
+            let ellipse = Ellipse::new(
+                random_point(&mut random, 5.0),
+                random.range(0.5, 5.0),
+                random.range(0.5, 5.0),
+                random.range(-PI, PI),
+            );
+            let fine = ellipse.to_polygon(4_000);
+            assert!((fine.area() - ellipse.area()).abs() < 1e-3 * ellipse.area());
+            assert!((fine.perimeter() - ellipse.perimeter()).abs() < 1e-3 * ellipse.perimeter());
+            let other = AnyShape::random(&mut random, case);
+            let other = other.shape();
+            // The polygon lies inside the ellipse, so it can only meet less.
+            if fine.intersects(other) {
+                assert!(
+                    ellipse.intersects(other),
+                    "{:?} {:?}",
+                    ellipse,
+                    other.outline()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,566 @@
+// `shapes` module: the shapes themselves.
+// Each shape has a constructor of its own, implements `Shape` for
+// measurements and `Transform` for moving it around.
+
+// `std::f64::consts::PI` is π (pi) to full `f64` precision.
+use std::f64::consts::PI;
+
+use super::intersect::{edges, polygon_contains, segment_distance};
+use super::{BoundingBox, Outline, Point, Shape, Transform, EPSILON};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    pub center: Point,
+    pub radius: f64,
+}
+
+impl Circle {
+    pub fn new(center: Point, radius: f64) -> Circle {
+        // `abs` keeps a negative radius from turning the maths inside out.
+        Circle {
+            center,
+            radius: radius.abs(),
+        }
+    }
+}
+
+impl Shape for Circle {
+    fn name(&self) -> &'static str {
+        "circle"
+    }
+
+    fn area(&self) -> f64 {
+        PI * self.radius * self.radius
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * PI * self.radius
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        let corner = Point::new(self.radius, self.radius);
+        BoundingBox {
+            min: self.center - corner,
+            max: self.center + corner,
+        }
+    }
+
+    fn centroid(&self) -> Point {
+        self.center
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        point.distance(self.center) <= self.radius + EPSILON
+    }
+
+    fn outline(&self) -> Outline {
+        // A circle is an ellipse with equal radii.
+        Ellipse::new(self.center, self.radius, self.radius, 0.0).outline()
+    }
+}
+
+impl Transform for Circle {
+    fn translate(&self, dx: f64, dy: f64) -> Circle {
+        Circle::new(self.center.translate(dx, dy), self.radius)
+    }
+
+    fn rotate(&self, radians: f64, about: Point) -> Circle {
+        Circle::new(self.center.rotate(radians, about), self.radius)
+    }
+
+    fn scale(&self, factor: f64, about: Point) -> Circle {
+        Circle::new(self.center.scale(factor, about), self.radius * factor.abs())
+    }
+}
+
+// An ellipse with radii `rx` and `ry` along its own axes, turned `angle`
+// radians counter-clockwise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ellipse {
+    pub center: Point,
+    pub rx: f64,
+    pub ry: f64,
+    pub angle: f64,
+}
+
+impl Ellipse {
+    pub fn new(center: Point, rx: f64, ry: f64, angle: f64) -> Ellipse {
+        Ellipse {
+            center,
+            rx: rx.abs(),
+            ry: ry.abs(),
+            angle,
+        }
+    }
+
+    // `point` in the ellipse's own frame: centred on the origin and turned
+    // back so that its radii lie along the x and y axes.
+    pub fn to_local(self, point: Point) -> Point {
+        (point - self.center).rotate(-self.angle, Point::ORIGIN)
+    }
+
+    // The point at `t` radians around the ellipse's edge.
+    pub fn point_at(&self, t: f64) -> Point {
+        let local = Point::new(self.rx * t.cos(), self.ry * t.sin());
+        self.center + local.rotate(self.angle, Point::ORIGIN)
+    }
+
+    // A polygon with `sides` vertices evenly spread around the edge.
+    pub fn to_polygon(self, sides: usize) -> Polygon {
+        let vertices = (0..sides.max(3))
+            .map(|i| self.point_at(2.0 * PI * i as f64 / sides.max(3) as f64))
+            .collect();
+        Polygon { vertices }
+    }
+}
+
+impl Shape for Ellipse {
+    fn name(&self) -> &'static str {
+        "ellipse"
+    }
+
+    fn area(&self) -> f64 {
+        PI * self.rx * self.ry
+    }
+
+    // An ellipse's perimeter has no exact formula in elementary functions.
+    // Ramanujan's second approximation is accurate to about one part in a
+    // million even for quite flat ellipses, and exact for circles.
+    fn perimeter(&self) -> f64 {
+        let (a, b) = (self.rx, self.ry);
+        if a + b == 0.0 {
+            return 0.0;
+        }
+        let h = ((a - b) / (a + b)).powi(2);
+        PI * (a + b) * (1.0 + 3.0 * h / (10.0 + (4.0 - 3.0 * h).sqrt()))
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        // How far the turned ellipse reaches along x and along y.
+        let (sin, cos) = self.angle.sin_cos();
+        let half = Point::new(
+            (self.rx * cos).hypot(self.ry * sin),
+            (self.rx * sin).hypot(self.ry * cos),
+        );
+        BoundingBox {
+            min: self.center - half,
+            max: self.center + half,
+        }
+    }
+
+    fn centroid(&self) -> Point {
+        self.center
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        if self.rx.min(self.ry) <= EPSILON {
+            // A flat ellipse is just a line; let its outline decide.
+            return match self.outline() {
+                Outline::Segment(segment) => segment.contains(point),
+                _ => unreachable!("flat ellipses have segment outlines"),
+            };
+        }
+        let local = self.to_local(point);
+        (local.x / self.rx).powi(2) + (local.y / self.ry).powi(2) <= 1.0 + EPSILON
+    }
+
+    fn outline(&self) -> Outline {
+        // Intersection tests divide by the radii, so an ellipse squashed
+        // flat is treated as the line segment it has become.
+        if self.rx.min(self.ry) <= EPSILON {
+            let reach = if self.rx >= self.ry {
+                Point::new(self.rx, 0.0)
+            } else {
+                Point::new(0.0, self.ry)
+            };
+            let reach = reach.rotate(self.angle, Point::ORIGIN);
+            Outline::Segment(Segment::new(self.center - reach, self.center + reach))
+        } else {
+            Outline::Ellipse(*self)
+        }
+    }
+}
+
+impl Transform for Ellipse {
+    fn translate(&self, dx: f64, dy: f64) -> Ellipse {
+        Ellipse::new(self.center.translate(dx, dy), self.rx, self.ry, self.angle)
+    }
+
+    fn rotate(&self, radians: f64, about: Point) -> Ellipse {
+        let center = self.center.rotate(radians, about);
+        Ellipse::new(center, self.rx, self.ry, self.angle + radians)
+    }
+
+    // A negative factor also turns the ellipse half a turn, which leaves it
+    // looking the same, so only the centre needs to know about the sign.
+    fn scale(&self, factor: f64, about: Point) -> Ellipse {
+        let k = factor.abs();
+        let center = self.center.scale(factor, about);
+        Ellipse::new(center, self.rx * k, self.ry * k, self.angle)
+    }
+}
+
+// A `width` by `height` rectangle turned `angle` radians around its centre.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rectangle {
+    pub center: Point,
+    pub width: f64,
+    pub height: f64,
+    pub angle: f64,
+}
+
+impl Rectangle {
+    pub fn new(center: Point, width: f64, height: f64, angle: f64) -> Rectangle {
+        Rectangle {
+            center,
+            width: width.abs(),
+            height: height.abs(),
+            angle,
+        }
+    }
+
+    // The four corners, counter-clockwise.
+    pub fn corners(&self) -> [Point; 4] {
+        let (w, h) = (self.width / 2.0, self.height / 2.0);
+        // `map` on an array builds a new array of the same length.
+        [
+            Point::new(-w, -h),
+            Point::new(w, -h),
+            Point::new(w, h),
+            Point::new(-w, h),
+        ]
+        .map(|corner| self.center + corner.rotate(self.angle, Point::ORIGIN))
+    }
+}
+
+impl Shape for Rectangle {
+    fn name(&self) -> &'static str {
+        "rectangle"
+    }
+
+    fn area(&self) -> f64 {
+        self.width * self.height
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * (self.width + self.height)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::around(&self.corners())
+    }
+
+    fn centroid(&self) -> Point {
+        self.center
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        // In the rectangle's own frame the test is a simple range check.
+        let local = (point - self.center).rotate(-self.angle, Point::ORIGIN);
+        local.x.abs() <= self.width / 2.0 + EPSILON && local.y.abs() <= self.height / 2.0 + EPSILON
+    }
+
+    fn outline(&self) -> Outline {
+        Outline::Polygon(self.corners().to_vec())
+    }
+}
+
+impl Transform for Rectangle {
+    fn translate(&self, dx: f64, dy: f64) -> Rectangle {
+        let center = self.center.translate(dx, dy);
+        Rectangle::new(center, self.width, self.height, self.angle)
+    }
+
+    fn rotate(&self, radians: f64, about: Point) -> Rectangle {
+        let center = self.center.rotate(radians, about);
+        Rectangle::new(center, self.width, self.height, self.angle + radians)
+    }
+
+    fn scale(&self, factor: f64, about: Point) -> Rectangle {
+        let k = factor.abs();
+        let center = self.center.scale(factor, about);
+        Rectangle::new(center, self.width * k, self.height * k, self.angle)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Triangle {
+    pub a: Point,
+    pub b: Point,
+    pub c: Point,
+}
+
+impl Triangle {
+    pub fn new(a: Point, b: Point, c: Point) -> Triangle {
+        Triangle { a, b, c }
+    }
+
+    // Applies `change` to each corner.
+    fn map(&self, change: impl Fn(Point) -> Point) -> Triangle {
+        Triangle::new(change(self.a), change(self.b), change(self.c))
+    }
+}
+
+impl Shape for Triangle {
+    fn name(&self) -> &'static str {
+        "triangle"
+    }
+
+    fn area(&self) -> f64 {
+        // Half the cross product of two sides; its sign only says which way round.
+        (self.b - self.a).cross(self.c - self.a).abs() / 2.0
+    }
+
+    fn perimeter(&self) -> f64 {
+        self.a.distance(self.b) + self.b.distance(self.c) + self.c.distance(self.a)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::around(&[self.a, self.b, self.c])
+    }
+
+    fn centroid(&self) -> Point {
+        (self.a + self.b + self.c) * (1.0 / 3.0)
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        polygon_contains(&[self.a, self.b, self.c], point)
+    }
+
+    fn outline(&self) -> Outline {
+        Outline::Polygon(vec![self.a, self.b, self.c])
+    }
+}
+
+impl Transform for Triangle {
+    fn translate(&self, dx: f64, dy: f64) -> Triangle {
+        self.map(|p| p.translate(dx, dy))
+    }
+
+    fn rotate(&self, radians: f64, about: Point) -> Triangle {
+        self.map(|p| p.rotate(radians, about))
+    }
+
+    fn scale(&self, factor: f64, about: Point) -> Triangle {
+        self.map(|p| p.scale(factor, about))
+    }
+}
+
+// A simple polygon: its edges join the vertices in order, and back to the
+// first, without crossing each other. Convex or not, either way round.
+// The field is private so that every polygon has at least three vertices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+impl Polygon {
+    // `None` with fewer than three vertices.
+    pub fn new(vertices: Vec<Point>) -> Option<Polygon> {
+        if vertices.len() >= 3 {
+            Some(Polygon { vertices })
+        } else {
+            None
+        }
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    // The "shoelace" sum: twice the area, positive when the vertices run
+    // counter-clockwise.
+    fn signed_double_area(&self) -> f64 {
+        edges(&self.vertices).map(|(a, b)| a.cross(b)).sum()
+    }
+
+    fn map(&self, change: impl Fn(Point) -> Point) -> Polygon {
+        Polygon {
+            vertices: self.vertices.iter().map(|&p| change(p)).collect(),
+        }
+    }
+}
+
+impl Shape for Polygon {
+    fn name(&self) -> &'static str {
+        "polygon"
+    }
+
+    fn area(&self) -> f64 {
+        self.signed_double_area().abs() / 2.0
+    }
+
+    fn perimeter(&self) -> f64 {
+        edges(&self.vertices).map(|(a, b)| a.distance(b)).sum()
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::around(&self.vertices)
+    }
+
+    fn centroid(&self) -> Point {
+        let double_area = self.signed_double_area();
+        if double_area.abs() <= EPSILON {
+            // A polygon with no area has no centre of area: use the
+            // average vertex instead.
+            let sum = self.vertices.iter().fold(Point::ORIGIN, |sum, &p| sum + p);
+            return sum * (1.0 / self.vertices.len() as f64);
+        }
+        // Each edge and the origin form a triangle; the polygon's centroid is
+        // the area-weighted average of their centroids.
+        let weighted =
+            edges(&self.vertices).fold(Point::ORIGIN, |sum, (a, b)| sum + (a + b) * a.cross(b));
+        weighted * (1.0 / (3.0 * double_area))
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        polygon_contains(&self.vertices, point)
+    }
+
+    fn outline(&self) -> Outline {
+        Outline::Polygon(self.vertices.clone())
+    }
+}
+
+impl Transform for Polygon {
+    fn translate(&self, dx: f64, dy: f64) -> Polygon {
+        self.map(|p| p.translate(dx, dy))
+    }
+
+    fn rotate(&self, radians: f64, about: Point) -> Polygon {
+        self.map(|p| p.rotate(radians, about))
+    }
+
+    fn scale(&self, factor: f64, about: Point) -> Polygon {
+        self.map(|p| p.scale(factor, about))
+    }
+}
+
+// A straight line from `start` to `end`. It has no area; its perimeter is
+// its length.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    pub start: Point,
+    pub end: Point,
+}
+
+impl Segment {
+    pub fn new(start: Point, end: Point) -> Segment {
+        Segment { start, end }
+    }
+}
+
+impl Shape for Segment {
+    fn name(&self) -> &'static str {
+        "segment"
+    }
+
+    fn area(&self) -> f64 {
+        0.0
+    }
+
+    fn perimeter(&self) -> f64 {
+        self.start.distance(self.end)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::around(&[self.start, self.end])
+    }
+
+    fn centroid(&self) -> Point {
+        (self.start + self.end) * 0.5
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        segment_distance(point, self.start, self.end) <= EPSILON
+    }
+
+    fn outline(&self) -> Outline {
+        Outline::Segment(*self)
+    }
+}
+
+impl Transform for Segment {
+    fn translate(&self, dx: f64, dy: f64) -> Segment {
+        Segment::new(self.start.translate(dx, dy), self.end.translate(dx, dy))
+    }
+
+    fn rotate(&self, radians: f64, about: Point) -> Segment {
+        Segment::new(
+            self.start.rotate(radians, about),
+            self.end.rotate(radians, about),
+        )
+    }
+
+    fn scale(&self, factor: f64, about: Point) -> Segment {
+        Segment::new(
+            self.start.scale(factor, about),
+            self.end.scale(factor, about),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn measurements_of_simple_shapes() {
+        let circle = Circle::new(Point::ORIGIN, 10.0);
+        assert!(close(circle.area(), 314.159_265_358_979_3));
+        assert!(close(circle.perimeter(), 20.0 * PI));
+        // A circle is an ellipse, and Ramanujan's formula is exact for it.
+        let round = Ellipse::new(Point::ORIGIN, 10.0, 10.0, 1.0);
+        assert!(close(round.perimeter(), circle.perimeter()));
+
+        let rectangle = Rectangle::new(Point::new(5.0, 10.0), 10.0, 20.0, 0.0);
+        assert_eq!(rectangle.area(), 200.0);
+        assert_eq!(rectangle.perimeter(), 60.0);
+        assert_eq!(rectangle.bounding_box().max, Point::new(10.0, 20.0));
+
+        let triangle = Triangle::new(Point::ORIGIN, Point::new(4.0, 0.0), Point::new(0.0, 3.0));
+        assert_eq!(triangle.area(), 6.0);
+        assert_eq!(triangle.perimeter(), 12.0);
+        assert!(triangle.contains(Point::new(2.0, 1.5)));
+        assert!(!triangle.contains(Point::new(2.0, 1.6)));
+
+        // An L shape: its centroid is outside the L itself.
+        let l_shape = Polygon::new(vec![
+            Point::ORIGIN,
+            Point::new(2.0, 0.0),
+            Point::new(2.0, 1.0),
+            Point::new(1.0, 1.0),
+            Point::new(1.0, 2.0),
+            Point::new(0.0, 2.0),
+        ])
+        .unwrap();
+        assert_eq!(l_shape.area(), 3.0);
+        assert!(close(l_shape.centroid().x, 5.0 / 6.0));
+        assert!(l_shape.contains(Point::new(1.0, 1.5)));
+        assert!(!l_shape.contains(Point::new(1.5, 1.5)));
+        assert_eq!(Polygon::new(vec![Point::ORIGIN, Point::ORIGIN]), None);
+    }
+
+    #[test]
+    fn turned_shapes() {
+        let flat = Ellipse::new(Point::ORIGIN, 4.0, 1.0, PI / 2.0);
+        assert!(flat.contains(Point::new(0.0, 3.9)));
+        assert!(!flat.contains(Point::new(3.9, 0.0)));
+        let bounds = flat.bounding_box();
+        assert!(close(bounds.width(), 2.0) && close(bounds.height(), 8.0));
+
+        let diamond = Rectangle::new(Point::ORIGIN, 2.0, 2.0, PI / 4.0);
+        assert!(close(diamond.bounding_box().max.x, 2f64.sqrt()));
+        assert!(diamond.contains(Point::new(1.4, 0.0)));
+        assert!(!diamond.contains(Point::new(1.0, 1.0)));
+
+        let segment = Segment::new(Point::ORIGIN, Point::new(2.0, 0.0));
+        let turned = segment.rotate(PI / 2.0, Point::ORIGIN);
+        assert!(turned.contains(Point::new(0.0, 1.0)));
+        assert!(close(turned.end.x, 0.0) && close(turned.end.y, 2.0));
+    }
+}
@@ -0,0 +1,475 @@
+// `import` module: reading bank statements into a wallet.
+// A bank statement is a CSV file with a header row naming its columns. Only
+// three columns are needed, in any order: `date` (YYYY-MM-DD), `payee` (or
+// `description`) and `amount`. A negative amount is money spent, a positive
+// one money received:
+//
+//     date,payee,amount
+//     2026-03-02,"TESCO STORES, LEEDS",-42.10
+//     2026-03-01,ACME LTD SALARY,2500.00
+//
+// Each expense gets a category from `CategoryRules`, read from a rules file:
+//
+//     # PAYEE TEXT = category
+//     tesco = groceries
+//
+// A rule matches when its text appears anywhere in the payee, ignoring case.
+// The first matching rule wins; payees no rule matches are `uncategorized`.
+//
+// Importing is safe to repeat: a row whose date, payee, amount and direction
+// match an entry already in the wallet is counted as already imported and
+// not recorded again, so overlapping statements can be imported one by one.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::calendar::{Date, Timestamp};
+use crate::money::{Currency, Money};
+use crate::wallet::{EntryKind, Wallet};
+
+// Where `main` looks for rules and a statement, relative to the working directory.
+pub const DEFAULT_RULES_FILE: &str = "category_rules.txt";
+pub const DEFAULT_STATEMENT_FILE: &str = "bank_statement.csv";
+
+// The category of an expense that no rule matches.
+pub const UNCATEGORIZED: &str = "uncategorized";
+
+// Why a rules file or a statement could not be read at all.
+#[derive(Debug)]
+pub enum ImportError {
+    Io(io::Error),
+    // Line `line` of a rules file is not `PAYEE TEXT = category`.
+    BadRule { line: usize, text: String },
+    // The statement's header row has no column of this name.
+    MissingColumn(&'static str),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::Io(error) => write!(f, "{}", error),
+            ImportError::BadRule { line, text } => {
+                write!(
+                    f,
+                    "line {}: expected `PAYEE TEXT = category`, found `{}`",
+                    line, text
+                )
+            }
+            ImportError::MissingColumn(name) => write!(f, "no `{}` column in the header", name),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<io::Error> for ImportError {
+    fn from(error: io::Error) -> ImportError {
+        ImportError::Io(error)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CategoryRules {
+    // (payee text in lowercase, category), in the order they were given.
+    rules: Vec<(String, String)>,
+}
+
+impl CategoryRules {
+    pub fn load(path: &Path) -> Result<CategoryRules, ImportError> {
+        CategoryRules::parse(&fs::read_to_string(path)?)
+    }
+
+    // Reads the text of a rules file (see the top of this module).
+    pub fn parse(text: &str) -> Result<CategoryRules, ImportError> {
+        let mut rules = CategoryRules::default();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            // `split_once` splits at the first `=` only.
+            match line.split_once('=') {
+                Some((payee, category))
+                    if !payee.trim().is_empty() && !category.trim().is_empty() =>
+                {
+                    rules.add(payee.trim(), category.trim());
+                }
+                _ => {
+                    return Err(ImportError::BadRule {
+                        line: index + 1,
+                        text: line.to_string(),
+                    })
+                }
+            }
+        }
+        Ok(rules)
+    }
+
+    // Adds a rule after the existing ones.
+    pub fn add(&mut self, payee: &str, category: &str) {
+        self.rules
+            .push((payee.to_lowercase(), category.to_string()));
+    }
+
+    // The category for an expense paid to `payee`.
+    pub fn category(&self, payee: &str) -> &str {
+        let payee = payee.to_lowercase();
+        self.rules
+            .iter()
+            .find(|(text, _)| payee.contains(text.as_str()))
+            .map_or(UNCATEGORIZED, |(_, category)| category.as_str())
+    }
+}
+
+// A statement row that was not imported, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Skipped {
+    pub line: usize,
+    pub reason: String,
+}
+
+// What an import did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    pub deposits: usize,
+    pub expenses: usize,
+    // Rows already in the wallet from an earlier import.
+    pub already_imported: usize,
+    pub skipped: Vec<Skipped>,
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Imported {} deposits and {} expenses, {} already imported, skipped {} rows",
+            self.deposits,
+            self.expenses,
+            self.already_imported,
+            self.skipped.len()
+        )?;
+        for skipped in &self.skipped {
+            write!(f, "\n  line {}: {}", skipped.line, skipped.reason)?;
+        }
+        Ok(())
+    }
+}
+
+// One row of a statement.
+struct Row {
+    line: usize,
+    date: Date,
+    payee: String,
+    amount: Money,
+    received: bool,
+}
+
+// Imports the statement in the CSV file at `path`; see `import_statement`.
+pub fn import_file(
+    wallet: &mut Wallet,
+    path: &Path,
+    currency: Currency,
+    rules: &CategoryRules,
+) -> Result<ImportReport, ImportError> {
+    import_statement(wallet, &fs::read_to_string(path)?, currency, rules)
+}
+
+// Records every row of the CSV statement `text` in `wallet`, in `currency`.
+// Rows are recorded oldest first, whatever order the bank listed them in,
+// and all at midnight since statements give dates only. Rows the wallet
+// already holds are counted but not recorded again. A row that cannot be
+// read, or that the wallet refuses, is skipped and listed in the report;
+// the rest are still imported.
+pub fn import_statement(
+    wallet: &mut Wallet,
+    text: &str,
+    currency: Currency,
+    rules: &CategoryRules,
+) -> Result<ImportReport, ImportError> {
+    let mut report = ImportReport::default();
+    let mut rows = read_rows(text, currency, &mut report.skipped)?;
+    // `sort_by_key` is stable: rows on the same day keep the bank's order.
+    rows.sort_by_key(|row| row.date);
+    let mut imported = imported_rows(wallet, currency);
+    for row in rows {
+        let at = Timestamp::at(row.date, 0, 0);
+        // Each matching entry accounts for one row, so two identical
+        // payments on the same day are both kept the first time round.
+        let key = (at, row.payee.clone(), row.amount, row.received);
+        if let Some(count) = imported.get_mut(&key).filter(|count| **count > 0) {
+            *count -= 1;
+            report.already_imported += 1;
+            continue;
+        }
+        let result = if row.received {
+            wallet.deposit(at, currency, row.amount, &row.payee)
+        } else {
+            let category = rules.category(&row.payee);
+            wallet.expense(at, currency, row.amount, category, &row.payee)
+        };
+        match result {
+            Ok(_) if row.received => report.deposits += 1,
+            Ok(_) => report.expenses += 1,
+            Err(error) => report.skipped.push(Skipped {
+                line: row.line,
+                reason: error.to_string(),
+            }),
+        }
+    }
+    report.skipped.sort_by_key(|skipped| skipped.line);
+    Ok(report)
+}
+
+// How many times each (time, payee, amount, received) row is already in the
+// wallet's ledger. Only deposits and expenses in `currency` can have come
+// from a statement; rows are always at midnight, so others never match.
+fn imported_rows(
+    wallet: &Wallet,
+    currency: Currency,
+) -> HashMap<(Timestamp, String, Money, bool), usize> {
+    let mut counts = HashMap::new();
+    for entry in wallet.ledger() {
+        let from_statement = matches!(entry.kind, EntryKind::Deposit | EntryKind::Expense { .. });
+        if from_statement && entry.currency == currency {
+            let key = (
+                entry.at,
+                entry.description.clone(),
+                entry.amount,
+                entry.is_credit(),
+            );
+            *counts.entry(key).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+// The rows of a statement in `currency` that could be read. Unreadable rows
+// go to `skipped`.
+fn read_rows(
+    text: &str,
+    currency: Currency,
+    skipped: &mut Vec<Skipped>,
+) -> Result<Vec<Row>, ImportError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let header = match lines.next() {
+        Some((_, header)) => split_csv(header).unwrap_or_default(),
+        None => Vec::new(),
+    };
+    // The position of the first header cell with one of `names`.
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|cell| names.contains(&cell.trim().to_lowercase().as_str()))
+    };
+    let date = column(&["date"]).ok_or(ImportError::MissingColumn("date"))?;
+    let payee = column(&["payee", "description"]).ok_or(ImportError::MissingColumn("payee"))?;
+    let amount = column(&["amount"]).ok_or(ImportError::MissingColumn("amount"))?;
+
+    let mut rows = Vec::new();
+    for (index, line) in lines {
+        let line_number = index + 1;
+        match read_row(line, date, payee, amount, currency) {
+            Ok((date, payee, amount, received)) => rows.push(Row {
+                line: line_number,
+                date,
+                payee,
+                amount,
+                received,
+            }),
+            Err(reason) => skipped.push(Skipped {
+                line: line_number,
+                reason,
+            }),
+        }
+    }
+    Ok(rows)
+}
+
+// Reads one row: (date, payee, amount, whether the money was received).
+fn read_row(
+    line: &str,
+    date: usize,
+    payee: usize,
+    amount: usize,
+    currency: Currency,
+) -> Result<(Date, String, Money, bool), String> {
+    let cells = split_csv(line).ok_or("unclosed quote")?;
+    // `get` returns `None` instead of panicking when a row is too short.
+    let cell = |index: usize| cells.get(index).map(|cell| cell.trim()).unwrap_or("");
+    let day = Date::parse(cell(date)).ok_or(format!("bad date `{}`", cell(date)))?;
+    let text = cell(amount);
+    // `strip_prefix` returns the rest of the text if the prefix was there.
+    let (received, digits) = match text.strip_prefix('-') {
+        Some(digits) => (false, digits),
+        None => (true, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let money = Money::parse(digits, currency).ok_or(format!("bad amount `{}`", text))?;
+    Ok((day, cell(payee).to_string(), money, received))
+}
+
+// Splits one CSV line into its cells. A cell in double quotes may contain
+// commas, and `""` inside it stands for one quote. `None` if a quote is left open.
+fn split_csv(line: &str) -> Option<Vec<String>> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    // `peekable` lets the loop look at the next character without taking it.
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => cells.push(std::mem::take(&mut cell)),
+            _ => cell.push(c),
+        }
+    }
+    if quoted {
+        return None;
+    }
+    cells.push(cell);
+    Some(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eur() -> Currency {
+        Currency::new("EUR").unwrap()
+    }
+
+    fn money(text: &str) -> Money {
+        Money::parse(text, eur()).unwrap()
+    }
+
+    #[test]
+    fn rules_pick_the_first_match() {
+        let rules = CategoryRules::parse("# rules\ntesco = groceries\nTESCO FUEL = car\n").unwrap();
+        assert_eq!(rules.category("Tesco Fuel Leeds"), "groceries");
+        assert_eq!(rules.category("Corner shop"), UNCATEGORIZED);
+        let error = CategoryRules::parse("tesco groceries").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1: expected `PAYEE TEXT = category`, found `tesco groceries`"
+        );
+    }
+
+    #[test]
+    fn csv_cells_may_be_quoted() {
+        assert_eq!(
+            split_csv(r#"2026-03-02,"TESCO, LEEDS",-1"#),
+            Some(vec![
+                String::from("2026-03-02"),
+                String::from("TESCO, LEEDS"),
+                String::from("-1")
+            ])
+        );
+        assert_eq!(
+            split_csv(r#""say ""hi""",,x"#),
+            Some(vec![
+                String::from(r#"say "hi""#),
+                String::new(),
+                String::from("x")
+            ])
+        );
+        assert_eq!(split_csv(r#""open"#), None);
+    }
+
+    #[test]
+    fn imports_a_statement_oldest_first() {
+        let rules = CategoryRules::parse("tesco = groceries\nacme = salary\n").unwrap();
+        let statement = "\
+Amount,Description,Date,Balance
+-42.10,\"TESCO STORES, LEEDS\",2026-03-03,100
+-7.5,Corner Cafe,2026-03-02,100
+2500.00,ACME LTD,2026-03-01,100
+-1.00,Nowhere,03/04/2026,100
+-abc,Nowhere,2026-03-04,100
+-9999,Car dealer,2026-03-05,100
+";
+        let mut wallet = Wallet::new("Alice");
+        let report = import_statement(&mut wallet, statement, eur(), &rules).unwrap();
+        assert_eq!((report.deposits, report.expenses), (1, 2));
+        let lines: Vec<usize> = report.skipped.iter().map(|s| s.line).collect();
+        assert_eq!(lines, [5, 6, 7]);
+        assert_eq!(report.skipped[0].reason, "bad date `03/04/2026`");
+        assert!(report.skipped[2].reason.starts_with("insufficient balance"));
+
+        assert_eq!(wallet.balance(eur()), money("2450.40"));
+        let entries: Vec<String> = wallet.ledger().iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            entries,
+            [
+                "2026-03-01 00:00  deposit          +2500.00 EUR  ACME LTD",
+                "2026-03-02 00:00  uncategorized       -7.50 EUR  Corner Cafe",
+                "2026-03-03 00:00  groceries          -42.10 EUR  TESCO STORES, LEEDS",
+            ]
+        );
+    }
+
+    #[test]
+    fn reimporting_skips_rows_already_in_the_wallet() {
+        let rules = CategoryRules::parse("cafe = eating out\n").unwrap();
+        let march = "\
+date,payee,amount
+2026-03-01,ACME LTD,100.00
+2026-03-02,Corner Cafe,-3.50
+2026-03-02,Corner Cafe,-3.50
+";
+        let mut wallet = Wallet::new("Alice");
+        let first = import_statement(&mut wallet, march, eur(), &rules).unwrap();
+        // Two identical coffees on one day are two real payments.
+        assert_eq!(
+            (first.deposits, first.expenses, first.already_imported),
+            (1, 2, 0)
+        );
+
+        let again = import_statement(&mut wallet, march, eur(), &rules).unwrap();
+        assert_eq!(
+            (again.deposits, again.expenses, again.already_imported),
+            (0, 0, 3)
+        );
+        assert!(again.skipped.is_empty());
+        assert_eq!(
+            again.to_string(),
+            "Imported 0 deposits and 0 expenses, 3 already imported, skipped 0 rows"
+        );
+
+        // An overlapping statement adds only what is new: the third coffee
+        // on the 2nd and everything after it.
+        let overlap = "\
+date,payee,amount
+2026-03-02,Corner Cafe,-3.50
+2026-03-02,Corner Cafe,-3.50
+2026-03-02,Corner Cafe,-3.50
+2026-03-03,Bakery,-4.00
+";
+        let report = import_statement(&mut wallet, overlap, eur(), &rules).unwrap();
+        assert_eq!(
+            (report.deposits, report.expenses, report.already_imported),
+            (0, 2, 2)
+        );
+        assert!(report.skipped.is_empty());
+        assert_eq!(wallet.ledger().len(), 5);
+        assert_eq!(wallet.balance(eur()), money("85.50"));
+    }
+
+    #[test]
+    fn statements_need_the_three_columns() {
+        let mut wallet = Wallet::new("Alice");
+        let rules = CategoryRules::default();
+        let result = import_statement(&mut wallet, "date,amount\n2026-03-01,5", eur(), &rules);
+        assert!(matches!(result, Err(ImportError::MissingColumn("payee"))));
+        let result = import_statement(&mut wallet, "", eur(), &rules);
+        assert!(matches!(result, Err(ImportError::MissingColumn("date"))));
+    }
+}
@@ -1,100 +1,229 @@
-// `struct` keyword is used to define a structure, similar to classes in other languages.
-struct Wallet {
-    name: String, // `String` is a growable, UTF-8 encoded string type.
-    balance: u32, // `u32` is an unsigned 32-bit integer type.
-}
+// `mod` declarations tell the compiler to load `calendar.rs`, `money.rs` and so on.
+mod budget;
+mod calendar;
+mod exchange;
+mod geometry;
+mod import;
+mod money;
+mod packing;
+mod password;
+#[cfg(test)]
+mod test_rng;
+mod users;
+mod wallet;
 
-// `impl` keyword is used to implement methods for a struct.
-impl Wallet {
-    // `&self` is a reference to the instance of the `Wallet` struct.
-    fn show_bal( &self) {
-        // `println!` is a macro for printing formatted text to the console.
-        println!("Name: {}, have Balance: {}.", self.name, self.balance);
-    }
-    // `&mut self` is a mutable reference to the instance of the `Wallet` struct,
-    // allowing the method to modify the struct's fields.
-    fn do_expense( & mut self, expenditure: u32) {
-        if expenditure <= self.balance {
-            self.balance -= expenditure;
-            println!("Name: {}, have Balance: {}.", self.name, self.balance);
-        } else {
-            println!("Insufficient Balance");
-        }
-    }
-}
+use std::f64::consts::PI;
+use std::path::Path;
+
+use budget::{Budget, Limit};
+use calendar::{Date, Month, Timestamp};
+use exchange::{ExchangeRates, DEFAULT_RATES_FILE};
+use geometry::{Circle, Ellipse, Point, Polygon, Rectangle, Segment, Shape, Transform, Triangle};
+use import::{CategoryRules, DEFAULT_RULES_FILE, DEFAULT_STATEMENT_FILE};
+use money::{Currency, Money};
+use packing::{Algorithm, Packer, Rect};
+use users::{UserRegistry, DEFAULT_USERS_FILE};
+use wallet::Wallet;
 
 // `fn main()` is the entry point of the program.
 fn main() {
+    wallet_demo();
+    budget_demo();
+    user_details();
+    cal_area();
+    packing_demo();
+    calc_size();
+}
+
+fn wallet_demo() {
     // `let mut` declares a mutable variable.
-    let mut wallet = Wallet {
-        // `String::to_string()` converts a string literal to a `String`.
-        name: "DrChain".to_string(),
-        balance: 200,
-    };
+    // `Wallet::new` is an associated function: a constructor called on the type.
+    let mut wallet = Wallet::new("DrChain");
+    let mut savings = Wallet::new("Savings");
+    // Closures keep the demo short. `Date::new`, `Currency::new` and
+    // `Money::parse` all return `None` for input that makes no sense.
+    let day = |day| Date::new(2026, 3, day).expect("a real date");
+    let eur = Currency::new("EUR").expect("a currency code");
+    let usd = Currency::new("USD").expect("a currency code");
+    let money = |text| Money::parse(text, eur).expect("a valid amount");
 
+    // Every operation returns a `Result`. `expect` is fine here because these
+    // amounts are known to be valid; the refused expense below shows an `Err`.
+    wallet
+        .deposit(Timestamp::at(day(1), 9, 0), eur, money("200"), "Opening deposit")
+        .expect("valid deposit");
     // Calling methods on the `wallet` instance using dot notation.
     wallet.show_bal();
-    wallet.do_expense(50);
+    wallet
+        .expense(Timestamp::at(day(3), 18, 30), eur, money("49.99"), "groceries", "Market")
+        .expect("enough money");
+    wallet
+        .transfer(&mut savings, Timestamp::at(day(10), 8, 0), eur, money("100"), "Put aside")
+        .expect("enough money");
     wallet.show_bal();
+    savings.show_bal();
 
-    user_details();
-    cal_area();
-    calc_size();
-}
+    // `match` handles both outcomes of a `Result`.
+    let laptop = Timestamp::at(day(12), 14, 0);
+    match wallet.expense(laptop, eur, money("500"), "electronics", "New laptop") {
+        Ok(balance) => println!("Bought a laptop, {} EUR left.", balance.display(eur)),
+        Err(error) => println!("Expense refused: {}", error),
+    }
 
-fn user_details() {
-    // Create an instance of the User struct
-    // All fields must be initialized
-    let user_data = User {
-        active: true,
-        username: String::from("Hello World"),
-        email: String::from("Hello@World.com"),
-        sign_in_count: 123,
-    };
-    // Access struct fields using dot notation
-    println!("User name is {}, email is {}, sign_in_count is {}, and user is active: {}", user_data.username, user_data.email, user_data.sign_in_count, user_data.active);
-}
+    // The exchange rates live in a file next to the program, not in the code.
+    match ExchangeRates::load(Path::new(DEFAULT_RATES_FILE)) {
+        Ok(rates) => {
+            let at = Timestamp::at(day(14), 10, 0);
+            match wallet.exchange(at, money("25"), eur, usd, &rates, "Travel money") {
+                Ok(bought) => println!(
+                    "Exchanged 25.00 EUR for {} USD ({} rounding).",
+                    bought.display(usd),
+                    rates.rounding().name()
+                ),
+                Err(error) => println!("Exchange refused: {}", error),
+            }
+            // Yen have no decimal places, so dollars buy a whole number of yen.
+            let jpy = Currency::new("JPY").expect("a currency code");
+            let dollars = Money::parse("10", usd).expect("a valid amount");
+            match wallet.exchange(at, dollars, usd, jpy, &rates, "Souvenirs") {
+                Ok(bought) => println!("Exchanged 10.00 USD for {} JPY.", bought.display(jpy)),
+                Err(error) => println!("Exchange refused: {}", error),
+            }
+        }
+        Err(error) => println!("No exchange rates from {}: {}", DEFAULT_RATES_FILE, error),
+    }
+    wallet.show_bal();
 
-/// User struct - custom data type grouping related data
-/// Structs are like classes in other languages (but without methods here)
-/// 
-/// # Fields
-/// * `active` - Whether the user account is active
-/// * `username` - User's display name
-/// * `email` - User's email address
-/// * `sign_in_count` - Number of times user has signed in
-struct User {
-    active: bool,
-    username: String,
-    email: String,
-    sign_in_count: u64,
+    // The statement skips the opening deposit but starts from its balance.
+    println!("{}", wallet.statement(eur, day(2), day(15)));
+    println!("{}", savings.statement(eur, day(1), day(31)));
+    for (&currency, &balance) in wallet.balances() {
+        // Replaying the ledger from zero must land on the same balance.
+        let replayed = wallet.ledger_balance(currency).expect("the ledger replays cleanly");
+        println!(
+            "{}: {} {} on hand, {} {} from replaying its {} ledger entries.",
+            wallet.name(),
+            balance.display(currency),
+            currency,
+            replayed.display(currency),
+            currency,
+            wallet.ledger().len()
+        );
+    }
 }
 
-/// Rectangle struct - stores dimensions
-/// 
-/// # Fields
-/// * `width` - Width of the rectangle in pixels
-/// * `height` - Height of the rectangle in pixels
-struct Rect {
-    width: u32,
-    height: u32,
+fn budget_demo() {
+    let day = |day| Date::new(2026, 3, day).expect("a real date");
+    let eur = Currency::new("EUR").expect("a currency code");
+    let money = |text| Money::parse(text, eur).expect("a valid amount");
+    let mut household = Wallet::new("Household");
+
+    // The bank statement brings in the salary and the month's card payments,
+    // each given a category by the rules file.
+    let rules = CategoryRules::load(Path::new(DEFAULT_RULES_FILE)).unwrap_or_else(|error| {
+        println!("No category rules from {}: {}", DEFAULT_RULES_FILE, error);
+        CategoryRules::default()
+    });
+    let statement = Path::new(DEFAULT_STATEMENT_FILE);
+    match import::import_file(&mut household, statement, eur, &rules) {
+        Ok(report) => println!("{}", report),
+        Err(error) => println!("Could not import {}: {}", DEFAULT_STATEMENT_FILE, error),
+    }
+
+    let mut budget = Budget::new(eur);
+    budget.set("groceries", Limit::Warn(money("300")));
+    budget.set("eating out", Limit::Warn(money("120")));
+    budget.set("rent", Limit::Hard(money("950")));
+    budget.set("fun", Limit::Hard(money("60")));
+
+    // Spending through the budget checks each category's limit first.
+    let spending = [
+        (day(20), "52.30", "groceries", "Farmers market"),
+        (day(21), "45.00", "fun", "Concert tickets"),
+        (day(22), "30.00", "fun", "Bowling"),
+        (day(24), "38.90", "eating out", "Pizzeria"),
+    ];
+    for (date, amount, category, description) in spending {
+        let at = Timestamp::at(date, 19, 0);
+        match budget.spend(&mut household, at, money(amount), category, description) {
+            Ok(None) => println!("Spent {} on {}.", amount, category),
+            Ok(Some(warning)) => {
+                println!("Spent {} on {}. Warning: {}.", amount, category, warning)
+            }
+            Err(error) => println!("Refused: {}.", error),
+        }
+    }
+
+    let march = Month::of(day(1));
+    println!("{}", budget.report(&household, march));
+    println!(
+        "Spent on groceries in {}: {} EUR.",
+        march,
+        budget.spent(&household, "groceries", march).display(eur)
+    );
 }
 
-/// Implementation block for Rect
-/// Contains methods (functions) associated with the Rect struct
-impl Rect {
-    /// Calculates the area of the rectangle
-    /// 
-    /// # Arguments
-    /// * `&self` - Immutable borrow of the instance (read-only access)
-    /// 
-    /// # Returns
-    /// Area as u32 (width * height)
-    fn area( & self) -> u32 {
-        return self.width * self.height;
-    }
-    fn perimeter( & self) -> u32 {
-        return 2 * (self.width + self.height);
+fn user_details() {
+    // A `UserRegistry` owns the users; the `User` struct lives in `users.rs`.
+    let mut registry = UserRegistry::new();
+    // `register` returns a `Result`: `Ok` with the new user, or `Err` with why not.
+    let attempts = [
+        ("HelloWorld", "Hello@World.com", "correct horse"),
+        ("DrChain", "chain@example.org", "battery staple"),
+        ("helloworld", "someone@example.com", "password1"), // username taken
+        ("Another", "hello@world.com", "password1"),        // email taken
+        ("Hello World", "hw@example.com", "password1"),     // space in username
+        ("Mallory", "mallory@example", "password1"),        // no top-level domain
+        ("Shorty", "shorty@example.com", "1234"),           // password too short
+    ];
+    for (username, email, password) in attempts {
+        match registry.register(username, email, password) {
+            Ok(user) => println!("Registered {} <{}>", user.username, user.email),
+            Err(error) => println!("Cannot register {}: {}", username, error),
+        }
+    }
+
+    // Each successful sign-in increments `sign_in_count`.
+    for _ in 0..2 {
+        if let Ok(user) = registry.sign_in("helloworld", "correct horse") {
+            println!("{} signed in, {} times so far", user.username, user.sign_in_count);
+        }
+    }
+    // Wrong passwords lock the account, and then even the right one fails.
+    for guess in ["123456", "qwerty", "letmein", "dragon", "monkey", "correct horse"] {
+        if let Err(error) = registry.sign_in("DrChain", guess) {
+            println!("Sign-in as DrChain with '{}': {}", guess, error);
+        }
+    }
+    if let Some(user) = registry.get("drchain") {
+        println!("DrChain locked: {}, failed sign-ins: {}", user.is_locked(), user.failed_sign_ins);
+    }
+    // `unlock`, `deactivate` and friends return `Result` too; `?` is not
+    // available in a function returning `()`, so `expect` unwraps them.
+    registry.unlock("DrChain").expect("DrChain is registered");
+    registry.change_password("DrChain", "battery staple", "tr0ub4dor&3").expect("right password");
+    registry.deactivate("HelloWorld").expect("HelloWorld is registered");
+    if let Err(error) = registry.sign_in("HelloWorld", "correct horse") {
+        println!("Sign-in as HelloWorld: {}", error);
+    }
+    registry.activate("HelloWorld").expect("HelloWorld is registered");
+
+    // Save the registry and read it back: only salted hashes reach the file.
+    let path = Path::new(DEFAULT_USERS_FILE);
+    // The two steps fail with different error types; `to_string` gives both
+    // the same type, so `and_then` can chain them.
+    let loaded = registry
+        .save(path)
+        .map_err(|error| error.to_string())
+        .and_then(|()| UserRegistry::load(path).map_err(|error| error.to_string()));
+    match loaded {
+        Ok(loaded) => {
+            for user in loaded.users() {
+                // Access struct fields using dot notation
+                println!("User name is {}, email is {}, sign_in_count is {}, and user is active: {}", user.username, user.email, user.sign_in_count, user.active);
+            }
+        }
+        Err(error) => println!("Could not save users to {}: {}", path.display(), error),
     }
 }
 
@@ -110,100 +239,104 @@ fn cal_area() {
     println!("Perimeter of the rectangle is {} pixels.", rect.perimeter());
 }
 
-// `trait` keyword defines a shared interface that types can implement.
-// Traits are similar to interfaces in other languages.
-// This Shape trait defines behavior that all shapes must have.
-trait Shape {
-    // `fn new` is a constructor method signature in the trait.
-    // `Self` (capital S) refers to the type implementing this trait.
-    // All implementing types must provide a constructor with these parameters.
-    fn new(length: f32, width: f32) -> Self;
-    // `&self` borrows the instance immutably (read-only access).
-    // `-> f32` specifies the return type as a 32-bit floating point number.
-    // All shapes must implement an area calculation method.
-    fn area( & self) -> f32;
-}
-
-// `struct` keyword defines a structure to represent a rectangle.
-// Structs group related data together as a single type.
-struct Rectangle {
-    // `f32` is a 32-bit floating point type for decimal numbers.
-    // Used for dimensions that can have fractional values.
-    length: f32,
-    width: f32,
-}
-
-// `struct` keyword defines a structure to represent a circle.
-// Unlike Rectangle, Circle only needs one dimension (radius).
-struct Circle {
-    // `f32` stores the radius as a floating point number.
-    radius: f32,
-}
-
-// `impl Trait for Type` syntax implements a trait for a specific type.
-// This block provides the concrete implementation of Shape for Rectangle.
-impl Shape for Rectangle {
-    // Implements the `new` constructor method required by the Shape trait.
-    // `fn new` is an associated function (called on the type, not an instance).
-    // Takes two f32 parameters and returns a Rectangle instance.
-    fn new(length: f32, width: f32) -> Rectangle {
-        // Field init shorthand: when parameter names match field names.
-        // This is equivalent to: Rectangle { length: length, width: width }
-        // The last expression is automatically returned (no semicolon).
-        Rectangle {
-            length,
-            width
+// Packs a set of sprites onto one sheet with each algorithm, to compare
+// how tall the sheet gets and how much of it is left empty.
+fn packing_demo() {
+    // Sizes in pixels, as a sprite sheet for a small game might need.
+    let sizes = [
+        (64, 64), (64, 64), (32, 48), (32, 48), (32, 48), (128, 32), (96, 16),
+        (16, 16), (16, 16), (16, 16), (48, 80), (24, 40), (80, 24), (40, 40),
+    ];
+    // `map` turns each (width, height) pair into a `Rect`.
+    let sprites: Vec<Rect> = sizes.iter().map(|&(width, height)| Rect { width, height }).collect();
+    for algorithm in Algorithm::ALL {
+        // `match` on the `Result`: `pack` refuses sprites wider than the sheet.
+        match Packer::new(160, algorithm).pack(&sprites) {
+            Ok(layout) => println!(
+                "{:<10} sheet 160 x {:<3} wasted {:>5} px ({:.1}%)",
+                algorithm.name(),
+                layout.height,
+                layout.wasted_area(),
+                layout.wasted_percent()
+            ),
+            Err(error) => println!("{}: {}", algorithm.name(), error),
         }
     }
-    // Implements the `area` method required by the Shape trait.
-    // `&self` borrows the Rectangle instance immutably.
-    // Calculates area using the formula: length × width.
-    fn area( & self) -> f32 {
-        // `self.length` accesses the length field using dot notation.
-        // `*` multiplies the two values together.
-        // Last expression without semicolon is the return value.
-        self.length * self.width
-    }
-}
-
-// `impl Trait for Type` implements the Shape trait for Circle.
-// This provides Circle-specific behavior for the Shape interface.
-impl Shape for Circle {
-    // Implements the `new` constructor for Circle.
-    // Note: The trait requires two parameters, but Circle only needs radius.
-    // `_width` has an underscore prefix to indicate it's intentionally unused.
-    fn new(length: f32, _width: f32) -> Circle {
-        // Only uses `length` parameter as the radius.
-        // The `_width` parameter is ignored (design limitation of the trait).
-        Circle {
-            radius: length
-        }
+    // Labels may be turned a quarter turn; the shelf packer lays them flat.
+    let labels = [
+        Rect { width: 20, height: 70 },
+        Rect { width: 70, height: 20 },
+        Rect { width: 30, height: 50 },
+    ];
+    match Packer::new(100, Algorithm::Shelf).with_rotation().pack(&labels) {
+        Ok(layout) => print!("{}", layout),
+        Err(error) => println!("Could not lay out labels: {}", error),
     }
-    // Implements the `area` method for Circle.
-    // Calculates area using the formula: π × radius².
-    fn area( & self) -> f32 {
-        // `self.radius * self.radius` calculates radius squared (r²).
-        // `* 3.14` multiplies by an approximation of π (pi).
-        // For better precision, use `std::f32::consts::PI` instead of 3.14.
-        self.radius * self.radius * 3.14
+    let banner = Rect { width: 400, height: 20 };
+    if let Err(error) = Packer::new(160, Algorithm::MaxRects).pack(&[banner]) {
+        println!("Could not pack banner: {}", error);
     }
 }
 
 // `fn` keyword defines a function named calc_size.
-// This function demonstrates creating shapes and calculating their areas.
+// This function demonstrates creating shapes and asking them questions.
+// The shapes and the `Shape` and `Transform` traits live in `geometry/`.
 fn calc_size() {
-    // `let` keyword declares an immutable variable binding.
-    // `Rectangle::new()` calls the associated function (constructor).
-    // Type annotation is optional here (Rust infers it as Rectangle).
-    let rec = Rectangle::new(10.0, 20.0);
-    // Creates a Circle with radius 10.0.
-    // Second parameter (0.0) is required by trait but ignored by Circle.
-    let cir = Circle::new(10.0, 0.0);
-    // `print!` macro prints formatted text without a newline.
-    // `{}` is a placeholder that formats using the Display trait.
-    // `.area()` method call uses dot notation to invoke the area method.
-    println!("Area of Rectangle: {}", rec.area());
-    // Prints the circle's area using the same pattern.
-    // Note: Using `print!` instead of `println!` means no newline is added.
-    println!("Area of Circle: {}", cir.area());
-}
\ No newline at end of file
+    // Each shape has its own constructor, taking what that shape needs.
+    let rec = Rectangle::new(Point::new(5.0, 10.0), 10.0, 20.0, 0.0);
+    let cir = Circle::new(Point::ORIGIN, 10.0);
+    let oval = Ellipse::new(Point::new(20.0, 0.0), 6.0, 2.0, PI / 6.0);
+    let tri = Triangle::new(Point::new(12.0, -4.0), Point::new(18.0, -4.0), Point::new(15.0, 3.0));
+    let line = Segment::new(Point::new(-15.0, -15.0), Point::new(30.0, 20.0));
+    // `Polygon::new` returns `None` for fewer than three vertices.
+    let arrow = Polygon::new(vec![
+        Point::new(0.0, 0.0),
+        Point::new(4.0, 2.0),
+        Point::new(0.0, 4.0),
+        Point::new(3.0, 2.0),
+    ])
+    .expect("four vertices");
+
+    // `&dyn Shape` lets one list hold shapes of different types.
+    let shapes: [&dyn Shape; 6] = [&rec, &cir, &oval, &tri, &line, &arrow];
+    for shape in shapes {
+        let bounds = shape.bounding_box();
+        println!(
+            "{:<9} area {:>7.2}, perimeter {:>6.2}, centroid {}, box {:.2} x {:.2}",
+            shape.name(),
+            shape.area(),
+            shape.perimeter(),
+            shape.centroid(),
+            bounds.width(),
+            bounds.height()
+        );
+    }
+    // The arrow is a thin chevron: its centroid sits in the notch, outside
+    // the shape itself, though still inside its box.
+    let centre = arrow.centroid();
+    println!(
+        "Arrow with {} vertices: box holds its centroid {}: {}, arrow does: {}",
+        arrow.vertices().len(),
+        centre,
+        arrow.bounding_box().contains(centre),
+        arrow.contains(centre)
+    );
+
+    // Intersections work between any two shapes.
+    let pairs: [(&dyn Shape, &dyn Shape); 4] =
+        [(&cir, &rec), (&cir, &oval), (&oval, &tri), (&line, &tri)];
+    for (a, b) in pairs {
+        println!("{} meets {}: {}", a.name(), b.name(), a.intersects(b));
+    }
+
+    // Transforms return a new shape and leave the old one alone.
+    let turned = rec.rotate(PI / 4.0, rec.centroid());
+    let bigger = tri.scale(2.0, tri.centroid());
+    let moved = oval.translate(-8.0, 0.0);
+    println!("Rectangle turned 45 degrees has box {}", turned.bounding_box());
+    println!("Triangle scaled 2x: area {:.2} (was {:.2})", bigger.area(), tri.area());
+    println!("Ellipse moved left now meets the circle: {}", moved.intersects(&cir));
+    // A polygon with many vertices on the ellipse's edge comes close to it.
+    let approx = oval.to_polygon(64);
+    println!("Ellipse perimeter {:.4}, 64-gon {:.4}", oval.perimeter(), approx.perimeter());
+}
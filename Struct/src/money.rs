@@ -0,0 +1,387 @@
+// `money` module: exact amounts of money.
+// Floats cannot hold 0.10 exactly, and a bare integer does not say what it
+// counts. `Money` is fixed-point: it stores a whole number of the currency's
+// minor unit (cents, pence, ...) and only ever does integer arithmetic on
+// them, so an amount is exact. How many decimal places that is depends on the
+// currency: two for euros, none for yen, three for Bahraini dinars.
+// Every operation that could overflow, or go below zero, is checked and
+// returns an error instead of wrapping around.
+
+use std::fmt;
+
+// Most currencies have two decimal places.
+const DEFAULT_MINOR_DIGITS: u32 = 2;
+
+// The ISO 4217 currencies with some other number of decimal places.
+const MINOR_DIGITS: [(&str, u32); 10] = [
+    ("BHD", 3),
+    ("CLP", 0),
+    ("ISK", 0),
+    ("JOD", 3),
+    ("JPY", 0),
+    ("KRW", 0),
+    ("KWD", 3),
+    ("OMR", 3),
+    ("TND", 3),
+    ("VND", 0),
+];
+
+// A currency, named by its three-letter ISO 4217 code such as "EUR".
+// Storing the letters in a `[u8; 3]` array makes `Currency` a small `Copy`
+// type that can be used as a map key without allocating a `String`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    // Reads a code of exactly three uppercase ASCII letters.
+    pub fn new(code: &str) -> Option<Currency> {
+        // `try_into` turns a byte slice into an array, failing if the length differs.
+        let letters: [u8; 3] = code.as_bytes().try_into().ok()?;
+        if letters.iter().all(u8::is_ascii_uppercase) {
+            Some(Currency(letters))
+        } else {
+            None
+        }
+    }
+
+    // How many decimal places an amount in this currency has: 2 for EUR,
+    // 0 for JPY, 3 for BHD. Codes missing from the table get 2.
+    pub fn minor_digits(&self) -> u32 {
+        MINOR_DIGITS
+            .iter()
+            .find(|(code, _)| *code == self.code())
+            .map_or(DEFAULT_MINOR_DIGITS, |&(_, digits)| digits)
+    }
+
+    pub fn code(&self) -> &str {
+        // The letters were checked to be ASCII, which is always valid UTF-8.
+        std::str::from_utf8(&self.0).expect("currency codes are ASCII")
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+// Arithmetic that was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoneyError {
+    // The result is too large for `Money` to hold.
+    Overflow,
+    // The result would be below zero.
+    Underflow,
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoneyError::Overflow => write!(f, "amount too large"),
+            MoneyError::Underflow => write!(f, "amount would go below zero"),
+        }
+    }
+}
+
+impl std::error::Error for MoneyError {}
+
+// A non-negative amount of money, in minor units of some currency. The field
+// is private, so a `Money` can only be made through the constructors below.
+// It does not know its currency; parsing and printing are given one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Money(u64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    // `minor` minor units: `Money::from_minor(1_250)` is 12.50 EUR or 1250 JPY.
+    pub const fn from_minor(minor: u64) -> Money {
+        Money(minor)
+    }
+
+    pub fn minor(&self) -> u64 {
+        self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    // Reads an amount of `currency`, such as "12", "12.5" or "12.50" euros.
+    // More decimal places than the currency has are refused rather than
+    // rounded: an amount typed in should never change silently.
+    pub fn parse(text: &str, currency: Currency) -> Option<Money> {
+        let minor = parse_fixed(text.trim(), currency.minor_digits())?;
+        Some(Money(u64::try_from(minor).ok()?))
+    }
+
+    // The amount written with `currency`'s decimal places, for `{}`:
+    // `Money::from_minor(1_250).display(eur)` prints "12.50".
+    pub fn display(self, currency: Currency) -> Amount {
+        Amount {
+            money: self,
+            currency,
+        }
+    }
+
+    // `checked_add` and `checked_sub` return `None` instead of overflowing;
+    // `ok_or` turns that `None` into the matching error.
+    pub fn checked_add(self, other: Money) -> Result<Money, MoneyError> {
+        self.0
+            .checked_add(other.0)
+            .map(Money)
+            .ok_or(MoneyError::Overflow)
+    }
+
+    pub fn checked_sub(self, other: Money) -> Result<Money, MoneyError> {
+        self.0
+            .checked_sub(other.0)
+            .map(Money)
+            .ok_or(MoneyError::Underflow)
+    }
+
+    // For totals in reports, where stopping at the largest (or smallest)
+    // possible amount is more useful than an error.
+    pub fn saturating_add(self, other: Money) -> Money {
+        Money(self.0.saturating_add(other.0))
+    }
+
+    pub fn saturating_sub(self, other: Money) -> Money {
+        Money(self.0.saturating_sub(other.0))
+    }
+}
+
+// A `Money` together with its currency, made by `Money::display`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Amount {
+    money: Money,
+    currency: Currency,
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = self.currency.minor_digits();
+        let minor_per_unit = 10u64.pow(digits);
+        let (whole, fraction) = (self.money.0 / minor_per_unit, self.money.0 % minor_per_unit);
+        // `{:0width$}` pads the fraction with zeros to `digits` places.
+        let text = if digits == 0 {
+            whole.to_string()
+        } else {
+            format!("{}.{:0width$}", whole, fraction, width = digits as usize)
+        };
+        // `pad` applies any width and alignment given in the format string,
+        // so `{:>10}` lines amounts up in a column.
+        f.pad(&text)
+    }
+}
+
+// How to round a result that falls between two minor units.
+// Each rule is explicit, so nobody has to guess which one a conversion used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    // Towards zero: 1.239 -> 1.23.
+    Down,
+    // Away from zero: 1.231 -> 1.24.
+    Up,
+    // To the nearest, halves up: 1.235 -> 1.24.
+    HalfUp,
+    // To the nearest, halves to the even neighbour ("banker's rounding"):
+    // 1.235 -> 1.24 but 1.245 -> 1.24. Over many conversions, the halves
+    // rounded up and down cancel out instead of all drifting upwards.
+    HalfEven,
+}
+
+impl Rounding {
+    pub const ALL: [Rounding; 4] = [
+        Rounding::Down,
+        Rounding::Up,
+        Rounding::HalfUp,
+        Rounding::HalfEven,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rounding::Down => "down",
+            Rounding::Up => "up",
+            Rounding::HalfUp => "half-up",
+            Rounding::HalfEven => "half-even",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Rounding> {
+        Rounding::ALL
+            .into_iter()
+            .find(|rounding| rounding.name() == name)
+    }
+
+    // `numerator / denominator`, rounded by this rule.
+    // Everything is a non-negative integer, so the remainder alone says
+    // whether the exact result was below, at or above the halfway point.
+    pub fn divide(&self, numerator: u128, denominator: u128) -> u128 {
+        let quotient = numerator / denominator;
+        let remainder = numerator % denominator;
+        if remainder == 0 {
+            return quotient;
+        }
+        // `2 * remainder` against `denominator` compares the dropped
+        // fraction with one half without any division.
+        let twice = 2 * remainder;
+        let round_up = match self {
+            Rounding::Down => false,
+            Rounding::Up => true,
+            Rounding::HalfUp => twice >= denominator,
+            Rounding::HalfEven => {
+                twice > denominator || (twice == denominator && quotient % 2 == 1)
+            }
+        };
+        quotient + u128::from(round_up)
+    }
+}
+
+// Reads a decimal number with at most `places` decimal places as a whole
+// number of `10^places` parts: `parse_fixed("1.5", 2)` is `Some(150)`.
+// Signs, exponents and empty parts ("1.", ".5") are refused.
+pub fn parse_fixed(text: &str, places: u32) -> Option<u128> {
+    let digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    // Without a '.', there is no fraction, which is fine even for `places` 0.
+    let (whole, fraction) = match text.split_once('.') {
+        Some((whole, fraction)) if digits(fraction) => (whole, fraction),
+        Some(_) => return None,
+        None => (text, ""),
+    };
+    if !digits(whole) || fraction.len() > places as usize {
+        return None;
+    }
+    let scale = 10u128.pow(places);
+    let fraction_scale = 10u128.pow(places - fraction.len() as u32);
+    // A whole part too long for `u128` makes `parse` fail; `?` passes that on.
+    let whole: u128 = whole.parse().ok()?;
+    let fraction: u128 = if fraction.is_empty() {
+        0
+    } else {
+        fraction.parse().ok()?
+    };
+    whole
+        .checked_mul(scale)?
+        .checked_add(fraction * fraction_scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(code: &str) -> Currency {
+        Currency::new(code).unwrap()
+    }
+
+    #[test]
+    fn money_parses_and_prints_exactly() {
+        let eur = code("EUR");
+        assert_eq!(Money::parse("12", eur), Some(Money::from_minor(1_200)));
+        assert_eq!(Money::parse("12.5", eur), Some(Money::from_minor(1_250)));
+        assert_eq!(Money::parse(" 0.07 ", eur), Some(Money::from_minor(7)));
+        assert_eq!(Money::parse("12.345", eur), None);
+        assert_eq!(Money::parse("-1", eur), None);
+        assert_eq!(Money::parse("1.", eur), None);
+        assert_eq!(Money::parse("99999999999999999999", eur), None);
+        assert_eq!(Money::from_minor(1_205).display(eur).to_string(), "12.05");
+        assert_eq!(
+            format!("{:>7}", Money::from_minor(5).display(eur)),
+            "   0.05"
+        );
+        // 0.1 + 0.2 is exactly 0.3, unlike with floats.
+        let sum = Money::parse("0.1", eur)
+            .unwrap()
+            .checked_add(Money::parse("0.2", eur).unwrap());
+        assert_eq!(sum, Ok(Money::parse("0.3", eur).unwrap()));
+    }
+
+    #[test]
+    fn money_uses_the_currency_decimal_places() {
+        let (jpy, bhd) = (code("JPY"), code("BHD"));
+        assert_eq!(Money::parse("1500", jpy), Some(Money::from_minor(1_500)));
+        assert_eq!(Money::parse("1500.5", jpy), None);
+        assert_eq!(Money::from_minor(1_500).display(jpy).to_string(), "1500");
+        assert_eq!(Money::parse("1.5", bhd), Some(Money::from_minor(1_500)));
+        assert_eq!(Money::parse("1.2345", bhd), None);
+        assert_eq!(Money::from_minor(1_005).display(bhd).to_string(), "1.005");
+        assert_eq!(
+            format!("{:>6}", Money::from_minor(7).display(bhd)),
+            " 0.007"
+        );
+    }
+
+    #[test]
+    fn arithmetic_refuses_overflow_and_underflow() {
+        let one = Money::from_minor(1);
+        assert_eq!(
+            Money::from_minor(u64::MAX).checked_add(one),
+            Err(MoneyError::Overflow)
+        );
+        assert_eq!(Money::ZERO.checked_sub(one), Err(MoneyError::Underflow));
+        assert_eq!(one.checked_sub(one), Ok(Money::ZERO));
+        assert_eq!(Money::ZERO.saturating_sub(one), Money::ZERO);
+        let max = Money::from_minor(u64::MAX);
+        assert_eq!(max.saturating_add(one), max);
+    }
+
+    #[test]
+    fn rounding_rules() {
+        // (numerator, denominator) -> (down, up, half-up, half-even)
+        let cases = [
+            (1_239, 10, (123, 124, 124, 124)),
+            (1_231, 10, (123, 124, 123, 123)),
+            (1_235, 10, (123, 124, 124, 124)),
+            (1_245, 10, (124, 125, 125, 124)),
+            (1_240, 10, (124, 124, 124, 124)),
+            (1, 3, (0, 1, 0, 0)),
+            (2, 3, (0, 1, 1, 1)),
+            (1, 2, (0, 1, 1, 0)),
+            (3, 2, (1, 2, 2, 2)),
+        ];
+        for (numerator, denominator, (down, up, half_up, half_even)) in cases {
+            let round = |rule: Rounding| rule.divide(numerator, denominator);
+            assert_eq!(
+                round(Rounding::Down),
+                down,
+                "{} / {}",
+                numerator,
+                denominator
+            );
+            assert_eq!(round(Rounding::Up), up, "{} / {}", numerator, denominator);
+            assert_eq!(
+                round(Rounding::HalfUp),
+                half_up,
+                "{} / {}",
+                numerator,
+                denominator
+            );
+            assert_eq!(
+                round(Rounding::HalfEven),
+                half_even,
+                "{} / {}",
+                numerator,
+                denominator
+            );
+        }
+        for rule in Rounding::ALL {
+            assert_eq!(Rounding::from_name(rule.name()), Some(rule));
+        }
+    }
+
+    #[test]
+    fn currency_codes() {
+        let euro = Currency::new("EUR").unwrap();
+        assert_eq!(euro.to_string(), "EUR");
+        assert_eq!(Currency::new("eur"), None);
+        assert_eq!(Currency::new("EURO"), None);
+        // Yen have no minor unit and dinars have thousandths; a code missing
+        // from the table is assumed to have hundredths like most currencies.
+        assert_eq!(euro.minor_digits(), 2);
+        assert_eq!(Currency::new("JPY").unwrap().minor_digits(), 0);
+        assert_eq!(Currency::new("KRW").unwrap().minor_digits(), 0);
+        assert_eq!(Currency::new("BHD").unwrap().minor_digits(), 3);
+        assert_eq!(Currency::new("XYZ").unwrap().minor_digits(), 2);
+        assert!(Currency::new("CHF").unwrap() < euro);
+    }
+}
@@ -0,0 +1,649 @@
+// `packing` module: laying out many rectangles on one sheet.
+// Given a sheet (a "bin") of fixed width and as much height as needed, a
+// `Packer` finds a position for every `Rect` so that none overlap and the
+// sheet stays as short as it can. That is the job behind a sprite sheet,
+// where many small images share one texture, or a page of labels.
+//
+// Coordinates start at the top-left corner of the sheet: `x` grows to the
+// right and `y` grows downwards, as in image files.
+//
+// Finding the best layout is NP-hard, so each algorithm is a heuristic:
+//
+// * `Shelf`: rows ("shelves") filled left to right, tallest rectangles first.
+//   Fast and simple, but wastes the space above short rectangles.
+// * `Guillotine`: each placement cuts the free space it lands in into two
+//   smaller free rectangles, like a guillotine cutting paper.
+// * `MaxRects`: keeps every largest free rectangle, even overlapping ones,
+//   so no free space is ever lost to an early cut. Slower, packs tightest.
+
+use std::cmp::Reverse;
+use std::fmt;
+
+/// Rectangle struct - stores dimensions
+///
+/// # Fields
+/// * `width` - Width of the rectangle in pixels
+/// * `height` - Height of the rectangle in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Implementation block for Rect
+/// Contains methods (functions) associated with the Rect struct
+impl Rect {
+    /// Calculates the area of the rectangle
+    ///
+    /// # Arguments
+    /// * `&self` - Immutable borrow of the instance (read-only access)
+    ///
+    /// # Returns
+    /// Area as u32 (width * height)
+    pub fn area(&self) -> u32 {
+        self.width * self.height
+    }
+
+    pub fn perimeter(&self) -> u32 {
+        2 * (self.width + self.height)
+    }
+
+    // The same rectangle turned a quarter turn.
+    pub fn rotated(&self) -> Rect {
+        Rect {
+            width: self.height,
+            height: self.width,
+        }
+    }
+
+    // Sheets can be large, so areas added up for a whole layout use `u64`.
+    fn wide_area(&self) -> u64 {
+        u64::from(self.width) * u64::from(self.height)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Shelf,
+    Guillotine,
+    MaxRects,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 3] = [Algorithm::Shelf, Algorithm::Guillotine, Algorithm::MaxRects];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Shelf => "shelf",
+            Algorithm::Guillotine => "guillotine",
+            Algorithm::MaxRects => "maxrects",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackError {
+    ZeroWidthBin,
+    // `index` is the rectangle's position in the input.
+    EmptyRect {
+        index: usize,
+    },
+    TooWide {
+        index: usize,
+        rect: Rect,
+        bin_width: u32,
+    },
+    // The rectangles could need a sheet taller than `u32::MAX`.
+    TooTall,
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PackError::ZeroWidthBin => write!(f, "the sheet must be at least 1 pixel wide"),
+            PackError::EmptyRect { index } => {
+                write!(f, "rectangle {} has no area", index)
+            }
+            PackError::TooWide {
+                index,
+                rect,
+                bin_width,
+            } => write!(
+                f,
+                "rectangle {} ({} x {}) does not fit on a sheet {} pixels wide",
+                index, rect.width, rect.height, bin_width
+            ),
+            PackError::TooTall => write!(f, "the rectangles are too tall to pack on one sheet"),
+        }
+    }
+}
+
+impl std::error::Error for PackError {}
+
+// Where one rectangle ended up. `rotated` means it was turned a quarter
+// turn, so `size` is the input rectangle with width and height swapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    pub x: u32,
+    pub y: u32,
+    pub size: Rect,
+    pub rotated: bool,
+}
+
+impl Placement {
+    pub fn right(&self) -> u32 {
+        self.x + self.size.width
+    }
+
+    pub fn bottom(&self) -> u32 {
+        self.y + self.size.height
+    }
+}
+
+// The result of packing: one placement per input rectangle, in input order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub algorithm: Algorithm,
+    pub width: u32,
+    // How tall the sheet needs to be: the bottom of the lowest rectangle.
+    pub height: u32,
+    pub placements: Vec<Placement>,
+}
+
+impl Layout {
+    pub fn sheet_area(&self) -> u64 {
+        u64::from(self.width) * u64::from(self.height)
+    }
+
+    pub fn used_area(&self) -> u64 {
+        self.placements.iter().map(|p| p.size.wide_area()).sum()
+    }
+
+    // Sheet area that no rectangle covers.
+    pub fn wasted_area(&self) -> u64 {
+        self.sheet_area() - self.used_area()
+    }
+
+    // The wasted share of the sheet, from 0 to 100.
+    pub fn wasted_percent(&self) -> f64 {
+        if self.sheet_area() == 0 {
+            return 0.0;
+        }
+        100.0 * self.wasted_area() as f64 / self.sheet_area() as f64
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} rectangles on a {} x {} sheet ({}): {} of {} px wasted ({:.1}%)",
+            self.placements.len(),
+            self.width,
+            self.height,
+            self.algorithm.name(),
+            self.wasted_area(),
+            self.sheet_area(),
+            self.wasted_percent()
+        )?;
+        for (index, placement) in self.placements.iter().enumerate() {
+            writeln!(
+                f,
+                "  #{:<3} {:>4} x {:<4} at ({}, {}) to ({}, {}){}",
+                index,
+                placement.size.width,
+                placement.size.height,
+                placement.x,
+                placement.y,
+                placement.right(),
+                placement.bottom(),
+                if placement.rotated { "  rotated" } else { "" }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Packs rectangles onto a sheet `width` pixels wide.
+// `allow_rotation` lets the packer turn rectangles a quarter turn when that
+// fits better; leave it off for sprites whose orientation matters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Packer {
+    pub width: u32,
+    pub algorithm: Algorithm,
+    pub allow_rotation: bool,
+}
+
+impl Packer {
+    pub fn new(width: u32, algorithm: Algorithm) -> Packer {
+        Packer {
+            width,
+            algorithm,
+            allow_rotation: false,
+        }
+    }
+
+    pub fn with_rotation(self) -> Packer {
+        Packer {
+            allow_rotation: true,
+            ..self
+        }
+    }
+
+    pub fn pack(&self, rects: &[Rect]) -> Result<Layout, PackError> {
+        let limit = self.check(rects)?;
+        // Big rectangles first: they are the hardest to fit later on.
+        // Keeping the input index lets the layout report in input order.
+        let mut order: Vec<usize> = (0..rects.len()).collect();
+        order.sort_by_key(|&i| Reverse((rects[i].wide_area(), rects[i].width)));
+        let mut placements = vec![None; rects.len()];
+        match self.algorithm {
+            Algorithm::Shelf => {
+                // Tallest first, once each rectangle is the way round it will be placed.
+                let turned: Vec<(Rect, bool)> = rects
+                    .iter()
+                    .map(|&rect| self.orient_for_shelf(rect))
+                    .collect();
+                order.sort_by_key(|&i| Reverse((turned[i].0.height, turned[i].0.width)));
+                let mut shelves = Shelves::default();
+                for i in order {
+                    placements[i] = Some(shelves.place(turned[i], self.width));
+                }
+            }
+            Algorithm::Guillotine => {
+                let mut free = vec![Space::new(0, 0, self.width, limit)];
+                for i in order {
+                    let placement = self.best_fit(&free, rects[i]);
+                    guillotine_cut(&mut free, &placement, limit);
+                    placements[i] = Some(placement);
+                }
+            }
+            Algorithm::MaxRects => {
+                let mut free = vec![Space::new(0, 0, self.width, limit)];
+                for i in order {
+                    let placement = self.best_fit(&free, rects[i]);
+                    maxrects_split(&mut free, &placement);
+                    placements[i] = Some(placement);
+                }
+            }
+        }
+        // `flatten` skips the `None`s, and there are none left by now.
+        let placements: Vec<Placement> = placements.into_iter().flatten().collect();
+        Ok(Layout {
+            algorithm: self.algorithm,
+            width: self.width,
+            height: placements.iter().map(Placement::bottom).max().unwrap_or(0),
+            placements,
+        })
+    }
+
+    // Refuses input no layout can hold, and returns a height that is
+    // always enough: every rectangle stacked on top of each other.
+    fn check(&self, rects: &[Rect]) -> Result<u32, PackError> {
+        if self.width == 0 {
+            return Err(PackError::ZeroWidthBin);
+        }
+        let mut total: u64 = 0;
+        for (index, &rect) in rects.iter().enumerate() {
+            if rect.width == 0 || rect.height == 0 {
+                return Err(PackError::EmptyRect { index });
+            }
+            let fits = rect.width <= self.width;
+            let fits_turned = self.allow_rotation && rect.height <= self.width;
+            if !fits && !fits_turned {
+                return Err(PackError::TooWide {
+                    index,
+                    rect,
+                    bin_width: self.width,
+                });
+            }
+            // Which way round it ends up is not known yet, so count the
+            // longer side when rotation is allowed.
+            let tallest = if fits_turned {
+                rect.height.max(rect.width)
+            } else {
+                rect.height
+            };
+            total += u64::from(tallest);
+        }
+        u32::try_from(total).map_err(|_| PackError::TooTall)
+    }
+
+    // Shelves are as tall as their tallest rectangle, so lying rectangles
+    // down, longer side across, keeps shelves low.
+    fn orient_for_shelf(&self, rect: Rect) -> (Rect, bool) {
+        let lie_down = self.allow_rotation && rect.height > rect.width && rect.height <= self.width;
+        if lie_down {
+            (rect.rotated(), true)
+        } else {
+            (rect, false)
+        }
+    }
+
+    // The free space, and way round, that puts the rectangle's bottom edge
+    // highest up the sheet, then furthest left ("bottom-left" in a sheet
+    // that grows downwards). The rectangle goes in the space's top-left corner.
+    fn best_fit(&self, free: &[Space], rect: Rect) -> Placement {
+        let turns: &[(Rect, bool)] = if self.allow_rotation && rect.width != rect.height {
+            &[(rect, false), (rect.rotated(), true)]
+        } else {
+            &[(rect, false)]
+        };
+        free.iter()
+            .flat_map(|space| turns.iter().map(move |&turn| (space, turn)))
+            .filter(|(space, (size, _))| space.fits(size))
+            .map(|(space, (size, rotated))| Placement {
+                x: space.x,
+                y: space.y,
+                size,
+                rotated,
+            })
+            .min_by_key(|placement| (placement.bottom(), placement.x))
+            .expect("the free space below everything placed always fits")
+    }
+}
+
+// The shelf algorithm's state: the shelves opened so far.
+#[derive(Default)]
+struct Shelves {
+    // (top, height, used width) of each shelf, top to bottom.
+    shelves: Vec<(u32, u32, u32)>,
+}
+
+impl Shelves {
+    fn place(&mut self, (size, rotated): (Rect, bool), bin_width: u32) -> Placement {
+        // The first shelf with room for it; shelves only get shorter
+        // further down, because the tallest rectangles came first.
+        let found = self
+            .shelves
+            .iter_mut()
+            .find(|(_, height, used)| size.height <= *height && size.width <= bin_width - *used);
+        let (x, y) = match found {
+            Some((top, _, used)) => {
+                let x = *used;
+                *used += size.width;
+                (x, *top)
+            }
+            None => {
+                let top = self
+                    .shelves
+                    .last()
+                    .map_or(0, |&(top, height, _)| top + height);
+                self.shelves.push((top, size.height, size.width));
+                (0, top)
+            }
+        };
+        Placement {
+            x,
+            y,
+            size,
+            rotated,
+        }
+    }
+}
+
+// A free rectangle of the sheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Space {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+impl Space {
+    fn new(x: u32, y: u32, width: u32, height: u32) -> Space {
+        Space {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn right(&self) -> u32 {
+        self.x + self.width
+    }
+
+    fn bottom(&self) -> u32 {
+        self.y + self.height
+    }
+
+    fn fits(&self, size: &Rect) -> bool {
+        size.width <= self.width && size.height <= self.height
+    }
+
+    fn contains(&self, other: &Space) -> bool {
+        self.x <= other.x
+            && self.y <= other.y
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+}
+
+// Guillotine: the placement sits in the top-left corner of one free space,
+// and the rest of that space is cut in two, along the shorter leftover side
+// so the bigger piece stays in one part.
+//
+// The space that reaches the bottom of the sheet is always cut straight
+// across instead, so a full-width strip stays free below everything placed
+// and even a rectangle as wide as the sheet still fits there.
+fn guillotine_cut(free: &mut Vec<Space>, placement: &Placement, limit: u32) {
+    let index = free
+        .iter()
+        .position(|space| {
+            space.x == placement.x && space.y == placement.y && space.fits(&placement.size)
+        })
+        .expect("placements are made in a free space");
+    let space = free.swap_remove(index);
+    let (w, h) = (placement.size.width, placement.size.height);
+    let (spare_w, spare_h) = (space.width - w, space.height - h);
+    let across = space.bottom() == limit || spare_w < spare_h;
+    let (right, below) = if across {
+        (
+            Space::new(space.x + w, space.y, spare_w, h),
+            Space::new(space.x, space.y + h, space.width, spare_h),
+        )
+    } else {
+        (
+            Space::new(space.x + w, space.y, spare_w, space.height),
+            Space::new(space.x, space.y + h, w, spare_h),
+        )
+    };
+    free.extend([right, below].into_iter().filter(|space| !space.is_empty()));
+}
+
+// MaxRects: every free space the placement overlaps is replaced by the up
+// to four largest spaces left around it, then spaces inside other spaces
+// are dropped, so the list holds only maximal free rectangles.
+fn maxrects_split(free: &mut Vec<Space>, placement: &Placement) {
+    let used = Space::new(
+        placement.x,
+        placement.y,
+        placement.size.width,
+        placement.size.height,
+    );
+    let mut next = Vec::with_capacity(free.len() + 4);
+    for space in free.drain(..) {
+        let overlaps = space.x < used.right()
+            && used.x < space.right()
+            && space.y < used.bottom()
+            && used.y < space.bottom();
+        if !overlaps {
+            next.push(space);
+            continue;
+        }
+        let parts = [
+            Space::new(
+                space.x,
+                space.y,
+                used.x.saturating_sub(space.x),
+                space.height,
+            ),
+            Space::new(
+                used.right(),
+                space.y,
+                space.right().saturating_sub(used.right()),
+                space.height,
+            ),
+            Space::new(
+                space.x,
+                space.y,
+                space.width,
+                used.y.saturating_sub(space.y),
+            ),
+            Space::new(
+                space.x,
+                used.bottom(),
+                space.width,
+                space.bottom().saturating_sub(used.bottom()),
+            ),
+        ];
+        next.extend(parts.into_iter().filter(|part| !part.is_empty()));
+    }
+    // Keep a space only if no other space holds it. Of two equal spaces,
+    // the first one is kept.
+    let kept: Vec<Space> = next
+        .iter()
+        .enumerate()
+        .filter(|&(i, space)| {
+            !next
+                .iter()
+                .enumerate()
+                .any(|(j, other)| j != i && other.contains(space) && (other != space || j < i))
+        })
+        .map(|(_, &space)| space)
+        .collect();
+    *free = kept;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng::TestRng;
+
+    fn rect(width: u32, height: u32) -> Rect {
+        Rect { width, height }
+    }
+
+    fn overlap(a: &Placement, b: &Placement) -> bool {
+        a.x < b.right() && b.x < a.right() && a.y < b.bottom() && b.y < a.bottom()
+    }
+
+    // Every rectangle is on the sheet, none overlap, each is its input
+    // rectangle (turned only if allowed), and the waste adds up.
+    fn assert_valid(layout: &Layout, rects: &[Rect], allow_rotation: bool) {
+        assert_eq!(layout.placements.len(), rects.len());
+        for (i, (placement, input)) in layout.placements.iter().zip(rects).enumerate() {
+            assert!(placement.right() <= layout.width && placement.bottom() <= layout.height);
+            let expected = if placement.rotated {
+                input.rotated()
+            } else {
+                *input
+            };
+            assert_eq!(placement.size, expected);
+            assert!(allow_rotation || !placement.rotated);
+            for other in &layout.placements[i + 1..] {
+                assert!(!overlap(placement, other), "{:?} {:?}", placement, other);
+            }
+        }
+        let used: u64 = rects.iter().map(Rect::wide_area).sum();
+        assert_eq!(layout.used_area(), used);
+        assert_eq!(layout.wasted_area() + used, layout.sheet_area());
+    }
+
+    #[test]
+    fn shelves_fill_rows_tallest_first() {
+        let rects = [rect(40, 10), rect(30, 30), rect(50, 20), rect(20, 10)];
+        let layout = Packer::new(100, Algorithm::Shelf).pack(&rects).unwrap();
+        assert_valid(&layout, &rects, false);
+        // The 30, 20 and last 10 tall ones share the first shelf; the 40 wide
+        // one no longer fits beside them and opens a second shelf.
+        let at = |i: usize| (layout.placements[i].x, layout.placements[i].y);
+        assert_eq!(
+            [at(1), at(2), at(3), at(0)],
+            [(0, 0), (30, 0), (80, 0), (0, 30)]
+        );
+        assert_eq!(layout.height, 40);
+        assert_eq!(layout.wasted_area(), 4000 - 2500);
+    }
+
+    #[test]
+    fn tight_fits_leave_no_waste() {
+        // Four quarters of a 100 x 100 square, and a full-width strip.
+        let rects = [
+            rect(50, 50),
+            rect(50, 50),
+            rect(50, 50),
+            rect(50, 50),
+            rect(100, 7),
+        ];
+        for algorithm in [Algorithm::Guillotine, Algorithm::MaxRects] {
+            let layout = Packer::new(100, algorithm).pack(&rects).unwrap();
+            assert_valid(&layout, &rects, false);
+            assert_eq!(
+                (layout.height, layout.wasted_area()),
+                (107, 0),
+                "{:?}",
+                algorithm
+            );
+        }
+        // Turning the tall rectangles lets them share a shelf.
+        let tall = [rect(10, 60), rect(10, 60), rect(60, 10)];
+        let layout = Packer::new(60, Algorithm::Shelf)
+            .with_rotation()
+            .pack(&tall)
+            .unwrap();
+        assert_valid(&layout, &tall, true);
+        assert_eq!(layout.height, 30);
+    }
+
+    #[test]
+    fn bad_input_is_refused() {
+        let packer = Packer::new(100, Algorithm::MaxRects);
+        assert_eq!(
+            Packer::new(0, Algorithm::Shelf).pack(&[]),
+            Err(PackError::ZeroWidthBin)
+        );
+        assert_eq!(
+            packer.pack(&[rect(1, 1), rect(0, 5)]),
+            Err(PackError::EmptyRect { index: 1 })
+        );
+        let wide = rect(120, 10);
+        assert!(matches!(
+            packer.pack(&[wide]),
+            Err(PackError::TooWide { index: 0, .. })
+        ));
+        assert!(packer.with_rotation().pack(&[wide]).unwrap().placements[0].rotated);
+        let huge = [rect(1, u32::MAX), rect(1, 1)];
+        assert_eq!(packer.pack(&huge), Err(PackError::TooTall));
+        let empty = packer.pack(&[]).unwrap();
+        assert_eq!((empty.height, empty.wasted_percent()), (0, 0.0));
+    }
+
+    #[test]
+    fn random_layouts_are_valid() {
+        // Sizes from 1 to `limit`, in a repeatable sequence.
+        let mut random = TestRng::new(23);
+        let mut next = |limit: u32| 1 + random.below(u64::from(limit)) as u32;
+        for _ in 0..200 {
+            let width = 20 + next(200);
+            let rects: Vec<Rect> = (0..next(40)).map(|_| rect(next(width), next(60))).collect();
+            for algorithm in Algorithm::ALL {
+                for rotation in [false, true] {
+                    let mut packer = Packer::new(width, algorithm);
+                    packer.allow_rotation = rotation;
+                    let layout = packer.pack(&rects).unwrap();
+                    assert_valid(&layout, &rects, rotation);
+                    // Never worse than stacking everything in one column.
+                    let stacked: u32 = rects.iter().map(|r| r.height.max(r.width)).sum();
+                    assert!(layout.height <= stacked);
+                }
+            }
+        }
+    }
+}
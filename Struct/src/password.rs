@@ -0,0 +1,330 @@
+// `password` module: storing passwords so that they cannot be read back.
+// A password is never stored itself. Instead we store a random "salt" and
+// the result of PBKDF2-HMAC-SHA256 over the password and salt, repeated
+// many times. Checking a password means hashing it again with the same salt
+// and comparing. The salt makes equal passwords hash differently, and the
+// repetition makes guessing slow for anyone who steals the file.
+//
+// SHA-256, HMAC and PBKDF2 are standard algorithms (FIPS 180-4, RFC 2104,
+// RFC 8018), written out here because this example uses no crates. Real
+// projects should use an audited crate such as `argon2` instead.
+
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// How many times `PasswordHash::new` repeats the hash. More is slower for
+// attackers and for signing in alike. Unoptimised debug builds take a good
+// part of a second per hash; `cargo run --release` takes milliseconds.
+pub const DEFAULT_ITERATIONS: u32 = 20_000;
+
+const SALT_LEN: usize = 16;
+const HASH_LEN: usize = 32;
+// The name stored in front of each hash, so the format can change later.
+const SCHEME: &str = "pbkdf2-sha256";
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct PasswordHash {
+    iterations: u32,
+    salt: [u8; SALT_LEN],
+    hash: [u8; HASH_LEN],
+}
+
+impl PasswordHash {
+    pub fn new(password: &str, iterations: u32) -> PasswordHash {
+        let salt = random_salt();
+        let iterations = iterations.max(1);
+        PasswordHash {
+            iterations,
+            salt,
+            hash: pbkdf2_sha256(password.as_bytes(), &salt, iterations),
+        }
+    }
+
+    // A stand-in that no password is expected to match, made without any
+    // hashing. Verifying against it takes as long as against a real hash
+    // with the same `iterations`.
+    pub fn placeholder(iterations: u32) -> PasswordHash {
+        PasswordHash {
+            iterations: iterations.max(1),
+            salt: [0; SALT_LEN],
+            hash: [0; HASH_LEN],
+        }
+    }
+
+    pub fn verify(&self, password: &str) -> bool {
+        let hash = pbkdf2_sha256(password.as_bytes(), &self.salt, self.iterations);
+        // Compare every byte even after a difference, so the time taken
+        // does not tell an attacker how much of the hash they got right.
+        hash.iter()
+            .zip(&self.hash)
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+    }
+
+    // Reads the form written by `Display`:
+    // `pbkdf2-sha256$ITERATIONS$SALT$HASH`, with salt and hash in hex.
+    pub fn parse(text: &str) -> Option<PasswordHash> {
+        let mut parts = text.split('$');
+        if parts.next()? != SCHEME {
+            return None;
+        }
+        let iterations: u32 = parts.next()?.parse().ok().filter(|&n| n > 0)?;
+        let salt = from_hex(parts.next()?)?;
+        let hash = from_hex(parts.next()?)?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(PasswordHash {
+            iterations,
+            salt,
+            hash,
+        })
+    }
+}
+
+impl fmt::Display for PasswordHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}${}${}${}",
+            SCHEME,
+            self.iterations,
+            hex(&self.salt),
+            hex(&self.hash)
+        )
+    }
+}
+
+// `Debug` is written by hand so that printing a user never prints the hash.
+impl fmt::Debug for PasswordHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "PasswordHash({}, {} iterations)",
+            SCHEME, self.iterations
+        )
+    }
+}
+
+// Salts need to be unique, not secret. `RandomState` is seeded from the
+// operating system's random source, so hashing the time with it gives
+// different bytes on every call without needing the `rand` crate.
+fn random_salt() -> [u8; SALT_LEN] {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    let mut salt = [0; SALT_LEN];
+    for chunk in salt.chunks_mut(8) {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(nanos);
+        chunk.copy_from_slice(&hasher.finish().to_le_bytes()[..chunk.len()]);
+    }
+    salt
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// `None` unless `text` is exactly `N` bytes of hex.
+fn from_hex<const N: usize>(text: &str) -> Option<[u8; N]> {
+    if text.len() != 2 * N || !text.is_ascii() {
+        return None;
+    }
+    let mut bytes = [0; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&text[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
+// PBKDF2 with HMAC-SHA256, producing one 32-byte block.
+fn pbkdf2_sha256(password: &[u8], salt: &[u8], iterations: u32) -> [u8; HASH_LEN] {
+    let hmac = Hmac::new(password);
+    let mut first = salt.to_vec();
+    first.extend_from_slice(&1u32.to_be_bytes()); // the block number
+    let mut block = hmac.sign(&first);
+    let mut result = block;
+    for _ in 1..iterations {
+        block = hmac.sign(&block);
+        for (r, b) in result.iter_mut().zip(&block) {
+            *r ^= b;
+        }
+    }
+    result
+}
+
+// HMAC-SHA256 with a fixed key. HMAC hashes the key, padded to one block,
+// in front of every message. That first block is the same each time, so
+// its hash state is worked out once here: PBKDF2 then needs two block
+// hashes per round instead of four.
+struct Hmac {
+    inner: [u32; 8],
+    outer: [u32; 8],
+}
+
+impl Hmac {
+    fn new(key: &[u8]) -> Hmac {
+        // Keys longer than a block are hashed first; shorter ones are padded.
+        let mut padded = [0u8; BLOCK_LEN];
+        if key.len() > BLOCK_LEN {
+            padded[..HASH_LEN].copy_from_slice(&sha256(key));
+        } else {
+            padded[..key.len()].copy_from_slice(key);
+        }
+        let keyed = |pad: u8| {
+            let mut state = INITIAL_STATE;
+            compress(&mut state, &padded.map(|byte| byte ^ pad));
+            state
+        };
+        Hmac {
+            inner: keyed(0x36),
+            outer: keyed(0x5c),
+        }
+    }
+
+    fn sign(&self, message: &[u8]) -> [u8; HASH_LEN] {
+        let inner = finish(self.inner, BLOCK_LEN, message);
+        finish(self.outer, BLOCK_LEN, &inner)
+    }
+}
+
+// The first 32 bits of the fractional parts of the cube roots of the first
+// 64 primes.
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const BLOCK_LEN: usize = 64;
+
+// The first 32 bits of the fractional parts of the square roots of the
+// first 8 primes.
+const INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+fn sha256(message: &[u8]) -> [u8; HASH_LEN] {
+    finish(INITIAL_STATE, 0, message)
+}
+
+// Hashes the rest of a message, `rest`, into `state`, which has already
+// taken in `done` bytes (a whole number of blocks), and returns the digest.
+fn finish(mut state: [u32; 8], done: usize, rest: &[u8]) -> [u8; HASH_LEN] {
+    let mut blocks = rest.chunks_exact(BLOCK_LEN);
+    for block in &mut blocks {
+        compress(&mut state, block);
+    }
+    // Padding: a 1 bit, zeros up to 8 bytes short of a block, then the
+    // message length in bits. That takes one more block, or two if the
+    // leftover bytes are too many to leave room for the length.
+    let tail = blocks.remainder();
+    let mut last = [0u8; 2 * BLOCK_LEN];
+    last[..tail.len()].copy_from_slice(tail);
+    last[tail.len()] = 0x80;
+    let end = if tail.len() < BLOCK_LEN - 8 {
+        BLOCK_LEN
+    } else {
+        2 * BLOCK_LEN
+    };
+    let bits = (done + rest.len()) as u64 * 8;
+    last[end - 8..end].copy_from_slice(&bits.to_be_bytes());
+    for block in last[..end].chunks_exact(BLOCK_LEN) {
+        compress(&mut state, block);
+    }
+
+    let mut digest = [0u8; HASH_LEN];
+    for (bytes, value) in digest.chunks_mut(4).zip(state) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+// The SHA-256 compression function: mixes one 64-byte block into `state`.
+fn compress(state: &mut [u32; 8], block: &[u8]) {
+    let mut w = [0u32; 64];
+    for (i, word) in block.chunks(4).enumerate() {
+        w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let choice = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(choice)
+            .wrapping_add(ROUND_CONSTANTS[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let majority = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(majority);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+    for (value, add) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *value = value.wrapping_add(add);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_answers() {
+        // SHA-256 examples from FIPS 180-2, and the widely published SHA-256
+        // versions of the PBKDF2 test vectors in RFC 6070.
+        let abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(hex(&sha256(b"abc")), abc);
+        let empty = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        assert_eq!(hex(&sha256(b"")), empty);
+        // Two blocks, because the padding does not fit after 56 bytes.
+        let long = "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1";
+        let message = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+        assert_eq!(hex(&sha256(message)), long);
+        let pbkdf2 = "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b";
+        assert_eq!(hex(&pbkdf2_sha256(b"password", b"salt", 1)), pbkdf2);
+        let twice = "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43";
+        assert_eq!(hex(&pbkdf2_sha256(b"password", b"salt", 2)), twice);
+    }
+
+    #[test]
+    fn hashes_verify_and_round_trip() {
+        let first = PasswordHash::new("correct horse", 10);
+        let second = PasswordHash::new("correct horse", 10);
+        assert!(first.verify("correct horse"));
+        assert!(!first.verify("correct horse "));
+        // Different salts, so the same password is stored differently.
+        assert_ne!(first, second);
+        assert_eq!(PasswordHash::parse(&first.to_string()), Some(first.clone()));
+        assert!(!format!("{:?}", first).contains(&hex(&first.hash)));
+        assert_eq!(PasswordHash::parse("md5$10$00$00"), None);
+        assert_eq!(PasswordHash::parse(&format!("{}$extra", first)), None);
+        // The placeholder keeps the iteration count but matches nothing real.
+        let placeholder = PasswordHash::placeholder(10);
+        assert_eq!(placeholder.iterations, first.iterations);
+        assert!(!placeholder.verify("") && !placeholder.verify("correct horse"));
+    }
+}
@@ -0,0 +1,33 @@
+// `test_rng` module: repeatable pseudo-random numbers for the tests.
+// A linear congruential generator is good enough to make test data, and
+// needs no `rand` crate. A test that starts from a fixed seed always sees
+// the same numbers, so any failure it finds can be reproduced.
+
+pub struct TestRng(u64);
+
+impl TestRng {
+    pub fn new(seed: u64) -> TestRng {
+        TestRng(seed)
+    }
+
+    // Steps the generator and returns its whole state.
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1);
+        self.0
+    }
+
+    // A whole number in `0..bound`, from the better-mixed high bits.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        (self.next() >> 33) % bound
+    }
+
+    // A number in `low..high`.
+    pub fn range(&mut self, low: f64, high: f64) -> f64 {
+        // The top 53 bits fill an `f64` fraction exactly.
+        let unit = (self.next() >> 11) as f64 / (1u64 << 53) as f64;
+        low + unit * (high - low)
+    }
+}
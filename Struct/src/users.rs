@@ -0,0 +1,620 @@
+// `users` module: user accounts, signing in, and saving them to a file.
+// A `UserRegistry` owns every `User`. It makes sure no two users share a
+// username or an email address (ignoring case), checks that email addresses
+// look like email addresses, and stores passwords only as salted hashes.
+//
+// Signing in counts successes in `sign_in_count`. After `MAX_FAILED_SIGN_INS`
+// wrong passwords in a row the account locks until `unlock` is called, and
+// an account that is not `active` cannot sign in at all. Changing a password
+// checks the old one the same way, so it cannot be used to guess around
+// the lock.
+//
+// The registry is saved as a text file with one user per line and the
+// fields separated by tabs:
+//
+//     # username	email	active	sign-ins	failed	password
+//     alice	alice@example.com	yes	3	0	pbkdf2-sha256$20000$...
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::hint;
+use std::io;
+use std::path::Path;
+
+use crate::password::{PasswordHash, DEFAULT_ITERATIONS};
+
+// Where `main` saves the registry, relative to the working directory.
+pub const DEFAULT_USERS_FILE: &str = "users.txt";
+
+pub const MAX_FAILED_SIGN_INS: u32 = 5;
+pub const MIN_PASSWORD_LEN: usize = 8;
+
+const HEADER: &str = "# username\temail\tactive\tsign-ins\tfailed\tpassword";
+
+/// User struct - custom data type grouping related data
+/// Structs are like classes in other languages
+///
+/// # Fields
+/// * `active` - Whether the user account is active
+/// * `username` - User's display name
+/// * `email` - User's email address
+/// * `sign_in_count` - Number of times user has signed in
+/// * `failed_sign_ins` - Wrong passwords since the last successful sign-in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct User {
+    pub active: bool,
+    pub username: String,
+    pub email: String,
+    pub sign_in_count: u64,
+    pub failed_sign_ins: u32,
+    // Private: nothing outside this module needs to see even the hash.
+    password: PasswordHash,
+}
+
+impl User {
+    pub fn is_locked(&self) -> bool {
+        self.failed_sign_ins >= MAX_FAILED_SIGN_INS
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserError {
+    // Usernames are 3 to 32 letters, digits, '.', '_' or '-'.
+    InvalidUsername(String),
+    InvalidEmail(String),
+    PasswordTooShort,
+    UsernameTaken(String),
+    EmailTaken(String),
+    UnknownUser(String),
+    // Sign-in failures. A wrong password and an unknown username give the
+    // same `BadCredentials`, so sign-in does not reveal which usernames exist.
+    BadCredentials,
+    Locked(String),
+    Inactive(String),
+}
+
+impl fmt::Display for UserError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UserError::InvalidUsername(name) => write!(
+                f,
+                "'{}' is not a valid username: use 3 to 32 letters, digits, '.', '_' or '-'",
+                name
+            ),
+            UserError::InvalidEmail(email) => {
+                write!(f, "'{}' is not a valid email address", email)
+            }
+            UserError::PasswordTooShort => write!(
+                f,
+                "passwords must be at least {} characters long",
+                MIN_PASSWORD_LEN
+            ),
+            UserError::UsernameTaken(name) => write!(f, "the username '{}' is taken", name),
+            UserError::EmailTaken(email) => {
+                write!(f, "'{}' already belongs to another user", email)
+            }
+            UserError::UnknownUser(name) => write!(f, "no user is called '{}'", name),
+            UserError::BadCredentials => write!(f, "wrong username or password"),
+            UserError::Locked(name) => write!(
+                f,
+                "'{}' is locked after {} failed sign-ins",
+                name, MAX_FAILED_SIGN_INS
+            ),
+            UserError::Inactive(name) => write!(f, "'{}' has been deactivated", name),
+        }
+    }
+}
+
+impl std::error::Error for UserError {}
+
+// Why a users file could not be loaded.
+#[derive(Debug)]
+pub enum RegistryFileError {
+    Io(io::Error),
+    // Line `line` (counting from 1) is not a valid user.
+    BadLine { line: usize, problem: String },
+}
+
+impl fmt::Display for RegistryFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistryFileError::Io(error) => write!(f, "{}", error),
+            RegistryFileError::BadLine { line, problem } => write!(f, "line {}: {}", line, problem),
+        }
+    }
+}
+
+impl std::error::Error for RegistryFileError {}
+
+impl From<io::Error> for RegistryFileError {
+    fn from(error: io::Error) -> RegistryFileError {
+        RegistryFileError::Io(error)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UserRegistry {
+    // Keyed by lowercase username, so "Alice" and "alice" are one user.
+    users: BTreeMap<String, User>,
+    // Lowercase email -> the key of the user it belongs to.
+    emails: BTreeMap<String, String>,
+    // How many times new password hashes are repeated.
+    iterations: u32,
+}
+
+impl UserRegistry {
+    pub fn new() -> UserRegistry {
+        UserRegistry {
+            users: BTreeMap::new(),
+            emails: BTreeMap::new(),
+            iterations: DEFAULT_ITERATIONS,
+        }
+    }
+
+    pub fn get(&self, username: &str) -> Option<&User> {
+        self.users.get(&username.to_lowercase())
+    }
+
+    // All users, in order of username.
+    pub fn users(&self) -> impl Iterator<Item = &User> {
+        self.users.values()
+    }
+
+    pub fn register(
+        &mut self,
+        username: &str,
+        email: &str,
+        password: &str,
+    ) -> Result<&User, UserError> {
+        // Checking everything before changing anything keeps the registry
+        // unchanged when registration fails.
+        let key = self.check_new(username, email)?;
+        if password.chars().count() < MIN_PASSWORD_LEN {
+            return Err(UserError::PasswordTooShort);
+        }
+        let user = User {
+            active: true,
+            username: username.to_string(),
+            email: email.to_string(),
+            sign_in_count: 0,
+            failed_sign_ins: 0,
+            password: PasswordHash::new(password, self.iterations),
+        };
+        Ok(self.insert(key, user))
+    }
+
+    pub fn sign_in(&mut self, username: &str, password: &str) -> Result<&User, UserError> {
+        let user = self.check_credentials(username, password)?;
+        user.sign_in_count += 1;
+        Ok(user)
+    }
+
+    pub fn deactivate(&mut self, username: &str) -> Result<(), UserError> {
+        self.user_mut(username)?.active = false;
+        Ok(())
+    }
+
+    pub fn activate(&mut self, username: &str) -> Result<(), UserError> {
+        self.user_mut(username)?.active = true;
+        Ok(())
+    }
+
+    // Clears the failed sign-ins of a locked account.
+    pub fn unlock(&mut self, username: &str) -> Result<(), UserError> {
+        self.user_mut(username)?.failed_sign_ins = 0;
+        Ok(())
+    }
+
+    pub fn change_password(
+        &mut self,
+        username: &str,
+        old: &str,
+        new: &str,
+    ) -> Result<(), UserError> {
+        if new.chars().count() < MIN_PASSWORD_LEN {
+            return Err(UserError::PasswordTooShort);
+        }
+        let iterations = self.iterations;
+        let user = self.check_credentials(username, old)?;
+        user.password = PasswordHash::new(new, iterations);
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<UserRegistry, RegistryFileError> {
+        UserRegistry::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<UserRegistry, RegistryFileError> {
+        let mut registry = UserRegistry::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let bad = |problem: String| RegistryFileError::BadLine {
+                line: index + 1,
+                problem,
+            };
+            let user = parse_user(line).map_err(|problem| bad(problem.to_string()))?;
+            // The same rules as `register`, so a file edited by hand cannot
+            // hold two users with one email.
+            let key = registry
+                .check_new(&user.username, &user.email)
+                .map_err(|error| bad(error.to_string()))?;
+            registry.insert(key, user);
+        }
+        Ok(registry)
+    }
+
+    // Writes to a temporary file first and then renames it over `path`, so
+    // a crash while saving leaves the old file whole rather than half written.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut text = String::from(HEADER);
+        text.push('\n');
+        for user in self.users.values() {
+            text.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\n",
+                user.username,
+                user.email,
+                if user.active { "yes" } else { "no" },
+                user.sign_in_count,
+                user.failed_sign_ins,
+                user.password
+            ));
+        }
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, text)?;
+        fs::rename(&temporary, path)
+    }
+
+    // Validates a new username and email and returns the username's key.
+    fn check_new(&self, username: &str, email: &str) -> Result<String, UserError> {
+        if !valid_username(username) {
+            return Err(UserError::InvalidUsername(username.to_string()));
+        }
+        if !valid_email(email) {
+            return Err(UserError::InvalidEmail(email.to_string()));
+        }
+        let key = username.to_lowercase();
+        if self.users.contains_key(&key) {
+            return Err(UserError::UsernameTaken(username.to_string()));
+        }
+        if self.emails.contains_key(&email.to_lowercase()) {
+            return Err(UserError::EmailTaken(email.to_string()));
+        }
+        Ok(key)
+    }
+
+    // The only place users are added, so the email index always matches.
+    fn insert(&mut self, key: String, user: User) -> &User {
+        self.emails.insert(user.email.to_lowercase(), key.clone());
+        // `entry(...).or_insert` hands back a reference to the stored user.
+        self.users.entry(key).or_insert(user)
+    }
+
+    // The checks behind `sign_in` and `change_password`. A wrong password
+    // counts towards the lock; the right one clears earlier failures.
+    fn check_credentials(
+        &mut self,
+        username: &str,
+        password: &str,
+    ) -> Result<&mut User, UserError> {
+        let Some(user) = self.users.get_mut(&username.to_lowercase()) else {
+            // Hash the password anyway, so an unknown username takes as long
+            // to refuse as a wrong password and the timing gives nothing away.
+            // `black_box` stops the compiler from skipping the unused result.
+            hint::black_box(PasswordHash::placeholder(self.iterations).verify(password));
+            return Err(UserError::BadCredentials);
+        };
+        // A locked account refuses even the right password, so guessing
+        // cannot go on once the limit is reached.
+        if user.is_locked() {
+            return Err(UserError::Locked(user.username.clone()));
+        }
+        if !user.password.verify(password) {
+            user.failed_sign_ins += 1;
+            if user.is_locked() {
+                return Err(UserError::Locked(user.username.clone()));
+            }
+            return Err(UserError::BadCredentials);
+        }
+        if !user.active {
+            return Err(UserError::Inactive(user.username.clone()));
+        }
+        user.failed_sign_ins = 0;
+        Ok(user)
+    }
+
+    fn user_mut(&mut self, username: &str) -> Result<&mut User, UserError> {
+        self.users
+            .get_mut(&username.to_lowercase())
+            .ok_or_else(|| UserError::UnknownUser(username.to_string()))
+    }
+}
+
+// `Default` gives `UserRegistry::default()`, the same as `new`.
+impl Default for UserRegistry {
+    fn default() -> UserRegistry {
+        UserRegistry::new()
+    }
+}
+
+// Reads one tab-separated line of a users file.
+fn parse_user(line: &str) -> Result<User, &'static str> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    let [username, email, active, sign_ins, failed, password] = fields[..] else {
+        return Err("expected 6 tab-separated fields");
+    };
+    let active = match active {
+        "yes" => true,
+        "no" => false,
+        _ => return Err("active must be 'yes' or 'no'"),
+    };
+    Ok(User {
+        active,
+        username: username.to_string(),
+        email: email.to_string(),
+        sign_in_count: sign_ins.parse().map_err(|_| "bad sign-in count")?,
+        failed_sign_ins: failed.parse().map_err(|_| "bad failed sign-in count")?,
+        password: PasswordHash::parse(password).ok_or("bad password hash")?,
+    })
+}
+
+fn valid_username(username: &str) -> bool {
+    (3..=32).contains(&username.len())
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+// A practical check of an address's syntax, a little stricter than the
+// standard (RFC 5322) allows: no quoted local parts and no IP address
+// domains. Whether the address really receives mail can only be known by
+// sending it some.
+pub fn valid_email(email: &str) -> bool {
+    // `split_once` splits at the first '@'; a second '@' fails the local part.
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    const LOCAL_SPECIALS: &str = "!#$%&'*+/=?^_`{|}~-";
+    let local_ok = (1..=64).contains(&local.len())
+        && !local.starts_with('.')
+        && !local.ends_with('.')
+        && !local.contains("..")
+        && local
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || LOCAL_SPECIALS.contains(c));
+    let labels: Vec<&str> = domain.split('.').collect();
+    // Each label: 1 to 63 letters, digits or hyphens, not starting or ending
+    // with a hyphen. The last one, the top-level domain, is letters only.
+    let label_ok = |label: &&str| {
+        (1..=63).contains(&label.len())
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    };
+    let tld = labels[labels.len() - 1];
+    local_ok
+        && email.len() <= 254
+        && labels.len() >= 2
+        && labels.iter().all(label_ok)
+        && tld.len() >= 2
+        && tld.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // Hashing once instead of thousands of times keeps the tests fast.
+    fn registry() -> UserRegistry {
+        let mut registry = UserRegistry::new();
+        registry.iterations = 1;
+        registry
+            .register("alice", "alice@example.com", "wonderland")
+            .unwrap();
+        registry
+            .register("Bob_99", "bob@mail.example.org", "builder123")
+            .unwrap();
+        registry
+    }
+
+    #[test]
+    fn registration_enforces_unique_valid_accounts() {
+        let mut users = registry();
+        let taken = users.register("ALICE", "other@example.com", "password1");
+        assert_eq!(
+            taken.unwrap_err(),
+            UserError::UsernameTaken("ALICE".to_string())
+        );
+        let same_email = users.register("carol", "Alice@Example.com", "password1");
+        assert!(matches!(same_email, Err(UserError::EmailTaken(_))));
+        assert!(matches!(
+            users.register("al", "al@example.com", "password1"),
+            Err(UserError::InvalidUsername(_))
+        ));
+        assert!(matches!(
+            users.register("carol", "carol@example", "password1"),
+            Err(UserError::InvalidEmail(_))
+        ));
+        assert_eq!(
+            users.register("carol", "carol@example.com", "short"),
+            Err(UserError::PasswordTooShort)
+        );
+        // Nothing was added by the failed attempts.
+        assert_eq!(users.users().count(), 2);
+        assert_eq!(users.get("bob_99").unwrap().username, "Bob_99");
+    }
+
+    #[test]
+    fn email_syntax() {
+        let good = [
+            "a@b.co",
+            "first.last+tag@sub.example.com",
+            "o'neil@ex-ample.org",
+        ];
+        for email in good {
+            assert!(valid_email(email), "{}", email);
+        }
+        let bad = [
+            "plain",
+            "@example.com",
+            "a@",
+            "a@@b.com",
+            "a@b@c.com",
+            ".a@b.com",
+            "a.@b.com",
+            "a..b@c.com",
+            "a b@c.com",
+            "a@-b.com",
+            "a@b-.com",
+            "a@b..com",
+            "a@b.c",
+            "a@b.c0m",
+            "a@localhost",
+        ];
+        for email in bad {
+            assert!(!valid_email(email), "{}", email);
+        }
+    }
+
+    #[test]
+    fn signing_in_counts_and_locks() {
+        let mut users = registry();
+        assert_eq!(
+            users.sign_in("Alice", "wonderland").unwrap().sign_in_count,
+            1
+        );
+        assert_eq!(
+            users.sign_in("alice", "wonderland").unwrap().sign_in_count,
+            2
+        );
+        assert_eq!(
+            users.sign_in("nobody", "wonderland"),
+            Err(UserError::BadCredentials)
+        );
+        // A success clears earlier failures.
+        assert_eq!(
+            users.sign_in("alice", "Wonderland"),
+            Err(UserError::BadCredentials)
+        );
+        users.sign_in("alice", "wonderland").unwrap();
+        assert_eq!(users.get("alice").unwrap().failed_sign_ins, 0);
+
+        for _ in 1..MAX_FAILED_SIGN_INS {
+            assert_eq!(
+                users.sign_in("alice", "guess"),
+                Err(UserError::BadCredentials)
+            );
+        }
+        let locked = Err(UserError::Locked("alice".to_string()));
+        assert_eq!(users.sign_in("alice", "guess"), locked);
+        assert_eq!(users.sign_in("alice", "wonderland"), locked);
+        users.unlock("alice").unwrap();
+        assert_eq!(
+            users.sign_in("alice", "wonderland").unwrap().sign_in_count,
+            4
+        );
+    }
+
+    #[test]
+    fn deactivated_users_cannot_sign_in() {
+        let mut users = registry();
+        users.deactivate("bob_99").unwrap();
+        assert_eq!(
+            users.sign_in("bob_99", "builder123"),
+            Err(UserError::Inactive("Bob_99".to_string()))
+        );
+        assert_eq!(users.get("bob_99").unwrap().sign_in_count, 0);
+        users.activate("bob_99").unwrap();
+        assert!(users.sign_in("bob_99", "builder123").is_ok());
+        assert_eq!(
+            users.deactivate("carol"),
+            Err(UserError::UnknownUser("carol".to_string()))
+        );
+        users
+            .change_password("bob_99", "builder123", "new password")
+            .unwrap();
+        assert_eq!(
+            users.sign_in("bob_99", "builder123"),
+            Err(UserError::BadCredentials)
+        );
+        assert!(users.sign_in("bob_99", "new password").is_ok());
+    }
+
+    #[test]
+    fn changing_a_password_checks_it_like_signing_in() {
+        let mut users = registry();
+        let change = |users: &mut UserRegistry, old: &str| {
+            users.change_password("alice", old, "through the looking glass")
+        };
+        // Wrong old passwords count towards the lock, just like sign-ins.
+        assert_eq!(change(&mut users, "guess"), Err(UserError::BadCredentials));
+        assert_eq!(users.get("alice").unwrap().failed_sign_ins, 1);
+        for _ in 2..MAX_FAILED_SIGN_INS {
+            assert_eq!(change(&mut users, "guess"), Err(UserError::BadCredentials));
+        }
+        let locked = Err(UserError::Locked("alice".to_string()));
+        assert_eq!(change(&mut users, "guess"), locked);
+        assert_eq!(change(&mut users, "wonderland"), locked);
+        assert_eq!(users.sign_in("alice", "wonderland").map(|_| ()), locked);
+
+        users.unlock("alice").unwrap();
+        users.deactivate("alice").unwrap();
+        assert_eq!(
+            change(&mut users, "wonderland"),
+            Err(UserError::Inactive("alice".to_string()))
+        );
+        users.activate("alice").unwrap();
+        assert_eq!(change(&mut users, "wonderland"), Ok(()));
+        assert!(users.sign_in("alice", "through the looking glass").is_ok());
+        // Unknown users are refused the same way as wrong passwords.
+        assert_eq!(
+            users.change_password("nobody", "wonderland", "new password"),
+            Err(UserError::BadCredentials)
+        );
+    }
+
+    #[test]
+    fn saves_and_loads() {
+        let path: PathBuf = std::env::temp_dir().join(format!("{}-users.txt", std::process::id()));
+        let mut users = registry();
+        users.sign_in("alice", "wonderland").unwrap();
+        users.deactivate("bob_99").unwrap();
+        users.save(&path).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert!(!text.contains("wonderland") && !text.contains("builder123"));
+
+        let mut loaded = UserRegistry::parse(&text).unwrap();
+        assert_eq!(
+            loaded.users().collect::<Vec<_>>(),
+            users.users().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            loaded.sign_in("alice", "wonderland").unwrap().sign_in_count,
+            2
+        );
+        assert!(matches!(
+            loaded.register("carol", "BOB@mail.example.org", "password1"),
+            Err(UserError::EmailTaken(_))
+        ));
+
+        let duplicate = format!("{}{}", text, text.lines().nth(1).unwrap());
+        let error = UserRegistry::parse(&duplicate).unwrap_err();
+        assert!(
+            matches!(error, RegistryFileError::BadLine { line: 4, .. }),
+            "{}",
+            error
+        );
+        let broken = UserRegistry::parse("alice\talice@example.com\tmaybe\t0\t0\tx");
+        assert!(matches!(
+            broken,
+            Err(RegistryFileError::BadLine { line: 1, .. })
+        ));
+    }
+}
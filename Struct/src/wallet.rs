@@ -0,0 +1,675 @@
+// `wallet` module: a wallet backed by a ledger.
+// A `Wallet` never changes its balances on its own: every deposit, expense,
+// transfer and exchange is written to the ledger as an `Entry`, and a balance
+// moves only together with a new entry (see `Wallet::record`). So each balance
+// is always the sum of the ledger, and `Wallet::ledger_balance` lets anyone
+// check it.
+// A wallet can hold several currencies at once, each with its own balance.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::calendar::{Date, Timestamp};
+use crate::exchange::{ExchangeError, ExchangeRates};
+use crate::money::{Currency, Money, MoneyError};
+
+// What kind of movement an entry records. Transfers name the other wallet,
+// so each side of a transfer can be read on its own. An exchange writes two
+// entries: money out in one currency, money in in the other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryKind {
+    Deposit,
+    // Every expense is tagged with a spending category, such as "groceries".
+    Expense { category: String },
+    TransferIn { from: String },
+    TransferOut { to: String },
+    ExchangeIn { from: Currency },
+    ExchangeOut { to: Currency },
+}
+
+// One line of the ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub at: Timestamp,
+    pub kind: EntryKind,
+    pub currency: Currency,
+    pub amount: Money,
+    pub description: String,
+}
+
+impl Entry {
+    // Whether the entry brought money in (`true`) or took it out (`false`).
+    pub fn is_credit(&self) -> bool {
+        matches!(
+            self.kind,
+            EntryKind::Deposit | EntryKind::TransferIn { .. } | EntryKind::ExchangeIn { .. }
+        )
+    }
+
+    // `balance` after this entry, or the error if it cannot be applied.
+    pub fn apply(&self, balance: Money) -> Result<Money, MoneyError> {
+        if self.is_credit() {
+            balance.checked_add(self.amount)
+        } else {
+            balance.checked_sub(self.amount)
+        }
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // `match` on a reference binds `from` and `to` as references too.
+        let kind = match &self.kind {
+            EntryKind::Deposit => String::from("deposit"),
+            EntryKind::Expense { category } => category.clone(),
+            EntryKind::TransferIn { from } => format!("from {}", from),
+            EntryKind::TransferOut { to } => format!("to {}", to),
+            EntryKind::ExchangeIn { from } => format!("exchange {}", from),
+            EntryKind::ExchangeOut { to } => format!("exchange {}", to),
+        };
+        let sign = if self.is_credit() { '+' } else { '-' };
+        let amount = format!("{}{}", sign, self.amount.display(self.currency));
+        write!(
+            f,
+            "{}  {:<14} {:>10} {}  {}",
+            self.at, kind, amount, self.currency, self.description
+        )
+    }
+}
+
+// Why an operation was refused. Every operation either succeeds completely
+// or returns one of these and leaves both the balances and the ledger untouched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WalletError {
+    ZeroAmount,
+    InsufficientBalance {
+        currency: Currency,
+        balance: Money,
+        needed: Money,
+    },
+    // The balance would grow too large for `Money` to hold.
+    Overflow {
+        currency: Currency,
+        balance: Money,
+        amount: Money,
+    },
+    // The ledger is kept in time order, so entries cannot be back-dated.
+    BeforeLastEntry {
+        at: Timestamp,
+        last: Timestamp,
+    },
+    // An exchange from a currency into itself.
+    SameCurrency(Currency),
+    Exchange(ExchangeError),
+}
+
+impl fmt::Display for WalletError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WalletError::ZeroAmount => write!(f, "amount must be greater than zero"),
+            WalletError::InsufficientBalance {
+                currency,
+                balance,
+                needed,
+            } => write!(
+                f,
+                "insufficient balance: have {} {}, need {} {}",
+                balance.display(*currency),
+                currency,
+                needed.display(*currency),
+                currency
+            ),
+            WalletError::Overflow {
+                currency,
+                balance,
+                amount,
+            } => write!(
+                f,
+                "a balance of {} {} cannot take {} more",
+                balance.display(*currency),
+                currency,
+                amount.display(*currency)
+            ),
+            WalletError::BeforeLastEntry { at, last } => {
+                write!(f, "{} is before the last ledger entry at {}", at, last)
+            }
+            WalletError::SameCurrency(currency) => {
+                write!(f, "cannot exchange {} into itself", currency)
+            }
+            WalletError::Exchange(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+// Implementing `std::error::Error` lets callers use `?` with `Box<dyn Error>`.
+impl std::error::Error for WalletError {}
+
+// `From` lets `?` turn an `ExchangeError` into a `WalletError` automatically.
+impl From<ExchangeError> for WalletError {
+    fn from(error: ExchangeError) -> WalletError {
+        WalletError::Exchange(error)
+    }
+}
+
+// The fields are private: the only way to change a balance is through the
+// methods below, which all go through `record`.
+#[derive(Debug)]
+pub struct Wallet {
+    name: String,
+    // A `BTreeMap` keeps the currencies sorted, so they always print in the same order.
+    balances: BTreeMap<Currency, Money>,
+    ledger: Vec<Entry>,
+}
+
+impl Wallet {
+    // An empty wallet.
+    pub fn new(name: &str) -> Wallet {
+        Wallet {
+            name: name.to_string(),
+            balances: BTreeMap::new(),
+            ledger: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // The balance in one currency: zero for a currency never used.
+    pub fn balance(&self, currency: Currency) -> Money {
+        // `copied` turns the `Option<&Money>` from `get` into an `Option<Money>`.
+        self.balances.get(&currency).copied().unwrap_or(Money::ZERO)
+    }
+
+    // Every currency the wallet has used, with its balance.
+    pub fn balances(&self) -> &BTreeMap<Currency, Money> {
+        &self.balances
+    }
+
+    // Every entry, oldest first.
+    pub fn ledger(&self) -> &[Entry] {
+        &self.ledger
+    }
+
+    // The balance in `currency` worked out from scratch by replaying the
+    // ledger. It always equals `balance(currency)`; tests check this after
+    // every operation. Replaying with checked arithmetic also shows that no
+    // step of the history ever went below zero or overflowed.
+    pub fn ledger_balance(&self, currency: Currency) -> Result<Money, MoneyError> {
+        self.ledger
+            .iter()
+            .filter(|entry| entry.currency == currency)
+            // `try_fold` stops at the first `Err`, like `?` inside a loop.
+            .try_fold(Money::ZERO, |balance, entry| entry.apply(balance))
+    }
+
+    // `&self` is a reference to the instance of the `Wallet` struct.
+    pub fn show_bal(&self) {
+        let balances: Vec<String> = self
+            .balances
+            .iter()
+            .map(|(&currency, balance)| format!("{} {}", balance.display(currency), currency))
+            .collect();
+        // `println!` is a macro for printing formatted text to the console.
+        println!(
+            "Name: {}, have Balance: {}.",
+            self.name,
+            balances.join(", ")
+        );
+    }
+
+    // Adds money to the wallet and returns the new balance in that currency.
+    pub fn deposit(
+        &mut self,
+        at: Timestamp,
+        currency: Currency,
+        amount: Money,
+        description: &str,
+    ) -> Result<Money, WalletError> {
+        self.check_can_receive(at, currency, amount)?;
+        self.record(at, EntryKind::Deposit, currency, amount, description);
+        Ok(self.balance(currency))
+    }
+
+    // Spends money from the wallet on something in `category`, and returns
+    // the new balance in that currency. The description says who was paid.
+    // `&mut self` is a mutable reference, allowing the method to modify the wallet.
+    pub fn expense(
+        &mut self,
+        at: Timestamp,
+        currency: Currency,
+        amount: Money,
+        category: &str,
+        description: &str,
+    ) -> Result<Money, WalletError> {
+        self.check_can_pay(at, currency, amount)?;
+        let kind = EntryKind::Expense {
+            category: category.to_string(),
+        };
+        self.record(at, kind, currency, amount, description);
+        Ok(self.balance(currency))
+    }
+
+    // Moves `amount` from this wallet to `to`, writing an entry in both ledgers.
+    // Taking `self` and `to` as two `&mut` borrows means the compiler itself
+    // rules out a transfer from a wallet to itself.
+    // Both wallets are checked before either is changed, so a refused
+    // transfer leaves no half-written entry behind.
+    pub fn transfer(
+        &mut self,
+        to: &mut Wallet,
+        at: Timestamp,
+        currency: Currency,
+        amount: Money,
+        description: &str,
+    ) -> Result<(), WalletError> {
+        self.check_can_pay(at, currency, amount)?;
+        to.check_can_receive(at, currency, amount)?;
+        let out = EntryKind::TransferOut {
+            to: to.name.clone(),
+        };
+        let into = EntryKind::TransferIn {
+            from: self.name.clone(),
+        };
+        self.record(at, out, currency, amount, description);
+        to.record(at, into, currency, amount, description);
+        Ok(())
+    }
+
+    // Changes `amount` of `from` into `to` at the rates in `rates`, and
+    // returns how much of `to` it bought. Like a transfer, the exchange is
+    // checked completely before either balance changes.
+    pub fn exchange(
+        &mut self,
+        at: Timestamp,
+        amount: Money,
+        from: Currency,
+        to: Currency,
+        rates: &ExchangeRates,
+        description: &str,
+    ) -> Result<Money, WalletError> {
+        if from == to {
+            return Err(WalletError::SameCurrency(from));
+        }
+        self.check_can_pay(at, from, amount)?;
+        let bought = rates.convert(amount, from, to)?;
+        // An amount that rounds away to nothing is refused too.
+        self.check_can_receive(at, to, bought)?;
+        self.record(at, EntryKind::ExchangeOut { to }, from, amount, description);
+        self.record(at, EntryKind::ExchangeIn { from }, to, bought, description);
+        Ok(bought)
+    }
+
+    // The entries in `currency` from `from` to `to`, both days included,
+    // with the balances before and after them.
+    pub fn statement(&self, currency: Currency, from: Date, to: Date) -> Statement<'_> {
+        let mut opening = Money::ZERO;
+        let mut entries = Vec::new();
+        for entry in self
+            .ledger
+            .iter()
+            .filter(|entry| entry.currency == currency)
+        {
+            let day = entry.at.date();
+            if day < from {
+                opening = entry.apply(opening).expect("the ledger replays cleanly");
+            } else if day <= to {
+                entries.push(entry);
+            }
+        }
+        // `fold` runs the statement's entries on from the opening balance.
+        let closing = entries.iter().fold(opening, |balance, entry| {
+            entry.apply(balance).expect("the ledger replays cleanly")
+        });
+        Statement {
+            wallet: &self.name,
+            currency,
+            from,
+            to,
+            opening,
+            closing,
+            entries,
+        }
+    }
+
+    fn check_order(&self, at: Timestamp) -> Result<(), WalletError> {
+        match self.ledger.last() {
+            Some(last) if at < last.at => Err(WalletError::BeforeLastEntry { at, last: last.at }),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_can_receive(
+        &self,
+        at: Timestamp,
+        currency: Currency,
+        amount: Money,
+    ) -> Result<(), WalletError> {
+        if amount.is_zero() {
+            return Err(WalletError::ZeroAmount);
+        }
+        self.check_order(at)?;
+        let balance = self.balance(currency);
+        match balance.checked_add(amount) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(WalletError::Overflow {
+                currency,
+                balance,
+                amount,
+            }),
+        }
+    }
+
+    fn check_can_pay(
+        &self,
+        at: Timestamp,
+        currency: Currency,
+        amount: Money,
+    ) -> Result<(), WalletError> {
+        if amount.is_zero() {
+            return Err(WalletError::ZeroAmount);
+        }
+        self.check_order(at)?;
+        let balance = self.balance(currency);
+        match balance.checked_sub(amount) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(WalletError::InsufficientBalance {
+                currency,
+                balance,
+                needed: amount,
+            }),
+        }
+    }
+
+    // The one place where a balance changes, always together with a new
+    // ledger entry. Callers have already checked the operation, so applying
+    // the entry cannot overflow or go below zero.
+    fn record(
+        &mut self,
+        at: Timestamp,
+        kind: EntryKind,
+        currency: Currency,
+        amount: Money,
+        description: &str,
+    ) {
+        let entry = Entry {
+            at,
+            kind,
+            currency,
+            amount,
+            description: description.to_string(),
+        };
+        let balance = entry
+            .apply(self.balance(currency))
+            .expect("operations are checked before they are recorded");
+        self.balances.insert(currency, balance);
+        self.ledger.push(entry);
+        // `debug_assert_eq!` is checked in debug builds and tests only.
+        debug_assert_eq!(Ok(balance), self.ledger_balance(currency));
+    }
+}
+
+// A statement for one currency. It borrows its entries from the wallet
+// instead of copying them; the lifetime `'a` says the statement cannot
+// outlive the wallet.
+pub struct Statement<'a> {
+    pub wallet: &'a str,
+    pub currency: Currency,
+    pub from: Date,
+    pub to: Date,
+    pub opening: Money,
+    pub closing: Money,
+    pub entries: Vec<&'a Entry>,
+}
+
+impl fmt::Display for Statement<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} statement for {}, {} to {}",
+            self.currency, self.wallet, self.from, self.to
+        )?;
+        let opening = self.opening.display(self.currency);
+        writeln!(f, "  Opening balance: {} {}", opening, self.currency)?;
+        for entry in &self.entries {
+            writeln!(f, "  {}", entry)?;
+        }
+        let closing = self.closing.display(self.currency);
+        write!(f, "  Closing balance: {} {}", closing, self.currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng::TestRng;
+
+    fn day(day: u32) -> Date {
+        Date::new(2026, 3, day).unwrap()
+    }
+
+    fn noon(on: u32) -> Timestamp {
+        Timestamp::at(day(on), 12, 0)
+    }
+
+    fn eur() -> Currency {
+        Currency::new("EUR").unwrap()
+    }
+
+    fn usd() -> Currency {
+        Currency::new("USD").unwrap()
+    }
+
+    // Every currency in these tests has two decimal places, like the euro.
+    fn money(text: &str) -> Money {
+        Money::parse(text, eur()).unwrap()
+    }
+
+    #[test]
+    fn operations_are_recorded_in_the_ledger() {
+        let mut alice = Wallet::new("Alice");
+        let mut bob = Wallet::new("Bob");
+        let deposit = alice.deposit(noon(1), eur(), money("200"), "Salary");
+        assert_eq!(deposit, Ok(money("200")));
+        let expense = alice.expense(noon(2), eur(), money("50.25"), "groceries", "Market");
+        assert_eq!(expense, Ok(money("149.75")));
+        let transfer = alice.transfer(&mut bob, noon(3), eur(), money("30"), "Lunch");
+        assert_eq!(transfer, Ok(()));
+
+        assert_eq!(alice.balance(eur()), money("119.75"));
+        assert_eq!(alice.balance(usd()), Money::ZERO);
+        assert_eq!(bob.balance(eur()), money("30"));
+        let kinds: Vec<&EntryKind> = alice.ledger().iter().map(|entry| &entry.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                &EntryKind::Deposit,
+                &EntryKind::Expense {
+                    category: String::from("groceries")
+                },
+                &EntryKind::TransferOut {
+                    to: String::from("Bob")
+                }
+            ]
+        );
+        assert_eq!(
+            bob.ledger()[0].kind,
+            EntryKind::TransferIn {
+                from: String::from("Alice")
+            }
+        );
+        assert_eq!(bob.ledger()[0].description, "Lunch");
+    }
+
+    #[test]
+    fn refused_operations_change_nothing() {
+        let mut alice = Wallet::new("Alice");
+        let mut bob = Wallet::new("Bob");
+        alice
+            .deposit(noon(1), eur(), money("100"), "Salary")
+            .unwrap();
+        bob.deposit(noon(5), eur(), Money::from_minor(u64::MAX), "Jackpot")
+            .unwrap();
+
+        assert_eq!(
+            alice.expense(noon(2), eur(), money("100.01"), "rent", "Landlord"),
+            Err(WalletError::InsufficientBalance {
+                currency: eur(),
+                balance: money("100"),
+                needed: money("100.01")
+            })
+        );
+        // Euros do not pay for dollars.
+        assert!(alice
+            .expense(noon(2), usd(), money("1"), "coffee", "Cafe")
+            .is_err());
+        assert_eq!(
+            alice.expense(noon(2), eur(), Money::ZERO, "misc", "Nothing"),
+            Err(WalletError::ZeroAmount)
+        );
+        assert_eq!(
+            alice.deposit(Timestamp::at(day(1), 8, 0), eur(), money("5"), "Back-dated"),
+            Err(WalletError::BeforeLastEntry {
+                at: Timestamp::at(day(1), 8, 0),
+                last: noon(1)
+            })
+        );
+        // Bob cannot hold one more cent, so Alice keeps her money too.
+        assert_eq!(
+            alice.transfer(&mut bob, noon(6), eur(), money("0.01"), "Gift"),
+            Err(WalletError::Overflow {
+                currency: eur(),
+                balance: Money::from_minor(u64::MAX),
+                amount: money("0.01")
+            })
+        );
+        assert_eq!(alice.balance(eur()), money("100"));
+        assert_eq!(alice.ledger().len(), 1);
+        assert_eq!(bob.ledger().len(), 1);
+    }
+
+    #[test]
+    fn exchanges_move_money_between_currencies() {
+        let rates = ExchangeRates::parse("rounding half-even\nEUR USD 1.085\n").unwrap();
+        let mut wallet = Wallet::new("Alice");
+        wallet
+            .deposit(noon(1), eur(), money("100"), "Salary")
+            .unwrap();
+
+        let bought = wallet.exchange(noon(2), money("10.10"), eur(), usd(), &rates, "Trip");
+        // 10.10 * 1.085 = 10.9585, rounded half-even to the cent.
+        assert_eq!(bought, Ok(money("10.96")));
+        assert_eq!(wallet.balance(eur()), money("89.90"));
+        assert_eq!(wallet.balance(usd()), money("10.96"));
+        assert_eq!(
+            wallet.ledger()[2].kind,
+            EntryKind::ExchangeIn { from: eur() }
+        );
+
+        let gbp = Currency::new("GBP").unwrap();
+        let refused = wallet.exchange(noon(3), money("1"), eur(), gbp, &rates, "Trip");
+        assert_eq!(
+            refused,
+            Err(WalletError::Exchange(ExchangeError::NoRate {
+                from: eur(),
+                to: gbp
+            }))
+        );
+        // One cent of dollars is 0.92 euro cents, which rounds down to nothing.
+        let down = ExchangeRates::parse("rounding down\nEUR USD 1.085\n").unwrap();
+        let dust = wallet.exchange(noon(3), money("0.01"), usd(), eur(), &down, "Change");
+        assert_eq!(dust, Err(WalletError::ZeroAmount));
+        assert_eq!(
+            wallet.exchange(noon(3), money("1"), eur(), eur(), &rates, "Nothing"),
+            Err(WalletError::SameCurrency(eur()))
+        );
+        assert_eq!(wallet.ledger().len(), 3);
+    }
+
+    #[test]
+    fn statements_cover_a_date_range() {
+        let mut wallet = Wallet::new("Alice");
+        wallet
+            .deposit(noon(1), eur(), money("100"), "Salary")
+            .unwrap();
+        wallet
+            .expense(noon(3), eur(), money("10"), "books", "Bookshop")
+            .unwrap();
+        wallet
+            .deposit(noon(3), usd(), money("7"), "Refund")
+            .unwrap();
+        let late = Timestamp::at(day(4), 23, 59);
+        wallet
+            .expense(late, eur(), money("20"), "eating out", "Bistro")
+            .unwrap();
+        wallet
+            .deposit(noon(6), eur(), money("40"), "Refund")
+            .unwrap();
+
+        let statement = wallet.statement(eur(), day(2), day(4));
+        assert_eq!(statement.opening, money("100"));
+        assert_eq!(statement.entries.len(), 2);
+        assert_eq!(statement.closing, money("70"));
+        assert_eq!(
+            statement.to_string().lines().nth(2),
+            Some("  2026-03-03 12:00  books              -10.00 EUR  Bookshop")
+        );
+
+        let empty = wallet.statement(eur(), day(5), day(5));
+        assert_eq!((empty.opening, empty.closing), (money("70"), money("70")));
+        assert!(wallet.statement(eur(), day(6), day(1)).entries.is_empty());
+        assert_eq!(
+            wallet.statement(eur(), day(1), day(31)).closing,
+            money("110")
+        );
+        assert_eq!(wallet.statement(usd(), day(1), day(31)).closing, money("7"));
+    }
+
+    #[test]
+    fn balances_always_equal_the_ledger() {
+        // A fixed pseudo-random sequence of operations.
+        let mut random = TestRng::new(42);
+        let mut next = |bound: u64| random.below(bound);
+        let rates = ExchangeRates::parse("rounding half-up\nEUR USD 1.085\n").unwrap();
+        let currencies = [eur(), usd()];
+        let mut wallets = [Wallet::new("A"), Wallet::new("B"), Wallet::new("C")];
+        for step in 0..5_000 {
+            let at = Timestamp(step);
+            let amount = Money::from_minor(next(30_000));
+            let currency = currencies[next(2) as usize];
+            let first = next(3) as usize;
+            // Each arm ignores refusals on purpose: they must change nothing.
+            match next(4) {
+                0 => {
+                    let _ = wallets[first].deposit(at, currency, amount, "in");
+                }
+                1 => {
+                    let _ = wallets[first].expense(at, currency, amount, "misc", "out");
+                }
+                2 => {
+                    let other = currencies[1 - next(2) as usize];
+                    let _ = wallets[first].exchange(at, amount, currency, other, &rates, "swap");
+                }
+                _ => {
+                    let second = (first + 1 + next(2) as usize) % 3;
+                    // `split_at_mut` hands out two `&mut` borrows of one array.
+                    let (low, high) = wallets.split_at_mut(first.max(second));
+                    let (from, to) = if first < second {
+                        (&mut low[first], &mut high[0])
+                    } else {
+                        (&mut high[0], &mut low[second])
+                    };
+                    let _ = from.transfer(to, at, currency, amount, "move");
+                }
+            }
+            for wallet in &wallets {
+                for currency in currencies {
+                    assert_eq!(
+                        Ok(wallet.balance(currency)),
+                        wallet.ledger_balance(currency)
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,472 @@
+//! # Computer Opponent
+//!
+//! A computer player for Tic Tac Toe built on top of the game logic in `lib.rs`
+//! (`is_cell_empty` and `is_winning_move`).
+//!
+//! The strongest setting performs a full minimax search with alpha-beta pruning,
+//! which on a 3x3 board is small enough to explore every reachable position and
+//! therefore never loses. Weaker settings limit how far ahead the search looks or
+//! simply pick a random empty cell.
+//!
+//! Larger boards have far too many positions to search exhaustively, so there the
+//! search is cut off after a few plies and only considers cells next to existing
+//! markers. It still takes wins and blocks threats it can see, but is no longer
+//! guaranteed to be unbeatable.
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::player::{MinimaxPlayer, Player, RandomPlayer};
+use crate::{is_cell_empty, is_winning_move, opponent, Board, EMPTY_CELL};
+
+// ============================================================================
+// CONSTANTS
+// ============================================================================
+
+/// Score awarded to a position the computer has won (before depth adjustment)
+const WIN_SCORE: i32 = 100;
+
+/// How many plies the medium difficulty looks ahead
+///
+/// Two plies are enough to take an immediate win or block an immediate loss,
+/// but not enough to see forks coming, so a careful human can still win.
+const MEDIUM_SEARCH_DEPTH: u32 = 2;
+
+/// How many plies the perfect difficulty looks ahead on boards larger than 3x3
+const LARGE_BOARD_SEARCH_DEPTH: u32 = 4;
+
+/// Largest board that is searched exhaustively, with every empty cell considered
+const EXHAUSTIVE_BOARD_SIZE: usize = 3;
+
+// ============================================================================
+// ENUMS
+// ============================================================================
+
+/// How strongly the computer plays
+///
+/// # Rust Concepts
+/// - Fieldless enums behave like C-style enums and can derive `Copy`
+/// - Deriving `PartialEq` lets variants be compared with `==`
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Difficulty {
+    /// Picks a random empty cell every turn
+    Easy,
+    /// Takes wins and blocks losses it can see, but misses deeper tactics
+    Medium,
+    /// Searches the full game tree and never loses (on larger boards it
+    /// searches as deep as is practical instead)
+    Perfect,
+}
+
+impl Difficulty {
+    /// Looks up a difficulty by the name its `Display` impl prints
+    ///
+    /// # Rust Concepts
+    /// - Associated functions returning `Option<Self>` are a lightweight parser
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        match name {
+            "Easy" => Some(Difficulty::Easy),
+            "Medium" => Some(Difficulty::Medium),
+            "Perfect" => Some(Difficulty::Perfect),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "Easy"),
+            Difficulty::Medium => write!(f, "Medium"),
+            Difficulty::Perfect => write!(f, "Perfect"),
+        }
+    }
+}
+
+// ============================================================================
+// RANDOM NUMBERS
+// ============================================================================
+
+/// A tiny xorshift pseudo-random number generator
+///
+/// The weaker difficulties only need "unpredictable enough" choices, so a few
+/// lines of bit twiddling avoid pulling in an external crate. Given the same
+/// seed it always produces the same numbers, which is what makes arena games
+/// reproducible.
+///
+/// # Rust Concepts
+/// - Tuple structs: `struct Rng(u64)` wraps a single unnamed field
+/// - Wrapping/bit operations on unsigned integers never overflow-panic
+/// - `pub(crate)` shares the type with other modules without exporting it
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    /// Seeds the generator with a fixed value
+    ///
+    /// The seed is scrambled first (one round of SplitMix64), so nearby seeds
+    /// such as 1 and 2 still give unrelated sequences.
+    pub(crate) fn seeded(seed: u64) -> Rng {
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        // xorshift gets stuck at zero, so make sure at least one bit is set
+        Rng(z | 1)
+    }
+
+    /// Returns the next pseudo-random value
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    /// Returns a value in `0..bound` (`bound` must be non-zero)
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+/// Returns a seed taken from the system clock
+///
+/// Front ends can print it so that a run can be repeated exactly later.
+pub fn seed_from_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// COMPUTER PLAYER
+// ============================================================================
+
+/// The computer opponent of the terminal game, configured by difficulty
+///
+/// Each difficulty is played by one of the strategies in `player`, so the
+/// terminal game and the arena share the same move choices:
+///
+/// | Difficulty | Strategy                                  |
+/// |------------|-------------------------------------------|
+/// | Easy       | `RandomPlayer`                            |
+/// | Medium     | `MinimaxPlayer` searching two plies ahead |
+/// | Perfect    | `MinimaxPlayer` at full strength          |
+///
+/// # Fields
+/// * `marker` - The character this player places on the board ('X' or 'O')
+/// * `difficulty` - How strongly the player searches for moves
+/// * `strategy` - The player that actually picks the moves
+pub struct ComputerPlayer {
+    marker: char,
+    difficulty: Difficulty,
+    strategy: Box<dyn Player>,
+}
+
+impl ComputerPlayer {
+    /// Creates a computer player that places `marker` at the given difficulty
+    ///
+    /// Its random choices are seeded from the system clock.
+    pub fn new(marker: char, difficulty: Difficulty) -> ComputerPlayer {
+        ComputerPlayer::with_seed(marker, difficulty, seed_from_time())
+    }
+
+    /// Creates a computer player whose random choices come from `seed`
+    ///
+    /// The same seed always plays the same moves, which makes games repeatable.
+    pub fn with_seed(marker: char, difficulty: Difficulty, seed: u64) -> ComputerPlayer {
+        let strategy: Box<dyn Player> = match difficulty {
+            Difficulty::Easy => Box::new(RandomPlayer::new(seed)),
+            Difficulty::Medium => Box::new(MinimaxPlayer::new(Some(MEDIUM_SEARCH_DEPTH), seed)),
+            Difficulty::Perfect => Box::new(MinimaxPlayer::new(None, seed)),
+        };
+        ComputerPlayer {
+            marker,
+            difficulty,
+            strategy,
+        }
+    }
+
+    /// Returns the marker this player places on the board
+    pub fn marker(&self) -> char {
+        self.marker
+    }
+
+    /// Returns the difficulty this player was created with
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+}
+
+/// Lets the computer opponent be used wherever a `Player` is expected
+///
+/// # Rust Concepts
+/// - Delegation: the trait methods forward to the boxed strategy
+impl Player for ComputerPlayer {
+    /// The name games against this player are recorded under,
+    /// e.g. "Computer (Perfect)"
+    fn name(&self) -> String {
+        format!("Computer ({})", self.difficulty)
+    }
+
+    fn choose_move(&mut self, board: &Board, marker: char) -> Option<(usize, usize)> {
+        self.strategy.choose_move(board, marker)
+    }
+}
+
+// ============================================================================
+// SEARCH FUNCTIONS
+// ============================================================================
+
+/// Returns how deep the strongest search goes on this board
+///
+/// Boards up to 3x3 are searched to the end of the game; larger boards are
+/// cut off after `LARGE_BOARD_SEARCH_DEPTH` plies.
+pub fn full_search_depth(board: &Board) -> u32 {
+    if board.size() <= EXHAUSTIVE_BOARD_SIZE {
+        u32::MAX
+    } else {
+        LARGE_BOARD_SEARCH_DEPTH
+    }
+}
+
+/// Collects the coordinates of every empty cell, in row-major order
+///
+/// # Rust Concepts
+/// - `flat_map` turns each row index into an iterator of (row, col) pairs
+/// - `filter` keeps only the cells accepted by the predicate
+pub fn empty_cells(board: &Board) -> Vec<(usize, usize)> {
+    let size = board.size();
+    (0..size)
+        .flat_map(|row| (0..size).map(move |col| (row, col)))
+        .filter(|&(row, col)| is_cell_empty(board, row, col))
+        .collect()
+}
+
+/// Collects the moves worth searching, in row-major order
+///
+/// Small boards consider every empty cell. On larger boards only empty cells
+/// touching an existing marker are considered (or the centre on an empty
+/// board), since moves far away from the action almost never matter and would
+/// make the search far too slow.
+///
+/// # Rust Concepts
+/// - `saturating_sub` stops at zero instead of underflowing an unsigned integer
+/// - `(a..=b).any(..)` scans a small square neighbourhood without allocating
+fn candidate_moves(board: &Board) -> Vec<(usize, usize)> {
+    let empty = empty_cells(board);
+    let size = board.size();
+    if size <= EXHAUSTIVE_BOARD_SIZE {
+        return empty;
+    }
+
+    let touches_marker = |&(row, col): &(usize, usize)| {
+        (row.saturating_sub(1)..=(row + 1).min(size - 1)).any(|r| {
+            (col.saturating_sub(1)..=(col + 1).min(size - 1)).any(|c| board[r][c] != EMPTY_CELL)
+        })
+    };
+    let near: Vec<(usize, usize)> = empty.iter().copied().filter(touches_marker).collect();
+
+    if !near.is_empty() {
+        near
+    } else if empty.len() == size * size {
+        vec![(size / 2, size / 2)]
+    } else {
+        empty
+    }
+}
+
+/// Finds every move that scores best for `player` within `max_depth` plies
+///
+/// # Arguments
+/// * `board` - Reference to the current game board
+/// * `player` - The player whose move is being chosen
+/// * `max_depth` - How many plies to search (`u32::MAX` searches to the end)
+///
+/// # Returns
+/// All equally good moves, in row-major order (empty if the board is full)
+///
+/// # Rust Concepts
+/// - `clone()` makes one scratch copy of the board that the search can freely
+///   modify, leaving the caller's board untouched
+pub fn best_moves(board: &Board, player: char, max_depth: u32) -> Vec<(usize, usize)> {
+    let mut scratch = board.clone();
+    let empty = empty_cells(board).len();
+    let search = Search {
+        me: player,
+        max_depth,
+    };
+
+    let mut best_score = i32::MIN;
+    let mut best = Vec::new();
+
+    for (row, col) in candidate_moves(board) {
+        scratch[row][col] = player;
+        // Every root move is searched with a full window so that ties are
+        // reported exactly, which lets the weaker difficulties vary their play.
+        let score = search.score(&mut scratch, (row, col), 1, empty - 1, i32::MIN, i32::MAX);
+        scratch[row][col] = EMPTY_CELL;
+
+        if score > best_score {
+            best_score = score;
+            best.clear();
+        }
+        if score == best_score {
+            best.push((row, col));
+        }
+    }
+
+    best
+}
+
+/// Settings shared by every node of one minimax search
+///
+/// # Fields
+/// * `me` - The player the search is maximising for
+/// * `max_depth` - Depth at which unfinished positions are scored as neutral
+struct Search {
+    me: char,
+    max_depth: u32,
+}
+
+impl Search {
+    /// Scores a position with minimax search and alpha-beta pruning
+    ///
+    /// Scores are always from `me`'s point of view: positive values are good
+    /// for `me`, negative values are good for the opponent. Wins found sooner
+    /// score higher than wins found later, so the computer finishes games
+    /// quickly and delays losses as long as possible.
+    ///
+    /// # Arguments
+    /// * `board` - The position right after `last_move` was played; moves are
+    ///   tried on it in place and undone before returning
+    /// * `last_move` - The most recent move, the only one that can have won
+    /// * `depth` - Number of plies already played from the root position
+    /// * `empty` - Number of empty cells left on the board
+    /// * `alpha` - Best score the maximising player is already assured of
+    /// * `beta` - Best score the minimising player is already assured of
+    ///
+    /// # Rust Concepts
+    /// - Recursion: the method calls itself for every reply
+    /// - Make/unmake: mutating one board through `&mut` and restoring it is
+    ///   much cheaper than cloning a board for every position searched
+    fn score(
+        &self,
+        board: &mut Board,
+        last_move: (usize, usize),
+        depth: u32,
+        empty: usize,
+        mut alpha: i32,
+        mut beta: i32,
+    ) -> i32 {
+        let (last_row, last_col) = last_move;
+        let last_player = board[last_row][last_col];
+
+        if is_winning_move(board, last_row, last_col) {
+            return if last_player == self.me {
+                WIN_SCORE - depth as i32
+            } else {
+                depth as i32 - WIN_SCORE
+            };
+        }
+        if empty == 0 || depth >= self.max_depth {
+            return 0;
+        }
+
+        let to_move = opponent(last_player);
+        let maximising = to_move == self.me;
+        let mut best = if maximising { i32::MIN } else { i32::MAX };
+
+        for (row, col) in candidate_moves(board) {
+            board[row][col] = to_move;
+            let score = self.score(board, (row, col), depth + 1, empty - 1, alpha, beta);
+            board[row][col] = EMPTY_CELL;
+
+            if maximising {
+                best = best.max(score);
+                alpha = alpha.max(best);
+            } else {
+                best = best.min(score);
+                beta = beta.min(best);
+            }
+
+            // The other player already has a better option elsewhere,
+            // so nothing in the remaining replies can change the result.
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Game, GameState, PLAYER_O, PLAYER_X};
+
+    /// Plays every game the perfect computer can reach against any opponent
+    ///
+    /// On the computer's turn it makes its one chosen move; on the opponent's
+    /// turn every legal reply is tried. Returns how many finished games were seen.
+    fn explore(game: &Game, computer: &mut ComputerPlayer) -> usize {
+        match game.state() {
+            GameState::Won(winner) => {
+                assert_ne!(
+                    winner,
+                    opponent(computer.marker()),
+                    "lost: {:?}",
+                    game.moves()
+                );
+                return 1;
+            }
+            GameState::Draw => return 1,
+            GameState::InProgress => {}
+        }
+
+        if game.current_player() == computer.marker() {
+            let (row, col) = computer
+                .choose_move(game.board(), computer.marker())
+                .expect("a legal move");
+            let mut next = game.clone();
+            next.apply_move(row, col)
+                .expect("the computer chose a legal move");
+            explore(&next, computer)
+        } else {
+            game.legal_moves()
+                .into_iter()
+                .map(|(row, col)| {
+                    let mut next = game.clone();
+                    next.apply_move(row, col).unwrap();
+                    explore(&next, computer)
+                })
+                .sum()
+        }
+    }
+
+    #[test]
+    fn perfect_never_loses_on_3x3() {
+        // Fixed seeds, so a failing game tree can be replayed.
+        for seed in 0..3 {
+            for marker in [PLAYER_X, PLAYER_O] {
+                let mut computer = ComputerPlayer::with_seed(marker, Difficulty::Perfect, seed);
+                let games = explore(&Game::new(3, 3).unwrap(), &mut computer);
+                assert!(games > 0, "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn difficulty_names_round_trip() {
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Perfect] {
+            assert_eq!(
+                Difficulty::from_name(&difficulty.to_string()),
+                Some(difficulty)
+            );
+        }
+        assert_eq!(Difficulty::from_name("perfect"), None);
+    }
+}
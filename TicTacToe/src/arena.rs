@@ -0,0 +1,293 @@
+//! # Arena
+//!
+//! Plays many games between two `Player`s without any terminal output and
+//! collects statistics: how often each side wins, how often games are drawn
+//! and how long they last. The two players take turns starting, so neither
+//! gets the first-move advantage more often than the other.
+//!
+//! Nothing in the arena is random by itself; seed the players (see
+//! `player::bot_from_name`) and the same seeds always give the same results.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::player::Player;
+use crate::{Game, GameError, GameState, PLAYER_X};
+
+// ============================================================================
+// ERRORS
+// ============================================================================
+
+/// Why an arena run had to stop early
+#[derive(Debug)]
+pub enum ArenaError {
+    /// A player offered no move although the game was still going
+    NoMove { player: String, game: usize },
+    /// A player chose a move the rules do not allow
+    IllegalMove {
+        player: String,
+        game: usize,
+        error: GameError,
+    },
+}
+
+impl fmt::Display for ArenaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArenaError::NoMove { player, game } => {
+                write!(f, "{} had no move to play in game {}", player, game)
+            }
+            ArenaError::IllegalMove { player, game, error } => {
+                write!(f, "{} played an illegal move in game {}: {}", player, game, error)
+            }
+        }
+    }
+}
+
+impl Error for ArenaError {}
+
+// ============================================================================
+// STATISTICS
+// ============================================================================
+
+/// Results of an arena run
+///
+/// Index 0 always refers to the first player passed to `run`, index 1 to the
+/// second, whichever marker they had in a particular game.
+///
+/// # Fields
+/// * `names` - The players' names
+/// * `games` - Number of games played
+/// * `wins` - Games won by each player
+/// * `wins_as_x` - Of those, games won while playing X (moving first)
+/// * `draws` - Drawn games
+/// * `total_moves` - Moves played over all games, for the average length
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArenaStats {
+    names: [String; 2],
+    games: usize,
+    wins: [usize; 2],
+    wins_as_x: [usize; 2],
+    draws: usize,
+    total_moves: usize,
+}
+
+impl ArenaStats {
+    /// Returns the name of player 0 or 1
+    pub fn name(&self, player: usize) -> &str {
+        &self.names[player]
+    }
+
+    /// Returns the number of games played
+    pub fn games(&self) -> usize {
+        self.games
+    }
+
+    /// Returns the number of games won by player 0 or 1
+    pub fn wins(&self, player: usize) -> usize {
+        self.wins[player]
+    }
+
+    /// Returns the number of games player 0 or 1 won while moving first
+    pub fn wins_as_x(&self, player: usize) -> usize {
+        self.wins_as_x[player]
+    }
+
+    /// Returns the number of drawn games
+    pub fn draws(&self) -> usize {
+        self.draws
+    }
+
+    /// Returns the fraction of games (0.0 to 1.0) won by player 0 or 1
+    pub fn win_rate(&self, player: usize) -> f64 {
+        self.fraction(self.wins[player])
+    }
+
+    /// Returns the fraction of games (0.0 to 1.0) that were drawn
+    pub fn draw_rate(&self) -> f64 {
+        self.fraction(self.draws)
+    }
+
+    /// Returns the average number of moves per game
+    pub fn average_length(&self) -> f64 {
+        self.fraction(self.total_moves)
+    }
+
+    /// Divides `count` by the number of games, treating no games as zero
+    ///
+    /// # Rust Concepts
+    /// - `as f64` converts integers to floating point for division
+    fn fraction(&self, count: usize) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            count as f64 / self.games as f64
+        }
+    }
+}
+
+// ============================================================================
+// PLAYING GAMES
+// ============================================================================
+
+/// Plays one game to the end
+///
+/// # Arguments
+/// * `game` - The starting position, usually a new game
+/// * `player_x` - Whoever plays X
+/// * `player_o` - Whoever plays O
+/// * `number` - The game's number, only used in error messages
+///
+/// # Returns
+/// The finished game
+///
+/// # Errors
+/// `ArenaError` if a player offers no move or an illegal one
+///
+/// # Rust Concepts
+/// - `&mut dyn Player` borrows any player type through the trait
+/// - `&mut *player_x` reborrows, so the original reference stays usable
+pub fn play_game(
+    mut game: Game,
+    player_x: &mut dyn Player,
+    player_o: &mut dyn Player,
+    number: usize,
+) -> Result<Game, ArenaError> {
+    while game.state() == GameState::InProgress {
+        let marker = game.current_player();
+        let player: &mut dyn Player = if marker == PLAYER_X {
+            &mut *player_x
+        } else {
+            &mut *player_o
+        };
+
+        let (row, col) = player.choose_move(game.board(), marker).ok_or_else(|| ArenaError::NoMove {
+            player: player.name(),
+            game: number,
+        })?;
+        game.apply_move(row, col).map_err(|error| ArenaError::IllegalMove {
+            player: player.name(),
+            game: number,
+            error,
+        })?;
+    }
+    Ok(game)
+}
+
+/// Plays a series of games between two players and collects statistics
+///
+/// The first player plays X in odd-numbered games (1, 3, 5, ...) and the
+/// second player plays X in even-numbered ones.
+///
+/// # Arguments
+/// * `template` - The empty game every game starts from (board size and rule)
+/// * `games` - How many games to play
+/// * `first` - Player 0 in the returned statistics
+/// * `second` - Player 1 in the returned statistics
+///
+/// # Errors
+/// `ArenaError` if a player offers no move or an illegal one
+pub fn run(
+    template: &Game,
+    games: usize,
+    first: &mut dyn Player,
+    second: &mut dyn Player,
+) -> Result<ArenaStats, ArenaError> {
+    let mut stats = ArenaStats {
+        names: [first.name(), second.name()],
+        games: 0,
+        wins: [0, 0],
+        wins_as_x: [0, 0],
+        draws: 0,
+        total_moves: 0,
+    };
+
+    for number in 1..=games {
+        let first_plays_x = number % 2 == 1;
+        let finished = if first_plays_x {
+            play_game(template.clone(), &mut *first, &mut *second, number)?
+        } else {
+            play_game(template.clone(), &mut *second, &mut *first, number)?
+        };
+
+        stats.games += 1;
+        stats.total_moves += finished.moves().len();
+        match finished.state() {
+            GameState::Won(marker) => {
+                let x_won = marker == PLAYER_X;
+                // Player 0 won if it had the winning marker this game
+                let winner = if x_won == first_plays_x { 0 } else { 1 };
+                stats.wins[winner] += 1;
+                if x_won {
+                    stats.wins_as_x[winner] += 1;
+                }
+            }
+            GameState::Draw => stats.draws += 1,
+            GameState::InProgress => unreachable!("play_game only returns finished games"),
+        }
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::bot_from_name;
+    use crate::Board;
+
+    /// Plays `games` games between two named bots on an N x N board, 3 in a row
+    fn run_bots(names: (&str, &str), seeds: (u64, u64), size: usize, games: usize) -> ArenaStats {
+        let template = Game::new(size, 3).unwrap();
+        let mut first = bot_from_name(names.0, seeds.0).unwrap();
+        let mut second = bot_from_name(names.1, seeds.1).unwrap();
+        run(&template, games, first.as_mut(), second.as_mut()).unwrap()
+    }
+
+    #[test]
+    fn the_same_seeds_give_the_same_statistics() {
+        let once = run_bots(("greedy", "random"), (7, 8), 4, 30);
+        assert_eq!(run_bots(("greedy", "random"), (7, 8), 4, 30), once);
+        assert_eq!(once.games(), 30);
+        assert_eq!(once.wins(0) + once.wins(1) + once.draws(), 30);
+        assert!(once.wins_as_x(0) <= once.wins(0));
+        assert_eq!((once.name(0), once.name(1)), ("greedy", "random"));
+    }
+
+    #[test]
+    fn perfect_players_always_draw() {
+        let stats = run_bots(("minimax", "minimax"), (1, 2), 3, 4);
+        assert_eq!(stats.draws(), 4);
+        assert_eq!(stats.draw_rate(), 1.0);
+        assert_eq!(stats.average_length(), 9.0);
+    }
+
+    /// A player that always offers the same cell, or nothing at all
+    struct Stubborn(Option<(usize, usize)>);
+
+    impl Player for Stubborn {
+        fn name(&self) -> String {
+            String::from("stubborn")
+        }
+
+        fn choose_move(&mut self, _board: &Board, _marker: char) -> Option<(usize, usize)> {
+            self.0
+        }
+    }
+
+    #[test]
+    fn bad_players_stop_the_run() {
+        let template = Game::new(3, 3).unwrap();
+        let mut corner = Stubborn(Some((0, 0)));
+        let mut random = bot_from_name("random", 1).unwrap();
+        let result = run(&template, 5, &mut corner, random.as_mut());
+        assert!(matches!(
+            result,
+            Err(ArenaError::IllegalMove { game: 1, error: GameError::CellOccupied { .. }, .. })
+        ));
+
+        let mut silent = Stubborn(None);
+        let result = run(&template, 5, random.as_mut(), &mut silent);
+        assert!(matches!(result, Err(ArenaError::NoMove { game: 1, .. })));
+    }
+}
@@ -0,0 +1,863 @@
+//! # Tic Tac Toe Engine
+//!
+//! The game rules for Tic Tac Toe and its larger "K in a row" variants, with no
+//! terminal input or output. Everything a front end needs lives here:
+//!
+//! - `Board`: an N x N grid with a configurable winning line length
+//! - `Game`: the board plus whose turn it is, the move list and the result;
+//!   illegal moves are reported as `GameError` values instead of being printed
+//! - `ai`: a computer opponent built on minimax search
+//! - `player`: the `Player` trait, with human, random, greedy and minimax players
+//! - `arena`: headless bot-vs-bot series with win, draw and game-length statistics
+//! - `ultimate`: Ultimate Tic Tac Toe, nine boards nested in a 3x3 meta-board
+//! - `record`: game records with undo/redo and a plain-text save format
+//! - `net`: two-player games over TCP with move validation on both ends
+//! - `tournament`: best-of-N matches and a leaderboard saved between runs
+//!
+//! The terminal game in `main.rs` is a thin client of this library.
+//!
+//! ## Example
+//! ```
+//! use tictactoe::{Game, GameState, PLAYER_X};
+//!
+//! let mut game = Game::new(3, 3).unwrap();
+//! game.apply_move(1, 1).unwrap();
+//! assert_eq!(game.state(), GameState::InProgress);
+//! assert_eq!(game.board()[1][1], PLAYER_X);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+pub mod ai;
+pub mod arena;
+pub mod net;
+pub mod player;
+pub mod record;
+pub mod tournament;
+pub mod ultimate;
+
+// ============================================================================
+// CONSTANTS
+// ============================================================================
+// In Rust, constants are immutable values that are inlined at compile time.
+// They must have explicit types and can only be set to constant expressions.
+// Convention: Use SCREAMING_SNAKE_CASE for constant names.
+
+/// Represents Player X's marker on the board
+pub const PLAYER_X: char = 'X';
+
+/// Represents Player O's marker on the board
+pub const PLAYER_O: char = 'O';
+
+/// Default dimensions of the game board (3x3)
+pub const DEFAULT_BOARD_SIZE: usize = 3;
+
+/// Smallest supported board size
+pub const MIN_BOARD_SIZE: usize = 3;
+
+/// Largest supported board size (15x15 is the classic Gomoku board)
+pub const MAX_BOARD_SIZE: usize = 15;
+
+/// Shortest supported winning line
+pub const MIN_WIN_LENGTH: usize = 3;
+
+/// Longest winning line offered by default (five in a row, as in Gomoku)
+pub const DEFAULT_MAX_WIN_LENGTH: usize = 5;
+
+/// Directions a winning line can run in: right, down, down-right and down-left
+///
+/// Every line on the board runs in one of these four directions (or their
+/// exact opposites), so checking them from each cell finds every possible line.
+const LINE_DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+/// Represents an empty cell on the board
+pub const EMPTY_CELL: char = ' ';
+
+// ============================================================================
+// STRUCTS
+// ============================================================================
+// Structs group related data into a single named type.
+// Unlike arrays, their contents can be sized at runtime when they hold a `Vec`.
+
+/// The game board structure
+/// 
+/// Represents a square grid whose size is chosen when the game starts,
+/// together with the number of marks in a row needed to win.
+/// 
+/// # Fields
+/// * `size` - Number of rows (and columns) on the board
+/// * `win_length` - How many marks in a row win the game ("K in a row")
+/// * `cells` - The grid as a vector of rows, each a vector of cells
+/// 
+/// Vectors are heap-allocated, so unlike arrays their length can be decided
+/// at runtime.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Board {
+    size: usize,
+    win_length: usize,
+    cells: Vec<Vec<char>>,
+}
+
+impl Board {
+    /// Returns the number of rows (and columns) on the board
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns how many marks in a row are needed to win
+    pub fn win_length(&self) -> usize {
+        self.win_length
+    }
+
+    /// Returns an iterator over the rows of the board
+    pub fn iter(&self) -> std::slice::Iter<'_, Vec<char>> {
+        self.cells.iter()
+    }
+}
+
+/// Draws the board as a grid with row and column numbers
+/// 
+/// Front ends decide where the text goes: `print!("{}", board)` writes it to
+/// the terminal, while `board.to_string()` keeps it as a `String`.
+/// 
+/// # Rust Concepts
+/// - `write!`/`writeln!` format into any `fmt::Write` target, not just stdout
+/// - The `?` operator stops at the first failed write and returns its error
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Every column is as wide as its largest index plus a separator, so
+        // two-digit indices on large boards stay lined up with their cells
+        let width = (self.size - 1).to_string().len();
+
+        // Column headers; `{:>width$}` right-aligns each index in `width` characters
+        write!(f, "   ")?;
+        for col in 0..self.size {
+            write!(f, "{:>width$} ", col, width = width)?;
+        }
+        writeln!(f)?;
+
+        for (row_idx, row) in self.cells.iter().enumerate() {
+            // `enumerate()` is an iterator adapter that yields (index, value) pairs
+            write!(f, "{:>2} ", row_idx)?;
+            for cell in row {
+                write!(f, "{:>width$}│", cell, width = width)?;
+            }
+            writeln!(f)?;
+            if row_idx < self.size - 1 {
+                // `repeat()` builds a separator as wide as the row
+                writeln!(f, "   {}", "─".repeat(self.size * (width + 1)))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Implements indexing so `board[row]` yields a row and `board[row][col]` a cell
+/// 
+/// # Rust Concepts
+/// - Operator overloading: the `Index` trait powers the `[]` operator
+/// - Associated types: `type Output` declares what indexing returns
+/// - Slices: returning `[char]` hides that rows are stored as `Vec<char>`
+impl Index<usize> for Board {
+    type Output = [char];
+
+    fn index(&self, row: usize) -> &[char] {
+        &self.cells[row]
+    }
+}
+
+/// Implements mutable indexing so `board[row][col] = player` places a marker
+impl IndexMut<usize> for Board {
+    fn index_mut(&mut self, row: usize) -> &mut [char] {
+        &mut self.cells[row]
+    }
+}
+
+// ============================================================================
+// ENUMS
+// ============================================================================
+// Enums in Rust are algebraic data types that can hold different variants.
+// They are memory-efficient and enable exhaustive pattern matching.
+
+/// Represents the possible outcomes of the game
+/// 
+/// Rust enums can carry data in their variants, making them more powerful
+/// than C-style enums. This is known as an "algebraic data type".
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GameState {
+    /// The game is still in progress
+    InProgress,
+    /// A player has won (stores the winning player's character)
+    Won(char),
+    /// The game ended in a draw
+    Draw,
+}
+
+// ============================================================================
+// IMPLEMENTATIONS
+// ============================================================================
+
+/// Implements the Display trait for GameState
+/// 
+/// The Display trait is part of Rust's formatting infrastructure.
+/// Implementing it allows custom types to be formatted with `{}` in print macros.
+/// This is similar to implementing toString() in other languages.
+impl fmt::Display for GameState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Pattern matching with match expressions is exhaustive in Rust,
+        // meaning all possible variants must be handled.
+        match self {
+            GameState::InProgress => write!(f, "Game in progress"),
+            GameState::Won(player) => write!(f, "Player {} wins!", player),
+            GameState::Draw => write!(f, "It's a draw!"),
+        }
+    }
+}
+
+// ============================================================================
+// BOARD MANAGEMENT FUNCTIONS
+// ============================================================================
+
+/// Initializes and returns a new empty game board
+/// 
+/// # Arguments
+/// * `size` - Number of rows and columns (`MIN_BOARD_SIZE..=MAX_BOARD_SIZE`)
+/// * `win_length` - Marks in a row needed to win (`MIN_WIN_LENGTH..=size`)
+/// 
+/// # Returns
+/// A `size` x `size` board filled with empty cells
+/// 
+/// # Panics
+/// Panics if `size` or `win_length` is outside the supported range
+/// 
+/// # Rust Concepts
+/// - Vector initialization: `vec![value; len]` creates a vector with repeated values
+/// - Nested `vec!` clones the inner row once per outer element
+/// - `assert!` documents and enforces preconditions at runtime
+pub fn initialize_board(size: usize, win_length: usize) -> Board {
+    assert!(
+        (MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&size),
+        "board size must be between {} and {}",
+        MIN_BOARD_SIZE,
+        MAX_BOARD_SIZE
+    );
+    assert!(
+        (MIN_WIN_LENGTH..=size).contains(&win_length),
+        "win length must be between {} and the board size",
+        MIN_WIN_LENGTH
+    );
+
+    Board {
+        size,
+        win_length,
+        cells: vec![vec![EMPTY_CELL; size]; size],
+    }
+}
+
+/// Checks if a specific cell on the board is empty
+/// 
+/// # Arguments
+/// * `board` - Reference to the game board
+/// * `row` - Row index
+/// * `col` - Column index
+/// 
+/// # Returns
+/// `true` if the cell is empty, `false` otherwise
+/// 
+/// # Rust Concepts
+/// - Boolean return type with explicit true/false values
+/// - Direct array indexing with bounds checking at runtime
+pub fn is_cell_empty(board: &Board, row: usize, col: usize) -> bool {
+    board[row][col] == EMPTY_CELL
+}
+
+/// Checks if all cells on the board are filled
+/// 
+/// # Arguments
+/// * `board` - Reference to the game board
+/// 
+/// # Returns
+/// `true` if the board is full, `false` otherwise
+/// 
+/// # Rust Concepts
+/// - Iterator methods: `iter()` creates an iterator over the board
+/// - `flatten()` converts nested iterators into a single iterator
+/// - `all()` is a higher-order function that checks if all elements satisfy a predicate
+/// - Closures: `|&cell|` is an anonymous function capturing the cell reference
+pub fn is_board_full(board: &Board) -> bool {
+    board.iter().flatten().all(|&cell| cell != EMPTY_CELL)
+}
+
+// ============================================================================
+// GAME LOGIC FUNCTIONS
+// ============================================================================
+
+/// Returns the opponent of the given player
+/// 
+/// # Rust Concepts
+/// - `if` is an expression, so both branches produce the returned value
+pub fn opponent(player: char) -> char {
+    if player == PLAYER_X {
+        PLAYER_O
+    } else {
+        PLAYER_X
+    }
+}
+
+/// Counts how many of `player`'s markers continue a line from a cell
+/// 
+/// Starting next to `(row, col)` and stepping by `(d_row, d_col)`, counts
+/// consecutive cells holding `player` until a different cell or the edge
+/// of the board is reached. The starting cell itself is not counted.
+/// 
+/// # Rust Concepts
+/// - Signed arithmetic: `isize` lets the walk step backwards and off the board
+/// - `try_from` converts back to `usize`, failing for negative positions
+fn count_in_direction(
+    board: &Board,
+    player: char,
+    (row, col): (usize, usize),
+    (d_row, d_col): (isize, isize),
+) -> usize {
+    let mut count = 0;
+    let mut r = row as isize + d_row;
+    let mut c = col as isize + d_col;
+
+    while let (Ok(ru), Ok(cu)) = (usize::try_from(r), usize::try_from(c)) {
+        if ru >= board.size() || cu >= board.size() || board[ru][cu] != player {
+            break;
+        }
+        count += 1;
+        r += d_row;
+        c += d_col;
+    }
+
+    count
+}
+
+/// Checks if the marker at a cell is part of a winning line
+/// 
+/// Only lines passing through `(row, col)` are examined, which makes this much
+/// cheaper than scanning the whole board right after a move has been played.
+/// 
+/// # Arguments
+/// * `board` - Reference to the game board
+/// * `row` - Row index of the cell to check
+/// * `col` - Column index of the cell to check
+/// 
+/// # Returns
+/// `true` if the cell is occupied and belongs to `win_length` or more in a row
+/// 
+/// # Rust Concepts
+/// - Tuple destructuring in closure parameters: `|&(d_row, d_col)|`
+/// - Negating a direction walks the same line the other way
+pub fn is_winning_move(board: &Board, row: usize, col: usize) -> bool {
+    let player = board[row][col];
+    if player == EMPTY_CELL {
+        return false;
+    }
+
+    LINE_DIRECTIONS.iter().any(|&(d_row, d_col)| {
+        let forwards = count_in_direction(board, player, (row, col), (d_row, d_col));
+        let backwards = count_in_direction(board, player, (row, col), (-d_row, -d_col));
+        1 + forwards + backwards >= board.win_length()
+    })
+}
+
+/// Lists the cells of the winning line through a cell, if there is one
+/// 
+/// Front ends use this to highlight how a game was won. When the marker at
+/// `(row, col)` is part of several winning lines, the first one found (in the
+/// order right, down, down-right, down-left) is returned.
+/// 
+/// # Returns
+/// `Some(cells)` with every cell of the run in order along the line (which
+/// may be longer than `win_length`), or `None` if the cell is not part of one
+/// 
+/// # Rust Concepts
+/// - `find_map` stops at the first direction that produces a line
+/// - Offsets are computed in `isize` and converted back once known to be valid
+pub fn winning_line_at(board: &Board, row: usize, col: usize) -> Option<Vec<(usize, usize)>> {
+    let player = board[row][col];
+    if player == EMPTY_CELL {
+        return None;
+    }
+
+    LINE_DIRECTIONS.iter().find_map(|&(d_row, d_col)| {
+        let forwards = count_in_direction(board, player, (row, col), (d_row, d_col));
+        let backwards = count_in_direction(board, player, (row, col), (-d_row, -d_col));
+        if 1 + forwards + backwards < board.win_length() {
+            return None;
+        }
+
+        let line = (-(backwards as isize)..=forwards as isize)
+            .map(|step| {
+                let r = row as isize + step * d_row;
+                let c = col as isize + step * d_col;
+                (r as usize, c as usize)
+            })
+            .collect();
+        Some(line)
+    })
+}
+
+/// Checks if the specified player has won the game
+/// 
+/// A player wins with `win_length` markers in a row anywhere on the board:
+/// horizontally, vertically, or along any diagonal (not only the two main ones).
+/// 
+/// # Arguments
+/// * `board` - Reference to the game board
+/// * `player` - The player character to check for ('X' or 'O')
+/// 
+/// # Returns
+/// `true` if the player has `win_length` in a row, `false` otherwise
+/// 
+/// # Rust Concepts
+/// - Range syntax: `0..board.size()` creates a range from 0 to size-1
+/// - Iterator methods: `any()` returns true if any element satisfies the condition
+/// - Short-circuit evaluation: `any()` stops at the first winning cell
+pub fn check_winner(board: &Board, player: char) -> bool {
+    (0..board.size()).any(|row| {
+        (0..board.size()).any(|col| board[row][col] == player && is_winning_move(board, row, col))
+    })
+}
+
+/// Determines the current state of the game
+/// 
+/// # Arguments
+/// * `board` - Reference to the game board
+/// * `last_player` - The player who made the most recent move
+/// 
+/// # Returns
+/// `GameState` enum representing the current game status
+/// 
+/// # Rust Concepts
+/// - Early returns: Using `return` to exit the function immediately
+/// - Enum variants: Constructing GameState variants with associated data
+pub fn evaluate_game_state(board: &Board, last_player: char) -> GameState {
+    if check_winner(board, last_player) {
+        return GameState::Won(last_player);
+    }
+    
+    if is_board_full(board) {
+        return GameState::Draw;
+    }
+    
+    GameState::InProgress
+}
+
+// ============================================================================
+// ERRORS
+// ============================================================================
+
+/// Reasons a game could not be created or a move could not be played
+/// 
+/// # Rust Concepts
+/// - Returning `Result<T, GameError>` lets callers decide how to report problems,
+///   instead of the library printing messages itself
+/// - Implementing `std::error::Error` lets the type work with `?` and `Box<dyn Error>`
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GameError {
+    /// The requested board size is outside `MIN_BOARD_SIZE..=MAX_BOARD_SIZE`
+    InvalidBoardSize(usize),
+    /// The requested win length is outside `MIN_WIN_LENGTH..=size`
+    InvalidWinLength(usize),
+    /// The move is not on the board
+    OutOfBounds { row: usize, col: usize, size: usize },
+    /// The target cell already holds a marker
+    CellOccupied { row: usize, col: usize },
+    /// The game has already been won or drawn
+    GameOver,
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::InvalidBoardSize(size) => write!(
+                f,
+                "board size {} is not supported (use {} to {})",
+                size, MIN_BOARD_SIZE, MAX_BOARD_SIZE
+            ),
+            GameError::InvalidWinLength(length) => write!(
+                f,
+                "{} in a row is not supported (use {} up to the board size)",
+                length, MIN_WIN_LENGTH
+            ),
+            GameError::OutOfBounds { row, col, size } => write!(
+                f,
+                "({} {}) is off the board (rows and columns go from 0 to {})",
+                row,
+                col,
+                size - 1
+            ),
+            GameError::CellOccupied { row, col } => write!(f, "({} {}) is already occupied", row, col),
+            GameError::GameOver => write!(f, "the game is already over"),
+        }
+    }
+}
+
+impl Error for GameError {}
+
+// ============================================================================
+// GAME
+// ============================================================================
+
+/// A game in progress: the board, the moves that led to it and the result
+/// 
+/// `Game` enforces the rules. Moves are only accepted for the player whose
+/// turn it is, on empty cells, while the game is still undecided.
+/// 
+/// # Fields
+/// * `board` - The current position
+/// * `moves` - Every move played so far, in order (X moves first)
+/// * `state` - Whether the game is still going, won or drawn
+#[derive(Debug, Clone, PartialEq)]
+pub struct Game {
+    board: Board,
+    moves: Vec<(usize, usize)>,
+    state: GameState,
+}
+
+impl Game {
+    /// Starts a new game on an empty board
+    /// 
+    /// # Arguments
+    /// * `size` - Number of rows and columns (`MIN_BOARD_SIZE..=MAX_BOARD_SIZE`)
+    /// * `win_length` - Marks in a row needed to win (`MIN_WIN_LENGTH..=size`)
+    /// 
+    /// # Errors
+    /// `GameError::InvalidBoardSize` or `GameError::InvalidWinLength` if the
+    /// dimensions are out of range
+    pub fn new(size: usize, win_length: usize) -> Result<Game, GameError> {
+        if !(MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&size) {
+            return Err(GameError::InvalidBoardSize(size));
+        }
+        if !(MIN_WIN_LENGTH..=size).contains(&win_length) {
+            return Err(GameError::InvalidWinLength(win_length));
+        }
+
+        Ok(Game {
+            board: initialize_board(size, win_length),
+            moves: Vec::new(),
+            state: GameState::InProgress,
+        })
+    }
+
+    /// Returns the current board
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Returns the moves played so far, in order
+    pub fn moves(&self) -> &[(usize, usize)] {
+        &self.moves
+    }
+
+    /// Returns whether the game is in progress, won or drawn
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    /// Returns the player whose turn it is
+    /// 
+    /// # Rust Concepts
+    /// - `is_multiple_of` checks divisibility without a manual `%` comparison
+    pub fn current_player(&self) -> char {
+        if self.moves.len().is_multiple_of(2) {
+            PLAYER_X
+        } else {
+            PLAYER_O
+        }
+    }
+
+    /// Lists every move the current player may make, in row-major order
+    /// 
+    /// # Returns
+    /// All empty cells, or nothing once the game has been decided
+    /// 
+    /// # Rust Concepts
+    /// - `flat_map` turns each row index into an iterator of (row, col) pairs
+    /// - `move` closures copy `row` into the inner iterator so it outlives the loop
+    pub fn legal_moves(&self) -> Vec<(usize, usize)> {
+        if self.state != GameState::InProgress {
+            return Vec::new();
+        }
+
+        let size = self.board.size();
+        (0..size)
+            .flat_map(|row| (0..size).map(move |col| (row, col)))
+            .filter(|&(row, col)| is_cell_empty(&self.board, row, col))
+            .collect()
+    }
+
+    /// Plays a move for the current player
+    /// 
+    /// # Arguments
+    /// * `row` - Row index of the target cell
+    /// * `col` - Column index of the target cell
+    /// 
+    /// # Returns
+    /// The game state after the move
+    /// 
+    /// # Errors
+    /// `GameError::GameOver`, `GameError::OutOfBounds` or
+    /// `GameError::CellOccupied`; the game is left unchanged
+    pub fn apply_move(&mut self, row: usize, col: usize) -> Result<GameState, GameError> {
+        if self.state != GameState::InProgress {
+            return Err(GameError::GameOver);
+        }
+        let size = self.board.size();
+        if row >= size || col >= size {
+            return Err(GameError::OutOfBounds { row, col, size });
+        }
+        if !is_cell_empty(&self.board, row, col) {
+            return Err(GameError::CellOccupied { row, col });
+        }
+
+        let player = self.current_player();
+        self.board[row][col] = player;
+        self.moves.push((row, col));
+        // Only lines through the new marker can have been completed
+        self.state = if is_winning_move(&self.board, row, col) {
+            GameState::Won(player)
+        } else if is_board_full(&self.board) {
+            GameState::Draw
+        } else {
+            GameState::InProgress
+        };
+        Ok(self.state)
+    }
+
+    /// Returns the cells of the line that won the game
+    /// 
+    /// # Returns
+    /// `Some(cells)` once the game has been won, `None` otherwise
+    /// 
+    /// # Rust Concepts
+    /// - `last()` returns an `Option<&T>`, and `?` exits early on `None`
+    pub fn winning_line(&self) -> Option<Vec<(usize, usize)>> {
+        if !matches!(self.state, GameState::Won(_)) {
+            return None;
+        }
+        // Only the final move can have completed the winning line
+        let &(row, col) = self.moves.last()?;
+        winning_line_at(&self.board, row, col)
+    }
+
+    /// Takes back the most recent move
+    /// 
+    /// # Returns
+    /// The move that was taken back, or `None` if no moves have been played
+    pub fn undo_move(&mut self) -> Option<(usize, usize)> {
+        let (row, col) = self.moves.pop()?;
+        self.board[row][col] = EMPTY_CELL;
+        // Before the removed move the game cannot have been decided,
+        // otherwise that move would have been rejected
+        self.state = GameState::InProgress;
+        Some((row, col))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a board from rows of text, `.` standing for an empty cell
+    fn board_from(win_length: usize, rows: &[&str]) -> Board {
+        let mut board = initialize_board(rows.len(), win_length);
+        for (row, text) in rows.iter().enumerate() {
+            for (col, cell) in text.chars().enumerate() {
+                if cell != '.' {
+                    board[row][col] = cell;
+                }
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn win_length_can_be_shorter_than_the_board() {
+        let board = board_from(3, &[
+            ".....",
+            ".XXX.",
+            ".....",
+            ".....",
+            ".....",
+        ]);
+        assert!(check_winner(&board, PLAYER_X));
+        assert!(!check_winner(&board, PLAYER_O));
+        assert!(is_winning_move(&board, 1, 2));
+        assert!(!is_winning_move(&board, 0, 0));
+    }
+
+    #[test]
+    fn win_length_equal_to_the_board_needs_a_full_line() {
+        let three = board_from(4, &[
+            "XXX.",
+            "....",
+            "....",
+            "....",
+        ]);
+        assert!(!check_winner(&three, PLAYER_X));
+
+        let mut four = board_from(4, &[
+            "....",
+            "...O",
+            "...O",
+            "...O",
+        ]);
+        assert!(!check_winner(&four, PLAYER_O));
+        four[0][3] = PLAYER_O;
+        assert!(check_winner(&four, PLAYER_O));
+    }
+
+    #[test]
+    fn win_length_longer_than_the_board_is_rejected() {
+        assert_eq!(Game::new(3, 4), Err(GameError::InvalidWinLength(4)));
+        assert_eq!(Game::new(5, 2), Err(GameError::InvalidWinLength(2)));
+        assert_eq!(Game::new(2, 2), Err(GameError::InvalidBoardSize(2)));
+        assert_eq!(Game::new(16, 5), Err(GameError::InvalidBoardSize(16)));
+        let game = Game::new(MAX_BOARD_SIZE, MAX_BOARD_SIZE).unwrap();
+        assert_eq!(game.board().win_length(), MAX_BOARD_SIZE);
+    }
+
+    #[test]
+    #[should_panic(expected = "win length")]
+    fn initialize_board_panics_on_a_long_win_length() {
+        initialize_board(3, 4);
+    }
+
+    #[test]
+    fn wins_along_the_edges_and_corners() {
+        let board = board_from(4, &[
+            "X......",
+            "X......",
+            "X......",
+            "X.....O",
+            "......O",
+            "......O",
+            "OOOO..O",
+        ]);
+        assert!(is_winning_move(&board, 0, 0));
+        assert!(is_winning_move(&board, 6, 6));
+        assert!(is_winning_move(&board, 6, 0));
+        assert_eq!(winning_line_at(&board, 3, 0), Some(vec![(0, 0), (1, 0), (2, 0), (3, 0)]));
+
+        // A run of three that stops at the edge does not win
+        let board = board_from(4, &[
+            "..XXX",
+            ".....",
+            ".....",
+            ".....",
+            ".....",
+        ]);
+        assert!(!check_winner(&board, PLAYER_X));
+    }
+
+    /// Plays `moves` from an empty board, alternating X and O
+    fn game_with(size: usize, win_length: usize, moves: &[(usize, usize)]) -> Game {
+        let mut game = Game::new(size, win_length).unwrap();
+        for &(row, col) in moves {
+            game.apply_move(row, col).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn apply_move_alternates_players() {
+        let mut game = Game::new(3, 3).unwrap();
+        assert_eq!(game.current_player(), PLAYER_X);
+        assert_eq!(game.apply_move(1, 1), Ok(GameState::InProgress));
+        assert_eq!(game.current_player(), PLAYER_O);
+        assert_eq!(game.apply_move(0, 0), Ok(GameState::InProgress));
+        assert_eq!(game.board()[1][1], PLAYER_X);
+        assert_eq!(game.board()[0][0], PLAYER_O);
+        assert_eq!(game.moves(), &[(1, 1), (0, 0)]);
+        assert_eq!(game.legal_moves().len(), 7);
+    }
+
+    #[test]
+    fn illegal_moves_leave_the_game_unchanged() {
+        let mut game = game_with(3, 3, &[(1, 1)]);
+        let before = game.clone();
+        assert_eq!(game.apply_move(1, 1), Err(GameError::CellOccupied { row: 1, col: 1 }));
+        assert_eq!(game.apply_move(3, 0), Err(GameError::OutOfBounds { row: 3, col: 0, size: 3 }));
+        assert_eq!(game.apply_move(0, 7), Err(GameError::OutOfBounds { row: 0, col: 7, size: 3 }));
+        assert_eq!(game, before);
+
+        let mut won = game_with(3, 3, &[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)]);
+        let before = won.clone();
+        assert_eq!(won.apply_move(2, 2), Err(GameError::GameOver));
+        assert_eq!(won, before);
+    }
+
+    #[test]
+    fn wins_on_rows_columns_and_diagonals() {
+        let row = game_with(3, 3, &[(2, 0), (0, 0), (2, 1), (0, 1), (2, 2)]);
+        assert_eq!(row.state(), GameState::Won(PLAYER_X));
+        assert_eq!(row.winning_line(), Some(vec![(2, 0), (2, 1), (2, 2)]));
+
+        let column = game_with(3, 3, &[(0, 0), (0, 1), (1, 0), (1, 1), (2, 2), (2, 1)]);
+        assert_eq!(column.state(), GameState::Won(PLAYER_O));
+        assert_eq!(column.winning_line(), Some(vec![(0, 1), (1, 1), (2, 1)]));
+
+        let diagonal = game_with(3, 3, &[(0, 0), (0, 1), (1, 1), (0, 2), (2, 2)]);
+        assert_eq!(diagonal.state(), GameState::Won(PLAYER_X));
+        assert_eq!(diagonal.winning_line(), Some(vec![(0, 0), (1, 1), (2, 2)]));
+
+        let anti_diagonal = game_with(3, 3, &[(0, 2), (0, 0), (1, 1), (0, 1), (2, 0)]);
+        assert_eq!(anti_diagonal.state(), GameState::Won(PLAYER_X));
+        assert_eq!(anti_diagonal.winning_line(), Some(vec![(0, 2), (1, 1), (2, 0)]));
+    }
+
+    #[test]
+    fn wins_on_off_centre_diagonals() {
+        // Three in a row on a 5x5 board, away from both main diagonals
+        let game = game_with(5, 3, &[(2, 3), (0, 0), (3, 2), (0, 1), (1, 4)]);
+        assert_eq!(game.state(), GameState::Won(PLAYER_X));
+        assert_eq!(game.winning_line(), Some(vec![(1, 4), (2, 3), (3, 2)]));
+
+        let moves = [(0, 0), (2, 1), (0, 5), (3, 2), (5, 5), (4, 3), (5, 0), (5, 4)];
+        let game = game_with(6, 4, &moves);
+        assert_eq!(game.state(), GameState::Won(PLAYER_O));
+        assert_eq!(game.winning_line(), Some(vec![(2, 1), (3, 2), (4, 3), (5, 4)]));
+        assert_eq!(winning_line_at(game.board(), 0, 0), None);
+        assert_eq!(winning_line_at(game.board(), 1, 1), None);
+    }
+
+    #[test]
+    fn a_full_board_without_a_line_is_a_draw() {
+        // X O X
+        // X O O
+        // O X X
+        let moves = [(0, 0), (0, 1), (0, 2), (1, 1), (1, 0), (1, 2), (2, 1), (2, 0), (2, 2)];
+        let mut game = game_with(3, 3, &moves[..8]);
+        assert_eq!(game.state(), GameState::InProgress);
+        assert_eq!(game.apply_move(2, 2), Ok(GameState::Draw));
+        assert_eq!(evaluate_game_state(game.board(), PLAYER_X), GameState::Draw);
+        assert!(game.legal_moves().is_empty());
+        assert_eq!(game.winning_line(), None);
+    }
+
+    #[test]
+    fn undo_move_restores_the_turn_and_state() {
+        let mut game = game_with(3, 3, &[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)]);
+        assert_eq!(game.state(), GameState::Won(PLAYER_X));
+        assert!(game.legal_moves().is_empty());
+
+        assert_eq!(game.undo_move(), Some((0, 2)));
+        assert_eq!(game.state(), GameState::InProgress);
+        assert_eq!(game.current_player(), PLAYER_X);
+        assert!(is_cell_empty(game.board(), 0, 2));
+        assert_eq!(game.legal_moves().len(), 5);
+        assert_eq!(game, game_with(3, 3, &[(0, 0), (1, 0), (0, 1), (1, 1)]));
+
+        assert_eq!(game.undo_move(), Some((1, 1)));
+        assert_eq!(game.current_player(), PLAYER_O);
+        while game.undo_move().is_some() {}
+        assert_eq!(game, Game::new(3, 3).unwrap());
+    }
+}
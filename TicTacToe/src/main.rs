@@ -1,11 +1,29 @@
 //! # Tic Tac Toe Game
 //!
-//! A professional implementation of the classic Tic Tac Toe game for two players.
+//! A professional implementation of the classic Tic Tac Toe game for two players,
+//! or for one player against the computer.
 //! This game demonstrates fundamental Rust concepts including ownership, borrowing,
 //! pattern matching, and error handling.
 //!
+//! This file is the terminal front end only: it reads input and prints output,
+//! while the rules, the computer opponent and game records live in the
+//! `tictactoe` library (`lib.rs`).
+//!
 //! ## Features
 //! - Two-player gameplay (X and O)
+//! - Computer opponent with easy, medium and perfect (minimax) difficulty
+//! - Any board size from 3x3 to 15x15 with a configurable "K in a row" rule
+//!   (for example 4x4 boards or 15x15 Gomoku with five in a row)
+//! - Undo/redo, and saving, resuming or replaying games in a plain-text notation
+//! - Network play between two terminals over TCP
+//! - Best-of-N matches between named players, with a leaderboard that is kept
+//!   between runs (overall standings and head-to-head records)
+//! - A headless arena that pits two strategies (human, random, greedy or
+//!   minimax) against each other over many reproducible, seeded games
+//! - Ultimate Tic Tac Toe: nine boards inside one, where each move picks the
+//!   board the opponent plays in next
+//! - Full-screen mode with arrow-key cursor navigation when run in a terminal
+//!   (falls back to typed coordinates when input is piped in)
 //! - Input validation and error handling
 //! - Win condition detection
 //! - Draw detection
@@ -13,222 +31,199 @@
 //!
 //! ## Usage
 //! ```bash
-//! cargo run
+//! cargo run                          # play on this terminal
+//! cargo run -- --host 7878           # host a network game on port 7878 (you play X)
+//! cargo run -- --host 0.0.0.0:7878   # ...that players on other machines can join
+//! cargo run -- --join 127.0.0.1:7878 # join a network game (you play O)
+//! cargo run -- --standings           # print the leaderboard
+//! cargo run -- --head-to-head Alice  # Alice's record against each opponent
+//! cargo run -- --head-to-head Alice Bob
+//! cargo run -- --arena greedy minimax --games 1000 --seed 42
+//! cargo run -- --arena minimax:2 random --size 5 --win 4
 //! ```
 
-use std::io::{self, Write};
-use std::fmt;
-
-// ============================================================================
-// CONSTANTS
-// ============================================================================
-// In Rust, constants are immutable values that are inlined at compile time.
-// They must have explicit types and can only be set to constant expressions.
-// Convention: Use SCREAMING_SNAKE_CASE for constant names.
-
-/// Represents Player X's marker on the board
-const PLAYER_X: char = 'X';
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::net::SocketAddr;
+use std::process;
 
-/// Represents Player O's marker on the board
-const PLAYER_O: char = 'O';
+use tictactoe::ai::{self, ComputerPlayer, Difficulty};
+use tictactoe::arena;
+use tictactoe::net::{self, NetError, NetworkGame};
+use tictactoe::player::{self, HumanPlayer, Player};
+use tictactoe::record::{GameRecord, RecordError};
+use tictactoe::tournament::{self, Leaderboard, Match, Tally};
+use tictactoe::ultimate::UltimateGame;
 
-/// Defines the dimensions of the game board (3x3)
-const BOARD_SIZE: usize = 3;
+mod tui;
 
-/// Represents an empty cell on the board
-const EMPTY_CELL: char = ' ';
+use tui::Tui;
+use tictactoe::{
+    opponent, Board, Game, GameState, DEFAULT_BOARD_SIZE, DEFAULT_MAX_WIN_LENGTH, MAX_BOARD_SIZE,
+    MIN_BOARD_SIZE, MIN_WIN_LENGTH, PLAYER_O, PLAYER_X,
+};
 
 // ============================================================================
-// TYPE ALIASES
+// CONSTANTS
 // ============================================================================
-// Type aliases create a new name for an existing type, improving code readability.
-// They don't create a new type, just an alternative name.
 
-/// Type alias for the game board structure
-/// 
-/// Represents a 3x3 grid as a 2D array: `[[char; 3]; 3]`
-/// - Outer array: rows
-/// - Inner array: columns
-/// Arrays in Rust are stack-allocated with fixed size known at compile time.
-type Board = [[char; BOARD_SIZE]; BOARD_SIZE];
+/// File the leaderboard is kept in, in the current directory
+const LEADERBOARD_FILE: &str = "tictactoe_leaderboard.txt";
 
-// ============================================================================
-// ENUMS
-// ============================================================================
-// Enums in Rust are algebraic data types that can hold different variants.
-// They are memory-efficient and enable exhaustive pattern matching.
+/// Longest match that can be set up from the menu
+const MAX_MATCH_GAMES: usize = 99;
 
-/// Represents the possible outcomes of the game
-/// 
-/// Rust enums can carry data in their variants, making them more powerful
-/// than C-style enums. This is known as an "algebraic data type".
-#[derive(Debug, PartialEq, Clone, Copy)]
-enum GameState {
-    /// The game is still in progress
-    InProgress,
-    /// A player has won (stores the winning player's character)
-    Won(char),
-    /// The game ended in a draw
-    Draw,
-}
+/// Match length offered by default
+const DEFAULT_MATCH_GAMES: usize = 3;
 
-// ============================================================================
-// IMPLEMENTATIONS
-// ============================================================================
-
-/// Implements the Display trait for GameState
-/// 
-/// The Display trait is part of Rust's formatting infrastructure.
-/// Implementing it allows custom types to be formatted with `{}` in print macros.
-/// This is similar to implementing toString() in other languages.
-impl fmt::Display for GameState {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        // Pattern matching with match expressions is exhaustive in Rust,
-        // meaning all possible variants must be handled.
-        match self {
-            GameState::InProgress => write!(f, "Game in progress"),
-            GameState::Won(player) => write!(f, "Player {} wins!", player),
-            GameState::Draw => write!(f, "It's a draw!"),
-        }
-    }
-}
+/// Number of arena games played when `--games` is not given
+const DEFAULT_ARENA_GAMES: usize = 1000;
 
 // ============================================================================
-// BOARD MANAGEMENT FUNCTIONS
+// ENUMS
 // ============================================================================
 
-/// Initializes and returns a new empty game board
-/// 
-/// # Returns
-/// A 3x3 board filled with empty cells
+/// Something a human player can ask for on their turn
 /// 
 /// # Rust Concepts
-/// - Array initialization: `[value; size]` creates an array with repeated values
-/// - Stack allocation: Arrays are allocated on the stack, making them very fast
-/// - Copy semantics: `char` implements Copy trait, so it can be duplicated cheaply
-fn initialize_board() -> Board {
-    [[EMPTY_CELL; BOARD_SIZE]; BOARD_SIZE]
+/// - Variants can carry different data: `Move` holds coordinates, `Save` a path
+/// - Unit variants like `Undo` carry no data at all
+#[derive(Debug, PartialEq, Clone)]
+enum Command {
+    /// Place a marker at (row, col)
+    Move(usize, usize),
+    /// Take back the last move (and the computer's reply, if any)
+    Undo,
+    /// Play again a move that was taken back
+    Redo,
+    /// Save the game so far to the given file
+    Save(String),
+    /// Stop playing without finishing the game
+    Quit,
 }
 
-/// Prints the current state of the game board with formatting
-/// 
-/// # Arguments
-/// * `board` - Immutable reference to the game board
+/// How the board is shown and moves are read while a game is running
 /// 
 /// # Rust Concepts
-/// - Borrowing: `&Board` borrows the board immutably (read-only access)
-/// - References allow functions to use data without taking ownership
-/// - This prevents unnecessary copies and allows the caller to keep using the board
-fn print_board(board: &Board) {
-    println!("\n  0   1   2");
-    for (row_idx, row) in board.iter().enumerate() {
-        // `enumerate()` is an iterator adapter that yields (index, value) pairs
-        print!("{} ", row_idx);
-        for cell in row {
-            print!("{}│", cell);
-        }
-        println!();
-        if row_idx < BOARD_SIZE - 1 {
-            println!("  ─────────");
-        }
-    }
-    println!();
+/// - Enums can hold whole structs: `FullScreen(Tui)` owns the terminal state,
+///   and dropping the enum restores the terminal through `Tui`'s `Drop` impl
+enum Screen {
+    /// Print the board after every move and read typed "row column" lines
+    Lines,
+    /// Redraw the board in place and pick cells with a cursor
+    FullScreen(Tui),
 }
 
-/// Checks if a specific cell on the board is empty
+/// Settings for a headless arena run, taken from the command line
 /// 
-/// # Arguments
-/// * `board` - Reference to the game board
-/// * `row` - Row index
-/// * `col` - Column index
-/// 
-/// # Returns
-/// `true` if the cell is empty, `false` otherwise
-/// 
-/// # Rust Concepts
-/// - Boolean return type with explicit true/false values
-/// - Direct array indexing with bounds checking at runtime
-fn is_cell_empty(board: &Board, row: usize, col: usize) -> bool {
-    board[row][col] == EMPTY_CELL
+/// # Fields
+/// * `players` - Strategy names of the two players, e.g. "greedy" or "minimax:2"
+/// * `games` - How many games to play
+/// * `seed` - Seed for the players' random choices (`None` picks one)
+/// * `size` - Board size
+/// * `win_length` - Marks in a row needed to win (`None` for the default)
+#[derive(Debug, PartialEq, Clone)]
+struct ArenaOptions {
+    players: [String; 2],
+    games: usize,
+    seed: Option<u64>,
+    size: usize,
+    win_length: Option<usize>,
 }
 
-/// Checks if all cells on the board are filled
-/// 
-/// # Arguments
-/// * `board` - Reference to the game board
-/// 
-/// # Returns
-/// `true` if the board is full, `false` otherwise
-/// 
-/// # Rust Concepts
-/// - Iterator methods: `iter()` creates an iterator over the board
-/// - `flatten()` converts nested iterators into a single iterator
-/// - `all()` is a higher-order function that checks if all elements satisfy a predicate
-/// - Closures: `|&cell|` is an anonymous function capturing the cell reference
-fn is_board_full(board: &Board) -> bool {
-    board.iter().flatten().all(|&cell| cell != EMPTY_CELL)
+/// Which side of a network game this terminal plays
+#[derive(Debug, PartialEq, Clone)]
+enum NetworkRole {
+    /// Wait for a player to connect on the given address (and play X)
+    Host(String),
+    /// Connect to a host at the given address (and play O)
+    Join(String),
 }
 
 // ============================================================================
-// GAME LOGIC FUNCTIONS
+// SCREEN
 // ============================================================================
 
-/// Checks if the specified player has won the game
-/// 
-/// # Arguments
-/// * `board` - Reference to the game board
-/// * `player` - The player character to check for ('X' or 'O')
-/// 
-/// # Returns
-/// `true` if the player has three in a row, `false` otherwise
-/// 
-/// # Rust Concepts
-/// - Range syntax: `0..BOARD_SIZE` creates a range from 0 to BOARD_SIZE-1
-/// - Iterator methods: `any()` returns true if any element satisfies the condition
-/// - Short-circuit evaluation: Logical OR (||) stops at the first true value
-fn check_winner(board: &Board, player: char) -> bool {
-    // Check rows: All cells in any row match the player
-    let row_win = (0..BOARD_SIZE).any(|i| {
-        (0..BOARD_SIZE).all(|j| board[i][j] == player)
-    });
+impl Screen {
+    /// Uses the full-screen interface when running in a terminal, otherwise lines
+    /// 
+    /// # Arguments
+    /// * `title` - Heading for the full-screen interface
+    /// * `help` - Key reminder for the full-screen interface
+    /// * `board_size` - Size of the board being played
+    fn start(title: &str, help: &str, board_size: usize) -> Screen {
+        if !tui::is_supported() {
+            return Screen::Lines;
+        }
+        match Tui::start(title, help, board_size) {
+            Ok(tui) => Screen::FullScreen(tui),
+            // Without `stty` there is no raw mode, so typed moves still work
+            Err(_) => Screen::Lines,
+        }
+    }
+
+    /// Shows the current position
+    fn show(&mut self, game: &Game) {
+        match self {
+            Screen::Lines => print_board(game.board()),
+            Screen::FullScreen(tui) => tui.draw(game),
+        }
+    }
 
-    // Check columns: All cells in any column match the player
-    let col_win = (0..BOARD_SIZE).any(|j| {
-        (0..BOARD_SIZE).all(|i| board[i][j] == player)
-    });
+    /// Tells the player something (an error, the computer's move, ...)
+    fn notify(&mut self, message: &str) {
+        match self {
+            Screen::Lines => println!("{}", message),
+            Screen::FullScreen(tui) => tui.set_message(message),
+        }
+    }
 
-    // Check main diagonal (top-left to bottom-right)
-    let diag1_win = (0..BOARD_SIZE).all(|i| board[i][i] == player);
+    /// Waits for the current player's command
+    fn read_command(&mut self, game: &Game) -> Command {
+        match self {
+            Screen::Lines => get_player_move(game.current_player()),
+            Screen::FullScreen(tui) => tui.read_command(game),
+        }
+    }
 
-    // Check anti-diagonal (top-right to bottom-left)
-    let diag2_win = (0..BOARD_SIZE).all(|i| board[i][BOARD_SIZE - 1 - i] == player);
+    /// Shows the position a game ended in
+    /// 
+    /// The full-screen interface stays up until a key is pressed, so the
+    /// highlighted winning line can be seen before `finish` closes it.
+    fn show_final(&mut self, game: &Game) {
+        match self {
+            Screen::Lines => print_board(game.board()),
+            Screen::FullScreen(tui) => tui.wait_for_key(game),
+        }
+    }
 
-    // Return true if any winning condition is met
-    row_win || col_win || diag1_win || diag2_win
+    /// Leaves the full-screen interface, leaving the final board on screen
+    /// 
+    /// # Rust Concepts
+    /// - Taking `self` by value consumes the screen; the `Tui` inside is
+    ///   dropped at the end of the match arm, restoring the terminal
+    fn finish(self, game: &Game) {
+        if let Screen::FullScreen(tui) = self {
+            drop(tui);
+            print_board(game.board());
+        }
+    }
 }
 
-/// Determines the current state of the game
+// ============================================================================
+// OUTPUT FUNCTIONS
+// ============================================================================
+
+/// Prints the current state of the game board with formatting
 /// 
 /// # Arguments
-/// * `board` - Reference to the game board
-/// * `last_player` - The player who made the most recent move
-/// 
-/// # Returns
-/// `GameState` enum representing the current game status
+/// * `board` - Immutable reference to the game board
 /// 
 /// # Rust Concepts
-/// - Early returns: Using `return` to exit the function immediately
-/// - Enum variants: Constructing GameState variants with associated data
-fn evaluate_game_state(board: &Board, last_player: char) -> GameState {
-    if check_winner(board, last_player) {
-        return GameState::Won(last_player);
-    }
-    
-    if is_board_full(board) {
-        return GameState::Draw;
-    }
-    
-    GameState::InProgress
+/// - Borrowing: `&Board` borrows the board immutably (read-only access)
+/// - `Board` implements `Display` in the library, so `{}` draws the grid
+fn print_board(board: &Board) {
+    println!("\n{}", board);
 }
 
 // ============================================================================
@@ -237,12 +232,17 @@ fn evaluate_game_state(board: &Board, last_player: char) -> GameState {
 
 /// Prompts the current player for their move and validates input
 /// 
+/// Besides a move, the player may type `undo`, `redo`, `save <file>` or `quit`.
+/// 
+/// Only the shape of the input is checked here; whether a move is actually
+/// legal is decided by the game engine when it is applied.
+/// 
 /// # Arguments
 /// * `current_player` - The character representing the current player
-/// * `board` - Reference to the game board for validation
 /// 
 /// # Returns
-/// A tuple `(row, col)` representing valid board coordinates
+/// The `Command` the player typed. Reaching the end of input is treated as
+/// `Command::Quit`.
 /// 
 /// # Rust Concepts
 /// - Infinite loops: `loop` creates an unconditional loop (broken explicitly)
@@ -250,8 +250,8 @@ fn evaluate_game_state(board: &Board, last_player: char) -> GameState {
 /// - String type: Heap-allocated, growable UTF-8 string
 /// - Error handling: `expect()` unwraps Result, panicking with a message on error
 /// - Method chaining: Calling multiple methods in sequence
-/// - Tuples: Fixed-size collection of values of different types
-fn get_player_move(current_player: char, board: &Board) -> (usize, usize) {
+/// - Slice patterns: `["save", path]` matches a two-element slice by content
+fn get_player_move(current_player: char) -> Command {
     loop {
         // Create a mutable String buffer for user input
         // Strings in Rust are UTF-8 encoded and heap-allocated
@@ -259,19 +259,38 @@ fn get_player_move(current_player: char, board: &Board) -> (usize, usize) {
         
         // Print prompt and flush stdout to ensure it displays before input
         // `print!` doesn't auto-flush like `println!` does
-        print!("Player {}, enter your move (row column): ", current_player);
+        print!("Player {}, enter your move (row column), or undo/redo/save <file>/quit: ", current_player);
         io::stdout().flush().expect("Failed to flush stdout");
         
         // Read a line of input from stdin
         // `read_line()` returns a Result<usize, Error> which must be handled
         // `expect()` unwraps the Result, panicking if it's an Err variant
-        io::stdin()
+        // Zero bytes read means stdin was closed, so there is no one left to play
+        let bytes_read = io::stdin()
             .read_line(&mut input)
             .expect("Failed to read input");
+        if bytes_read == 0 {
+            println!();
+            return Command::Quit;
+        }
+        
+        // Recognise the word commands before trying to read coordinates
+        let words: Vec<&str> = input.split_whitespace().collect();
+        match words.as_slice() {
+            ["undo"] => return Command::Undo,
+            ["redo"] => return Command::Redo,
+            ["quit"] => return Command::Quit,
+            ["save", path] => return Command::Save(path.to_string()),
+            ["save"] => {
+                println!("❌ Please give a file name, e.g. 'save game.ttt'.");
+                continue;
+            }
+            _ => {}
+        }
         
         // Parse input using iterator adapters and functional programming
-        let coordinates: Vec<usize> = input
-            .split_whitespace()              // Split by any whitespace
+        let coordinates: Vec<usize> = words
+            .iter()
             .filter_map(|s| s.parse().ok())  // Parse each token, filtering out errors
             .collect();                       // Collect into a Vec<usize>
         
@@ -282,22 +301,222 @@ fn get_player_move(current_player: char, board: &Board) -> (usize, usize) {
         }
         
         // Destructure the vector into individual variables
-        let (row, col) = (coordinates[0], coordinates[1]);
-        
-        // Validate coordinates are within bounds
-        if row >= BOARD_SIZE || col >= BOARD_SIZE {
-            println!("❌ Coordinates out of range. Use 0-{} for both row and column.", BOARD_SIZE - 1);
-            continue;
+        return Command::Move(coordinates[0], coordinates[1]);
+    }
+}
+
+/// Prints a prompt and reads one trimmed line from stdin
+///
+/// # Arguments
+/// * `prompt` - Text displayed before waiting for input
+///
+/// # Returns
+/// The entered line without surrounding whitespace. If stdin has been closed
+/// there is nobody left to answer, so the program exits instead.
+///
+/// # Rust Concepts
+/// - `&str` parameters accept both string literals and borrowed `String`s
+/// - `trim()` returns a slice; `to_string()` turns it back into an owned `String`
+/// - `process::exit` ends the program immediately with a status code
+fn prompt_line(prompt: &str) -> String {
+    let mut input = String::new();
+    print!("{}", prompt);
+    io::stdout().flush().expect("Failed to flush stdout");
+    let bytes_read = io::stdin()
+        .read_line(&mut input)
+        .expect("Failed to read input");
+    if bytes_read == 0 {
+        println!("\n👋 Input closed. Goodbye!");
+        process::exit(0);
+    }
+    input.trim().to_string()
+}
+
+/// Asks for a number in a range, using a default when the input is empty
+///
+/// # Arguments
+/// * `prompt` - Text displayed before waiting for input
+/// * `range` - Inclusive range of accepted values
+/// * `default` - Value used when the player just presses Enter
+///
+/// # Rust Concepts
+/// - `RangeInclusive<T>`: `a..=b` is a value that can be passed around and queried
+/// - `parse::<usize>()` uses the turbofish to pick the target type
+fn prompt_number(prompt: &str, range: std::ops::RangeInclusive<usize>, default: usize) -> usize {
+    loop {
+        let input = prompt_line(prompt);
+        if input.is_empty() {
+            return default;
         }
-        
-        // Validate the cell is empty
-        if !is_cell_empty(board, row, col) {
-            println!("❌ That cell is already occupied. Choose another.");
-            continue;
+        match input.parse::<usize>() {
+            Ok(value) if range.contains(&value) => return value,
+            _ => println!("❌ Please enter a number from {} to {}.", range.start(), range.end()),
         }
-        
-        // All validations passed, return the valid coordinates
-        return (row, col);
+    }
+}
+
+/// Asks for the board size and the number of marks in a row needed to win
+///
+/// # Returns
+/// A new game on an empty board with the chosen dimensions and win rule
+///
+/// # Rust Concepts
+/// - `format!` builds a `String` the same way `println!` prints one
+/// - `min()` on integers picks the smaller of two values
+fn choose_game() -> Game {
+    let size = prompt_number(
+        &format!(
+            "Board size [{}-{}] (Enter for {}): ",
+            MIN_BOARD_SIZE, MAX_BOARD_SIZE, DEFAULT_BOARD_SIZE
+        ),
+        MIN_BOARD_SIZE..=MAX_BOARD_SIZE,
+        DEFAULT_BOARD_SIZE,
+    );
+
+    let default_win_length = size.min(DEFAULT_MAX_WIN_LENGTH);
+    let win_length = prompt_number(
+        &format!(
+            "Marks in a row to win [{}-{}] (Enter for {}): ",
+            MIN_WIN_LENGTH, size, default_win_length
+        ),
+        MIN_WIN_LENGTH..=size,
+        default_win_length,
+    );
+
+    // The prompts only accept supported values, so creating the game cannot fail
+    Game::new(size, win_length).expect("board dimensions were validated by the prompts")
+}
+
+/// Asks whether to play against another human or the computer
+///
+/// # Returns
+/// `true` for a game against the computer
+///
+/// # Rust Concepts
+/// - Matching on `&str` slices: `match choice.as_str()` compares string contents
+fn choose_versus_computer() -> bool {
+    println!("\nChoose a game mode:");
+    println!("  1) Two players");
+    println!("  2) Play against the computer");
+    loop {
+        match prompt_line("Mode [1-2]: ").as_str() {
+            "1" => return false,
+            "2" => return true,
+            _ => println!("❌ Please enter 1 or 2."),
+        }
+    }
+}
+
+/// Asks how strongly the computer should play
+///
+/// # Rust Concepts
+/// - Loops as expressions: `break value` returns a value out of a `loop`
+fn choose_difficulty() -> Difficulty {
+    println!("\nChoose the computer's difficulty:");
+    println!("  1) {}", Difficulty::Easy);
+    println!("  2) {}", Difficulty::Medium);
+    println!("  3) {} (never loses on 3x3)", Difficulty::Perfect);
+    loop {
+        match prompt_line("Difficulty [1-3]: ").as_str() {
+            "1" => break Difficulty::Easy,
+            "2" => break Difficulty::Medium,
+            "3" => break Difficulty::Perfect,
+            _ => println!("❌ Please enter 1, 2 or 3."),
+        }
+    }
+}
+
+/// Asks whether to play against a human or the computer, and how
+///
+/// # Returns
+/// `None` for a two-player game, or `Some(ComputerPlayer)` configured with the
+/// chosen difficulty and playing the side the human did not pick
+fn choose_opponent() -> Option<ComputerPlayer> {
+    if !choose_versus_computer() {
+        return None;
+    }
+    let difficulty = choose_difficulty();
+
+    let human = loop {
+        match prompt_line("Play as X or O? (X moves first): ").to_ascii_uppercase().as_str() {
+            "X" => break PLAYER_X,
+            "O" => break PLAYER_O,
+            _ => println!("❌ Please enter X or O."),
+        }
+    };
+
+    let computer = if human == PLAYER_X { PLAYER_O } else { PLAYER_X };
+    Some(ComputerPlayer::new(computer, difficulty))
+}
+
+/// Asks for a player's name until a usable one is entered
+/// 
+/// # Arguments
+/// * `prompt` - Text displayed before waiting for input
+/// * `taken` - A name already in use in this match, which cannot be reused
+fn prompt_player_name(prompt: &str, taken: Option<&str>) -> String {
+    loop {
+        let name = prompt_line(prompt);
+        if !tournament::is_valid_player_name(&name) {
+            println!("❌ Please enter a name (tabs are not allowed).");
+        } else if taken == Some(name.as_str()) {
+            println!("❌ That name is already taken in this match.");
+        } else {
+            return name;
+        }
+    }
+}
+
+/// Returns the name recorded for whoever plays `marker`
+fn player_name(computer: &Option<ComputerPlayer>, marker: char) -> String {
+    match computer {
+        Some(ai) if ai.marker() == marker => ai.name(),
+        _ => String::from("Human"),
+    }
+}
+
+/// Recreates the computer opponent named in a game record, if there is one
+/// 
+/// # Rust Concepts
+/// - `find_map` returns the first `Some` produced by the closure
+/// - Chained `Option` methods: `strip_prefix(..)?` style parsing with `and_then`
+fn computer_from_record(record: &GameRecord) -> Option<ComputerPlayer> {
+    [PLAYER_X, PLAYER_O].into_iter().find_map(|marker| {
+        record
+            .player_name(marker)
+            .strip_prefix("Computer (")
+            .and_then(|rest| rest.strip_suffix(')'))
+            .and_then(Difficulty::from_name)
+            .map(|difficulty| ComputerPlayer::new(marker, difficulty))
+    })
+}
+
+/// Asks for a saved game file until one loads successfully
+/// 
+/// # Returns
+/// The loaded record, or `None` if the player leaves the prompt empty
+fn prompt_saved_game() -> Option<GameRecord> {
+    loop {
+        let path = prompt_line("Saved game file (Enter to cancel): ");
+        if path.is_empty() {
+            return None;
+        }
+        match GameRecord::load(&path) {
+            Ok(record) => return Some(record),
+            Err(error) => println!("❌ Could not load '{}': {}", path, error),
+        }
+    }
+}
+
+/// Offers to save a game that has just ended
+fn offer_save(record: &GameRecord) {
+    let path = prompt_line("💾 Save this game? Enter a file name (or press Enter to skip): ");
+    if path.is_empty() {
+        return;
+    }
+    match record.save(&path) {
+        Ok(()) => println!("💾 Game saved to {}", path),
+        Err(error) => println!("❌ Could not save the game: {}", error),
     }
 }
 
@@ -305,64 +524,682 @@ fn get_player_move(current_player: char, board: &Board) -> (usize, usize) {
 // GAME FLOW FUNCTIONS
 // ============================================================================
 
+/// Replays a recorded game move by move
+/// 
+/// # Rust Concepts
+/// - `1..=n` is an inclusive range, visiting every prefix of the move list
+/// - Slices: `moves()[n - 1]` indexes the borrowed move list directly
+fn replay_game(record: &GameRecord) {
+    println!(
+        "\n🎬 Replaying {} (X) vs {} (O)",
+        record.player_name(PLAYER_X),
+        record.player_name(PLAYER_O)
+    );
+    print_board(&record.board_after(0));
+
+    for count in 1..=record.moves().len() {
+        let (row, col) = record.moves()[count - 1];
+        let player = if count % 2 == 1 { PLAYER_X } else { PLAYER_O };
+        println!("Move {}: {} plays {} {}", count, player, row, col);
+        print_board(&record.board_after(count));
+    }
+
+    match record.result() {
+        GameState::InProgress => println!("⏸️  The game was not finished."),
+        result => println!("🏁 {}", result),
+    }
+}
+
 /// Main game loop that manages the flow of the game
 /// 
+/// Every move is stored in a `GameRecord`, and the board shown to the players
+/// is rebuilt from that record, so undo, redo and saving all work from one
+/// source of truth.
+/// 
+/// # Arguments
+/// * `record` - The game to play; a new empty record or one loaded from a file
+/// * `computer` - The computer opponent, or `None` for a two-player game
+/// 
+/// # Returns
+/// The record of the game, finished or not
+/// 
 /// # Rust Concepts
+/// - `Option<T>`: `if let Some(..)` runs code only when a value is present
 /// - Mutable state: `mut` allows variables to be modified
-/// - Ownership: The function owns the board and can modify it
+/// - Ownership: The function takes the record, updates it, and hands it back
 /// - Control flow: Using loop, if, and match for game logic
 /// - Breaking loops: Using `break` to exit the loop explicitly
-fn play_game() {
-    // Initialize a mutable board (ownership transfers to this function)
-    let mut board = initialize_board();
-    
-    // Start with Player X
-    let mut current_player = PLAYER_X;
-    
-    println!("\n🎮 Game started! Player X goes first.");
-    println!("📝 Enter moves as: row column (e.g., '1 2' for middle-right)");
+fn play_game(mut record: GameRecord, mut computer: Option<ComputerPlayer>) -> GameRecord {
+    let board = record.board();
+    if record.moves().is_empty() {
+        println!("\n🎮 Game started! Player X goes first.");
+    } else {
+        println!("\n🎮 Game resumed after {} moves.", record.moves().len());
+    }
+    println!(
+        "📏 {0}x{0} board, {1} in a row wins.",
+        board.size(),
+        board.win_length()
+    );
+    if let Some(ai) = &computer {
+        println!("🤖 The computer ({}) plays {}.", ai.difficulty(), ai.marker());
+    }
+    println!("📝 Enter moves as: row column (e.g., '1 2' for row 1, column 2)");
+
+    let title = format!(
+        "Tic Tac Toe │ {0}x{0}, {1} in a row │ X: {2} │ O: {3}",
+        board.size(),
+        board.win_length(),
+        record.player_name(PLAYER_X),
+        record.player_name(PLAYER_O)
+    );
+    let mut screen = Screen::start(
+        &title,
+        "Arrows/hjkl move · Enter places · u undo · r redo · s save · q quit",
+        board.size(),
+    );
     
     // Main game loop
-    loop {
-        // Display the current board state
-        print_board(&board);
-        
-        // Get and validate the player's move
-        let (row, col) = get_player_move(current_player, &board);
-        
-        // Place the player's marker on the board
-        // This is a mutable operation, modifying the board in place
-        board[row][col] = current_player;
-        
-        // Evaluate the game state after the move
-        let game_state = evaluate_game_state(&board, current_player);
+    // Keep playing until the game is won or drawn
+    while record.result() == GameState::InProgress {
+        // Ask the computer for its move on its turn, otherwise the human
+        let current_player = record.current_player();
+        let command = match computer.as_mut() {
+            Some(ai) if ai.marker() == current_player => {
+                let (row, col) = ai
+                    .choose_move(record.board(), current_player)
+                    .expect("An in-progress game always has an empty cell");
+                screen.notify(&format!("🤖 Computer ({}) plays {} {}", current_player, row, col));
+                Command::Move(row, col)
+            }
+            _ => {
+                // Display the current board state from the record
+                screen.show(record.game());
+                screen.read_command(record.game())
+            }
+        };
         
-        // Pattern matching on the game state
-        // This is exhaustive: all possible GameState variants must be handled
-        match game_state {
-            GameState::Won(player) => {
-                print_board(&board);
-                println!("🎉 {}", GameState::Won(player));
-                break; // Exit the game loop
+        match command {
+            Command::Move(row, col) => {
+                // The engine decides whether the move is legal; on a bad move
+                // the board is shown again and the same player tries again
+                match record.push(row, col) {
+                    Ok(_) => {}
+                    Err(RecordError::IllegalMove { error, .. }) => screen.notify(&format!("❌ {}", error)),
+                    Err(error) => screen.notify(&format!("❌ {}", error)),
+                }
+            }
+            Command::Undo => {
+                // Against the computer, keep undoing until it is the human's
+                // turn again so the computer's reply is taken back too
+                let mut undone = 0;
+                while record.undo().is_some() {
+                    undone += 1;
+                    if computer.is_none() || record.current_player() == current_player {
+                        break;
+                    }
+                }
+                if undone == 0 {
+                    screen.notify("❌ There is nothing to undo.");
+                }
             }
-            GameState::Draw => {
-                print_board(&board);
-                println!("🤝 {}", GameState::Draw);
-                break; // Exit the game loop
+            Command::Redo => {
+                let mut redone = 0;
+                while record.redo().is_some() {
+                    redone += 1;
+                    if computer.is_none()
+                        || record.current_player() == current_player
+                        || record.result() != GameState::InProgress
+                    {
+                        break;
+                    }
+                }
+                if redone == 0 {
+                    screen.notify("❌ There is nothing to redo.");
+                }
             }
-            GameState::InProgress => {
-                // Switch to the other player using a ternary-like expression
-                // This demonstrates Rust's expression-based nature
-                current_player = if current_player == PLAYER_X {
-                    PLAYER_O
-                } else {
-                    PLAYER_X
-                };
+            Command::Save(path) => match record.save(&path) {
+                Ok(()) => screen.notify(&format!("💾 Game saved to {}", path)),
+                Err(error) => screen.notify(&format!("❌ Could not save the game: {}", error)),
+            },
+            Command::Quit => {
+                screen.finish(record.game());
+                println!("⏸️  Game stopped. Save it next time to resume later.");
+                return record;
             }
         }
     }
     
-    println!("\n👋 Thanks for playing!");
+    // Show the final position; the full-screen view is closed afterwards so
+    // the result stays visible in the normal terminal
+    screen.show_final(record.game());
+    screen.finish(record.game());
+    match record.result() {
+        GameState::Won(player) => println!("🎉 {}", GameState::Won(player)),
+        result => println!("🤝 {}", result),
+    }
+    record
+}
+
+/// Game loop for a network game
+/// 
+/// On this side's turn the local player's move is checked by the engine and
+/// sent; on the other side's turn the program waits for their move, which the
+/// engine checks against this side's copy of the game.
+/// 
+/// # Arguments
+/// * `net` - A connected network game (after the handshake)
+/// 
+/// # Returns
+/// `Ok(())` when the game ends normally, or the `NetError` that ended it
+/// 
+/// # Rust Concepts
+/// - Generic functions: `<R: BufRead, W: Write>` works for any connection type
+/// - `?` propagates network errors straight to the caller
+fn play_network<R: BufRead, W: Write>(mut net: NetworkGame<R, W>) -> Result<(), NetError> {
+    let local = net.local_player();
+    let board = net.game().board();
+    println!("\n🌐 Connected! You play {}. Player X goes first.", local);
+    println!(
+        "📏 {0}x{0} board, {1} in a row wins.",
+        board.size(),
+        board.win_length()
+    );
+    println!("📝 Enter moves as: row column (e.g., '1 2' for row 1, column 2)");
+
+    let title = format!(
+        "Tic Tac Toe │ Network game │ {0}x{0}, {1} in a row │ You play {2}",
+        board.size(),
+        board.win_length(),
+        local
+    );
+    let mut screen = Screen::start(
+        &title,
+        "Arrows/hjkl move · Enter places · q quit",
+        board.size(),
+    );
+
+    while net.game().state() == GameState::InProgress {
+        if net.is_local_turn() {
+            screen.show(net.game());
+            match screen.read_command(net.game()) {
+                Command::Move(row, col) => {
+                    if let Err(error) = net.play_local(row, col)? {
+                        screen.notify(&format!("❌ {}", error));
+                    }
+                }
+                Command::Quit => {
+                    net.quit()?;
+                    screen.finish(net.game());
+                    println!("⏸️  You left the game.");
+                    return Ok(());
+                }
+                Command::Undo | Command::Redo | Command::Save(_) => {
+                    screen.notify("❌ That command is not available in network games.");
+                }
+            }
+        } else {
+            screen.notify(&format!("⏳ Waiting for Player {}...", net.game().current_player()));
+            screen.show(net.game());
+            let ((row, col), _) = net.receive_remote()?;
+            screen.notify(&format!("🌐 Player {} plays {} {}", opponent(local), row, col));
+        }
+    }
+
+    screen.show_final(net.game());
+    screen.finish(net.game());
+    match net.game().state() {
+        GameState::Won(player) if player == local => println!("🎉 {} You win!", GameState::Won(player)),
+        GameState::Won(player) => println!("😞 {} Better luck next time.", GameState::Won(player)),
+        result => println!("🤝 {}", result),
+    }
+    Ok(())
+}
+
+/// Plays a best-of-N match and records every finished game on the leaderboard
+/// 
+/// The leaderboard is saved after each game, so results are kept even if the
+/// match is abandoned part way through. Quitting a game ends the match without
+/// recording that game.
+/// 
+/// # Rust Concepts
+/// - Tuples as values: one `if` expression produces both names and the computer, if any
+/// - `Option::map` builds a computer player only when the match has one
+fn run_match() {
+    let mut leaderboard = load_leaderboard();
+    let game = choose_game();
+
+    // The player named first starts the first game
+    let (first, second, computer) = if choose_versus_computer() {
+        let difficulty = choose_difficulty();
+        // The human cannot take the computer's name, so the names tell the sides apart
+        let computer = ComputerPlayer::new(PLAYER_X, difficulty).name();
+        let human = prompt_player_name("Your name: ", Some(&computer));
+        let human_first = loop {
+            match prompt_line("Start the first game as X? [y/n]: ").to_ascii_lowercase().as_str() {
+                "y" | "yes" => break true,
+                "n" | "no" => break false,
+                _ => println!("❌ Please enter y or n."),
+            }
+        };
+        let opponent = Some((difficulty, computer.clone()));
+        if human_first {
+            (human, computer, opponent)
+        } else {
+            (computer, human, opponent)
+        }
+    } else {
+        let first = prompt_player_name("Name of the player who starts: ", None);
+        let second = prompt_player_name("Name of the other player: ", Some(&first));
+        (first, second, None)
+    };
+    let best_of = prompt_number(
+        &format!(
+            "Best of how many games? [1-{}] (Enter for {}): ",
+            MAX_MATCH_GAMES, DEFAULT_MATCH_GAMES
+        ),
+        1..=MAX_MATCH_GAMES,
+        DEFAULT_MATCH_GAMES,
+    );
+
+    let mut series = Match::new(&first, &second, best_of);
+    while !series.is_over() {
+        let (x_name, o_name) = series.next_pairing();
+        let (x_name, o_name) = (x_name.to_string(), o_name.to_string());
+        println!(
+            "\n🏆 Game {} of up to {}: {} (X) vs {} (O)",
+            series.games_played() + 1,
+            best_of,
+            x_name,
+            o_name
+        );
+
+        // The computer plays whichever marker its name was paired with this game
+        let ai = computer.as_ref().map(|(difficulty, name)| {
+            let marker = if x_name == *name { PLAYER_X } else { PLAYER_O };
+            ComputerPlayer::new(marker, *difficulty)
+        });
+        let record = play_game(GameRecord::new(game.clone(), &x_name, &o_name), ai);
+        if record.result() == GameState::InProgress {
+            println!("⏸️  Match abandoned after {} games.", series.games_played());
+            break;
+        }
+
+        leaderboard.record_game(&x_name, &o_name, record.result());
+        if let Err(error) = leaderboard.save(LEADERBOARD_FILE) {
+            println!("❌ Could not save the leaderboard: {}", error);
+        }
+        if let Some(winner) = series.record_game(record.result()) {
+            println!("🏅 {} takes the game.", winner);
+        }
+        println!(
+            "📊 Match score: {} {} - {} {} ({} drawn)",
+            first,
+            series.wins(&first),
+            series.wins(&second),
+            second,
+            series.draws()
+        );
+    }
+
+    if series.is_over() {
+        match series.winner() {
+            Some(winner) => println!("\n🎉 {} wins the match!", winner),
+            None => println!("\n🤝 The match is tied!"),
+        }
+    }
+    print_head_to_head(&leaderboard, &first, &second);
+    print_standings(&leaderboard);
+}
+
+/// Loads the leaderboard file, starting afresh if it cannot be read
+fn load_leaderboard() -> Leaderboard {
+    match Leaderboard::load(LEADERBOARD_FILE) {
+        Ok(leaderboard) => leaderboard,
+        Err(error) => {
+            println!("❌ Ignoring unreadable leaderboard {}: {}", LEADERBOARD_FILE, error);
+            Leaderboard::new()
+        }
+    }
+}
+
+/// Formats the wins, losses, draws and games columns of a record
+fn format_tally(tally: &Tally) -> String {
+    format!(
+        "{:>3} {:>3} {:>3} {:>6}",
+        tally.wins,
+        tally.losses,
+        tally.draws,
+        tally.games()
+    )
+}
+
+/// Prints every player's overall record, best first
+/// 
+/// # Rust Concepts
+/// - `{:<width$}` pads a value to a width chosen at runtime
+fn print_standings(leaderboard: &Leaderboard) {
+    let standings = leaderboard.standings();
+    if standings.is_empty() {
+        println!("\n📋 The leaderboard is empty. Play a match to get on it!");
+        return;
+    }
+
+    let width = standings.iter().map(|s| s.name.chars().count()).max().unwrap_or(0).max(6);
+    println!("\n📋 Standings (2 points per win, 1 per draw)");
+    println!("  #  {:<width$}   W   L   D  Games  Points", "Player", width = width);
+    for (rank, standing) in standings.iter().enumerate() {
+        println!(
+            "{:>3}  {:<width$} {} {:>7}",
+            rank + 1,
+            standing.name,
+            format_tally(&standing.tally),
+            standing.tally.points(),
+            width = width
+        );
+    }
+}
+
+/// Prints one player's record against one opponent
+fn print_head_to_head(leaderboard: &Leaderboard, player: &str, opponent: &str) {
+    let tally = leaderboard.head_to_head(player, opponent);
+    println!(
+        "\n⚔️  {} vs {}: {} wins, {} losses, {} draws",
+        player, opponent, tally.wins, tally.losses, tally.draws
+    );
+}
+
+/// Prints a player's record against each opponent they have met
+fn print_opponents(leaderboard: &Leaderboard, player: &str) {
+    let opponents = leaderboard.opponents(player);
+    if opponents.is_empty() {
+        println!("\n📋 {} has no recorded games.", player);
+        return;
+    }
+
+    let width = opponents.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0).max(8);
+    println!("\n⚔️  Head-to-head record of {}", player);
+    println!("  {:<width$}   W   L   D  Games", "Opponent", width = width);
+    for (opponent, tally) in &opponents {
+        println!("  {:<width$} {}", opponent, format_tally(tally), width = width);
+    }
+}
+
+/// Game loop for two players on the Ultimate Tic Tac Toe grid
+/// 
+/// Uses the line-based interface only: the 9x9 grid with its sub-board
+/// separators is printed after every move, along with where the next move
+/// has to go.
+/// 
+/// # Rust Concepts
+/// - `if let Some(..) = .. else` covers both a forced sub-board and free choice
+fn play_ultimate() {
+    let mut game = UltimateGame::new();
+    println!("\n🎮 Ultimate Tic Tac Toe! Player X goes first.");
+    println!("📏 Win three of the nine sub-boards in a row. Your move decides which");
+    println!("   sub-board your opponent plays in next.");
+    println!("📝 Enter moves as: row column on the 9x9 grid (e.g., '4 4' for the centre)");
+
+    while game.state() == GameState::InProgress {
+        println!("\n{}", game);
+        if let Some(sub_board) = game.forced_sub_board() {
+            println!("👉 Player {} must play in sub-board {}.", game.current_player(), sub_board);
+        } else {
+            println!("👉 Player {} may play in any open sub-board.", game.current_player());
+        }
+
+        match get_player_move(game.current_player()) {
+            Command::Move(row, col) => {
+                if let Err(error) = game.apply_move(row, col) {
+                    println!("❌ {}", error);
+                }
+            }
+            Command::Quit => {
+                println!("⏸️  Game stopped.");
+                return;
+            }
+            Command::Undo | Command::Redo | Command::Save(_) => {
+                println!("❌ That command is not available in Ultimate Tic Tac Toe.");
+            }
+        }
+    }
+
+    println!("\n{}", game);
+    match game.state() {
+        GameState::Won(player) => println!("🎉 {}", GameState::Won(player)),
+        result => println!("🤝 {}", result),
+    }
+}
+
+/// Runs the local game menu: new game, resume, replay, match, leaderboard
+/// or Ultimate Tic Tac Toe
+fn run_local() {
+    println!("\nWhat would you like to do?");
+    println!("  1) Start a new game");
+    println!("  2) Resume a saved game");
+    println!("  3) Replay a saved game");
+    println!("  4) Play a best-of-N match");
+    println!("  5) Show the leaderboard");
+    println!("  6) Play Ultimate Tic Tac Toe (two players)");
+    loop {
+        match prompt_line("Choice [1-6]: ").as_str() {
+            "1" => {
+                let game = choose_game();
+                let computer = choose_opponent();
+                let record = GameRecord::new(
+                    game,
+                    &player_name(&computer, PLAYER_X),
+                    &player_name(&computer, PLAYER_O),
+                );
+                let record = play_game(record, computer);
+                println!("\n👋 Thanks for playing!");
+                offer_save(&record);
+                return;
+            }
+            "2" => {
+                if let Some(record) = prompt_saved_game() {
+                    let computer = computer_from_record(&record);
+                    let record = play_game(record, computer);
+                    println!("\n👋 Thanks for playing!");
+                    offer_save(&record);
+                    return;
+                }
+            }
+            "3" => {
+                if let Some(record) = prompt_saved_game() {
+                    replay_game(&record);
+                    return;
+                }
+            }
+            "4" => {
+                run_match();
+                println!("\n👋 Thanks for playing!");
+                return;
+            }
+            "5" => {
+                print_standings(&load_leaderboard());
+                return;
+            }
+            "6" => {
+                play_ultimate();
+                println!("\n👋 Thanks for playing!");
+                return;
+            }
+            _ => println!("❌ Please enter a number from 1 to 6."),
+        }
+    }
+}
+
+/// Works out where to listen from the argument following `--host`
+/// 
+/// A bare port listens on this machine only; a full `address:port` (such as
+/// `0.0.0.0:7878`) is used as given.
+/// 
+/// # Rust Concepts
+/// - `Result::ok` and `Option::or_else` chain two parsing attempts
+fn host_address(target: &str) -> Option<String> {
+    target
+        .parse::<u16>()
+        .ok()
+        .map(|port| format!("{}:{}", net::DEFAULT_BIND_ADDRESS, port))
+        .or_else(|| target.parse::<SocketAddr>().ok().map(|address| address.to_string()))
+}
+
+/// Hosts or joins a network game and plays it to the end
+/// 
+/// # Arguments
+/// * `role` - Whether to host (choosing the board) or join an existing host
+fn run_network(role: NetworkRole) {
+    let connected = match role {
+        NetworkRole::Host(address) => {
+            let game = choose_game();
+            println!("\n⏳ Waiting for a player to join on {}...", address);
+            net::host(&address, game)
+        }
+        NetworkRole::Join(address) => {
+            println!("\n⏳ Connecting to {}...", address);
+            net::join(&address)
+        }
+    };
+
+    let result = connected.and_then(play_network);
+    match result {
+        Ok(()) => println!("\n👋 Thanks for playing!"),
+        Err(NetError::PeerQuit) => println!("\n👋 The other player left the game."),
+        Err(error) => {
+            eprintln!("\n❌ Network game ended: {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+/// Reads the arguments following `--arena`
+/// 
+/// # Returns
+/// The options, or `None` if the arguments do not make sense
+/// 
+/// # Rust Concepts
+/// - `chunks(2)` walks `--flag value` pairs
+/// - `.ok()?` turns a failed `parse` into an early `None`
+fn parse_arena_args(args: &[String]) -> Option<ArenaOptions> {
+    let (first, second, flags) = match args {
+        [first, second, flags @ ..] => (first, second, flags),
+        _ => return None,
+    };
+    let mut options = ArenaOptions {
+        players: [first.clone(), second.clone()],
+        games: DEFAULT_ARENA_GAMES,
+        seed: None,
+        size: DEFAULT_BOARD_SIZE,
+        win_length: None,
+    };
+
+    for pair in flags.chunks(2) {
+        let [flag, value] = pair else {
+            return None;
+        };
+        match flag.as_str() {
+            "--games" => options.games = value.parse().ok().filter(|&games| games > 0)?,
+            "--seed" => options.seed = Some(value.parse().ok()?),
+            "--size" => options.size = value.parse().ok()?,
+            "--win" => options.win_length = Some(value.parse().ok()?),
+            _ => return None,
+        }
+    }
+    Some(options)
+}
+
+/// Builds the player for an arena strategy name
+/// 
+/// "human" reads moves from the terminal; every other name is looked up with
+/// `player::bot_from_name`.
+fn arena_player(name: &str, seed: u64) -> Option<Box<dyn Player>> {
+    if name == "human" {
+        return Some(Box::new(HumanPlayer::new(io::stdin().lock(), io::stdout())));
+    }
+    player::bot_from_name(name, seed)
+}
+
+/// Plays a series of games between two strategies and prints the statistics
+/// 
+/// # Rust Concepts
+/// - `Box<dyn Player>` lets the strategies be chosen at runtime
+/// - `as_mut()` borrows the boxed player as `&mut dyn Player`
+fn run_arena(options: ArenaOptions) {
+    let win_length = options
+        .win_length
+        .unwrap_or_else(|| options.size.min(DEFAULT_MAX_WIN_LENGTH));
+    let template = match Game::new(options.size, win_length) {
+        Ok(game) => game,
+        Err(error) => {
+            eprintln!("❌ {}", error);
+            process::exit(2);
+        }
+    };
+
+    // Each player gets its own seed, derived from the run's seed
+    let seed = options.seed.unwrap_or_else(ai::seed_from_time);
+    let [first, second] = &options.players;
+    let players = (arena_player(first, seed), arena_player(second, seed.wrapping_add(1)));
+    let (Some(mut first), Some(mut second)) = players else {
+        eprintln!(
+            "❌ Unknown strategy. Choose from: human, {}, or minimax:<depth>",
+            player::BOT_NAMES.join(", ")
+        );
+        process::exit(2);
+    };
+
+    println!(
+        "\n🤖 Arena: {} vs {}, {} games on {}x{} with {} in a row",
+        first.name(),
+        second.name(),
+        options.games,
+        options.size,
+        options.size,
+        win_length
+    );
+    println!("🎲 Seed {} (pass --seed {} to play the same games again)", seed, seed);
+
+    let stats = match arena::run(&template, options.games, first.as_mut(), second.as_mut()) {
+        Ok(stats) => stats,
+        Err(error) => {
+            eprintln!("❌ Arena stopped: {}", error);
+            process::exit(1);
+        }
+    };
+
+    let width = stats.name(0).len().max(stats.name(1).len()).max(6);
+    println!("\n{:<width$}  {:>6}  {:>7}  {:>9}", "Player", "Wins", "Win %", "Wins as X", width = width);
+    for player in 0..2 {
+        println!(
+            "{:<width$}  {:>6}  {:>6.1}%  {:>9}",
+            stats.name(player),
+            stats.wins(player),
+            stats.win_rate(player) * 100.0,
+            stats.wins_as_x(player),
+            width = width
+        );
+    }
+    println!(
+        "{:<width$}  {:>6}  {:>6.1}%",
+        "Draws",
+        stats.draws(),
+        stats.draw_rate() * 100.0,
+        width = width
+    );
+    println!("\n📏 Average game length: {:.2} moves", stats.average_length());
+}
+
+/// Prints command-line usage and exits with an error status
+fn usage_error(program: &str) -> ! {
+    eprintln!("Usage: {} [--host <port> | --host <address> | --join <address>]", program);
+    eprintln!("       {} --standings | --head-to-head <player> [<opponent>]", program);
+    eprintln!(
+        "       {} --arena <strategy> <strategy> [--games N] [--seed S] [--size N] [--win K]",
+        program
+    );
+    eprintln!("       strategies: human, {}, minimax:<depth>", player::BOT_NAMES.join(", "));
+    process::exit(2);
 }
 
 // ============================================================================
@@ -380,5 +1217,25 @@ fn main() {
     println!("║   Welcome to Tic Tac Toe!    ║");
     println!("╔════════════════════════════════╗");
     
-    play_game();
-}
\ No newline at end of file
+    // Command-line flags pick between local play and the two network roles
+    let args: Vec<String> = env::args().collect();
+    let program = args.first().map(String::as_str).unwrap_or("tictactoe");
+    match args.get(1..).unwrap_or_default() {
+        [] => run_local(),
+        [flag, target] if flag == "--host" => match host_address(target) {
+            Some(address) => run_network(NetworkRole::Host(address)),
+            None => usage_error(program),
+        },
+        [flag, address] if flag == "--join" => run_network(NetworkRole::Join(address.clone())),
+        [flag] if flag == "--standings" => print_standings(&load_leaderboard()),
+        [flag, player] if flag == "--head-to-head" => print_opponents(&load_leaderboard(), player),
+        [flag, player, opponent] if flag == "--head-to-head" => {
+            print_head_to_head(&load_leaderboard(), player, opponent)
+        }
+        [flag, rest @ ..] if flag == "--arena" => match parse_arena_args(rest) {
+            Some(options) => run_arena(options),
+            None => usage_error(program),
+        },
+        _ => usage_error(program),
+    }
+}
@@ -0,0 +1,629 @@
+//! # Network Play
+//!
+//! Lets two terminals play one game over TCP. One side hosts (and plays X),
+//! the other joins (and plays O). Both ends keep their own `Game` and check
+//! every move against it, so an illegal move or a board that no longer matches
+//! is caught straight away instead of silently corrupting the game.
+//!
+//! ## Protocol
+//! One message per line, fields separated by spaces:
+//!
+//! ```text
+//! HELLO <version> <size> <win_length>   host -> joiner, right after connecting
+//! READY <version>                       joiner -> host, accepting the game
+//! MOVE <number> <row> <col> <checksum>  either side, after playing a move
+//! QUIT                                  either side, leaving the game
+//! ERROR <message>                       either side, before hanging up
+//! ```
+//!
+//! `number` counts moves from 1 and `checksum` summarises the sender's board
+//! after the move, which is how a desync is detected.
+//!
+//! Everything is generic over `BufRead`/`Write`, so the same code runs on a
+//! `TcpStream` or on in-memory buffers.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+use crate::{Board, Game, GameError, GameState, PLAYER_O, PLAYER_X};
+
+/// Protocol version sent in `HELLO` and `READY`; both sides must agree
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Longest line accepted from the other side, in bytes
+///
+/// Every valid message is far shorter. Without a limit, a peer that never
+/// sends a newline could make `read_line` buffer until memory runs out.
+pub const MAX_LINE: usize = 1024;
+
+/// Address `host` listens on when only a port is given: this machine only
+///
+/// Listening on `0.0.0.0` instead accepts players from other machines, which
+/// has to be asked for explicitly.
+pub const DEFAULT_BIND_ADDRESS: &str = "127.0.0.1";
+
+// ============================================================================
+// ERRORS
+// ============================================================================
+
+/// Everything that can end a network game early
+///
+/// # Rust Concepts
+/// - Variants wrap lower-level errors (`io::Error`, `GameError`) so nothing is lost
+#[derive(Debug)]
+pub enum NetError {
+    /// Reading from or writing to the connection failed
+    Io(io::Error),
+    /// The connection was closed by the other side
+    Disconnected,
+    /// The other side sent something that is not a valid message here
+    Protocol(String),
+    /// The other side speaks a different protocol version
+    VersionMismatch { ours: u32, theirs: u32 },
+    /// The other side sent a move that breaks the rules
+    IllegalMove { number: usize, error: GameError },
+    /// The other side's board no longer matches ours
+    Desync { number: usize },
+    /// The other side left the game
+    PeerQuit,
+    /// The other side reported an error and hung up
+    PeerError(String),
+}
+
+impl fmt::Display for NetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NetError::Io(error) => write!(f, "connection error: {}", error),
+            NetError::Disconnected => write!(f, "the other player disconnected"),
+            NetError::Protocol(message) => write!(f, "protocol error: {}", message),
+            NetError::VersionMismatch { ours, theirs } => write!(
+                f,
+                "protocol version mismatch (we speak {}, they speak {})",
+                ours, theirs
+            ),
+            NetError::IllegalMove { number, error } => {
+                write!(f, "the other player's move {} is illegal: {}", number, error)
+            }
+            NetError::Desync { number } => {
+                write!(f, "boards no longer match after move {}", number)
+            }
+            NetError::PeerQuit => write!(f, "the other player left the game"),
+            NetError::PeerError(message) => write!(f, "the other player reported: {}", message),
+        }
+    }
+}
+
+impl Error for NetError {}
+
+/// Lets the `?` operator turn an `io::Error` into a `NetError`
+impl From<io::Error> for NetError {
+    fn from(error: io::Error) -> NetError {
+        NetError::Io(error)
+    }
+}
+
+// ============================================================================
+// MESSAGES
+// ============================================================================
+
+/// One line of the protocol
+#[derive(Debug, PartialEq, Clone)]
+pub enum Message {
+    /// Sent by the host to propose a game
+    Hello { version: u32, size: usize, win_length: usize },
+    /// Sent by the joiner to accept the game
+    Ready { version: u32 },
+    /// A move, numbered from 1, with the sender's board checksum afterwards
+    Move { number: usize, row: usize, col: usize, checksum: u64 },
+    /// The sender is leaving the game
+    Quit,
+    /// The sender hit a problem and is about to hang up
+    Error(String),
+}
+
+/// Writes a message in its wire format (without the trailing newline)
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Message::Hello { version, size, win_length } => {
+                write!(f, "HELLO {} {} {}", version, size, win_length)
+            }
+            Message::Ready { version } => write!(f, "READY {}", version),
+            Message::Move { number, row, col, checksum } => {
+                write!(f, "MOVE {} {} {} {}", number, row, col, checksum)
+            }
+            Message::Quit => write!(f, "QUIT"),
+            Message::Error(message) => write!(f, "ERROR {}", message),
+        }
+    }
+}
+
+impl Message {
+    /// Parses one line of the protocol
+    ///
+    /// # Errors
+    /// `NetError::Protocol` if the line is not a well-formed message
+    ///
+    /// # Rust Concepts
+    /// - Slice patterns: `["MOVE", number, row, col, checksum]` checks the word
+    ///   count and binds each word in one step
+    pub fn parse(line: &str) -> Result<Message, NetError> {
+        let line = line.trim();
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let bad = || NetError::Protocol(format!("unexpected message '{}'", line));
+
+        let message = match words.as_slice() {
+            ["HELLO", version, size, win_length] => Message::Hello {
+                version: version.parse().map_err(|_| bad())?,
+                size: size.parse().map_err(|_| bad())?,
+                win_length: win_length.parse().map_err(|_| bad())?,
+            },
+            ["READY", version] => Message::Ready {
+                version: version.parse().map_err(|_| bad())?,
+            },
+            ["MOVE", number, row, col, checksum] => Message::Move {
+                number: number.parse().map_err(|_| bad())?,
+                row: row.parse().map_err(|_| bad())?,
+                col: col.parse().map_err(|_| bad())?,
+                checksum: checksum.parse().map_err(|_| bad())?,
+            },
+            ["QUIT"] => Message::Quit,
+            ["ERROR", ..] => Message::Error(line["ERROR".len()..].trim().to_string()),
+            _ => return Err(bad()),
+        };
+        Ok(message)
+    }
+}
+
+/// Summarises a board as a number, so two boards can be compared cheaply
+///
+/// Uses the FNV-1a hash over the board size, win length and every cell.
+///
+/// # Rust Concepts
+/// - `wrapping_mul` multiplies without overflow checks, as hashes expect
+pub fn board_checksum(board: &Board) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let header = [board.size() as u64, board.win_length() as u64];
+    let cells = board.iter().flatten().map(|&cell| cell as u64);
+
+    header
+        .into_iter()
+        .chain(cells)
+        .fold(FNV_OFFSET, |hash, value| (hash ^ value).wrapping_mul(FNV_PRIME))
+}
+
+// ============================================================================
+// NETWORK GAME
+// ============================================================================
+
+/// One side of a game played over a connection
+///
+/// # Type Parameters
+/// * `R` - Where messages from the other side are read from
+/// * `W` - Where messages to the other side are written to
+///
+/// # Fields
+/// * `game` - This side's copy of the game
+/// * `local_player` - The marker played on this side ('X' for the host)
+/// * `reader` - Incoming half of the connection
+/// * `writer` - Outgoing half of the connection
+pub struct NetworkGame<R: BufRead, W: Write> {
+    game: Game,
+    local_player: char,
+    reader: R,
+    writer: W,
+}
+
+impl<R: BufRead, W: Write> NetworkGame<R, W> {
+    /// Proposes a game to the other side and waits for it to accept
+    ///
+    /// The host always plays X and therefore moves first.
+    ///
+    /// # Errors
+    /// Any `NetError` raised while exchanging `HELLO`/`READY`
+    pub fn host(game: Game, reader: R, writer: W) -> Result<NetworkGame<R, W>, NetError> {
+        let mut net = NetworkGame {
+            game,
+            local_player: PLAYER_X,
+            reader,
+            writer,
+        };
+
+        let board = net.game.board();
+        let hello = Message::Hello {
+            version: PROTOCOL_VERSION,
+            size: board.size(),
+            win_length: board.win_length(),
+        };
+        net.send(&hello)?;
+
+        match net.receive()? {
+            Message::Ready { version } if version == PROTOCOL_VERSION => Ok(net),
+            Message::Ready { version } => Err(NetError::VersionMismatch {
+                ours: PROTOCOL_VERSION,
+                theirs: version,
+            }),
+            other => Err(net.unexpected(&other)),
+        }
+    }
+
+    /// Waits for the host's proposal and accepts it
+    ///
+    /// The joining side always plays O. The board dimensions come from the host.
+    ///
+    /// # Errors
+    /// Any `NetError` raised while exchanging `HELLO`/`READY`, or a protocol
+    /// error if the host proposes an unsupported board
+    pub fn join(mut reader: R, mut writer: W) -> Result<NetworkGame<R, W>, NetError> {
+        let hello = read_message(&mut reader)?;
+        let (size, win_length) = match hello {
+            Message::Hello { version, size, win_length } if version == PROTOCOL_VERSION => {
+                (size, win_length)
+            }
+            Message::Hello { version, .. } => {
+                let error = NetError::VersionMismatch {
+                    ours: PROTOCOL_VERSION,
+                    theirs: version,
+                };
+                write_message(&mut writer, &Message::Error(error.to_string()))?;
+                return Err(error);
+            }
+            other => return Err(NetError::Protocol(format!("expected HELLO, got '{}'", other))),
+        };
+
+        let game = Game::new(size, win_length).map_err(|error| NetError::Protocol(error.to_string()))?;
+        let mut net = NetworkGame {
+            game,
+            local_player: PLAYER_O,
+            reader,
+            writer,
+        };
+        net.send(&Message::Ready {
+            version: PROTOCOL_VERSION,
+        })?;
+        Ok(net)
+    }
+
+    /// Returns this side's copy of the game
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Returns the marker played on this side
+    pub fn local_player(&self) -> char {
+        self.local_player
+    }
+
+    /// Returns `true` if it is this side's turn to move
+    pub fn is_local_turn(&self) -> bool {
+        self.game.state() == GameState::InProgress && self.game.current_player() == self.local_player
+    }
+
+    /// Plays a move for this side and sends it to the other side
+    ///
+    /// # Returns
+    /// `Ok(Ok(state))` once the move is played and sent, or `Ok(Err(error))`
+    /// if the game rejected the move (nothing is sent, so the player can retry)
+    ///
+    /// # Errors
+    /// A `NetError` if it is not this side's turn or sending fails
+    ///
+    /// # Rust Concepts
+    /// - Nested `Result`s separate "try another move" from "the game is broken"
+    pub fn play_local(&mut self, row: usize, col: usize) -> Result<Result<GameState, GameError>, NetError> {
+        if !self.is_local_turn() {
+            return Err(NetError::Protocol("it is not our turn".to_string()));
+        }
+
+        let state = match self.game.apply_move(row, col) {
+            Ok(state) => state,
+            Err(error) => return Ok(Err(error)),
+        };
+        let message = Message::Move {
+            number: self.game.moves().len(),
+            row,
+            col,
+            checksum: board_checksum(self.game.board()),
+        };
+        self.send(&message)?;
+        Ok(Ok(state))
+    }
+
+    /// Waits for the other side's move and plays it on this side's board
+    ///
+    /// The move must carry the expected move number, be legal, and leave both
+    /// boards with the same checksum. Otherwise an `ERROR` is sent back and the
+    /// game ends.
+    ///
+    /// # Returns
+    /// The coordinates of the move and the game state after it
+    pub fn receive_remote(&mut self) -> Result<((usize, usize), GameState), NetError> {
+        let message = match self.receive() {
+            Err(error @ NetError::Protocol(_)) => return Err(self.fail(error)),
+            received => received?,
+        };
+        let (number, row, col, checksum) = match message {
+            Message::Move { number, row, col, checksum } => (number, row, col, checksum),
+            Message::Quit => return Err(NetError::PeerQuit),
+            other => return Err(self.fail(self.unexpected(&other))),
+        };
+
+        let expected = self.game.moves().len() + 1;
+        if number != expected || self.is_local_turn() {
+            return Err(self.fail(NetError::Desync { number }));
+        }
+
+        let state = match self.game.apply_move(row, col) {
+            Ok(state) => state,
+            Err(error) => return Err(self.fail(NetError::IllegalMove { number, error })),
+        };
+
+        if board_checksum(self.game.board()) != checksum {
+            return Err(self.fail(NetError::Desync { number }));
+        }
+        Ok(((row, col), state))
+    }
+
+    /// Tells the other side that this side is leaving
+    pub fn quit(&mut self) -> Result<(), NetError> {
+        self.send(&Message::Quit)
+    }
+
+    /// Sends one message
+    fn send(&mut self, message: &Message) -> Result<(), NetError> {
+        write_message(&mut self.writer, message)
+    }
+
+    /// Receives one message, turning a reported error into `NetError::PeerError`
+    fn receive(&mut self) -> Result<Message, NetError> {
+        match read_message(&mut self.reader)? {
+            Message::Error(message) => Err(NetError::PeerError(message)),
+            message => Ok(message),
+        }
+    }
+
+    /// Builds the error for a message that is valid but out of place
+    fn unexpected(&self, message: &Message) -> NetError {
+        NetError::Protocol(format!("unexpected message '{}'", message))
+    }
+
+    /// Tells the other side about `error` (best effort) and returns it
+    fn fail(&mut self, error: NetError) -> NetError {
+        // The connection may already be broken; the original error matters more
+        let _ = self.send(&Message::Error(error.to_string()));
+        error
+    }
+}
+
+/// Writes one message followed by a newline, and flushes it
+fn write_message<W: Write>(writer: &mut W, message: &Message) -> Result<(), NetError> {
+    writeln!(writer, "{}", message)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads and parses one message
+///
+/// # Errors
+/// `NetError::Disconnected` if the connection was closed, or
+/// `NetError::Protocol` if the line is longer than `MAX_LINE`
+///
+/// # Rust Concepts
+/// - `take` wraps a reader so it reports end of input after a number of bytes
+/// - `by_ref` lends the reader to `take` instead of giving it away
+fn read_message<R: BufRead>(reader: &mut R) -> Result<Message, NetError> {
+    let mut line = String::new();
+    let read = reader.by_ref().take(MAX_LINE as u64).read_line(&mut line)?;
+    if read == 0 {
+        return Err(NetError::Disconnected);
+    }
+    if read == MAX_LINE && !line.ends_with('\n') {
+        return Err(NetError::Protocol(format!("message longer than {} bytes", MAX_LINE)));
+    }
+    Message::parse(&line)
+}
+
+// ============================================================================
+// TCP CONNECTIONS
+// ============================================================================
+
+/// A network game over a TCP connection
+pub type TcpGame = NetworkGame<BufReader<TcpStream>, TcpStream>;
+
+/// Waits on `address` (e.g. `"127.0.0.1:7878"`) for one player to join,
+/// then starts `game` with them
+///
+/// # Rust Concepts
+/// - `try_clone` gives a second handle to the same socket, so reading and
+///   writing can be owned separately
+pub fn host(address: &str, game: Game) -> Result<TcpGame, NetError> {
+    let listener = TcpListener::bind(address)?;
+    let (stream, _) = listener.accept()?;
+    let reader = BufReader::new(stream.try_clone()?);
+    NetworkGame::host(game, reader, stream)
+}
+
+/// Connects to a host at `address` (e.g. `"127.0.0.1:7878"`) and joins its game
+pub fn join(address: &str) -> Result<TcpGame, NetError> {
+    let stream = TcpStream::connect(address)?;
+    let reader = BufReader::new(stream.try_clone()?);
+    NetworkGame::join(reader, stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::thread;
+
+    /// The `MOVE` line a well-behaved peer sends after `moves` have been played
+    fn move_line(moves: &[(usize, usize)]) -> String {
+        let mut game = Game::new(3, 3).unwrap();
+        for &(row, col) in moves {
+            game.apply_move(row, col).unwrap();
+        }
+        let &(row, col) = moves.last().unwrap();
+        let message = Message::Move {
+            number: moves.len(),
+            row,
+            col,
+            checksum: board_checksum(game.board()),
+        };
+        format!("{}\n", message)
+    }
+
+    /// Hosts a 3x3 game against a scripted peer that accepts it and then
+    /// sends `incoming`; everything sent to the peer ends up in `out`
+    fn host_against<'a>(
+        incoming: &str,
+        out: &'a mut Vec<u8>,
+    ) -> NetworkGame<Cursor<String>, &'a mut Vec<u8>> {
+        let script = Cursor::new(format!("READY 1\n{}", incoming));
+        NetworkGame::host(Game::new(3, 3).unwrap(), script, out).unwrap()
+    }
+
+    #[test]
+    fn messages_round_trip() {
+        let messages = [
+            Message::Hello { version: 1, size: 5, win_length: 4 },
+            Message::Ready { version: 1 },
+            Message::Move { number: 3, row: 0, col: 2, checksum: u64::MAX },
+            Message::Quit,
+            Message::Error("boards no longer match".to_string()),
+        ];
+        for message in messages {
+            assert_eq!(Message::parse(&message.to_string()).unwrap(), message);
+        }
+        assert!(matches!(Message::parse("MOVE 1 1"), Err(NetError::Protocol(_))));
+        assert!(matches!(Message::parse("MOVE 1 -1 0 0"), Err(NetError::Protocol(_))));
+    }
+
+    #[test]
+    fn handshake() {
+        let mut out = Vec::new();
+        let net = host_against("", &mut out);
+        assert_eq!(net.local_player(), PLAYER_X);
+        assert!(net.is_local_turn());
+        assert_eq!(out, b"HELLO 1 3 3\n");
+
+        let mut out = Vec::new();
+        let net = NetworkGame::join(&b"HELLO 1 5 4\n"[..], &mut out).unwrap();
+        assert_eq!(net.local_player(), PLAYER_O);
+        assert_eq!(net.game().board().size(), 5);
+        assert_eq!(net.game().board().win_length(), 4);
+        assert!(!net.is_local_turn());
+        assert_eq!(out, b"READY 1\n");
+    }
+
+    #[test]
+    fn version_mismatch() {
+        let mut out = Vec::new();
+        let game = Game::new(3, 3).unwrap();
+        let result = NetworkGame::host(game, &b"READY 2\n"[..], &mut out);
+        assert!(matches!(result, Err(NetError::VersionMismatch { ours: 1, theirs: 2 })));
+
+        let mut out = Vec::new();
+        let result = NetworkGame::join(&b"HELLO 2 3 3\n"[..], &mut out);
+        assert!(matches!(result, Err(NetError::VersionMismatch { ours: 1, theirs: 2 })));
+        assert!(String::from_utf8(out).unwrap().starts_with("ERROR "));
+    }
+
+    #[test]
+    fn a_game_over_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        // O joins from another thread and answers X's first two moves
+        let joiner = thread::spawn(move || {
+            let mut net = join(&address).unwrap();
+            assert_eq!(net.receive_remote().unwrap(), ((1, 1), GameState::InProgress));
+            assert_eq!(net.play_local(0, 0).unwrap(), Ok(GameState::InProgress));
+            assert_eq!(net.receive_remote().unwrap(), ((2, 0), GameState::InProgress));
+            assert_eq!(net.play_local(0, 1).unwrap(), Ok(GameState::InProgress));
+            assert_eq!(net.receive_remote().unwrap(), ((0, 2), GameState::Won(PLAYER_X)));
+            net.game().clone()
+        });
+
+        let (stream, _) = listener.accept().unwrap();
+        let reader = BufReader::new(stream.try_clone().unwrap());
+        let mut net = NetworkGame::host(Game::new(3, 3).unwrap(), reader, stream).unwrap();
+        assert_eq!(net.play_local(1, 1).unwrap(), Ok(GameState::InProgress));
+        assert!(matches!(net.play_local(0, 0), Err(NetError::Protocol(_))));
+        assert_eq!(net.receive_remote().unwrap(), ((0, 0), GameState::InProgress));
+        // A move the game rejects is not sent, so the player can try again
+        assert!(matches!(net.play_local(0, 0).unwrap(), Err(GameError::CellOccupied { .. })));
+        assert_eq!(net.play_local(2, 0).unwrap(), Ok(GameState::InProgress));
+        assert_eq!(net.receive_remote().unwrap(), ((0, 1), GameState::InProgress));
+        assert_eq!(net.play_local(0, 2).unwrap(), Ok(GameState::Won(PLAYER_X)));
+
+        assert_eq!(&joiner.join().unwrap(), net.game());
+    }
+
+    #[test]
+    fn an_illegal_move_ends_the_game() {
+        let mut out = Vec::new();
+        // O answers X's centre move by playing on the centre too
+        let mut net = host_against("MOVE 2 1 1 0\n", &mut out);
+        net.play_local(1, 1).unwrap().unwrap();
+        let error = net.receive_remote().unwrap_err();
+        assert!(matches!(
+            error,
+            NetError::IllegalMove {
+                number: 2,
+                error: GameError::CellOccupied { row: 1, col: 1 }
+            }
+        ));
+        let sent = String::from_utf8(out).unwrap();
+        assert!(sent.ends_with(&format!("ERROR {}\n", error)), "{}", sent);
+    }
+
+    #[test]
+    fn a_checksum_mismatch_is_a_desync() {
+        let good = move_line(&[(1, 1), (0, 0)]);
+        let checksum: u64 = good.split_whitespace().last().unwrap().parse().unwrap();
+        let bad = good.replace(&checksum.to_string(), &(checksum ^ 1).to_string());
+
+        let mut out = Vec::new();
+        let mut net = host_against(&good, &mut out);
+        net.play_local(1, 1).unwrap().unwrap();
+        assert_eq!(net.receive_remote().unwrap(), ((0, 0), GameState::InProgress));
+
+        let mut out = Vec::new();
+        let mut net = host_against(&bad, &mut out);
+        net.play_local(1, 1).unwrap().unwrap();
+        assert!(matches!(net.receive_remote(), Err(NetError::Desync { number: 2 })));
+
+        // A move with the wrong number is caught the same way
+        let mut out = Vec::new();
+        let skipped = move_line(&[(1, 1), (0, 0)]).replacen("MOVE 2", "MOVE 3", 1);
+        let mut net = host_against(&skipped, &mut out);
+        net.play_local(1, 1).unwrap().unwrap();
+        assert!(matches!(net.receive_remote(), Err(NetError::Desync { number: 3 })));
+        assert!(String::from_utf8(out).unwrap().contains("\nERROR boards no longer match"));
+    }
+
+    #[test]
+    fn quit_and_disconnect() {
+        let mut out = Vec::new();
+        let mut net = host_against("QUIT\n", &mut out);
+        net.play_local(1, 1).unwrap().unwrap();
+        assert!(matches!(net.receive_remote(), Err(NetError::PeerQuit)));
+        // After the QUIT line the connection has nothing more to give
+        assert!(matches!(net.receive_remote(), Err(NetError::Disconnected)));
+        net.quit().unwrap();
+        assert!(String::from_utf8(out).unwrap().ends_with("\nQUIT\n"));
+    }
+
+    #[test]
+    fn overlong_lines_are_refused() {
+        let flood = "MOVE ".repeat(MAX_LINE);
+        let mut out = Vec::new();
+        let game = Game::new(3, 3).unwrap();
+        let result = NetworkGame::host(game, flood.as_bytes(), &mut out);
+        assert!(matches!(result, Err(NetError::Protocol(message)) if message.contains("longer")));
+    }
+}
@@ -0,0 +1,348 @@
+//! # Players
+//!
+//! The `Player` trait is anything that can look at a board and pick a move:
+//! a person typing coordinates, or one of the computer strategies below.
+//! Front ends and the `arena` only talk to `dyn Player`, so any two players
+//! can be matched up without either knowing what the other is.
+//!
+//! | Strategy  | How it picks a move                                         |
+//! |-----------|-------------------------------------------------------------|
+//! | `random`  | Any empty cell                                              |
+//! | `greedy`  | Wins if it can, blocks if it must, else joins its own marks |
+//! | `minimax` | Searches ahead with `ai::best_moves`                        |
+//!
+//! Every computer player takes a seed, so the same seed always gives the same
+//! games.
+
+use std::io::{self, BufRead, Write};
+
+use crate::ai::{self, Rng};
+use crate::{is_cell_empty, is_winning_move, opponent, Board, EMPTY_CELL};
+
+/// Names accepted by `bot_from_name`, for help and error messages
+pub const BOT_NAMES: [&str; 3] = ["random", "greedy", "minimax"];
+
+// ============================================================================
+// PLAYER TRAIT
+// ============================================================================
+
+/// Something that chooses moves
+///
+/// # Rust Concepts
+/// - Traits describe shared behaviour; each strategy is a separate type
+/// - `&mut self` lets a player keep state between moves (e.g. its RNG)
+/// - Trait objects: `Box<dyn Player>` holds any implementation at runtime
+pub trait Player {
+    /// A short description for scoreboards, e.g. "minimax" or "Human"
+    fn name(&self) -> String;
+
+    /// Chooses the next move
+    ///
+    /// # Arguments
+    /// * `board` - The current position
+    /// * `marker` - The marker this player places ('X' or 'O')
+    ///
+    /// # Returns
+    /// `Some((row, col))` of an empty cell, or `None` if the player has no
+    /// move to offer (a full board, or a human who closed the input)
+    fn choose_move(&mut self, board: &Board, marker: char) -> Option<(usize, usize)>;
+}
+
+/// Creates a computer player from its name
+///
+/// # Arguments
+/// * `name` - One of `BOT_NAMES`; `minimax` may add a depth, as in `minimax:2`
+/// * `seed` - Seed for the player's random choices
+///
+/// # Returns
+/// The player, or `None` if the name is not recognised
+pub fn bot_from_name(name: &str, seed: u64) -> Option<Box<dyn Player>> {
+    match name.split_once(':') {
+        None if name == "random" => Some(Box::new(RandomPlayer::new(seed))),
+        None if name == "greedy" => Some(Box::new(GreedyPlayer::new(seed))),
+        None if name == "minimax" => Some(Box::new(MinimaxPlayer::new(None, seed))),
+        Some(("minimax", depth)) => {
+            let depth = depth.parse().ok().filter(|&depth| depth > 0)?;
+            Some(Box::new(MinimaxPlayer::new(Some(depth), seed)))
+        }
+        _ => None,
+    }
+}
+
+// ============================================================================
+// HUMAN
+// ============================================================================
+
+/// A person entering moves as "row column" lines
+///
+/// Generic over the input and output so it works on stdin/stdout as well as
+/// on in-memory buffers.
+///
+/// # Fields
+/// * `input` - Where moves are read from
+/// * `output` - Where prompts and complaints are written to
+pub struct HumanPlayer<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> HumanPlayer<R, W> {
+    /// Creates a human player reading from `input` and prompting on `output`
+    pub fn new(input: R, output: W) -> HumanPlayer<R, W> {
+        HumanPlayer { input, output }
+    }
+
+    /// Shows the board, then prompts for and reads one line
+    ///
+    /// # Returns
+    /// The line, or `None` at the end of input
+    fn read_line(&mut self, board: &Board, marker: char) -> io::Result<Option<String>> {
+        write!(self.output, "\n{}\nPlayer {}, enter your move (row column): ", board, marker)?;
+        self.output.flush()?;
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(line))
+    }
+}
+
+impl<R: BufRead, W: Write> Player for HumanPlayer<R, W> {
+    fn name(&self) -> String {
+        String::from("Human")
+    }
+
+    /// Asks until an empty cell on the board is entered
+    fn choose_move(&mut self, board: &Board, marker: char) -> Option<(usize, usize)> {
+        loop {
+            let line = self.read_line(board, marker).ok()??;
+            let numbers: Vec<usize> = line
+                .split_whitespace()
+                .filter_map(|part| part.parse().ok())
+                .collect();
+            let complaint = match numbers[..] {
+                [row, col] if row < board.size() && col < board.size() => {
+                    if is_cell_empty(board, row, col) {
+                        return Some((row, col));
+                    }
+                    "That cell is already occupied."
+                }
+                [_, _] => "That cell is off the board.",
+                _ => "Please enter two numbers: row and column.",
+            };
+            // Nothing useful can be done if the complaint cannot be written
+            let _ = writeln!(self.output, "❌ {}", complaint);
+        }
+    }
+}
+
+// ============================================================================
+// COMPUTER STRATEGIES
+// ============================================================================
+
+/// Plays a random empty cell every turn
+pub struct RandomPlayer {
+    rng: Rng,
+}
+
+impl RandomPlayer {
+    /// Creates a random player with a fixed seed
+    pub fn new(seed: u64) -> RandomPlayer {
+        RandomPlayer {
+            rng: Rng::seeded(seed),
+        }
+    }
+}
+
+impl Player for RandomPlayer {
+    fn name(&self) -> String {
+        String::from("random")
+    }
+
+    fn choose_move(&mut self, board: &Board, _marker: char) -> Option<(usize, usize)> {
+        pick(&mut self.rng, &ai::empty_cells(board))
+    }
+}
+
+/// Looks one move ahead: wins if it can, blocks if it must
+///
+/// Otherwise it plays next to as many of its own markers as possible, which
+/// tends to build lines, picking at random among equally good cells.
+pub struct GreedyPlayer {
+    rng: Rng,
+}
+
+impl GreedyPlayer {
+    /// Creates a greedy player with a fixed seed
+    pub fn new(seed: u64) -> GreedyPlayer {
+        GreedyPlayer {
+            rng: Rng::seeded(seed),
+        }
+    }
+}
+
+impl Player for GreedyPlayer {
+    fn name(&self) -> String {
+        String::from("greedy")
+    }
+
+    /// # Rust Concepts
+    /// - `find` stops at the first cell passing the test
+    /// - `max()` finds the best score, then `filter` keeps every cell with it
+    fn choose_move(&mut self, board: &Board, marker: char) -> Option<(usize, usize)> {
+        let empty = ai::empty_cells(board);
+        let mut scratch = board.clone();
+
+        // A cell that completes a line for `player` if they play it
+        let mut completes_line = |player: char, (row, col): (usize, usize)| {
+            scratch[row][col] = player;
+            let wins = is_winning_move(&scratch, row, col);
+            scratch[row][col] = EMPTY_CELL;
+            wins
+        };
+        if let Some(&win) = empty.iter().find(|&&cell| completes_line(marker, cell)) {
+            return Some(win);
+        }
+        let threat = opponent(marker);
+        if let Some(&block) = empty.iter().find(|&&cell| completes_line(threat, cell)) {
+            return Some(block);
+        }
+
+        let friends = |&(row, col): &(usize, usize)| {
+            let last = board.size() - 1;
+            (row.saturating_sub(1)..=(row + 1).min(last))
+                .flat_map(|r| (col.saturating_sub(1)..=(col + 1).min(last)).map(move |c| (r, c)))
+                .filter(|&(r, c)| board[r][c] == marker)
+                .count()
+        };
+        let most = empty.iter().map(friends).max()?;
+        let best: Vec<(usize, usize)> = empty.iter().copied().filter(|cell| friends(cell) == most).collect();
+        pick(&mut self.rng, &best)
+    }
+}
+
+/// Plays the best move found by minimax search with alpha-beta pruning
+///
+/// # Fields
+/// * `depth` - How many plies to search, or `None` for the strongest setting
+///   (the whole game on 3x3, see `ai::full_search_depth`)
+/// * `rng` - Breaks ties between equally good moves, so games vary
+pub struct MinimaxPlayer {
+    depth: Option<u32>,
+    rng: Rng,
+}
+
+impl MinimaxPlayer {
+    /// Creates a minimax player searching `depth` plies (`None` for full strength)
+    pub fn new(depth: Option<u32>, seed: u64) -> MinimaxPlayer {
+        MinimaxPlayer {
+            depth,
+            rng: Rng::seeded(seed),
+        }
+    }
+}
+
+impl Player for MinimaxPlayer {
+    fn name(&self) -> String {
+        match self.depth {
+            Some(depth) => format!("minimax:{}", depth),
+            None => String::from("minimax"),
+        }
+    }
+
+    fn choose_move(&mut self, board: &Board, marker: char) -> Option<(usize, usize)> {
+        let depth = self.depth.unwrap_or_else(|| ai::full_search_depth(board));
+        pick(&mut self.rng, &ai::best_moves(board, marker, depth))
+    }
+}
+
+/// Picks one of `moves` at random, or `None` if there are none
+fn pick(rng: &mut Rng, moves: &[(usize, usize)]) -> Option<(usize, usize)> {
+    if moves.is_empty() {
+        return None;
+    }
+    Some(moves[rng.below(moves.len())])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arena;
+    use crate::{initialize_board, Game, PLAYER_O, PLAYER_X};
+
+    /// Builds a 3x3 board from rows of text, `.` standing for an empty cell
+    fn board_from(rows: [&str; 3]) -> Board {
+        let mut board = initialize_board(3, 3);
+        for (row, text) in rows.iter().enumerate() {
+            for (col, cell) in text.chars().enumerate() {
+                if cell != '.' {
+                    board[row][col] = cell;
+                }
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn bots_by_name() {
+        for (name, expected) in [
+            ("random", "random"),
+            ("greedy", "greedy"),
+            ("minimax", "minimax"),
+            ("minimax:2", "minimax:2"),
+        ] {
+            assert_eq!(bot_from_name(name, 1).unwrap().name(), expected);
+        }
+        for name in ["minimax:0", "minimax:two", "random:1", "perfect", ""] {
+            assert!(bot_from_name(name, 1).is_none(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn greedy_takes_wins_and_blocks_threats() {
+        let mut greedy = GreedyPlayer::new(3);
+        let both_threaten = board_from(["XX.", "OO.", "X.."]);
+        assert_eq!(greedy.choose_move(&both_threaten, PLAYER_X), Some((0, 2)));
+        assert_eq!(greedy.choose_move(&both_threaten, PLAYER_O), Some((1, 2)));
+
+        let o_threatens = board_from(["X..", "OO.", "X.."]);
+        assert_eq!(greedy.choose_move(&o_threatens, PLAYER_X), Some((1, 2)));
+        let x_threatens = board_from(["X..", ".X.", "O.."]);
+        assert_eq!(greedy.choose_move(&x_threatens, PLAYER_O), Some((2, 2)));
+    }
+
+    #[test]
+    fn minimax_never_loses_to_random() {
+        let template = Game::new(3, 3).unwrap();
+        for seed in 0..3 {
+            let mut minimax = MinimaxPlayer::new(None, seed);
+            let mut random = RandomPlayer::new(seed);
+            let stats = arena::run(&template, 10, &mut minimax, &mut random).unwrap();
+            assert_eq!(stats.wins(1), 0, "seed {}", seed);
+            assert!(stats.wins(0) > 0);
+        }
+    }
+
+    #[test]
+    fn full_board_has_no_move() {
+        let full = board_from(["XOX", "XOO", "OXX"]);
+        for name in BOT_NAMES {
+            assert_eq!(bot_from_name(name, 1).unwrap().choose_move(&full, PLAYER_O), None);
+        }
+    }
+
+    #[test]
+    fn human_moves_are_checked() {
+        let board = board_from(["X..", "...", "..."]);
+        let input = "hello\n3 1\n0 0\n1 2\n";
+        let mut output = Vec::new();
+        let mut human = HumanPlayer::new(input.as_bytes(), &mut output);
+        assert_eq!(human.choose_move(&board, PLAYER_O), Some((1, 2)));
+        assert_eq!(human.choose_move(&board, PLAYER_O), None);
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Please enter two numbers"));
+        assert!(output.contains("off the board"));
+        assert!(output.contains("already occupied"));
+    }
+}
@@ -0,0 +1,471 @@
+//! # Game Records
+//!
+//! A `GameRecord` remembers everything needed to reproduce a game: the board
+//! dimensions, who played each side, every move in order and the result.
+//! It wraps a `Game`, which enforces the rules, and adds player names, a redo
+//! list and the plain-text notation used to save and load games.
+//!
+//! ## Notation
+//! Records are saved as plain text. Header lines hold `[Tag "value"]` pairs and
+//! the remaining lines list the moves as numbered `row,col` pairs, X's move
+//! first in each pair:
+//!
+//! ```text
+//! [Size "3"]
+//! [WinLength "3"]
+//! [X "Human"]
+//! [O "Computer (Perfect)"]
+//! [Result "Draw"]
+//!
+//! 1. 1,1 0,0
+//! 2. 2,2 0,2
+//! ```
+//!
+//! `Result` is `X`, `O`, `Draw`, or `*` for an unfinished game. Lines starting
+//! with `#` are comments and are ignored when loading.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::{Board, Game, GameError, GameState, PLAYER_X};
+
+/// Result tag written for a game that has not finished yet
+const UNFINISHED_RESULT: &str = "*";
+
+// ============================================================================
+// ERRORS
+// ============================================================================
+
+/// Everything that can go wrong while saving, loading or replaying a record
+///
+/// # Rust Concepts
+/// - Enums as error types: each variant describes one kind of failure
+/// - Wrapping `io::Error` keeps the original cause available to callers
+#[derive(Debug)]
+pub enum RecordError {
+    /// Reading or writing the record file failed
+    Io(io::Error),
+    /// A line of the notation could not be understood
+    Parse { line: usize, message: String },
+    /// The record as a whole is unusable (e.g. a required tag is missing)
+    Invalid(String),
+    /// Move `number` (counting from 1) broke the rules
+    IllegalMove { number: usize, error: GameError },
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordError::Io(error) => write!(f, "could not access record file: {}", error),
+            RecordError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            RecordError::Invalid(message) => write!(f, "invalid record: {}", message),
+            RecordError::IllegalMove { number, error } => write!(f, "move {}: {}", number, error),
+        }
+    }
+}
+
+impl Error for RecordError {}
+
+/// Lets the `?` operator turn an `io::Error` into a `RecordError`
+impl From<io::Error> for RecordError {
+    fn from(error: io::Error) -> RecordError {
+        RecordError::Io(error)
+    }
+}
+
+// ============================================================================
+// GAME RECORD
+// ============================================================================
+
+/// The complete history of one game
+///
+/// # Fields
+/// * `game` - The game itself: board, moves played and result
+/// * `player_x` - Display name of whoever plays X
+/// * `player_o` - Display name of whoever plays O
+/// * `undone` - Moves taken back with `undo`, most recent last, for `redo`
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    game: Game,
+    player_x: String,
+    player_o: String,
+    undone: Vec<(usize, usize)>,
+}
+
+impl GameRecord {
+    /// Creates a record for a game
+    ///
+    /// # Arguments
+    /// * `game` - The game to record, usually freshly created with `Game::new`
+    /// * `player_x` - Display name of whoever plays X
+    /// * `player_o` - Display name of whoever plays O
+    pub fn new(game: Game, player_x: &str, player_o: &str) -> GameRecord {
+        GameRecord {
+            game,
+            player_x: player_x.to_string(),
+            player_o: player_o.to_string(),
+            undone: Vec::new(),
+        }
+    }
+
+    /// Returns the display name of whoever plays `marker`
+    pub fn player_name(&self, marker: char) -> &str {
+        if marker == PLAYER_X {
+            &self.player_x
+        } else {
+            &self.player_o
+        }
+    }
+
+    /// Returns the recorded game
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Returns the moves played so far, in order
+    pub fn moves(&self) -> &[(usize, usize)] {
+        self.game.moves()
+    }
+
+    /// Returns the outcome after the last recorded move
+    pub fn result(&self) -> GameState {
+        self.game.state()
+    }
+
+    /// Returns the player whose turn it is
+    pub fn current_player(&self) -> char {
+        self.game.current_player()
+    }
+
+    /// Returns the board after the last recorded move
+    pub fn board(&self) -> &Board {
+        self.game.board()
+    }
+
+    /// Rebuilds the board as it was after the first `count` moves
+    ///
+    /// # Rust Concepts
+    /// - `clone()` gives a scratch copy of the game to rewind, leaving `self` as is
+    pub fn board_after(&self, count: usize) -> Board {
+        let mut replay = self.game.clone();
+        while replay.moves().len() > count {
+            replay.undo_move();
+        }
+        replay.board().clone()
+    }
+
+    /// Records a move for the current player
+    ///
+    /// Playing a new move discards any moves that could still have been redone.
+    ///
+    /// # Returns
+    /// The game state after the move
+    ///
+    /// # Errors
+    /// `RecordError::IllegalMove` if the game rejects the move; the record is
+    /// left unchanged
+    ///
+    /// # Rust Concepts
+    /// - `map_err` converts the library's `GameError` into a `RecordError`
+    pub fn push(&mut self, row: usize, col: usize) -> Result<GameState, RecordError> {
+        let number = self.game.moves().len() + 1;
+        let state = self
+            .game
+            .apply_move(row, col)
+            .map_err(|error| RecordError::IllegalMove { number, error })?;
+        self.undone.clear();
+        Ok(state)
+    }
+
+    /// Takes back the most recent move
+    ///
+    /// # Returns
+    /// The move that was taken back, or `None` if no moves have been played
+    pub fn undo(&mut self) -> Option<(usize, usize)> {
+        let last = self.game.undo_move()?;
+        self.undone.push(last);
+        Some(last)
+    }
+
+    /// Plays again the most recently undone move
+    ///
+    /// # Returns
+    /// The move that was replayed, or `None` if there is nothing to redo
+    pub fn redo(&mut self) -> Option<(usize, usize)> {
+        let (row, col) = self.undone.pop()?;
+        self.game
+            .apply_move(row, col)
+            .expect("an undone move is always legal to replay");
+        Some((row, col))
+    }
+
+    // ------------------------------------------------------------------------
+    // NOTATION
+    // ------------------------------------------------------------------------
+
+    /// Writes the record in the plain-text notation described in the module docs
+    ///
+    /// # Rust Concepts
+    /// - `chunks(2)` walks a slice two elements at a time (one X and one O move)
+    /// - `push_str` appends to a growable `String` in place
+    pub fn to_notation(&self) -> String {
+        let result = match self.game.state() {
+            GameState::Won(player) => player.to_string(),
+            GameState::Draw => "Draw".to_string(),
+            GameState::InProgress => UNFINISHED_RESULT.to_string(),
+        };
+
+        let mut text = String::new();
+        text.push_str(&format!("[Size \"{}\"]\n", self.board().size()));
+        text.push_str(&format!("[WinLength \"{}\"]\n", self.board().win_length()));
+        text.push_str(&format!("[X \"{}\"]\n", self.player_x));
+        text.push_str(&format!("[O \"{}\"]\n", self.player_o));
+        text.push_str(&format!("[Result \"{}\"]\n\n", result));
+
+        for (turn, pair) in self.moves().chunks(2).enumerate() {
+            let cells: Vec<String> = pair.iter().map(|(row, col)| format!("{},{}", row, col)).collect();
+            text.push_str(&format!("{}. {}\n", turn + 1, cells.join(" ")));
+        }
+
+        text
+    }
+
+    /// Reads a record written by `to_notation`
+    ///
+    /// Every move is replayed and checked, so a loaded record is always a
+    /// legal game. The `Result` tag is informational: the result is
+    /// recomputed from the moves.
+    ///
+    /// # Errors
+    /// `RecordError::Parse` for malformed lines, `RecordError::Invalid` for a
+    /// missing or unsupported board size, and `RecordError::IllegalMove` for
+    /// moves that break the rules
+    ///
+    /// # Rust Concepts
+    /// - `strip_prefix`/`strip_suffix` return `Option<&str>`, ideal for `?`-style parsing
+    /// - `ok_or_else` converts an `Option` into a `Result` lazily
+    pub fn from_notation(text: &str) -> Result<GameRecord, RecordError> {
+        let mut size = None;
+        let mut win_length = None;
+        let mut player_x = String::from("X");
+        let mut player_o = String::from("O");
+        let mut moves = Vec::new();
+
+        for (index, raw_line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = raw_line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parse_error = |message: &str| RecordError::Parse {
+                line: line_number,
+                message: message.to_string(),
+            };
+
+            if let Some(tag) = line.strip_prefix('[') {
+                let (name, value) = parse_tag(tag).ok_or_else(|| parse_error("malformed tag"))?;
+                match name {
+                    "Size" => size = Some(parse_number(value).ok_or_else(|| parse_error("invalid size"))?),
+                    "WinLength" => {
+                        win_length = Some(parse_number(value).ok_or_else(|| parse_error("invalid win length"))?)
+                    }
+                    "X" => player_x = value.to_string(),
+                    "O" => player_o = value.to_string(),
+                    // Unknown tags (and `Result`) are kept out of the record
+                    _ => {}
+                }
+                continue;
+            }
+
+            // A move line: "<turn>. <row>,<col> [<row>,<col>]"
+            let mut tokens = line.split_whitespace();
+            let turn = tokens.next().unwrap_or_default();
+            if !turn.ends_with('.') || turn[..turn.len() - 1].parse::<usize>().is_err() {
+                return Err(parse_error("expected a numbered move line such as \"1. 1,1 0,0\""));
+            }
+            for token in tokens {
+                let cell = parse_cell(token).ok_or_else(|| parse_error("moves must look like row,col"))?;
+                moves.push(cell);
+            }
+        }
+
+        let size = size.ok_or_else(|| RecordError::Invalid("missing [Size] tag".to_string()))?;
+        let win_length = win_length.unwrap_or(size);
+        let game = Game::new(size, win_length).map_err(|error| RecordError::Invalid(error.to_string()))?;
+        let mut record = GameRecord::new(game, &player_x, &player_o);
+        for (row, col) in moves {
+            record.push(row, col)?;
+        }
+        Ok(record)
+    }
+
+    /// Saves the record to a file in plain-text notation
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RecordError> {
+        fs::write(path, self.to_notation())?;
+        Ok(())
+    }
+
+    /// Loads a record previously written by `save`
+    ///
+    /// # Rust Concepts
+    /// - `impl AsRef<Path>` accepts `&str`, `String`, `PathBuf` and more
+    /// - The `?` operator converts `io::Error` via the `From` impl above
+    pub fn load(path: impl AsRef<Path>) -> Result<GameRecord, RecordError> {
+        let text = fs::read_to_string(path)?;
+        GameRecord::from_notation(&text)
+    }
+}
+
+// ============================================================================
+// PARSING HELPERS
+// ============================================================================
+
+/// Splits the inside of a tag line (`Name "value"]`) into name and value
+fn parse_tag(tag: &str) -> Option<(&str, &str)> {
+    let inner = tag.strip_suffix(']')?;
+    let (name, quoted) = inner.split_once(' ')?;
+    let value = quoted.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some((name, value))
+}
+
+/// Parses a non-negative number
+fn parse_number(value: &str) -> Option<usize> {
+    value.trim().parse().ok()
+}
+
+/// Parses a `row,col` move
+fn parse_cell(token: &str) -> Option<(usize, usize)> {
+    let (row, col) = token.split_once(',')?;
+    Some((parse_number(row)?, parse_number(col)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EMPTY_CELL, PLAYER_O};
+
+    fn record_with(moves: &[(usize, usize)]) -> GameRecord {
+        let mut record = GameRecord::new(Game::new(3, 3).unwrap(), "Alice", "Computer (Perfect)");
+        for &(row, col) in moves {
+            record.push(row, col).unwrap();
+        }
+        record
+    }
+
+    #[test]
+    fn notation_round_trip() {
+        let record = record_with(&[(1, 1), (0, 0), (2, 2), (0, 2), (0, 1)]);
+        let text = record.to_notation();
+        assert!(text.contains("[O \"Computer (Perfect)\"]\n"));
+        assert!(text.contains("[Result \"*\"]\n"));
+        assert!(text.ends_with("1. 1,1 0,0\n2. 2,2 0,2\n3. 0,1\n"));
+
+        let loaded = GameRecord::from_notation(&text).unwrap();
+        assert_eq!(loaded, record);
+        assert_eq!(loaded.player_name(PLAYER_O), "Computer (Perfect)");
+    }
+
+    #[test]
+    fn finished_games_round_trip_with_their_result() {
+        let record = record_with(&[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)]);
+        assert_eq!(record.result(), GameState::Won(PLAYER_X));
+        let text = record.to_notation();
+        assert!(text.contains("[Result \"X\"]\n"));
+        assert_eq!(GameRecord::from_notation(&text).unwrap().result(), GameState::Won(PLAYER_X));
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut record = record_with(&[(1, 1), (0, 0)]);
+        assert_eq!(record.undo(), Some((0, 0)));
+        assert_eq!(record.undo(), Some((1, 1)));
+        assert_eq!(record.undo(), None);
+        assert_eq!(record.board()[1][1], EMPTY_CELL);
+
+        assert_eq!(record.redo(), Some((1, 1)));
+        assert_eq!(record.moves(), &[(1, 1)]);
+        assert_eq!(record.current_player(), PLAYER_O);
+        assert_eq!(record.redo(), Some((0, 0)));
+        assert_eq!(record.redo(), None);
+        assert_eq!(record.board_after(1), record_with(&[(1, 1)]).board().clone());
+    }
+
+    #[test]
+    fn a_new_move_clears_the_redo_list() {
+        let mut record = record_with(&[(1, 1), (0, 0)]);
+        record.undo();
+        record.push(2, 2).unwrap();
+        assert_eq!(record.redo(), None);
+        assert_eq!(record.moves(), &[(1, 1), (2, 2)]);
+
+        // A rejected move leaves the redo list alone
+        record.undo();
+        assert!(record.push(1, 1).is_err());
+        assert_eq!(record.redo(), Some((2, 2)));
+    }
+
+    #[test]
+    fn malformed_notation_is_rejected() {
+        let cases = [
+            ("[Size \"3\"]\n1. 1;1\n", 2),
+            ("[Size \"3\"]\n1 1,1\n", 2),
+            ("[Size 3]\n", 1),
+            ("[Size \"3\"]\n[WinLength \"three\"]\n", 2),
+        ];
+        for (text, expected_line) in cases {
+            match GameRecord::from_notation(text) {
+                Err(RecordError::Parse { line, .. }) => assert_eq!(line, expected_line, "{}", text),
+                other => panic!("{:?} for {:?}", other, text),
+            }
+        }
+
+        assert!(matches!(GameRecord::from_notation("1. 1,1\n"), Err(RecordError::Invalid(_))));
+        assert!(matches!(
+            GameRecord::from_notation("[Size \"3\"]\n[WinLength \"4\"]\n"),
+            Err(RecordError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn illegal_moves_are_rejected() {
+        let occupied = "[Size \"3\"]\n1. 1,1 0,0\n2. 1,1\n";
+        assert!(matches!(
+            GameRecord::from_notation(occupied),
+            Err(RecordError::IllegalMove {
+                number: 3,
+                error: GameError::CellOccupied { row: 1, col: 1 }
+            })
+        ));
+
+        let off_board = "[Size \"3\"]\n1. 3,0\n";
+        assert!(matches!(
+            GameRecord::from_notation(off_board),
+            Err(RecordError::IllegalMove { number: 1, error: GameError::OutOfBounds { .. } })
+        ));
+
+        let after_the_end = "[Size \"3\"]\n1. 0,0 1,0\n2. 0,1 1,1\n3. 0,2 1,2\n";
+        assert!(matches!(
+            GameRecord::from_notation(after_the_end),
+            Err(RecordError::IllegalMove { number: 6, error: GameError::GameOver })
+        ));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("{}-record-test.txt", std::process::id()));
+        let record = record_with(&[(0, 0), (1, 1), (2, 2)]);
+        record.save(&path).unwrap();
+        let loaded = GameRecord::load(&path);
+        fs::write(&path, "[Size \"3\"]\n1. 0,0 0,0\n").unwrap();
+        let illegal = GameRecord::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.unwrap(), record);
+        assert!(matches!(illegal, Err(RecordError::IllegalMove { number: 2, .. })));
+        assert!(matches!(GameRecord::load(&path), Err(RecordError::Io(_))));
+    }
+}
@@ -0,0 +1,581 @@
+//! # Matches and Leaderboard
+//!
+//! A `Match` is a best-of-N series between two named players. Players take
+//! turns starting (playing X), and the match ends as soon as one of them can no
+//! longer be caught, or when all N games have been played.
+//!
+//! The `Leaderboard` keeps every finished game's result per pair of players, so
+//! it can answer both "who is on top overall?" (`standings`) and "how do these
+//! two do against each other?" (`head_to_head`). It is saved as plain text,
+//! one pair of players per line, with tab-separated fields (shown as `<TAB>`):
+//!
+//! ```text
+//! # Tic Tac Toe leaderboard
+//! # player<TAB>opponent<TAB>player wins<TAB>opponent wins<TAB>draws
+//! Alice<TAB>Bob<TAB>3<TAB>1<TAB>2
+//! Alice<TAB>Computer (Perfect)<TAB>0<TAB>4<TAB>5
+//! ```
+//!
+//! Each pair appears once, with the names in alphabetical order. Names may
+//! contain spaces but not tabs or line breaks.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::{GameState, PLAYER_O, PLAYER_X};
+
+/// Points for a won game when ranking the standings (a draw is worth 1)
+const POINTS_PER_WIN: u32 = 2;
+
+// ============================================================================
+// ERRORS
+// ============================================================================
+
+/// Everything that can go wrong while loading or saving a leaderboard
+#[derive(Debug)]
+pub enum LeaderboardError {
+    /// Reading or writing the leaderboard file failed
+    Io(io::Error),
+    /// A line of the leaderboard file could not be understood
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for LeaderboardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LeaderboardError::Io(error) => write!(f, "could not access leaderboard file: {}", error),
+            LeaderboardError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl Error for LeaderboardError {}
+
+/// Lets the `?` operator turn an `io::Error` into a `LeaderboardError`
+impl From<io::Error> for LeaderboardError {
+    fn from(error: io::Error) -> LeaderboardError {
+        LeaderboardError::Io(error)
+    }
+}
+
+// ============================================================================
+// MATCHES
+// ============================================================================
+
+/// A best-of-N series of games between two players
+///
+/// # Fields
+/// * `players` - The two players' names; `players[0]` starts the first game
+/// * `best_of` - The most games the match can last
+/// * `wins` - Games won by each player, in the same order as `players`
+/// * `draws` - Drawn games
+///
+/// # Rust Concepts
+/// - Fixed-size arrays `[T; 2]` keep the two sides' data side by side
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    players: [String; 2],
+    best_of: usize,
+    wins: [usize; 2],
+    draws: usize,
+}
+
+impl Match {
+    /// Creates a match between two players
+    ///
+    /// # Arguments
+    /// * `first` - Name of the player who plays X in the first game
+    /// * `second` - Name of the other player
+    /// * `best_of` - The most games the match can last (at least 1)
+    ///
+    /// # Panics
+    /// Panics if `best_of` is zero
+    pub fn new(first: &str, second: &str, best_of: usize) -> Match {
+        assert!(best_of > 0, "a match needs at least one game");
+        Match {
+            players: [first.to_string(), second.to_string()],
+            best_of,
+            wins: [0, 0],
+            draws: 0,
+        }
+    }
+
+    /// Returns the two players' names, first starter first
+    pub fn players(&self) -> (&str, &str) {
+        (&self.players[0], &self.players[1])
+    }
+
+    /// Returns the most games the match can last
+    pub fn best_of(&self) -> usize {
+        self.best_of
+    }
+
+    /// Returns how many games have been played so far
+    pub fn games_played(&self) -> usize {
+        self.wins[0] + self.wins[1] + self.draws
+    }
+
+    /// Returns the number of games won by `player` (0 for unknown names)
+    pub fn wins(&self, player: &str) -> usize {
+        self.players
+            .iter()
+            .position(|name| name == player)
+            .map_or(0, |index| self.wins[index])
+    }
+
+    /// Returns the number of drawn games
+    pub fn draws(&self) -> usize {
+        self.draws
+    }
+
+    /// Returns the names playing X and O in the next game
+    ///
+    /// The starting player alternates: the first player plays X in games
+    /// 1, 3, 5, ... and the second player in games 2, 4, 6, ...
+    pub fn next_pairing(&self) -> (&str, &str) {
+        let (first, second) = self.players();
+        if self.games_played().is_multiple_of(2) {
+            (first, second)
+        } else {
+            (second, first)
+        }
+    }
+
+    /// Records the result of the game started by `next_pairing`
+    ///
+    /// # Arguments
+    /// * `result` - How the game ended; an unfinished game is not recorded
+    ///
+    /// # Returns
+    /// The winner's name, or `None` for a draw or an unfinished game
+    pub fn record_game(&mut self, result: GameState) -> Option<String> {
+        let (player_x, player_o) = self.next_pairing();
+        let winner = match result {
+            GameState::Won(PLAYER_X) => player_x.to_string(),
+            GameState::Won(PLAYER_O) => player_o.to_string(),
+            GameState::Draw => {
+                self.draws += 1;
+                return None;
+            }
+            GameState::Won(_) | GameState::InProgress => return None,
+        };
+        let index = if winner == self.players[0] { 0 } else { 1 };
+        self.wins[index] += 1;
+        Some(winner)
+    }
+
+    /// Returns `true` once the match has been decided
+    ///
+    /// That is when all games have been played, or when the leader has more
+    /// wins than the other player could still reach in the remaining games.
+    pub fn is_over(&self) -> bool {
+        let remaining = self.best_of - self.games_played();
+        let leader = self.wins[0].max(self.wins[1]);
+        let trailer = self.wins[0].min(self.wins[1]);
+        remaining == 0 || leader > trailer + remaining
+    }
+
+    /// Returns the winner of a finished match, or `None` for a tied match
+    pub fn winner(&self) -> Option<&str> {
+        if !self.is_over() || self.wins[0] == self.wins[1] {
+            return None;
+        }
+        let index = if self.wins[0] > self.wins[1] { 0 } else { 1 };
+        Some(&self.players[index])
+    }
+}
+
+// ============================================================================
+// LEADERBOARD
+// ============================================================================
+
+/// Games won, lost and drawn, from one player's point of view
+///
+/// Used both for a player's overall record and for their record against a
+/// single opponent.
+///
+/// # Rust Concepts
+/// - `Default` derives an all-zero record for players with no games yet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Tally {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+impl Tally {
+    /// Returns the total number of games
+    pub fn games(&self) -> u32 {
+        self.wins + self.losses + self.draws
+    }
+
+    /// Returns the ranking points: two per win and one per draw
+    pub fn points(&self) -> u32 {
+        POINTS_PER_WIN * self.wins + self.draws
+    }
+
+    /// Returns the same record seen from the opponent's side
+    fn reversed(self) -> Tally {
+        Tally {
+            wins: self.losses,
+            losses: self.wins,
+            draws: self.draws,
+        }
+    }
+
+    /// Adds another record to this one
+    fn add(&mut self, other: Tally) {
+        self.wins += other.wins;
+        self.losses += other.losses;
+        self.draws += other.draws;
+    }
+}
+
+/// One row of the standings table
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    pub name: String,
+    pub tally: Tally,
+}
+
+/// Results of every recorded game, grouped by pair of players
+///
+/// # Fields
+/// * `pairs` - For each pair `(a, b)` with `a < b`, `a`'s record against `b`
+///
+/// # Rust Concepts
+/// - `BTreeMap` keeps its keys sorted, so the saved file has a stable order
+/// - Storing each pair once (in sorted order) means the two players' views of
+///   their head-to-head record can never disagree
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Leaderboard {
+    pairs: BTreeMap<(String, String), Tally>,
+}
+
+impl Leaderboard {
+    /// Creates an empty leaderboard
+    pub fn new() -> Leaderboard {
+        Leaderboard::default()
+    }
+
+    /// Records one finished game
+    ///
+    /// # Arguments
+    /// * `player_x` - Name of whoever played X
+    /// * `player_o` - Name of whoever played O
+    /// * `result` - How the game ended; unfinished games are ignored
+    pub fn record_game(&mut self, player_x: &str, player_o: &str, result: GameState) {
+        let tally_for_x = match result {
+            GameState::Won(PLAYER_X) => Tally { wins: 1, ..Tally::default() },
+            GameState::Won(PLAYER_O) => Tally { losses: 1, ..Tally::default() },
+            GameState::Draw => Tally { draws: 1, ..Tally::default() },
+            GameState::Won(_) | GameState::InProgress => return,
+        };
+
+        if player_x <= player_o {
+            self.pair_mut(player_x, player_o).add(tally_for_x);
+        } else {
+            self.pair_mut(player_o, player_x).add(tally_for_x.reversed());
+        }
+    }
+
+    /// Returns the stored record for a pair given in sorted order
+    fn pair_mut(&mut self, first: &str, second: &str) -> &mut Tally {
+        self.pairs
+            .entry((first.to_string(), second.to_string()))
+            .or_default()
+    }
+
+    /// Returns `player`'s record against `opponent` (all zero if they never met)
+    pub fn head_to_head(&self, player: &str, opponent: &str) -> Tally {
+        if player <= opponent {
+            let key = (player.to_string(), opponent.to_string());
+            self.pairs.get(&key).copied().unwrap_or_default()
+        } else {
+            self.head_to_head(opponent, player).reversed()
+        }
+    }
+
+    /// Returns every opponent `player` has met, with their record against each
+    ///
+    /// # Returns
+    /// `(opponent, record)` pairs sorted by opponent name
+    pub fn opponents(&self, player: &str) -> Vec<(String, Tally)> {
+        let mut opponents: Vec<(String, Tally)> = self
+            .pairs
+            .iter()
+            .filter_map(|((first, second), tally)| {
+                if first == player {
+                    Some((second.clone(), *tally))
+                } else if second == player {
+                    Some((first.clone(), tally.reversed()))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        opponents.sort_by(|a, b| a.0.cmp(&b.0));
+        opponents
+    }
+
+    /// Returns every player's overall record, best first
+    ///
+    /// Players are ranked by points (two per win, one per draw), then by
+    /// wins, then by name.
+    ///
+    /// # Rust Concepts
+    /// - `sort_by` with chained `then_with` sorts on several keys
+    /// - Comparing `b` to `a` instead of `a` to `b` sorts in descending order
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut totals: BTreeMap<&str, Tally> = BTreeMap::new();
+        for ((first, second), tally) in &self.pairs {
+            totals.entry(first).or_default().add(*tally);
+            totals.entry(second).or_default().add(tally.reversed());
+        }
+
+        let mut standings: Vec<Standing> = totals
+            .into_iter()
+            .map(|(name, tally)| Standing {
+                name: name.to_string(),
+                tally,
+            })
+            .collect();
+        standings.sort_by(|a, b| {
+            b.tally
+                .points()
+                .cmp(&a.tally.points())
+                .then_with(|| b.tally.wins.cmp(&a.tally.wins))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        standings
+    }
+
+    // ------------------------------------------------------------------------
+    // FILE FORMAT
+    // ------------------------------------------------------------------------
+
+    /// Writes the leaderboard in the tab-separated format described above
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("# Tic Tac Toe leaderboard\n");
+        text.push_str("# player\topponent\tplayer wins\topponent wins\tdraws\n");
+        for ((first, second), tally) in &self.pairs {
+            text.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\n",
+                first, second, tally.wins, tally.losses, tally.draws
+            ));
+        }
+        text
+    }
+
+    /// Reads a leaderboard written by `to_text`
+    ///
+    /// Pairs may appear in either order and more than once; their results are
+    /// added together.
+    ///
+    /// # Errors
+    /// `LeaderboardError::Parse` for lines without five tab-separated fields
+    /// or with counts that are not numbers
+    pub fn from_text(text: &str) -> Result<Leaderboard, LeaderboardError> {
+        let mut leaderboard = Leaderboard::new();
+
+        for (index, raw_line) in text.lines().enumerate() {
+            let line = raw_line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parse_error = |message: &str| LeaderboardError::Parse {
+                line: index + 1,
+                message: message.to_string(),
+            };
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [player, opponent, wins, losses, draws] = fields[..] else {
+                return Err(parse_error("expected 5 tab-separated fields"));
+            };
+            if player.is_empty() || opponent.is_empty() || player == opponent {
+                return Err(parse_error("expected two different player names"));
+            }
+            let count = |field: &str| {
+                field
+                    .trim()
+                    .parse::<u32>()
+                    .map_err(|_| parse_error("counts must be whole numbers"))
+            };
+            let tally = Tally {
+                wins: count(wins)?,
+                losses: count(losses)?,
+                draws: count(draws)?,
+            };
+
+            if player < opponent {
+                leaderboard.pair_mut(player, opponent).add(tally);
+            } else {
+                leaderboard.pair_mut(opponent, player).add(tally.reversed());
+            }
+        }
+
+        Ok(leaderboard)
+    }
+
+    /// Loads a leaderboard file, or starts an empty one if it does not exist yet
+    ///
+    /// # Rust Concepts
+    /// - Matching on `io::ErrorKind` tells "missing file" apart from real errors
+    pub fn load(path: impl AsRef<Path>) -> Result<Leaderboard, LeaderboardError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Leaderboard::from_text(&text),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Leaderboard::new()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Saves the leaderboard to a file, replacing its previous contents
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), LeaderboardError> {
+        fs::write(path, self.to_text())?;
+        Ok(())
+    }
+}
+
+/// Checks that a name can be stored in the leaderboard file
+///
+/// # Returns
+/// `true` for non-empty names without tabs or line breaks
+pub fn is_valid_player_name(name: &str) -> bool {
+    !name.trim().is_empty() && !name.contains(['\t', '\n', '\r'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const X_WINS: GameState = GameState::Won(PLAYER_X);
+    const O_WINS: GameState = GameState::Won(PLAYER_O);
+
+    #[test]
+    fn players_alternate_starting() {
+        let mut series = Match::new("Alice", "Bob", 5);
+        assert_eq!(series.next_pairing(), ("Alice", "Bob"));
+        assert_eq!(series.record_game(X_WINS), Some("Alice".to_string()));
+        assert_eq!(series.next_pairing(), ("Bob", "Alice"));
+        assert_eq!(series.record_game(X_WINS), Some("Bob".to_string()));
+        assert_eq!(series.next_pairing(), ("Alice", "Bob"));
+        assert_eq!(series.record_game(O_WINS), Some("Bob".to_string()));
+        assert_eq!((series.wins("Alice"), series.wins("Bob")), (1, 2));
+        // Unfinished games are not counted
+        assert_eq!(series.record_game(GameState::InProgress), None);
+        assert_eq!(series.games_played(), 3);
+    }
+
+    #[test]
+    fn a_match_ends_once_the_leader_cannot_be_caught() {
+        let mut series = Match::new("Alice", "Bob", 3);
+        series.record_game(X_WINS);
+        assert!(!series.is_over());
+        assert_eq!(series.winner(), None);
+        series.record_game(O_WINS);
+        assert!(series.is_over());
+        assert_eq!(series.winner(), Some("Alice"));
+        assert_eq!(series.games_played(), 2);
+    }
+
+    #[test]
+    fn draws_use_up_games() {
+        let mut series = Match::new("Alice", "Bob", 3);
+        series.record_game(X_WINS);
+        assert_eq!(series.record_game(GameState::Draw), None);
+        // One game left, and Bob could still level the match
+        assert!(!series.is_over());
+        series.record_game(GameState::Draw);
+        assert!(series.is_over());
+        assert_eq!(series.winner(), Some("Alice"));
+        assert_eq!(series.draws(), 2);
+
+        let mut drawn = Match::new("Alice", "Bob", 2);
+        drawn.record_game(GameState::Draw);
+        drawn.record_game(GameState::Draw);
+        assert!(drawn.is_over());
+        assert_eq!(drawn.winner(), None);
+
+        let mut tied = Match::new("Alice", "Bob", 2);
+        tied.record_game(X_WINS);
+        tied.record_game(X_WINS);
+        assert!(tied.is_over());
+        assert_eq!(tied.winner(), None);
+    }
+
+    fn sample_leaderboard() -> Leaderboard {
+        let mut leaderboard = Leaderboard::new();
+        leaderboard.record_game("Bob", "Alice", X_WINS);
+        leaderboard.record_game("Alice", "Bob", X_WINS);
+        leaderboard.record_game("Alice", "Bob", GameState::Draw);
+        leaderboard.record_game("Alice", "Computer (Perfect)", O_WINS);
+        leaderboard.record_game("Computer (Perfect)", "Bob", GameState::Draw);
+        leaderboard.record_game("Bob", "Alice", GameState::InProgress);
+        leaderboard
+    }
+
+    #[test]
+    fn head_to_head_is_the_same_from_both_sides() {
+        let leaderboard = sample_leaderboard();
+        let alice = leaderboard.head_to_head("Alice", "Bob");
+        assert_eq!(alice, Tally { wins: 1, losses: 1, draws: 1 });
+        assert_eq!(leaderboard.head_to_head("Bob", "Alice"), alice.reversed());
+        assert_eq!(leaderboard.head_to_head("Alice", "Nobody"), Tally::default());
+
+        let opponents = leaderboard.opponents("Alice");
+        let names: Vec<&str> = opponents.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["Bob", "Computer (Perfect)"]);
+        assert_eq!(opponents[1].1, Tally { wins: 0, losses: 1, draws: 0 });
+    }
+
+    #[test]
+    fn standings_rank_by_points_then_wins() {
+        let standings = sample_leaderboard().standings();
+        let table: Vec<(&str, u32)> = standings
+            .iter()
+            .map(|standing| (standing.name.as_str(), standing.tally.points()))
+            .collect();
+        // Alice and the computer are level on points and wins, so go by name
+        assert_eq!(table, [("Bob", 4), ("Alice", 3), ("Computer (Perfect)", 3)]);
+    }
+
+    #[test]
+    fn leaderboard_save_and_load() {
+        let leaderboard = sample_leaderboard();
+        let text = leaderboard.to_text();
+        assert!(text.contains("Alice\tBob\t1\t1\t1\n"));
+        assert_eq!(Leaderboard::from_text(&text).unwrap(), leaderboard);
+
+        let name = format!("{}-leaderboard-test.txt", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        let missing = Leaderboard::load(&path).unwrap();
+        leaderboard.save(&path).unwrap();
+        let loaded = Leaderboard::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(missing, Leaderboard::new());
+        assert_eq!(loaded.unwrap(), leaderboard);
+    }
+
+    #[test]
+    fn malformed_leaderboard_lines_are_rejected() {
+        for (text, expected_line) in [
+            ("# header\nAlice\tBob\t1\t2\n", 2),
+            ("Alice\tAlice\t1\t2\t3\n", 1),
+            ("Alice\tBob\t1\t2\tthree\n", 1),
+        ] {
+            match Leaderboard::from_text(text) {
+                Err(LeaderboardError::Parse { line, .. }) => assert_eq!(line, expected_line),
+                other => panic!("{:?} for {:?}", other, text),
+            }
+        }
+        // The same pair listed twice, in either order, is added up
+        let twice = Leaderboard::from_text("Alice\tBob\t1\t0\t0\nBob\tAlice\t1\t0\t2\n").unwrap();
+        assert_eq!(twice.head_to_head("Alice", "Bob"), Tally { wins: 1, losses: 1, draws: 2 });
+    }
+}
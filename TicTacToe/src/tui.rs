@@ -0,0 +1,430 @@
+//! # Full-Screen Terminal Interface
+//!
+//! Draws the board in place and lets the player pick a cell with the arrow
+//! keys instead of typing coordinates. The winning line is highlighted when a
+//! game ends, and a status bar shows the `GameState`.
+//!
+//! Only the standard library is used: the terminal is switched into raw mode
+//! with the `stty` command, and drawing uses ANSI escape sequences. When stdin
+//! or stdout is not a terminal (for example when input is piped in), callers
+//! should use the line-based interface instead; see `is_supported`.
+
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::{Command as Process, Stdio};
+
+use tictactoe::{Game, GameState, EMPTY_CELL};
+
+use crate::Command;
+
+// ============================================================================
+// CONSTANTS
+// ============================================================================
+// ANSI escape sequences understood by practically every terminal emulator.
+
+/// Switches to the alternate screen, so the player's scrollback is kept intact
+const ENTER_ALT_SCREEN: &str = "\x1b[?1049h";
+
+/// Returns from the alternate screen to the normal one
+const LEAVE_ALT_SCREEN: &str = "\x1b[?1049l";
+
+/// Hides the terminal's own blinking cursor while the board is shown
+const HIDE_CURSOR: &str = "\x1b[?25l";
+
+/// Shows the terminal's cursor again
+const SHOW_CURSOR: &str = "\x1b[?25h";
+
+/// Moves to the top-left corner and clears the screen
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+
+/// Draws text with swapped foreground and background colours
+const REVERSE: &str = "\x1b[7m";
+
+/// Draws text in bold green
+const WINNING: &str = "\x1b[1;32m";
+
+/// Draws text dimmed
+const DIM: &str = "\x1b[2m";
+
+/// Resets all text attributes
+const RESET: &str = "\x1b[0m";
+
+// ============================================================================
+// KEYS
+// ============================================================================
+
+/// A key press, decoded from the raw bytes the terminal sends
+///
+/// # Rust Concepts
+/// - `Char(u8)` carries the byte for every key without a dedicated variant
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Backspace,
+    Char(u8),
+    /// The Esc key on its own, or an escape sequence that is not understood
+    Escape,
+    /// Ctrl-C or the end of input; raw mode turns off the usual signal
+    Interrupt,
+}
+
+/// The escape byte that starts every special key's sequence
+const ESC: u8 = 0x1b;
+
+/// Reads one byte from `input`, or `None` at the end of input
+///
+/// # Rust Concepts
+/// - `impl Read` accepts stdin in the game and a byte slice in the tests
+fn read_byte(input: &mut impl Read) -> Option<u8> {
+    let mut byte = [0u8; 1];
+    match input.read(&mut byte) {
+        Ok(1) => Some(byte[0]),
+        _ => None,
+    }
+}
+
+/// Decodes the key press that starts with the byte `first`
+///
+/// Only an escape sequence reads more bytes from `rest`; every other key is
+/// a single byte.
+///
+/// # Rust Concepts
+/// - Byte literals: `b'q'` is the `u8` value of the ASCII character
+fn decode_key(first: u8, rest: &mut impl Read) -> Key {
+    match first {
+        3 => Key::Interrupt,
+        b'\r' | b'\n' => Key::Enter,
+        127 | 8 => Key::Backspace,
+        ESC => decode_escape_sequence(rest),
+        byte => Key::Char(byte),
+    }
+}
+
+/// Decodes the bytes that follow an escape
+///
+/// Arrow keys arrive as escape, `[`, then `A`-`D`. Other keys send longer
+/// `CSI` sequences: `[`, then parameter bytes such as `1;5` (Ctrl-Up is
+/// `ESC [ 1 ; 5 A`), then one final byte from `@` to `~`. The whole sequence
+/// is read up to its final byte, so an unknown key leaves nothing behind to
+/// be mistaken for typed characters.
+///
+/// # Returns
+/// An arrow key, or `Key::Escape` for a lone Esc or any other sequence
+///
+/// # Rust Concepts
+/// - Range patterns like `0x20..=0x3f` match a whole class of bytes at once
+fn decode_escape_sequence(rest: &mut impl Read) -> Key {
+    if read_byte(rest) != Some(b'[') {
+        return Key::Escape;
+    }
+    let mut has_parameters = false;
+    loop {
+        match read_byte(rest) {
+            // Parameter and intermediate bytes: digits, `;`, spaces and the like
+            Some(0x20..=0x3f) => has_parameters = true,
+            Some(b'A') if !has_parameters => return Key::Up,
+            Some(b'B') if !has_parameters => return Key::Down,
+            Some(b'C') if !has_parameters => return Key::Right,
+            Some(b'D') if !has_parameters => return Key::Left,
+            // Any other final byte, a stray byte or no byte at all
+            _ => return Key::Escape,
+        }
+    }
+}
+
+/// Waits for and decodes one key press from stdin
+///
+/// The terminal sends the bytes of an arrow key together, so they are already
+/// waiting. A lone Esc press is followed by nothing, and a plain blocking read
+/// would hang until the next key. So after an escape `stty min 0 time 1`
+/// makes reads return empty-handed once a tenth of a second passes without
+/// input, until the sequence has been read.
+fn read_key() -> Key {
+    let mut stdin = io::stdin();
+    match read_byte(&mut stdin) {
+        None => Key::Interrupt,
+        Some(ESC) => {
+            // Without the timeout the next read could block, so give up straight away
+            if stty(&["min", "0", "time", "1"]).is_err() {
+                return Key::Escape;
+            }
+            let key = decode_key(ESC, &mut stdin);
+            // Back to waiting for each key; `stty` has just worked, so this should too
+            let _ = stty(&["min", "1", "time", "0"]);
+            key
+        }
+        Some(byte) => decode_key(byte, &mut stdin),
+    }
+}
+
+// ============================================================================
+// TERMINAL USER INTERFACE
+// ============================================================================
+
+/// Returns `true` if both stdin and stdout are interactive terminals
+///
+/// # Rust Concepts
+/// - The `IsTerminal` trait adds `is_terminal()` to standard handles
+pub fn is_supported() -> bool {
+    io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+/// Runs `stty` on the controlling terminal with the given arguments
+///
+/// # Returns
+/// Whatever `stty` printed, which for `-g` is the saved terminal settings
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Process::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other("stty failed"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// A full-screen view of a game in a raw-mode terminal
+///
+/// Creating a `Tui` takes over the terminal; dropping it gives the terminal
+/// back exactly as it was, even if the game ends early.
+///
+/// # Fields
+/// * `saved_mode` - Terminal settings to restore, as printed by `stty -g`
+/// * `title` - Heading drawn above the board
+/// * `help` - Key reminder drawn below the status bar
+/// * `cursor` - The highlighted cell as (row, col)
+/// * `message` - Latest notice for the player (errors, computer moves, ...)
+pub struct Tui {
+    saved_mode: String,
+    title: String,
+    help: String,
+    cursor: (usize, usize),
+    message: String,
+}
+
+impl Tui {
+    /// Switches the terminal to raw mode and the alternate screen
+    ///
+    /// # Arguments
+    /// * `title` - Heading drawn above the board
+    /// * `help` - Key reminder drawn below the status bar
+    /// * `board_size` - Size of the board, used to start the cursor in the centre
+    ///
+    /// # Errors
+    /// Any error from running `stty`; the terminal is left unchanged
+    pub fn start(title: &str, help: &str, board_size: usize) -> io::Result<Tui> {
+        let saved_mode = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+
+        print!("{}{}", ENTER_ALT_SCREEN, HIDE_CURSOR);
+        io::stdout().flush()?;
+
+        Ok(Tui {
+            saved_mode,
+            title: title.to_string(),
+            help: help.to_string(),
+            cursor: (board_size / 2, board_size / 2),
+            message: String::new(),
+        })
+    }
+
+    /// Sets the notice shown under the status bar on the next draw
+    pub fn set_message(&mut self, message: &str) {
+        self.message = message.to_string();
+    }
+
+    /// Redraws the whole screen for the current position
+    ///
+    /// In raw mode `\n` only moves down a line, so every line ends in `\r\n`.
+    ///
+    /// # Rust Concepts
+    /// - Building the frame in one `String` and writing it once avoids flicker
+    /// - `contains` on a `Vec` checks membership for the few winning cells
+    pub fn draw(&mut self, game: &Game) {
+        let board = game.board();
+        let size = board.size();
+        let winning_line = game.winning_line().unwrap_or_default();
+        let show_cursor = game.state() == GameState::InProgress;
+
+        let mut frame = String::from(CLEAR_SCREEN);
+        frame.push_str(&format!("{}\r\n\r\n", self.title));
+
+        for row in 0..size {
+            frame.push_str("  ");
+            for col in 0..size {
+                let cell = board[row][col];
+                let shown = if cell == EMPTY_CELL { '·' } else { cell };
+                let style = if show_cursor && (row, col) == self.cursor {
+                    REVERSE
+                } else if winning_line.contains(&(row, col)) {
+                    WINNING
+                } else if cell == EMPTY_CELL {
+                    DIM
+                } else {
+                    ""
+                };
+                frame.push_str(&format!("{} {} {}", style, shown, RESET));
+            }
+            frame.push_str("\r\n");
+        }
+
+        // Status bar: the game state, plus whose turn it is while playing
+        let status = match game.state() {
+            GameState::InProgress => format!(
+                "{} │ Player {} to move",
+                game.state(),
+                game.current_player()
+            ),
+            finished => finished.to_string(),
+        };
+        frame.push_str(&format!("\r\n{} {} {}\r\n", REVERSE, status, RESET));
+        frame.push_str(&format!("{}\r\n", self.message));
+        frame.push_str(&format!("{}{}{}\r\n", DIM, self.help, RESET));
+
+        print!("{}", frame);
+        // A failed flush only delays the redraw; the next one will catch up
+        let _ = io::stdout().flush();
+    }
+
+    /// Lets the player pick a cell with the cursor, or type a command key
+    ///
+    /// Arrow keys (or h/j/k/l) move the cursor, Enter or Space places a
+    /// marker, `u`/`r` undo and redo, `s` saves and `q` quits.
+    ///
+    /// # Returns
+    /// The chosen `Command`; whether a move is legal is left to the engine
+    pub fn read_command(&mut self, game: &Game) -> Command {
+        let last = game.board().size() - 1;
+        loop {
+            self.draw(game);
+            let (row, col) = self.cursor;
+            match read_key() {
+                Key::Up | Key::Char(b'k') => self.cursor.0 = row.saturating_sub(1),
+                Key::Down | Key::Char(b'j') => self.cursor.0 = (row + 1).min(last),
+                Key::Left | Key::Char(b'h') => self.cursor.1 = col.saturating_sub(1),
+                Key::Right | Key::Char(b'l') => self.cursor.1 = (col + 1).min(last),
+                Key::Enter | Key::Char(b' ') => {
+                    self.message.clear();
+                    return Command::Move(row, col);
+                }
+                Key::Char(b'u') => return Command::Undo,
+                Key::Char(b'r') => return Command::Redo,
+                Key::Char(b'q') | Key::Interrupt => return Command::Quit,
+                Key::Char(b's') => {
+                    let path = self.read_text(game, "Save to file (Enter to cancel): ");
+                    if !path.is_empty() {
+                        return Command::Save(path);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Keeps the final position on screen until a key is pressed
+    ///
+    /// Leaving the full-screen view as soon as the game ends would take the
+    /// highlighted winning line away before the player could see it.
+    pub fn wait_for_key(&mut self, game: &Game) {
+        self.help = "Press any key to continue".to_string();
+        self.draw(game);
+        read_key();
+    }
+
+    /// Reads a line of text on the message line, echoing it as it is typed
+    ///
+    /// # Returns
+    /// The text typed before Enter, or an empty string if cancelled
+    fn read_text(&mut self, game: &Game, prompt: &str) -> String {
+        let mut text = String::new();
+        loop {
+            self.message = format!("{}{}", prompt, text);
+            self.draw(game);
+            match read_key() {
+                Key::Enter => break,
+                Key::Interrupt | Key::Escape => {
+                    text.clear();
+                    break;
+                }
+                Key::Backspace => {
+                    text.pop();
+                }
+                Key::Char(byte) if byte.is_ascii_graphic() || byte == b' ' => text.push(byte as char),
+                _ => {}
+            }
+        }
+        self.message.clear();
+        text
+    }
+}
+
+/// Restores the terminal when the `Tui` goes out of scope
+///
+/// # Rust Concepts
+/// - RAII: implementing `Drop` ties cleanup to scope, so it also runs on
+///   early returns and when the game loop exits through `?`
+impl Drop for Tui {
+    fn drop(&mut self) {
+        print!("{}{}", SHOW_CURSOR, LEAVE_ALT_SCREEN);
+        let _ = io::stdout().flush();
+        // Nothing sensible can be done if this fails while already cleaning up
+        let _ = stty(&[self.saved_mode.as_str()]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decodes every key in `bytes`, as `read_key` would from stdin
+    fn keys(mut bytes: &[u8]) -> Vec<Key> {
+        let mut keys = Vec::new();
+        while let Some(first) = read_byte(&mut bytes) {
+            keys.push(decode_key(first, &mut bytes));
+        }
+        keys
+    }
+
+    #[test]
+    fn arrow_keys() {
+        assert_eq!(
+            keys(b"\x1b[A\x1b[B\x1b[C\x1b[D"),
+            [Key::Up, Key::Down, Key::Right, Key::Left]
+        );
+    }
+
+    #[test]
+    fn single_byte_keys() {
+        assert_eq!(
+            keys(b"\r\n\x7f\x08q\x03"),
+            [
+                Key::Enter,
+                Key::Enter,
+                Key::Backspace,
+                Key::Backspace,
+                Key::Char(b'q'),
+                Key::Interrupt
+            ]
+        );
+    }
+
+    #[test]
+    fn lone_escape() {
+        assert_eq!(keys(b"\x1b"), [Key::Escape]);
+        // A sequence cut off before its final byte is a lone Esc as well
+        assert_eq!(keys(b"\x1b[1;"), [Key::Escape]);
+    }
+
+    #[test]
+    fn unknown_sequences_are_consumed_whole() {
+        // Ctrl-Up, Delete and F5, each followed by a typed `x`
+        assert_eq!(keys(b"\x1b[1;5Ax"), [Key::Escape, Key::Char(b'x')]);
+        assert_eq!(keys(b"\x1b[3~x"), [Key::Escape, Key::Char(b'x')]);
+        assert_eq!(keys(b"\x1b[15~x"), [Key::Escape, Key::Char(b'x')]);
+        assert_eq!(keys(b"\x1b[Hx"), [Key::Escape, Key::Char(b'x')]);
+    }
+}
@@ -0,0 +1,543 @@
+//! # Ultimate Tic Tac Toe
+//!
+//! Nine ordinary 3x3 boards arranged in a 3x3 meta-board, 81 cells in all.
+//! Win three sub-boards in a row (across, down or diagonally) to win the game.
+//!
+//! ## Rules
+//! - X moves first, anywhere on the 9x9 grid.
+//! - The cell you play inside a sub-board picks the sub-board your opponent
+//!   must play in next: playing the top-right cell of any sub-board sends them
+//!   to the top-right sub-board, and so on.
+//! - A sub-board is closed once someone wins it or it fills up. If you are sent
+//!   to a closed sub-board, you may play in any open one instead.
+//! - The game is drawn when every sub-board is closed and nobody has three in
+//!   a row on the meta-board.
+//!
+//! Each sub-board is a regular `Board`, and results are decided with the same
+//! `check_winner` and `is_board_full` functions as the classic game.
+//!
+//! ## Coordinates
+//! Moves use rows and columns 0-8 of the whole grid. Sub-boards are numbered
+//! 0-8 in reading order, so `(row, col)` lies in sub-board
+//! `(row / 3) * 3 + col / 3`.
+//!
+//! ## Example
+//! ```
+//! use tictactoe::ultimate::{UltimateError, UltimateGame};
+//!
+//! let mut game = UltimateGame::new();
+//! // X plays the centre cell of the top-left sub-board...
+//! game.apply_move(1, 1).unwrap();
+//! // ...which sends O to the centre sub-board (number 4)
+//! assert_eq!(game.forced_sub_board(), Some(4));
+//! assert_eq!(game.apply_move(0, 0), Err(UltimateError::WrongSubBoard { required: 4 }));
+//! game.apply_move(3, 3).unwrap();
+//! ```
+//!
+//! Winning sub-boards 0, 1 and 2 gives X the top row of the meta-board:
+//! ```
+//! use tictactoe::ultimate::{UltimateError, UltimateGame};
+//! use tictactoe::{GameError, GameState};
+//!
+//! let moves = [
+//!     (1, 7), (3, 4), (2, 3), (6, 2), (2, 7), (6, 3), (0, 1), (0, 5), (0, 7),
+//!     (0, 4), (1, 3), (3, 2), (0, 3), (0, 2), (1, 1), (3, 3), (2, 1),
+//! ];
+//! let mut game = UltimateGame::new();
+//! for &(row, col) in &moves[..moves.len() - 1] {
+//!     assert_eq!(game.apply_move(row, col), Ok(GameState::InProgress));
+//! }
+//! assert_eq!(game.apply_move(2, 1), Ok(GameState::Won('X')));
+//! assert!(game.legal_moves().is_empty());
+//! assert_eq!(game.apply_move(8, 8), Err(UltimateError::Move(GameError::GameOver)));
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use crate::{
+    check_winner, initialize_board, is_board_full, Board, GameError, GameState, EMPTY_CELL, PLAYER_O,
+    PLAYER_X,
+};
+
+/// Rows and columns in one sub-board, and sub-boards per side of the meta-board
+pub const SUB_BOARD_SIZE: usize = 3;
+
+/// Rows and columns of the whole grid
+pub const ULTIMATE_SIZE: usize = SUB_BOARD_SIZE * SUB_BOARD_SIZE;
+
+/// Number of sub-boards
+const SUB_BOARD_COUNT: usize = SUB_BOARD_SIZE * SUB_BOARD_SIZE;
+
+// ============================================================================
+// ERRORS
+// ============================================================================
+
+/// Reasons a move is not allowed in Ultimate Tic Tac Toe
+///
+/// # Rust Concepts
+/// - Wrapping `GameError` reuses the classic game's messages for the problems
+///   both variants share (off the board, occupied, game over)
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UltimateError {
+    /// A problem shared with the classic game
+    Move(GameError),
+    /// The previous move sent this player to another sub-board
+    WrongSubBoard { required: usize },
+    /// The chosen sub-board has already been won or filled
+    SubBoardClosed { sub_board: usize },
+}
+
+impl fmt::Display for UltimateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UltimateError::Move(error) => write!(f, "{}", error),
+            UltimateError::WrongSubBoard { required } => {
+                write!(f, "you must play in sub-board {}", required)
+            }
+            UltimateError::SubBoardClosed { sub_board } => {
+                write!(f, "sub-board {} is already decided", sub_board)
+            }
+        }
+    }
+}
+
+impl Error for UltimateError {}
+
+// ============================================================================
+// GAME
+// ============================================================================
+
+/// A game of Ultimate Tic Tac Toe
+///
+/// # Fields
+/// * `boards` - The nine sub-boards in reading order
+/// * `results` - Whether each sub-board is still open, won or drawn
+/// * `meta` - The meta-board: a won sub-board's cell holds the winner's marker
+/// * `forced` - The sub-board the next player must use, if any
+/// * `moves` - Every move played so far as grid coordinates, in order
+/// * `state` - Whether the whole game is still going, won or drawn
+#[derive(Debug, Clone, PartialEq)]
+pub struct UltimateGame {
+    boards: Vec<Board>,
+    results: [GameState; SUB_BOARD_COUNT],
+    meta: Board,
+    forced: Option<usize>,
+    moves: Vec<(usize, usize)>,
+    state: GameState,
+}
+
+impl Default for UltimateGame {
+    fn default() -> UltimateGame {
+        UltimateGame::new()
+    }
+}
+
+impl UltimateGame {
+    /// Starts a new game with nine empty sub-boards
+    ///
+    /// # Rust Concepts
+    /// - `(0..n).map(..).collect()` builds a `Vec` of independent boards
+    pub fn new() -> UltimateGame {
+        UltimateGame {
+            boards: (0..SUB_BOARD_COUNT)
+                .map(|_| initialize_board(SUB_BOARD_SIZE, SUB_BOARD_SIZE))
+                .collect(),
+            results: [GameState::InProgress; SUB_BOARD_COUNT],
+            meta: initialize_board(SUB_BOARD_SIZE, SUB_BOARD_SIZE),
+            forced: None,
+            moves: Vec::new(),
+            state: GameState::InProgress,
+        }
+    }
+
+    /// Returns whether the game is in progress, won or drawn
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    /// Returns the moves played so far, in order
+    pub fn moves(&self) -> &[(usize, usize)] {
+        &self.moves
+    }
+
+    /// Returns the player whose turn it is
+    pub fn current_player(&self) -> char {
+        if self.moves.len().is_multiple_of(2) {
+            PLAYER_X
+        } else {
+            PLAYER_O
+        }
+    }
+
+    /// Returns one of the nine sub-boards (numbered 0-8 in reading order)
+    pub fn sub_board(&self, index: usize) -> &Board {
+        &self.boards[index]
+    }
+
+    /// Returns whether a sub-board is still open, won or drawn
+    pub fn sub_board_result(&self, index: usize) -> GameState {
+        self.results[index]
+    }
+
+    /// Returns the meta-board, where each won sub-board holds its winner
+    pub fn meta_board(&self) -> &Board {
+        &self.meta
+    }
+
+    /// Returns the sub-board the next move must be played in
+    ///
+    /// # Returns
+    /// `Some(index)` when the last move sent the player to an open sub-board,
+    /// or `None` when any open sub-board may be chosen
+    pub fn forced_sub_board(&self) -> Option<usize> {
+        self.forced
+    }
+
+    /// Returns the sub-boards the next move may be played in
+    pub fn playable_sub_boards(&self) -> Vec<usize> {
+        if self.state != GameState::InProgress {
+            return Vec::new();
+        }
+        match self.forced {
+            Some(index) => vec![index],
+            None => (0..SUB_BOARD_COUNT)
+                .filter(|&index| self.results[index] == GameState::InProgress)
+                .collect(),
+        }
+    }
+
+    /// Returns the marker at a cell of the whole grid
+    pub fn cell(&self, row: usize, col: usize) -> char {
+        let (index, inner_row, inner_col) = split(row, col);
+        self.boards[index][inner_row][inner_col]
+    }
+
+    /// Lists every legal move as grid coordinates, in reading order
+    ///
+    /// # Rust Concepts
+    /// - `flat_map` turns each row index into an iterator of (row, col) pairs
+    pub fn legal_moves(&self) -> Vec<(usize, usize)> {
+        let playable = self.playable_sub_boards();
+        (0..ULTIMATE_SIZE)
+            .flat_map(|row| (0..ULTIMATE_SIZE).map(move |col| (row, col)))
+            .filter(|&(row, col)| playable.contains(&split(row, col).0) && self.cell(row, col) == EMPTY_CELL)
+            .collect()
+    }
+
+    /// Plays a move for the current player
+    ///
+    /// # Arguments
+    /// * `row` - Row of the whole grid (0-8)
+    /// * `col` - Column of the whole grid (0-8)
+    ///
+    /// # Returns
+    /// The state of the whole game after the move
+    ///
+    /// # Errors
+    /// `UltimateError::WrongSubBoard` if the move ignores the forced sub-board,
+    /// `UltimateError::SubBoardClosed` if it targets a decided sub-board, and
+    /// `UltimateError::Move` for moves off the grid, onto an occupied cell or
+    /// after the game has ended. The game is unchanged on error.
+    ///
+    /// # Examples
+    /// Winning a sub-board claims its cell on the meta-board, and a player
+    /// sent to a decided sub-board may choose any open one:
+    /// ```
+    /// use tictactoe::ultimate::{UltimateError, UltimateGame};
+    /// use tictactoe::GameState;
+    ///
+    /// let mut game = UltimateGame::new();
+    /// // X takes the top row of sub-board 0; O's replies send X straight back
+    /// for (row, col) in [(0, 1), (0, 3), (0, 2), (0, 6), (0, 0)] {
+    ///     game.apply_move(row, col).unwrap();
+    /// }
+    /// assert_eq!(game.sub_board_result(0), GameState::Won('X'));
+    /// assert_eq!(game.meta_board()[0][0], 'X');
+    ///
+    /// // X's last move points at the decided sub-board 0, so O may choose
+    /// assert_eq!(game.forced_sub_board(), None);
+    /// assert_eq!(game.apply_move(2, 2), Err(UltimateError::SubBoardClosed { sub_board: 0 }));
+    /// game.apply_move(4, 4).unwrap();
+    /// // O played the centre cell, so X must answer in the centre sub-board
+    /// assert_eq!(game.apply_move(0, 4), Err(UltimateError::WrongSubBoard { required: 4 }));
+    /// ```
+    pub fn apply_move(&mut self, row: usize, col: usize) -> Result<GameState, UltimateError> {
+        if self.state != GameState::InProgress {
+            return Err(UltimateError::Move(GameError::GameOver));
+        }
+        if row >= ULTIMATE_SIZE || col >= ULTIMATE_SIZE {
+            return Err(UltimateError::Move(GameError::OutOfBounds {
+                row,
+                col,
+                size: ULTIMATE_SIZE,
+            }));
+        }
+
+        let (index, inner_row, inner_col) = split(row, col);
+        if let Some(required) = self.forced {
+            if index != required {
+                return Err(UltimateError::WrongSubBoard { required });
+            }
+        }
+        if self.results[index] != GameState::InProgress {
+            return Err(UltimateError::SubBoardClosed { sub_board: index });
+        }
+        if self.boards[index][inner_row][inner_col] != EMPTY_CELL {
+            return Err(UltimateError::Move(GameError::CellOccupied { row, col }));
+        }
+
+        let player = self.current_player();
+        self.boards[index][inner_row][inner_col] = player;
+        self.moves.push((row, col));
+
+        // Decide the sub-board, then let a win there roll up to the meta-board
+        let board = &self.boards[index];
+        if check_winner(board, player) {
+            self.results[index] = GameState::Won(player);
+            self.meta[index / SUB_BOARD_SIZE][index % SUB_BOARD_SIZE] = player;
+        } else if is_board_full(board) {
+            self.results[index] = GameState::Draw;
+        }
+
+        if check_winner(&self.meta, player) {
+            self.state = GameState::Won(player);
+        } else if self.results.iter().all(|&result| result != GameState::InProgress) {
+            self.state = GameState::Draw;
+        }
+
+        // The cell just played picks the opponent's sub-board, if it is open
+        let target = inner_row * SUB_BOARD_SIZE + inner_col;
+        self.forced = if self.results[target] == GameState::InProgress {
+            Some(target)
+        } else {
+            None
+        };
+
+        Ok(self.state)
+    }
+}
+
+/// Splits grid coordinates into (sub-board, row inside it, column inside it)
+fn split(row: usize, col: usize) -> (usize, usize, usize) {
+    let index = row / SUB_BOARD_SIZE * SUB_BOARD_SIZE + col / SUB_BOARD_SIZE;
+    (index, row % SUB_BOARD_SIZE, col % SUB_BOARD_SIZE)
+}
+
+// ============================================================================
+// DISPLAY
+// ============================================================================
+
+/// Draws the 9x9 grid with heavy lines between sub-boards, then the meta-board
+///
+/// Empty cells where the next move may go are shown as `·`; other empty cells
+/// are left blank, so the playable area stands out. On the meta-board, `-`
+/// marks a drawn sub-board.
+///
+/// # Rust Concepts
+/// - `is_multiple_of` finds the sub-board boundaries
+/// - `writeln!` returns `fmt::Result`, so `?` stops at the first write error
+impl fmt::Display for UltimateGame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let playable = self.playable_sub_boards();
+
+        write!(f, "   ")?;
+        for col in 0..ULTIMATE_SIZE {
+            if col > 0 && col.is_multiple_of(SUB_BOARD_SIZE) {
+                write!(f, "  ")?;
+            }
+            write!(f, " {}", col)?;
+        }
+        writeln!(f)?;
+
+        for row in 0..ULTIMATE_SIZE {
+            if row > 0 && row.is_multiple_of(SUB_BOARD_SIZE) {
+                writeln!(f, "   {0}╬{0}╬{0}", "═".repeat(2 * SUB_BOARD_SIZE + 1))?;
+            }
+            write!(f, " {} ", row)?;
+            for col in 0..ULTIMATE_SIZE {
+                if col > 0 && col.is_multiple_of(SUB_BOARD_SIZE) {
+                    write!(f, " ║")?;
+                }
+                let marker = self.cell(row, col);
+                let shown = if marker != EMPTY_CELL {
+                    marker
+                } else if playable.contains(&split(row, col).0) {
+                    '·'
+                } else {
+                    ' '
+                };
+                write!(f, " {}", shown)?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f, "\nSub-boards won:")?;
+        for meta_row in 0..SUB_BOARD_SIZE {
+            write!(f, "   ")?;
+            for meta_col in 0..SUB_BOARD_SIZE {
+                let shown = match self.results[meta_row * SUB_BOARD_SIZE + meta_col] {
+                    GameState::Won(player) => player,
+                    GameState::Draw => '-',
+                    GameState::InProgress => '·',
+                };
+                write!(f, " {}", shown)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::Rng;
+
+    /// Plays `moves`, which must all be legal
+    fn game_with(moves: &[(usize, usize)]) -> UltimateGame {
+        let mut game = UltimateGame::new();
+        for &(row, col) in moves {
+            game.apply_move(row, col).unwrap();
+        }
+        game
+    }
+
+    /// Plays random legal moves from a seed until `stop` holds or the game ends
+    fn random_game(seed: u64, stop: impl Fn(&UltimateGame) -> bool) -> UltimateGame {
+        let mut rng = Rng::seeded(seed);
+        let mut game = UltimateGame::new();
+        while game.state() == GameState::InProgress && !stop(&game) {
+            let moves = game.legal_moves();
+            let (row, col) = moves[rng.below(moves.len())];
+            game.apply_move(row, col).unwrap();
+        }
+        game
+    }
+
+    /// Finds a seeded random game that reaches a position where `found` holds
+    fn find_game(found: impl Fn(&UltimateGame) -> bool) -> UltimateGame {
+        (0..1_000)
+            .map(|seed| random_game(seed, &found))
+            .find(|game| found(game))
+            .expect("some seed reaches the position")
+    }
+
+    /// The sub-board the last move sent the current player to
+    fn target_of_last_move(game: &UltimateGame) -> Option<usize> {
+        let &(row, col) = game.moves().last()?;
+        let (_, inner_row, inner_col) = split(row, col);
+        Some(inner_row * SUB_BOARD_SIZE + inner_col)
+    }
+
+    #[test]
+    fn the_last_move_picks_the_next_sub_board() {
+        // X plays the top-right cell of the centre sub-board
+        let mut game = game_with(&[(3, 5)]);
+        assert_eq!(game.forced_sub_board(), Some(2));
+        assert_eq!(game.playable_sub_boards(), vec![2]);
+        let legal = game.legal_moves();
+        assert_eq!(legal.len(), 9);
+        assert!(legal.iter().all(|&(row, col)| split(row, col).0 == 2));
+
+        assert_eq!(game.apply_move(4, 4), Err(UltimateError::WrongSubBoard { required: 2 }));
+        assert_eq!(game.apply_move(2, 6), Ok(GameState::InProgress));
+        // O played the bottom-left cell of sub-board 2, so X goes to sub-board 6
+        assert_eq!(game.forced_sub_board(), Some(6));
+        assert_eq!(game.cell(2, 6), PLAYER_O);
+        assert_eq!(game.current_player(), PLAYER_X);
+    }
+
+    #[test]
+    fn a_won_sub_board_frees_the_choice() {
+        // X takes the top row of sub-board 0; O keeps sending X back there
+        let mut game = game_with(&[(0, 1), (0, 3), (0, 2), (0, 6), (0, 0)]);
+        assert_eq!(game.sub_board_result(0), GameState::Won(PLAYER_X));
+        assert_eq!(game.forced_sub_board(), None);
+        assert_eq!(game.playable_sub_boards(), (1..9).collect::<Vec<_>>());
+        assert!(game.legal_moves().iter().all(|&(row, col)| split(row, col).0 != 0));
+
+        // Sub-board 0 is closed even though it still has empty cells
+        let before = game.clone();
+        assert_eq!(game.apply_move(2, 2), Err(UltimateError::SubBoardClosed { sub_board: 0 }));
+        assert_eq!(game, before);
+        assert_eq!(game.apply_move(8, 8), Ok(GameState::InProgress));
+    }
+
+    #[test]
+    fn a_full_sub_board_frees_the_choice() {
+        let game = find_game(|game| {
+            game.state() == GameState::InProgress
+                && target_of_last_move(game)
+                    .is_some_and(|target| game.sub_board_result(target) == GameState::Draw)
+        });
+        let full = target_of_last_move(&game).unwrap();
+        assert!(is_board_full(game.sub_board(full)));
+        assert_eq!(game.forced_sub_board(), None);
+
+        let open: Vec<usize> = (0..SUB_BOARD_COUNT)
+            .filter(|&index| game.sub_board_result(index) == GameState::InProgress)
+            .collect();
+        assert_eq!(game.playable_sub_boards(), open);
+        assert!(!open.contains(&full));
+    }
+
+    #[test]
+    fn closed_sub_boards_reject_moves() {
+        let mut game = find_game(|game| {
+            let drawn = (0..SUB_BOARD_COUNT).any(|i| game.sub_board_result(i) == GameState::Draw);
+            let won = (0..SUB_BOARD_COUNT)
+                .any(|i| matches!(game.sub_board_result(i), GameState::Won(_)));
+            let free = game.forced_sub_board().is_none();
+            game.state() == GameState::InProgress && drawn && won && free
+        });
+        let before = game.clone();
+        for index in 0..SUB_BOARD_COUNT {
+            if game.sub_board_result(index) == GameState::InProgress {
+                continue;
+            }
+            // Every cell of a closed sub-board is refused, empty or not
+            let (top, left) = (index / 3 * 3, index % 3 * 3);
+            for row in top..top + 3 {
+                for col in left..left + 3 {
+                    let result = game.apply_move(row, col);
+                    assert_eq!(result, Err(UltimateError::SubBoardClosed { sub_board: index }));
+                }
+            }
+        }
+        assert_eq!(game, before);
+    }
+
+    #[test]
+    fn sub_board_wins_roll_up_to_the_meta_board() {
+        // The same game as the module example: X wins sub-boards 1, 2 and 0
+        let moves = [
+            (1, 7), (3, 4), (2, 3), (6, 2), (2, 7), (6, 3), (0, 1), (0, 5), (0, 7),
+            (0, 4), (1, 3), (3, 2), (0, 3), (0, 2), (1, 1), (3, 3), (2, 1),
+        ];
+        let mut game = UltimateGame::new();
+        let mut won = Vec::new();
+        for &(row, col) in &moves {
+            game.apply_move(row, col).unwrap();
+            let (index, _, _) = split(row, col);
+            if game.sub_board_result(index) != GameState::InProgress && !won.contains(&index) {
+                won.push(index);
+                assert_eq!(game.meta_board()[index / 3][index % 3], PLAYER_X);
+            }
+        }
+        assert_eq!(won, [2, 1, 0]);
+        assert_eq!(game.state(), GameState::Won(PLAYER_X));
+        assert!(check_winner(game.meta_board(), PLAYER_X));
+        assert!(game.playable_sub_boards().is_empty());
+    }
+
+    #[test]
+    fn a_meta_board_without_a_line_is_a_draw() {
+        let mut game = find_game(|game| game.state() == GameState::Draw);
+        assert!((0..SUB_BOARD_COUNT).all(|i| game.sub_board_result(i) != GameState::InProgress));
+        assert!(!check_winner(game.meta_board(), PLAYER_X));
+        assert!(!check_winner(game.meta_board(), PLAYER_O));
+        assert!(game.legal_moves().is_empty());
+        assert_eq!(game.apply_move(0, 0), Err(UltimateError::Move(GameError::GameOver)));
+    }
+}